@@ -0,0 +1,45 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Example printing Ledger devices as they are plugged in and out
+//!
+//! This example shows how to:
+//! 1. Start a `DeviceWatcher` polling for HID devices
+//! 2. Forward its events into a Tokio channel
+//! 3. Stop watching on Ctrl-C by dropping the watcher
+
+use std::error::Error;
+
+use ledger_sdk_transport_hid::{DeviceEvent, DeviceWatcher};
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn Error>> {
+    // Initialize logging
+    env_logger::init();
+
+    let (sender, mut events) = tokio::sync::mpsc::unbounded_channel();
+    let watcher = DeviceWatcher::spawn(move |event| {
+        // The receiver only goes away when main returns
+        let _ = sender.send(event);
+    })?;
+
+    println!("👀 Watching for Ledger devices, press Ctrl-C to stop");
+
+    loop {
+        tokio::select! {
+            Some(event) = events.recv() => match event {
+                DeviceEvent::Connected(info) => println!(
+                    "🔌 Connected: {:?} (PID 0x{:04x}, serial {:?}) at {}",
+                    info.model, info.product_id, info.serial_number, info.path
+                ),
+                DeviceEvent::Disconnected(info) => {
+                    println!("❌ Disconnected: {:?} at {}", info.model, info.path)
+                }
+            },
+            _ = tokio::signal::ctrl_c() => break,
+        }
+    }
+
+    drop(watcher);
+    println!("👋 Stopped watching");
+    Ok(())
+}
@@ -21,17 +21,79 @@ pub struct APDUCommand<B> {
     pub data: B,
 }
 
+/// Largest payload of a command with a one-byte Lc
+pub const MAX_SHORT_DATA_LEN: usize = 0xFF;
+
+/// Largest payload of a command with an extended (three-byte) Lc
+pub const MAX_EXTENDED_DATA_LEN: usize = 0xFFFF;
+
+#[derive(Debug, Snafu, PartialEq, Eq)]
+/// Error serializing an APDU command
+pub enum APDUCommandError {
+    #[snafu(display("command data too long ({len} bytes > {max})"))]
+    /// Payload does not fit the length field of the encoding
+    DataTooLong {
+        /// Payload length
+        len: usize,
+        /// Largest payload of the encoding
+        max: usize,
+    },
+}
+
 #[cfg(feature = "std")]
 impl<B> APDUCommand<B>
 where
     B: Deref<Target = [u8]>,
 {
     /// Serialize this [APDUCommand] to be sent to the device
+    ///
+    /// Payloads over 255 bytes have their length truncated, producing a corrupt
+    /// command.
+    #[deprecated(note = "truncates payloads over 255 bytes, use `try_serialize`")]
     pub fn serialize(&self) -> std::vec::Vec<u8> {
         let mut v = std::vec![self.cla, self.ins, self.p1, self.p2, self.data.len() as u8];
         v.extend(self.data.iter());
         v
     }
+
+    /// Serialize this [APDUCommand] with a one-byte Lc, as Ledger devices expect
+    ///
+    /// Fails for payloads over [MAX_SHORT_DATA_LEN] bytes.
+    pub fn try_serialize(&self) -> Result<std::vec::Vec<u8>, APDUCommandError> {
+        let len = self.data.len();
+        ensure!(
+            len <= MAX_SHORT_DATA_LEN,
+            DataTooLongSnafu {
+                len,
+                max: MAX_SHORT_DATA_LEN
+            }
+        );
+
+        let mut v = std::vec![self.cla, self.ins, self.p1, self.p2, len as u8];
+        v.extend(self.data.iter());
+        Ok(v)
+    }
+
+    /// Serialize this [APDUCommand] with an ISO 7816 extended Lc: `0x00` then the
+    /// length on two bytes, big-endian
+    ///
+    /// Only for devices accepting extended-length commands; Ledger apps do not.
+    /// Fails for payloads over [MAX_EXTENDED_DATA_LEN] bytes.
+    pub fn serialize_extended(&self) -> Result<std::vec::Vec<u8>, APDUCommandError> {
+        let len = self.data.len();
+        ensure!(
+            len <= MAX_EXTENDED_DATA_LEN,
+            DataTooLongSnafu {
+                len,
+                max: MAX_EXTENDED_DATA_LEN
+            }
+        );
+
+        let mut v = std::vec![self.cla, self.ins, self.p1, self.p2, 0x00];
+        v.extend_from_slice(&(len as u16).to_be_bytes());
+        v.extend(self.data.iter());
+        Ok(v)
+    }
 }
 
 #[derive(Debug)]
@@ -88,6 +150,12 @@ where
     pub fn retcode(&self) -> u16 {
         self.retcode
     }
+
+    /// Returns the answer's payload together with the raw return code
+    #[inline(always)]
+    pub fn split(&self) -> (&[u8], u16) {
+        (self.apdu_data(), self.retcode)
+    }
 }
 
 #[derive(Copy, Clone, Debug, Snafu, PartialEq, Eq)]
@@ -163,3 +231,57 @@ impl TryFrom<u16> for APDUErrorCode {
         Ok(this)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split() {
+        let answer = APDUAnswer::from_answer(&[0xAA, 0xBB, 0x90, 0x00][..]).unwrap();
+        assert_eq!(answer.split(), (&[0xAA, 0xBB][..], 0x9000));
+
+        let answer = APDUAnswer::from_answer(&[0x6A, 0x80][..]).unwrap();
+        assert_eq!(answer.split(), (&[][..], 0x6A80));
+    }
+
+    fn command(len: usize) -> APDUCommand<std::vec::Vec<u8>> {
+        APDUCommand {
+            cla: 0xE0,
+            ins: 0x04,
+            p1: 0x00,
+            p2: 0x00,
+            data: std::vec![0xAB; len],
+        }
+    }
+
+    #[test]
+    fn test_try_serialize() {
+        let bytes = command(255).try_serialize().unwrap();
+        assert_eq!(&bytes[..5], &[0xE0, 0x04, 0x00, 0x00, 0xFF]);
+        assert_eq!(bytes.len(), 5 + 255);
+
+        assert_eq!(
+            command(256).try_serialize(),
+            Err(APDUCommandError::DataTooLong { len: 256, max: 255 })
+        );
+    }
+
+    #[test]
+    fn test_serialize_extended() {
+        let bytes = command(256).serialize_extended().unwrap();
+        assert_eq!(&bytes[..7], &[0xE0, 0x04, 0x00, 0x00, 0x00, 0x01, 0x00]);
+        assert_eq!(bytes.len(), 7 + 256);
+
+        let bytes = command(0).serialize_extended().unwrap();
+        assert_eq!(bytes, [0xE0, 0x04, 0x00, 0x00, 0x00, 0x00, 0x00]);
+
+        assert_eq!(
+            command(0x10000).serialize_extended(),
+            Err(APDUCommandError::DataTooLong {
+                len: 0x10000,
+                max: 0xFFFF
+            })
+        );
+    }
+}
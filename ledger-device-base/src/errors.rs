@@ -1,6 +1,139 @@
+use std::fmt;
+
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+/// APDU status word returned by the device
+///
+/// Common ISO 7816 / Ledger status words get their own variant, anything else
+/// is kept as [`StatusWord::Other`]. Apps with their own codes match on `Other`.
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq, Deserialize, Serialize)]
+pub enum StatusWord {
+    /// 0x9000: success
+    NoError,
+    /// 0x6400: error during command execution
+    ExecutionError,
+    /// 0x6700: APDU length is wrong
+    WrongLength,
+    /// 0x6982: security status not satisfied (device locked or request canceled)
+    SecurityStatusNotSatisfied,
+    /// 0x6983: data length is wrong for the command
+    WrongDataLength,
+    /// 0x6984: data is invalid
+    DataInvalid,
+    /// 0x6985: conditions of use not satisfied, the user rejected the request
+    DenyByUser,
+    /// 0x6986: command not allowed
+    CommandNotAllowed,
+    /// 0x6A80: data field is incorrect
+    InvalidData,
+    /// 0x6A84: not enough memory on the device
+    InsufficientMemory,
+    /// 0x6A88: referenced data not found
+    DataNotFound,
+    /// 0x6B00: P1 or P2 is incorrect
+    InvalidP1P2,
+    /// 0x6D00: instruction not supported
+    InsNotSupported,
+    /// 0x6E00: class not supported, usually the wrong app is open
+    ClaNotSupported,
+    /// 0x6F00: technical problem
+    TechnicalProblem,
+    /// 0x6F01: signature verification failed
+    SignVerifyError,
+    /// 0x5515: the device is locked, e.g. its screen locked mid-session
+    DeviceLocked,
+    /// Any other status word
+    Other(u16),
+}
+
+impl StatusWord {
+    /// Raw 2-byte status word
+    pub fn code(&self) -> u16 {
+        match self {
+            StatusWord::NoError => 0x9000,
+            StatusWord::ExecutionError => 0x6400,
+            StatusWord::WrongLength => 0x6700,
+            StatusWord::SecurityStatusNotSatisfied => 0x6982,
+            StatusWord::WrongDataLength => 0x6983,
+            StatusWord::DataInvalid => 0x6984,
+            StatusWord::DenyByUser => 0x6985,
+            StatusWord::CommandNotAllowed => 0x6986,
+            StatusWord::InvalidData => 0x6A80,
+            StatusWord::InsufficientMemory => 0x6A84,
+            StatusWord::DataNotFound => 0x6A88,
+            StatusWord::InvalidP1P2 => 0x6B00,
+            StatusWord::InsNotSupported => 0x6D00,
+            StatusWord::ClaNotSupported => 0x6E00,
+            StatusWord::TechnicalProblem => 0x6F00,
+            StatusWord::SignVerifyError => 0x6F01,
+            StatusWord::DeviceLocked => 0x5515,
+            StatusWord::Other(code) => *code,
+        }
+    }
+
+    /// Short human readable description
+    pub fn description(&self) -> &'static str {
+        match self {
+            StatusWord::NoError => "success",
+            StatusWord::ExecutionError => "execution error",
+            StatusWord::WrongLength => "wrong length",
+            StatusWord::SecurityStatusNotSatisfied => "security status not satisfied",
+            StatusWord::WrongDataLength => "wrong data length",
+            StatusWord::DataInvalid => "data invalid",
+            StatusWord::DenyByUser => "denied by user",
+            StatusWord::CommandNotAllowed => "command not allowed",
+            StatusWord::InvalidData => "invalid data",
+            StatusWord::InsufficientMemory => "insufficient memory",
+            StatusWord::DataNotFound => "data not found",
+            StatusWord::InvalidP1P2 => "invalid P1/P2",
+            StatusWord::InsNotSupported => "instruction not supported",
+            StatusWord::ClaNotSupported => "class not supported",
+            StatusWord::TechnicalProblem => "technical problem",
+            StatusWord::SignVerifyError => "signature verification error",
+            StatusWord::DeviceLocked => "device locked",
+            StatusWord::Other(_) => "unknown status",
+        }
+    }
+}
+
+impl From<u16> for StatusWord {
+    fn from(code: u16) -> Self {
+        match code {
+            0x9000 => StatusWord::NoError,
+            0x6400 => StatusWord::ExecutionError,
+            0x6700 => StatusWord::WrongLength,
+            0x6982 => StatusWord::SecurityStatusNotSatisfied,
+            0x6983 => StatusWord::WrongDataLength,
+            0x6984 => StatusWord::DataInvalid,
+            0x6985 => StatusWord::DenyByUser,
+            0x6986 => StatusWord::CommandNotAllowed,
+            0x6A80 => StatusWord::InvalidData,
+            0x6A84 => StatusWord::InsufficientMemory,
+            0x6A88 => StatusWord::DataNotFound,
+            0x6B00 => StatusWord::InvalidP1P2,
+            0x6D00 => StatusWord::InsNotSupported,
+            0x6E00 => StatusWord::ClaNotSupported,
+            0x6F00 => StatusWord::TechnicalProblem,
+            0x6F01 => StatusWord::SignVerifyError,
+            0x5515 => StatusWord::DeviceLocked,
+            other => StatusWord::Other(other),
+        }
+    }
+}
+
+impl From<StatusWord> for u16 {
+    fn from(status: StatusWord) -> Self {
+        status.code()
+    }
+}
+
+impl fmt::Display for StatusWord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x{:04X} ({})", self.code(), self.description())
+    }
+}
+
 /// App Error
 #[derive(Clone, Debug, Eq, Error, PartialEq, Deserialize, Serialize)]
 pub enum LedgerAppError<E: std::error::Error> {
@@ -40,13 +173,38 @@ pub enum LedgerAppError<E: std::error::Error> {
     /// Format ID error
     #[error("response format ID not recognized")]
     InvalidFormatID,
+    /// The response payload is shorter than its declared fields
+    #[error("response payload is truncated or malformed")]
+    MalformedResponse,
     /// HexEncode
     #[error("Couldn't encode string to HEX")]
     HexEncode,
-    /// Application specific error
-    #[error("App Error: | {0} {1}")]
-    AppSpecific(u16, String),
-    ///Unknown error has occurred
-    #[error("Unknown error: {0}")]
-    Unknown(u16),
+    /// The device answered with a status word other than 0x9000
+    ///
+    /// Replaces the former `AppSpecific(u16, String)` and `Unknown(u16)` variants:
+    /// match on the [`StatusWord`] variant, or on `StatusWord::Other(code)` for
+    /// app specific codes.
+    #[error("Device status {0}")]
+    Status(StatusWord),
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_status_word_round_trip() {
+        for code in [
+            0x9000, 0x6982, 0x6985, 0x6A80, 0x6D00, 0x6E00, 0x6F01, 0x5515, 0x6501,
+        ] {
+            assert_eq!(StatusWord::from(code).code(), code);
+        }
+        assert_eq!(StatusWord::from(0x6985), StatusWord::DenyByUser);
+        assert_eq!(StatusWord::from(0x5515), StatusWord::DeviceLocked);
+        assert_eq!(StatusWord::from(0x6501), StatusWord::Other(0x6501));
+        assert_eq!(
+            StatusWord::InsNotSupported.to_string(),
+            "0x6D00 (instruction not supported)"
+        );
+    }
 }
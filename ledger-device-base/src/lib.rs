@@ -1,9 +1,9 @@
 mod errors;
-use std::str;
+use std::{fmt, str};
 
 use async_trait::async_trait;
 pub use errors::*;
-use ledger_sdk_transport::{APDUAnswer, APDUCommand, APDUErrorCode, Exchange};
+use ledger_sdk_transport::{APDUAnswer, APDUCommand, Exchange};
 use serde::{Deserialize, Serialize};
 
 // Ledger generic (non app-specific) APDU constants
@@ -12,6 +12,12 @@ const CLA_APP_INFO: u8 = 0xb0;
 const INS_APP_INFO: u8 = 0x01;
 const CLA_DEVICE_INFO: u8 = 0xe0;
 const INS_DEVICE_INFO: u8 = 0x01;
+const CLA_OPEN_APP: u8 = 0xe0;
+const INS_OPEN_APP: u8 = 0xd8;
+const CLA_QUIT_APP: u8 = 0xb0;
+const INS_QUIT_APP: u8 = 0xa7;
+/// App name reported by `get_app_info` while the dashboard is open
+pub const DASHBOARD_APP_NAME: &str = "BOLOS";
 const USER_MESSAGE_CHUNK_SIZE: usize = 250;
 
 pub enum ChunkPayloadType {
@@ -41,6 +47,105 @@ pub struct Version {
     pub target_id: [u8; 4],
 }
 
+impl Version {
+    /// Target ID, all zeros when the app does not report it
+    pub fn target(&self) -> TargetId {
+        TargetId(self.target_id)
+    }
+
+    /// Device model the app runs on
+    pub fn model(&self) -> DeviceModel {
+        self.target().model()
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+#[serde(transparent)]
+/// BOLOS target ID, identifying the device model and its hardware revision
+pub struct TargetId(pub [u8; 4]);
+
+impl TargetId {
+    /// Target ID as the big-endian integer Ledger documents, e.g. `0x33000004`
+    pub fn to_u32(self) -> u32 {
+        u32::from_be_bytes(self.0)
+    }
+
+    /// Whether this is the bootloader (MCU) target rather than a firmware one
+    ///
+    /// Firmware targets have 0x3 in the top nibble.
+    pub fn is_bootloader(self) -> bool {
+        self.to_u32() & 0xF000_0000 != 0x3000_0000
+    }
+
+    /// Device model of a firmware target
+    ///
+    /// The low bytes only tell hardware and firmware revisions of a model apart,
+    /// e.g. `0x31100002` to `0x31100004` are all Nano S. Bootloader and unknown
+    /// targets map to [`DeviceModel::Unknown`].
+    pub fn model(self) -> DeviceModel {
+        match self.to_u32() & 0xFFFF_0000 {
+            0x3100_0000 | 0x3101_0000 => DeviceModel::Blue,
+            0x3110_0000 => DeviceModel::NanoS,
+            0x3300_0000 => DeviceModel::NanoX,
+            0x3310_0000 => DeviceModel::NanoSPlus,
+            0x3320_0000 => DeviceModel::Stax,
+            0x3330_0000 => DeviceModel::Flex,
+            _ => DeviceModel::Unknown,
+        }
+    }
+}
+
+impl From<[u8; 4]> for TargetId {
+    fn from(target_id: [u8; 4]) -> Self {
+        TargetId(target_id)
+    }
+}
+
+impl From<u32> for TargetId {
+    fn from(target_id: u32) -> Self {
+        TargetId(target_id.to_be_bytes())
+    }
+}
+
+impl fmt::Display for TargetId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:#010x}", self.to_u32())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, Deserialize, Serialize)]
+/// Ledger device model, from [`TargetId::model`] or a transport's USB product ID
+pub enum DeviceModel {
+    /// Ledger Blue
+    Blue,
+    /// Ledger Nano S
+    NanoS,
+    /// Ledger Nano S Plus
+    NanoSPlus,
+    /// Ledger Nano X
+    NanoX,
+    /// Ledger Stax
+    Stax,
+    /// Ledger Flex
+    Flex,
+    /// Bootloader target or model unknown to this crate
+    Unknown,
+}
+
+impl fmt::Display for DeviceModel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            DeviceModel::Blue => "Ledger Blue",
+            DeviceModel::NanoS => "Ledger Nano S",
+            DeviceModel::NanoSPlus => "Ledger Nano S Plus",
+            DeviceModel::NanoX => "Ledger Nano X",
+            DeviceModel::Stax => "Ledger Stax",
+            DeviceModel::Flex => "Ledger Flex",
+            DeviceModel::Unknown => "unknown device",
+        })
+    }
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 /// App Device Info
 pub struct DeviceInfo {
@@ -57,6 +162,53 @@ pub struct DeviceInfo {
     pub mcu_version: String,
 }
 
+impl DeviceInfo {
+    /// Target ID of the running firmware or bootloader
+    pub fn target(&self) -> TargetId {
+        TargetId(self.target_id)
+    }
+
+    /// Device model, [`DeviceModel::Unknown`] in the bootloader
+    pub fn model(&self) -> DeviceModel {
+        self.target().model()
+    }
+
+    /// Decode the BOLOS flags and the target ID into named booleans
+    pub fn flags(&self) -> DeviceFlags {
+        let flags = self.flag.first().copied().unwrap_or(0);
+        DeviceFlags {
+            is_bootloader: self.target().is_bootloader(),
+            recovery_mode: (flags & 1) != 0,
+            signed_mcu_code: (flags & 2) != 0,
+            onboarded: (flags & 4) != 0,
+            manager_allowed: (flags & 8) != 0,
+            pin_validated: (flags & 128) != 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize)]
+/// Device state decoded from [`DeviceInfo`]
+pub struct DeviceFlags {
+    /// Device is running its bootloader: app commands will fail
+    #[serde(rename(serialize = "isBootloader"))]
+    pub is_bootloader: bool,
+    /// Device booted in recovery mode
+    #[serde(rename(serialize = "recoveryMode"))]
+    pub recovery_mode: bool,
+    /// MCU code is signed
+    #[serde(rename(serialize = "signedMCUCode"))]
+    pub signed_mcu_code: bool,
+    /// Device has been set up with a seed
+    pub onboarded: bool,
+    /// Ledger Live may install and remove apps
+    #[serde(rename(serialize = "managerAllowed"))]
+    pub manager_allowed: bool,
+    /// PIN has been entered since the device was powered on
+    #[serde(rename(serialize = "pinValidated"))]
+    pub pin_validated: bool,
+}
+
 #[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
 /// App Information
 pub struct AppInfo {
@@ -86,6 +238,107 @@ pub struct AppInfo {
     pub flag_pin_validated: bool,
 }
 
+/// Split `len` bytes off the front of `data`, failing instead of panicking on short input
+fn take<'a, E: std::error::Error>(
+    data: &mut &'a [u8],
+    len: usize,
+) -> Result<&'a [u8], LedgerAppError<E>> {
+    if data.len() < len {
+        return Err(LedgerAppError::MalformedResponse);
+    }
+    let (head, tail) = data.split_at(len);
+    *data = tail;
+    Ok(head)
+}
+
+/// Split a one-byte length prefix and the bytes it announces off the front of `data`
+fn take_lv<'a, E: std::error::Error>(data: &mut &'a [u8]) -> Result<&'a [u8], LedgerAppError<E>> {
+    let len = take(data, 1)?[0] as usize;
+    take(data, len)
+}
+
+/// Parse the BOLOS device info payload: target_id, then length-prefixed SE version,
+/// flags and MCU version.
+fn parse_device_info<E: std::error::Error>(
+    mut data: &[u8],
+) -> Result<DeviceInfo, LedgerAppError<E>> {
+    // First 4 bytes: target_id
+    let mut target_id = [0u8; 4];
+    target_id.copy_from_slice(take(&mut data, 4)?);
+
+    let se_version_bytes = take_lv(&mut data)?;
+    let flag = take_lv(&mut data)?;
+
+    // MCU version: strip trailing NUL if present
+    let mut mcu_version_bytes = take_lv(&mut data)?;
+    if let [rest @ .., 0] = mcu_version_bytes {
+        mcu_version_bytes = rest;
+    }
+
+    // Convert string slices, map UTF-8 errors to domain error
+    let se_version = str::from_utf8(se_version_bytes).map_err(|_e| LedgerAppError::Utf8)?;
+    let mcu_version = str::from_utf8(mcu_version_bytes).map_err(|_e| LedgerAppError::Utf8)?;
+
+    Ok(DeviceInfo {
+        target_id,
+        se_version: se_version.to_string(),
+        flag: flag.to_vec(),
+        mcu_version: mcu_version.to_string(),
+    })
+}
+
+/// Parse the BOLOS app info payload: format id (1), then length-prefixed app name,
+/// app version and flags.
+pub fn parse_app_info<E: std::error::Error>(mut data: &[u8]) -> Result<AppInfo, LedgerAppError<E>> {
+    if take(&mut data, 1)?[0] != 1 {
+        return Err(LedgerAppError::InvalidFormatID);
+    }
+
+    let app_name_bytes = take_lv(&mut data)?;
+    let app_version_bytes = take_lv(&mut data)?;
+    let flags = take_lv(&mut data)?;
+    let flags_value = flags.first().copied().unwrap_or(0);
+
+    let app_name = str::from_utf8(app_name_bytes).map_err(|_e| LedgerAppError::Utf8)?;
+    let app_version = str::from_utf8(app_version_bytes).map_err(|_e| LedgerAppError::Utf8)?;
+
+    Ok(AppInfo {
+        app_name: app_name.to_string(),
+        app_version: app_version.to_string(),
+        flag_len: flags.len() as u8,
+        flags_value,
+        flag_recovery: (flags_value & 1) != 0,
+        flag_signed_mcu_code: (flags_value & 2) != 0,
+        flag_onboarded: (flags_value & 4) != 0,
+        flag_pin_validated: (flags_value & 128) != 0,
+    })
+}
+
+/// 0x9000 with its bytes swapped
+const REVERSED_NO_ERROR: u16 = 0x0090;
+
+/// Ok on 0x9000, otherwise the status word as `LedgerAppError::Status`
+///
+/// Devices always send the status word big-endian. 0x0090 is no status a device
+/// returns, only 0x9000 reversed by a misbehaving transport or emulator, so it is
+/// accepted with a warning rather than failing the command.
+fn check_status<A, E>(response: &APDUAnswer<A>) -> Result<(), LedgerAppError<E>>
+where
+    A: std::ops::Deref<Target = [u8]>,
+    E: std::error::Error,
+{
+    match StatusWord::from(response.retcode()) {
+        StatusWord::NoError => Ok(()),
+        StatusWord::Other(REVERSED_NO_ERROR) => {
+            tracing::warn!(
+                "status word 0x0090 is 0x9000 with its bytes swapped; check the transport's byte order"
+            );
+            Ok(())
+        }
+        status => Err(LedgerAppError::Status(status)),
+    }
+}
+
 /// Defines what we can consider an "App"
 pub trait App {
     /// App's APDU CLA
@@ -98,33 +351,22 @@ where
     E: Exchange + Send + Sync,
     E::Error: std::error::Error,
 {
-    /// Check APDU status word. Ok on 0x9000, otherwise map to SDK errors.
-    // Normalize common APDU status handling: Ok on 0x9000, map others to AppSpecific/Unknown
+    /// Check APDU status word. Ok on 0x9000, otherwise `LedgerAppError::Status`.
     fn handle_response_error(
         response: &APDUAnswer<E::AnswerType>,
     ) -> Result<(), LedgerAppError<E::Error>> {
-        match response.error_code() {
-            Ok(APDUErrorCode::NoError) => Ok(()),
-            Ok(err) => Err(LedgerAppError::AppSpecific(err as _, err.description())),
-            Err(err) => Err(LedgerAppError::Unknown(err)),
-        }
+        check_status(response)
     }
 
     /// Same as `handle_response_error`, but also requires non-empty payload (signature).
     fn handle_response_error_signature(
         response: &APDUAnswer<E::AnswerType>,
     ) -> Result<(), LedgerAppError<E::Error>> {
-        match response.error_code() {
-            Ok(APDUErrorCode::NoError) if response.data().is_empty() => {
-                Err(LedgerAppError::NoSignature)
-            }
-            Ok(APDUErrorCode::NoError) => Ok(()),
-            Ok(err) => Err(LedgerAppError::AppSpecific(err as _, err.description())),
-            Err(err) => Err(LedgerAppError::AppSpecific(
-                err,
-                "[APDU_ERROR] Unknown".to_string(),
-            )),
+        check_status(response)?;
+        if response.data().is_empty() {
+            return Err(LedgerAppError::NoSignature);
         }
+        Ok(())
     }
 
     /// Query device info (target_id, SE/MCU versions, flags) via BOLOS CLA/INS.
@@ -140,57 +382,9 @@ where
 
         // Send APDU and ensure status is success
         let response = transport.exchange(&command).await?;
-        match response.error_code() {
-            Ok(APDUErrorCode::NoError) => {}
-            Ok(err) => return Err(LedgerAppError::Unknown(err as _)),
-            Err(err) => return Err(LedgerAppError::Unknown(err)),
-        }
+        check_status(&response)?;
 
-        // Start parsing the BOLOS device info payload
-        let response_data = response.data();
-
-        // First 4 bytes: target_id
-        let target_id_slice = &response_data[0..4];
-        let mut idx = 4;
-
-        // Next: SE version (len + bytes)
-        let se_version_len: usize = response_data[idx] as usize;
-        idx += 1;
-        let se_version_bytes = &response_data[idx..(idx + se_version_len)];
-
-        idx += se_version_len;
-
-        // Flags: len + bytes
-        let flags_len: usize = response_data[idx] as usize;
-        idx += 1;
-        let flag = &response_data[idx..idx + flags_len];
-        idx += flags_len;
-
-        // MCU version: len + bytes (strip trailing NUL if present)
-        let mcu_version_len: usize = response_data[idx] as usize;
-        idx += 1;
-        let mut tmp = &response_data[idx..idx + mcu_version_len];
-        if tmp[mcu_version_len - 1] == 0 {
-            tmp = &response_data[idx..idx + mcu_version_len - 1];
-        }
-
-        // Copy target_id to fixed-size array
-        let mut target_id = [Default::default(); 4];
-        target_id.copy_from_slice(target_id_slice);
-
-        // Convert string slices, map UTF-8 errors to domain error
-        let se_version = str::from_utf8(se_version_bytes).map_err(|_e| LedgerAppError::Utf8)?;
-        let mcu_version = str::from_utf8(tmp).map_err(|_e| LedgerAppError::Utf8)?;
-
-        // Assemble strongly-typed device info
-        let device_info = DeviceInfo {
-            target_id,
-            se_version: se_version.to_string(),
-            flag: flag.to_vec(),
-            mcu_version: mcu_version.to_string(),
-        };
-
-        Ok(device_info)
+        parse_device_info(response.data())
     }
 
     /// Query current app info (name, version, flags) from the device.
@@ -204,50 +398,50 @@ where
         };
 
         let response = transport.exchange(&command).await?;
-        match response.error_code() {
-            Ok(APDUErrorCode::NoError) => {}
-            Ok(err) => return Err(LedgerAppError::AppSpecific(err as _, err.description())),
-            Err(err) => return Err(LedgerAppError::Unknown(err as _)),
-        }
+        check_status(&response)?;
 
-        let response_data = response.data();
-
-        if response_data[0] != 1 {
-            return Err(LedgerAppError::InvalidFormatID);
-        }
-
-        let app_name_len: usize = response_data[1] as usize;
-        let app_name_bytes = &response_data[2..app_name_len];
-
-        let mut idx = 2 + app_name_len;
-        let app_version_len: usize = response_data[idx] as usize;
-        idx += 1;
-        let app_version_bytes = &response_data[idx..idx + app_version_len];
-
-        idx += app_version_len;
+        parse_app_info(response.data())
+    }
 
-        let app_flags_len = response_data[idx];
-        idx += 1;
-        let flags_value = response_data[idx];
+    /// Ask the dashboard to open the app called `name`.
+    ///
+    /// Only the dashboard answers this command. Once the app starts the device
+    /// re-enumerates, so the answer may be lost and the transport must be reopened
+    /// before talking to the app.
+    async fn open_app(transport: &E, name: &str) -> Result<(), LedgerAppError<E::Error>> {
+        let command = APDUCommand {
+            cla: CLA_OPEN_APP,
+            ins: INS_OPEN_APP,
+            p1: 0x00,
+            p2: 0x00,
+            data: name.as_bytes().to_vec(),
+        };
 
-        let app_name = str::from_utf8(app_name_bytes).map_err(|_e| LedgerAppError::Utf8)?;
-        let app_version = str::from_utf8(app_version_bytes).map_err(|_e| LedgerAppError::Utf8)?;
+        let response = transport.exchange(&command).await?;
+        check_status(&response)
+    }
 
-        let app_info = AppInfo {
-            app_name: app_name.to_string(),
-            app_version: app_version.to_string(),
-            flag_len: app_flags_len,
-            flags_value,
-            flag_recovery: (flags_value & 1) != 0,
-            flag_signed_mcu_code: (flags_value & 2) != 0,
-            flag_onboarded: (flags_value & 4) != 0,
-            flag_pin_validated: (flags_value & 128) != 0,
+    /// Quit the current app back to the dashboard.
+    ///
+    /// Like [`AppExt::open_app`], the device re-enumerates afterwards.
+    async fn quit_app(transport: &E) -> Result<(), LedgerAppError<E::Error>> {
+        let command = APDUCommand {
+            cla: CLA_QUIT_APP,
+            ins: INS_QUIT_APP,
+            p1: 0x00,
+            p2: 0x00,
+            data: Vec::new(),
         };
 
-        Ok(app_info)
+        let response = transport.exchange(&command).await?;
+        check_status(&response)
     }
 
     /// Query application version using the implementing app's CLA.
+    ///
+    /// Not every app answers INS 0x00 with its own version: on some targets it
+    /// returns the BOLOS version instead. Apps with a dedicated version command
+    /// (e.g. the Ethereum app's GET APP CONFIGURATION) should prefer it.
     async fn get_version(transport: &E) -> Result<Version, LedgerAppError<E::Error>> {
         let command = APDUCommand {
             cla: Self::CLA,
@@ -258,11 +452,7 @@ where
         };
 
         let response = transport.exchange(&command).await?;
-        match response.error_code() {
-            Ok(APDUErrorCode::NoError) => {}
-            Ok(err) => return Err(LedgerAppError::Unknown(err as _)),
-            Err(err) => return Err(LedgerAppError::Unknown(err)),
-        }
+        check_status(&response)?;
 
         let response_data = response.data();
 
@@ -369,3 +559,289 @@ where
     E::Error: std::error::Error,
 {
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type TestError = std::io::Error;
+
+    /// Nano S Plus style payload: target id, "1.1.1", 4 flag bytes, "5.24" + NUL
+    fn sample_payload() -> Vec<u8> {
+        let mut data = vec![0x33, 0x10, 0x00, 0x04];
+        data.push(5);
+        data.extend_from_slice(b"1.1.1");
+        data.push(4);
+        data.extend_from_slice(&[0xAA, 0x00, 0x00, 0x00]);
+        data.push(5);
+        data.extend_from_slice(b"5.24\0");
+        data
+    }
+
+    #[test]
+    fn test_parse_device_info() {
+        let info = parse_device_info::<TestError>(&sample_payload()).unwrap();
+        assert_eq!(info.target_id, [0x33, 0x10, 0x00, 0x04]);
+        assert_eq!(info.se_version, "1.1.1");
+        assert_eq!(info.flag, vec![0xAA, 0x00, 0x00, 0x00]);
+        assert_eq!(info.mcu_version, "5.24");
+    }
+
+    #[test]
+    fn test_device_flags() {
+        let info = parse_device_info::<TestError>(&sample_payload()).unwrap();
+        assert_eq!(
+            info.flags(),
+            DeviceFlags {
+                is_bootloader: false,
+                recovery_mode: false,
+                signed_mcu_code: true,
+                onboarded: false,
+                manager_allowed: true,
+                pin_validated: true,
+            }
+        );
+
+        let mut info = info;
+        info.flag = vec![0x05, 0x00, 0x00, 0x00];
+        let flags = info.flags();
+        assert!(flags.recovery_mode && flags.onboarded);
+        assert!(!flags.pin_validated && !flags.manager_allowed);
+
+        info.flag.clear();
+        assert_eq!(info.flags(), DeviceFlags::default());
+    }
+
+    #[test]
+    fn test_device_flags_bootloader() {
+        let mut info = parse_device_info::<TestError>(&sample_payload()).unwrap();
+        // Bootloader target IDs lack the 0x3 firmware nibble
+        info.target_id = [0x01, 0x10, 0x00, 0x04];
+        assert!(info.flags().is_bootloader);
+    }
+
+    #[test]
+    fn test_target_id_models() {
+        let known = [
+            (0x3100_0002, DeviceModel::Blue),
+            (0x3101_0004, DeviceModel::Blue),
+            (0x3110_0002, DeviceModel::NanoS),
+            (0x3110_0003, DeviceModel::NanoS),
+            (0x3110_0004, DeviceModel::NanoS),
+            (0x3300_0004, DeviceModel::NanoX),
+            (0x3310_0004, DeviceModel::NanoSPlus),
+            (0x3320_0004, DeviceModel::Stax),
+            (0x3330_0004, DeviceModel::Flex),
+            (0x33F0_0004, DeviceModel::Unknown),
+            (0x0100_0001, DeviceModel::Unknown),
+            (0x0000_0000, DeviceModel::Unknown),
+        ];
+        for (target_id, model) in known {
+            assert_eq!(
+                TargetId::from(target_id).model(),
+                model,
+                "{:#010x}",
+                target_id
+            );
+        }
+
+        let target = TargetId([0x33, 0x00, 0x00, 0x04]);
+        assert_eq!(target.to_string(), "0x33000004");
+        assert_eq!(target.model().to_string(), "Ledger Nano X");
+        assert_eq!(TargetId::from(0x3300_0004), target);
+        assert!(!target.is_bootloader());
+        assert!(TargetId::from(0x0100_0001).is_bootloader());
+    }
+
+    #[test]
+    fn test_target_accessors() {
+        let info = parse_device_info::<TestError>(&sample_payload()).unwrap();
+        assert_eq!(info.target(), TargetId::from(0x3310_0004));
+        assert_eq!(info.model(), DeviceModel::NanoSPlus);
+
+        let version = Version {
+            mode: 0,
+            major: 1,
+            minor: 0,
+            patch: 0,
+            locked: false,
+            target_id: [0, 0, 0, 0],
+        };
+        assert_eq!(version.model(), DeviceModel::Unknown);
+    }
+
+    #[test]
+    fn test_parse_device_info_empty_mcu_version() {
+        let mut data = sample_payload();
+        let mcu_len_idx = data.len() - 6;
+        data.truncate(mcu_len_idx);
+        data.push(0);
+
+        let info = parse_device_info::<TestError>(&data).unwrap();
+        assert_eq!(info.mcu_version, "");
+    }
+
+    #[test]
+    fn test_parse_device_info_truncated() {
+        let data = sample_payload();
+        for len in 0..data.len() {
+            assert!(
+                matches!(
+                    parse_device_info::<TestError>(&data[..len]),
+                    Err(LedgerAppError::MalformedResponse)
+                ),
+                "accepted payload truncated to {} bytes",
+                len
+            );
+        }
+    }
+
+    #[test]
+    fn test_parse_device_info_corrupt_lengths() {
+        // SE version length pointing past the end of the payload
+        let mut data = sample_payload();
+        data[4] = 0xFF;
+        assert!(matches!(
+            parse_device_info::<TestError>(&data),
+            Err(LedgerAppError::MalformedResponse)
+        ));
+
+        // Invalid UTF-8 in SE version
+        let mut data = sample_payload();
+        data[5] = 0xFF;
+        assert!(matches!(
+            parse_device_info::<TestError>(&data),
+            Err(LedgerAppError::Utf8)
+        ));
+    }
+
+    /// App info payload as returned by the dashboard-level GET APP INFO
+    fn app_info_payload(name: &str, version: &str, flags: u8) -> Vec<u8> {
+        let mut data = vec![0x01, name.len() as u8];
+        data.extend_from_slice(name.as_bytes());
+        data.push(version.len() as u8);
+        data.extend_from_slice(version.as_bytes());
+        data.extend_from_slice(&[0x01, flags]);
+        data
+    }
+
+    #[test]
+    fn test_parse_app_info() {
+        let data = [
+            0x01, 0x08, b'E', b't', b'h', b'e', b'r', b'e', b'u', b'm', 0x06, b'1', b'.', b'1',
+            b'0', b'.', b'3', 0x01, 0x02,
+        ];
+        let info = parse_app_info::<TestError>(&data).unwrap();
+        assert_eq!(info.app_name, "Ethereum");
+        assert_eq!(info.app_version, "1.10.3");
+        assert_eq!(info.flag_len, 1);
+        assert_eq!(info.flags_value, 0x02);
+        assert!(info.flag_signed_mcu_code);
+        assert!(!info.flag_recovery);
+
+        // Longer name shifting every following field
+        let info = parse_app_info::<TestError>(&app_info_payload("Bitcoin Legacy", "2.1.3", 0x82))
+            .unwrap();
+        assert_eq!(info.app_name, "Bitcoin Legacy");
+        assert_eq!(info.app_version, "2.1.3");
+        assert_eq!(info.flags_value, 0x82);
+        assert!(info.flag_pin_validated);
+    }
+
+    #[test]
+    fn test_parse_app_info_malformed() {
+        let data = app_info_payload("Bitcoin Legacy", "2.1.3", 0x02);
+        for len in 0..data.len() {
+            assert!(
+                matches!(
+                    parse_app_info::<TestError>(&data[..len]),
+                    Err(LedgerAppError::MalformedResponse)
+                ),
+                "accepted payload truncated to {} bytes",
+                len
+            );
+        }
+
+        let mut bad_format = data.clone();
+        bad_format[0] = 0x02;
+        assert!(matches!(
+            parse_app_info::<TestError>(&bad_format),
+            Err(LedgerAppError::InvalidFormatID)
+        ));
+
+        let mut bad_name = data;
+        bad_name[2] = 0xFF;
+        assert!(matches!(
+            parse_app_info::<TestError>(&bad_name),
+            Err(LedgerAppError::Utf8)
+        ));
+    }
+
+    #[test]
+    fn test_parse_device_info_random_payloads_never_panic() {
+        // Deterministic xorshift corpus mixing random bytes and mutated valid payloads
+        let mut state = 0x9E37_79B9_7F4A_7C15u64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        let sample = sample_payload();
+        for _ in 0..2_000 {
+            let data: Vec<u8> = if next() % 2 == 0 {
+                let len = (next() % 64) as usize;
+                (0..len).map(|_| next() as u8).collect()
+            } else {
+                let mut data = sample.clone();
+                let idx = (next() as usize) % data.len();
+                data[idx] = next() as u8;
+                data.truncate((next() as usize) % (data.len() + 1));
+                data
+            };
+
+            let _ = parse_device_info::<TestError>(&data);
+        }
+    }
+
+    #[test]
+    fn test_reversed_status_word_warns() {
+        use std::sync::{Arc, Mutex};
+
+        #[derive(Clone, Default)]
+        struct Capture(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Capture {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let capture = Capture::default();
+        let writer = capture.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let reversed = APDUAnswer::from_answer(vec![0xAA, 0x00, 0x90]).unwrap();
+        assert!(check_status::<_, TestError>(&reversed).is_ok());
+        let logs = String::from_utf8(capture.0.lock().unwrap().clone()).unwrap();
+        assert!(logs.contains("WARN"), "{}", logs);
+        assert!(logs.contains("bytes swapped"), "{}", logs);
+
+        // Genuine errors are unaffected
+        let denied = APDUAnswer::from_answer(vec![0x69, 0x85]).unwrap();
+        assert!(matches!(
+            check_status::<_, TestError>(&denied),
+            Err(LedgerAppError::Status(StatusWord::DenyByUser))
+        ));
+    }
+}
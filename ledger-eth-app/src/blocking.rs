@@ -0,0 +1,457 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Blocking wrapper around [`EthereumApp`]
+//!
+//! Available with the `blocking` feature. Each call is driven to completion on a
+//! single-threaded Tokio runtime owned by the wrapper, so callers don't need a
+//! runtime of their own. Don't call these methods from inside an async context:
+//! Tokio panics when a runtime is blocked on from within another one.
+
+use std::{
+    ops::{Deref, Range},
+    time::Duration,
+};
+
+use ledger_sdk_device_base::Version;
+use ledger_sdk_transport::{APDUAnswer, APDUCommand, Exchange};
+use tokio::io::AsyncRead;
+use tokio::runtime::{Builder, Runtime};
+
+use crate::commands::sign_transaction::TransactionMode;
+use crate::instructions::length;
+use crate::{
+    AppConfiguration, AppVersion, BipPath, CompatibilityReport, Eip712FilterBundle,
+    Eip712FilterConfig, Eip712FilterParams, Eip712StructDefinition, Eip712StructImplementation,
+    Eip712TypedData, Eip7702Authorization, Eip7702Signature, EthAppResult, EthereumApp,
+    ExtendedPublicKey, GetAddressParams, NetworkDescriptor, NftCollectionInfo, PathPolicy,
+    PluginDescriptor, PrivacyOperationParams, PublicKeyInfo, SafeAccountInfo, SignEip712Params,
+    SignMessageParams, SignMessageStreamParams, SignTransactionParams, Signature,
+    TxSimulationResult, UnsignedTransaction,
+};
+
+/// Synchronous Ethereum application client
+///
+/// Exposes the methods of [`EthereumApp`] with the same arguments, minus `async`.
+#[derive(Debug)]
+pub struct BlockingEthereumApp<E: Exchange> {
+    app: EthereumApp<E>,
+    runtime: Runtime,
+}
+
+impl<E: Exchange> BlockingEthereumApp<E> {
+    /// Create a new blocking client
+    ///
+    /// Fails if the runtime cannot be created.
+    pub fn new(transport: E) -> std::io::Result<Self> {
+        Self::from_app(EthereumApp::new(transport))
+    }
+
+    /// Wrap an already configured [`EthereumApp`]
+    pub fn from_app(app: EthereumApp<E>) -> std::io::Result<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(Self { app, runtime })
+    }
+
+    /// Get a reference to the wrapped async client
+    pub fn app(&self) -> &EthereumApp<E> {
+        &self.app
+    }
+
+    /// Get a reference to the underlying transport
+    pub fn transport(&self) -> &E {
+        self.app.transport()
+    }
+
+    /// Return the wrapped async client
+    pub fn into_inner(self) -> EthereumApp<E> {
+        self.app
+    }
+}
+
+impl<E> BlockingEthereumApp<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    /// See [`EthereumApp::raw_exchange`]
+    pub fn raw_exchange<I>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> EthAppResult<APDUAnswer<E::AnswerType>, E::Error>
+    where
+        I: Deref<Target = [u8]> + Send + Sync,
+    {
+        self.runtime.block_on(self.app.raw_exchange(command))
+    }
+
+    /// See [`EthereumApp::exchange_raw`]
+    pub fn exchange_raw(
+        &self,
+        cla: u8,
+        ins: u8,
+        p1: u8,
+        p2: u8,
+        data: &[u8],
+    ) -> EthAppResult<Vec<u8>, E::Error> {
+        self.runtime
+            .block_on(self.app.exchange_raw(cla, ins, p1, p2, data))
+    }
+
+    /// See [`EthereumApp::exchange_raw_hex`]
+    pub fn exchange_raw_hex(&self, apdu: &str) -> EthAppResult<Vec<u8>, E::Error> {
+        self.runtime.block_on(self.app.exchange_raw_hex(apdu))
+    }
+
+    /// See [`EthereumApp::ensure_app_open`]
+    pub fn ensure_app_open(&self, open: bool, timeout: Duration) -> EthAppResult<(), E::Error> {
+        self.runtime
+            .block_on(self.app.ensure_app_open(open, timeout))
+    }
+
+    /// See [`EthereumApp::wait_until_ready`]
+    pub fn wait_until_ready(&self, timeout: Duration) -> EthAppResult<(), E::Error> {
+        self.runtime.block_on(self.app.wait_until_ready(timeout))
+    }
+
+    /// See [`EthereumApp::get_address`]
+    pub fn get_address(&self, params: GetAddressParams) -> EthAppResult<PublicKeyInfo, E::Error> {
+        self.runtime.block_on(self.app.get_address(params))
+    }
+
+    /// See [`EthereumApp::get_account_xpub`]
+    pub fn get_account_xpub(&self, account: u32) -> EthAppResult<ExtendedPublicKey, E::Error> {
+        self.runtime.block_on(self.app.get_account_xpub(account))
+    }
+
+    /// See [`EthereumApp::get_address_range`]
+    pub fn get_address_range(
+        &self,
+        base: BipPath,
+        range: Range<u32>,
+        display: bool,
+    ) -> EthAppResult<Vec<(u32, PublicKeyInfo)>, E::Error> {
+        self.runtime
+            .block_on(self.app.get_address_range(base, range, display))
+    }
+
+    /// See [`EthereumApp::get_addresses`]
+    pub fn get_addresses<P>(
+        &self,
+        paths: P,
+        options: GetAddressParams,
+    ) -> EthAppResult<Vec<PublicKeyInfo>, E::Error>
+    where
+        P: IntoIterator<Item = BipPath>,
+    {
+        self.runtime
+            .block_on(self.app.get_addresses(paths, options))
+    }
+
+    /// See [`EthereumApp::get_configuration`]
+    pub fn get_configuration(&self) -> EthAppResult<AppConfiguration, E::Error> {
+        self.runtime.block_on(self.app.get_configuration())
+    }
+
+    /// See [`EthereumApp::refresh_configuration`]
+    pub fn refresh_configuration(&self) -> EthAppResult<AppConfiguration, E::Error> {
+        self.runtime.block_on(self.app.refresh_configuration())
+    }
+
+    /// See [`EthereumApp::app_configuration`]
+    pub fn app_configuration(&self) -> EthAppResult<AppConfiguration, E::Error> {
+        self.runtime.block_on(self.app.app_configuration())
+    }
+
+    /// See [`EthereumApp::app_version`]
+    pub fn app_version(&self) -> EthAppResult<AppVersion, E::Error> {
+        self.runtime.block_on(self.app.app_version())
+    }
+
+    /// See [`EthereumApp::bolos_version`]
+    pub fn bolos_version(&self) -> EthAppResult<Version, E::Error> {
+        self.runtime.block_on(self.app.bolos_version())
+    }
+
+    /// See [`EthereumApp::check_compatibility`]
+    pub fn check_compatibility(&self) -> EthAppResult<CompatibilityReport, E::Error> {
+        self.runtime.block_on(self.app.check_compatibility())
+    }
+
+    /// See [`EthereumApp::sign_personal_message`]
+    pub fn sign_personal_message(
+        &self,
+        params: SignMessageParams,
+    ) -> EthAppResult<Signature, E::Error> {
+        self.runtime
+            .block_on(self.app.sign_personal_message(params))
+    }
+
+    /// See [`EthereumApp::sign_personal_message_streaming`]
+    pub fn sign_personal_message_streaming<R>(
+        &self,
+        params: SignMessageStreamParams,
+        reader: R,
+    ) -> EthAppResult<Signature, E::Error>
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        self.runtime
+            .block_on(self.app.sign_personal_message_streaming(params, reader))
+    }
+
+    /// See [`EthereumApp::sign_transaction`]
+    pub fn sign_transaction(
+        &self,
+        params: SignTransactionParams,
+    ) -> EthAppResult<Signature, E::Error> {
+        self.runtime.block_on(self.app.sign_transaction(params))
+    }
+
+    /// See [`EthereumApp::sign_transaction_with_mode`]
+    pub fn sign_transaction_with_mode(
+        &self,
+        params: SignTransactionParams,
+        mode: TransactionMode,
+    ) -> EthAppResult<Option<Signature>, E::Error> {
+        self.runtime
+            .block_on(self.app.sign_transaction_with_mode(params, mode))
+    }
+
+    /// See [`EthereumApp::sign_authorization`]
+    pub fn sign_authorization(
+        &self,
+        path: &BipPath,
+        authorization: &Eip7702Authorization,
+        path_policy: PathPolicy,
+    ) -> EthAppResult<Eip7702Signature, E::Error> {
+        self.runtime.block_on(
+            self.app
+                .sign_authorization(path, authorization, path_policy),
+        )
+    }
+
+    /// See [`EthereumApp::get_eth2_public_key`]
+    pub fn get_eth2_public_key(
+        &self,
+        path: &BipPath,
+        display: bool,
+    ) -> EthAppResult<[u8; length::ETH2_PUBLIC_KEY_SIZE], E::Error> {
+        self.runtime
+            .block_on(self.app.get_eth2_public_key(path, display))
+    }
+
+    /// See [`EthereumApp::set_eth2_withdrawal_index`]
+    pub fn set_eth2_withdrawal_index(&self, index: u32) -> EthAppResult<(), E::Error> {
+        self.runtime
+            .block_on(self.app.set_eth2_withdrawal_index(index))
+    }
+
+    /// See [`EthereumApp::perform_privacy_operation`]
+    pub fn perform_privacy_operation(
+        &self,
+        params: PrivacyOperationParams,
+    ) -> EthAppResult<Vec<u8>, E::Error> {
+        self.runtime
+            .block_on(self.app.perform_privacy_operation(params))
+    }
+
+    /// See [`EthereumApp::set_plugin`]
+    pub fn set_plugin(&self, descriptor: &PluginDescriptor) -> EthAppResult<(), E::Error> {
+        self.runtime.block_on(self.app.set_plugin(descriptor))
+    }
+
+    /// See [`EthereumApp::set_external_plugin`]
+    pub fn set_external_plugin(&self, descriptor: &PluginDescriptor) -> EthAppResult<(), E::Error> {
+        self.runtime
+            .block_on(self.app.set_external_plugin(descriptor))
+    }
+
+    /// See [`EthereumApp::sign_typed_transaction`]
+    pub fn sign_typed_transaction<T: UnsignedTransaction>(
+        &self,
+        path: &BipPath,
+        transaction: &T,
+    ) -> EthAppResult<Signature, E::Error> {
+        self.runtime
+            .block_on(self.app.sign_typed_transaction(path, transaction))
+    }
+
+    /// See [`EthereumApp::provide_network_information`]
+    pub fn provide_network_information(
+        &self,
+        descriptor: &NetworkDescriptor,
+    ) -> EthAppResult<(), E::Error> {
+        self.runtime
+            .block_on(self.app.provide_network_information(descriptor))
+    }
+
+    /// See [`EthereumApp::provide_nft_information`]
+    pub fn provide_nft_information(&self, info: &NftCollectionInfo) -> EthAppResult<(), E::Error> {
+        self.runtime
+            .block_on(self.app.provide_nft_information(info))
+    }
+
+    /// See [`EthereumApp::provide_safe_account`]
+    pub fn provide_safe_account(&self, info: &SafeAccountInfo) -> EthAppResult<(), E::Error> {
+        self.runtime.block_on(self.app.provide_safe_account(info))
+    }
+
+    /// See [`EthereumApp::transaction_check_enabled`]
+    pub fn transaction_check_enabled(&self) -> EthAppResult<bool, E::Error> {
+        self.runtime.block_on(self.app.transaction_check_enabled())
+    }
+
+    /// See [`EthereumApp::provide_tx_simulation`]
+    pub fn provide_tx_simulation(
+        &self,
+        simulation: &TxSimulationResult,
+    ) -> EthAppResult<(), E::Error> {
+        self.runtime
+            .block_on(self.app.provide_tx_simulation(simulation))
+    }
+
+    /// See [`EthereumApp::sign_eip712_v0`]
+    pub fn sign_eip712_v0(&self, params: SignEip712Params) -> EthAppResult<Signature, E::Error> {
+        self.runtime.block_on(self.app.sign_eip712_v0(params))
+    }
+
+    /// See [`EthereumApp::sign_eip712_full`]
+    pub fn sign_eip712_full(
+        &self,
+        path: &BipPath,
+        path_policy: PathPolicy,
+    ) -> EthAppResult<Signature, E::Error> {
+        self.runtime
+            .block_on(self.app.sign_eip712_full(path, path_policy))
+    }
+
+    /// See [`EthereumApp::send_struct_definition`]
+    pub fn send_struct_definition(
+        &self,
+        struct_def: &Eip712StructDefinition,
+    ) -> EthAppResult<(), E::Error> {
+        self.runtime
+            .block_on(self.app.send_struct_definition(struct_def))
+    }
+
+    /// See [`EthereumApp::send_struct_implementation`]
+    pub fn send_struct_implementation(
+        &self,
+        struct_impl: &Eip712StructImplementation,
+    ) -> EthAppResult<(), E::Error> {
+        self.runtime
+            .block_on(self.app.send_struct_implementation(struct_impl))
+    }
+
+    /// See [`EthereumApp::set_array_size`]
+    pub fn set_array_size(&self, size: usize) -> EthAppResult<(), E::Error> {
+        self.runtime.block_on(self.app.set_array_size(size))
+    }
+
+    /// See [`EthereumApp::send_filter_config`]
+    pub fn send_filter_config(
+        &self,
+        filter_params: &Eip712FilterParams,
+    ) -> EthAppResult<(), E::Error> {
+        self.runtime
+            .block_on(self.app.send_filter_config(filter_params))
+    }
+
+    /// See [`EthereumApp::activate_filtering`]
+    pub fn activate_filtering(&self) -> EthAppResult<(), E::Error> {
+        self.runtime.block_on(self.app.activate_filtering())
+    }
+
+    /// See [`EthereumApp::sign_eip712_typed_data`]
+    pub fn sign_eip712_typed_data(
+        &self,
+        path: &BipPath,
+        typed_data: &Eip712TypedData,
+    ) -> EthAppResult<Signature, E::Error> {
+        self.runtime
+            .block_on(self.app.sign_eip712_typed_data(path, typed_data))
+    }
+
+    /// See [`EthereumApp::sign_eip712_typed_data_with_filters`]
+    pub fn sign_eip712_typed_data_with_filters(
+        &self,
+        path: &BipPath,
+        typed_data: &Eip712TypedData,
+        filters: Option<&Eip712FilterConfig>,
+        path_policy: PathPolicy,
+    ) -> EthAppResult<Signature, E::Error> {
+        self.runtime
+            .block_on(self.app.sign_eip712_typed_data_with_filters(
+                path,
+                typed_data,
+                filters,
+                path_policy,
+            ))
+    }
+
+    /// See [`EthereumApp::sign_eip712_with_filters`]
+    pub fn sign_eip712_with_filters(
+        &self,
+        path: &BipPath,
+        typed_data: &Eip712TypedData,
+        bundle: &Eip712FilterBundle,
+    ) -> EthAppResult<Signature, E::Error> {
+        self.runtime
+            .block_on(self.app.sign_eip712_with_filters(path, typed_data, bundle))
+    }
+
+    /// See [`EthereumApp::sign_eip712_from_json`]
+    pub fn sign_eip712_from_json(
+        &self,
+        path: &BipPath,
+        json_str: &str,
+    ) -> EthAppResult<Signature, E::Error> {
+        self.runtime
+            .block_on(self.app.sign_eip712_from_json(path, json_str))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger_sdk_transport::mock::MockExchange;
+
+    use super::*;
+    use crate::instructions::ins;
+
+    #[test]
+    fn test_blocking_calls_without_runtime() {
+        let mock = MockExchange::new().with_responder(|command| {
+            let mut answer = match command.ins {
+                ins::GET_APP_CONFIGURATION => vec![0x00, 1, 12, 0],
+                ins::SIGN_ETH_PERSONAL_MESSAGE => {
+                    let mut signature = vec![0x1c];
+                    signature.extend_from_slice(&[0xAA; 32]);
+                    signature.extend_from_slice(&[0xBB; 32]);
+                    signature
+                }
+                _ => Vec::new(),
+            };
+            answer.extend_from_slice(&[0x90, 0x00]);
+            Ok(answer)
+        });
+
+        let app = BlockingEthereumApp::new(mock).unwrap();
+        assert_eq!(
+            app.app_version().unwrap(),
+            AppVersion {
+                major: 1,
+                minor: 12,
+                patch: 0,
+            }
+        );
+
+        let signature = app
+            .sign_personal_message(SignMessageParams::new(
+                BipPath::ethereum_standard(0, 0),
+                b"Hello".to_vec(),
+            ))
+            .unwrap();
+        assert_eq!(signature.v, 0x1c);
+        assert_eq!(app.transport().sent().len(), 2);
+    }
+}
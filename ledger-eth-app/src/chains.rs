@@ -0,0 +1,73 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bundled table of well-known EVM chains
+//!
+//! Maps user-facing aliases to chain IDs and SLIP-44 coin types so callers do not
+//! have to hard-code them.
+
+/// Well-known EVM chain
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ChainInfo {
+    /// Human-readable name
+    pub name: &'static str,
+    /// Lowercase aliases accepted by [`chain_by_alias`]
+    pub aliases: &'static [&'static str],
+    /// EIP-155 chain ID
+    pub chain_id: u64,
+    /// SLIP-44 coin type used in BIP44 derivation paths
+    pub coin_type: u32,
+    /// Native currency ticker
+    pub ticker: &'static str,
+}
+
+/// Chains known to the SDK
+pub const KNOWN_CHAINS: &[ChainInfo] = &[
+    ChainInfo {
+        name: "Ethereum",
+        aliases: &["eth", "ethereum", "mainnet"],
+        chain_id: 1,
+        coin_type: 60,
+        ticker: "ETH",
+    },
+    ChainInfo {
+        name: "Sepolia",
+        aliases: &["sepolia"],
+        chain_id: 11_155_111,
+        coin_type: 1,
+        ticker: "ETH",
+    },
+    ChainInfo {
+        name: "Ethereum Classic",
+        aliases: &["etc", "classic"],
+        chain_id: 61,
+        coin_type: 61,
+        ticker: "ETC",
+    },
+    ChainInfo {
+        name: "Polygon",
+        aliases: &["polygon", "matic"],
+        chain_id: 137,
+        coin_type: 966,
+        ticker: "POL",
+    },
+    ChainInfo {
+        name: "BNB Smart Chain",
+        aliases: &["bsc", "bnb"],
+        chain_id: 56,
+        coin_type: 9006,
+        ticker: "BNB",
+    },
+];
+
+/// Look up a chain by alias (case-insensitive)
+pub fn chain_by_alias(alias: &str) -> Option<&'static ChainInfo> {
+    let alias = alias.trim().to_ascii_lowercase();
+    KNOWN_CHAINS
+        .iter()
+        .find(|chain| chain.aliases.contains(&alias.as_str()))
+}
+
+/// Look up a chain by EIP-155 chain ID
+pub fn chain_by_id(chain_id: u64) -> Option<&'static ChainInfo> {
+    KNOWN_CHAINS.iter().find(|chain| chain.chain_id == chain_id)
+}
@@ -0,0 +1,396 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signed EIP-712 filter descriptors
+//!
+//! The device only accepts a filter signed by the Ledger crypto asset list (CAL)
+//! key. The signature covers a payload binding the filter to the chain, the
+//! verifying contract, the message schema and the filtered field; a filter whose
+//! display name or path differs from the signed payload fails with 0x6A80.
+//! [`FilterDescriptor`] builds that payload and the matching
+//! [`Eip712FilterParams`] from a single source.
+//!
+//! With the `filter-signatures` feature, descriptors can also compute the schema
+//! hash, check a signature against a public key before sending it, and be signed
+//! with a development key, e.g. the CAL test key Speculos trusts.
+
+use crate::types::{Eip712FilterParams, Eip712FilterType};
+
+/// Prefix byte of each signed payload, identifying the filter kind
+mod magic {
+    pub const AMOUNT_JOIN_TOKEN: u8 = 11;
+    pub const AMOUNT_JOIN_VALUE: u8 = 22;
+    pub const DATE_TIME: u8 = 33;
+    pub const TRUSTED_NAME: u8 = 44;
+    pub const RAW_FIELD: u8 = 72;
+    pub const MESSAGE_INFO: u8 = 183;
+}
+
+/// What a [`FilterDescriptor`] describes
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum FilterDescriptorKind {
+    /// Message name shown before the filtered fields
+    MessageInfo {
+        display_name: String,
+        filters_count: u8,
+    },
+    /// Field displayed as-is
+    RawField {
+        field_path: String,
+        display_name: String,
+    },
+    /// Timestamp field displayed as a date
+    DateTime {
+        field_path: String,
+        display_name: String,
+    },
+    /// Token address for amounts joined under `token_index`
+    AmountJoinToken { field_path: String, token_index: u8 },
+    /// Amount of the token joined under `token_index`
+    AmountJoinValue {
+        field_path: String,
+        display_name: String,
+        token_index: u8,
+    },
+    /// Address field resolved through trusted names
+    TrustedName {
+        field_path: String,
+        display_name: String,
+        name_types: Vec<u8>,
+        name_sources: Vec<u8>,
+    },
+}
+
+/// One EIP-712 filter as signed by the CAL
+///
+/// Field paths use the [`crate::Eip712FilterBuilder`] notation, e.g.
+/// `"details.amount"` or `"recipients.[].wallet"`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FilterDescriptor {
+    /// Chain ID of the domain
+    pub chain_id: u64,
+    /// Verifying contract of the domain
+    pub contract_address: [u8; 20],
+    /// First 28 bytes of the SHA-224 of the message schema
+    pub schema_hash: [u8; 28],
+    /// Described filter
+    pub kind: FilterDescriptorKind,
+}
+
+impl FilterDescriptor {
+    /// Describe `kind` for messages of the schema `schema_hash` verified by
+    /// `contract_address` on `chain_id`
+    pub fn new(
+        chain_id: u64,
+        contract_address: [u8; 20],
+        schema_hash: [u8; 28],
+        kind: FilterDescriptorKind,
+    ) -> Self {
+        FilterDescriptor {
+            chain_id,
+            contract_address,
+            schema_hash,
+            kind,
+        }
+    }
+
+    /// Path of the filtered field, `None` for message info
+    pub fn field_path(&self) -> Option<&str> {
+        match &self.kind {
+            FilterDescriptorKind::MessageInfo { .. } => None,
+            FilterDescriptorKind::RawField { field_path, .. }
+            | FilterDescriptorKind::DateTime { field_path, .. }
+            | FilterDescriptorKind::AmountJoinToken { field_path, .. }
+            | FilterDescriptorKind::AmountJoinValue { field_path, .. }
+            | FilterDescriptorKind::TrustedName { field_path, .. } => Some(field_path),
+        }
+    }
+
+    /// Payload the CAL signs: kind prefix, chain ID, contract address, schema
+    /// hash, then the kind-specific fields, without length prefixes
+    pub fn signable_payload(&self) -> Vec<u8> {
+        let prefix = match &self.kind {
+            FilterDescriptorKind::MessageInfo { .. } => magic::MESSAGE_INFO,
+            FilterDescriptorKind::RawField { .. } => magic::RAW_FIELD,
+            FilterDescriptorKind::DateTime { .. } => magic::DATE_TIME,
+            FilterDescriptorKind::AmountJoinToken { .. } => magic::AMOUNT_JOIN_TOKEN,
+            FilterDescriptorKind::AmountJoinValue { .. } => magic::AMOUNT_JOIN_VALUE,
+            FilterDescriptorKind::TrustedName { .. } => magic::TRUSTED_NAME,
+        };
+
+        let mut payload = vec![prefix];
+        payload.extend_from_slice(&self.chain_id.to_be_bytes());
+        payload.extend_from_slice(&self.contract_address);
+        payload.extend_from_slice(&self.schema_hash);
+        if let Some(field_path) = self.field_path() {
+            payload.extend_from_slice(field_path.as_bytes());
+        }
+
+        match &self.kind {
+            FilterDescriptorKind::MessageInfo {
+                display_name,
+                filters_count,
+            } => {
+                payload.push(*filters_count);
+                payload.extend_from_slice(display_name.as_bytes());
+            }
+            FilterDescriptorKind::RawField { display_name, .. }
+            | FilterDescriptorKind::DateTime { display_name, .. } => {
+                payload.extend_from_slice(display_name.as_bytes());
+            }
+            FilterDescriptorKind::AmountJoinToken { token_index, .. } => {
+                payload.push(*token_index);
+            }
+            FilterDescriptorKind::AmountJoinValue {
+                display_name,
+                token_index,
+                ..
+            } => {
+                payload.extend_from_slice(display_name.as_bytes());
+                payload.push(*token_index);
+            }
+            FilterDescriptorKind::TrustedName {
+                display_name,
+                name_types,
+                name_sources,
+                ..
+            } => {
+                payload.extend_from_slice(display_name.as_bytes());
+                payload.extend_from_slice(name_types);
+                payload.extend_from_slice(name_sources);
+            }
+        }
+
+        payload
+    }
+
+    /// Filter type sending this descriptor with its `signature`
+    pub fn to_filter_type(&self, signature: Vec<u8>) -> Eip712FilterType {
+        match self.kind.clone() {
+            FilterDescriptorKind::MessageInfo {
+                display_name,
+                filters_count,
+            } => Eip712FilterType::MessageInfo {
+                display_name,
+                filters_count,
+                signature,
+            },
+            FilterDescriptorKind::RawField { display_name, .. } => Eip712FilterType::RawField {
+                display_name,
+                signature,
+            },
+            FilterDescriptorKind::DateTime { display_name, .. } => Eip712FilterType::DateTime {
+                display_name,
+                signature,
+            },
+            FilterDescriptorKind::AmountJoinToken { token_index, .. } => {
+                Eip712FilterType::AmountJoinToken {
+                    token_index,
+                    signature,
+                }
+            }
+            FilterDescriptorKind::AmountJoinValue {
+                display_name,
+                token_index,
+                ..
+            } => Eip712FilterType::AmountJoinValue {
+                display_name,
+                token_index,
+                signature,
+            },
+            FilterDescriptorKind::TrustedName {
+                display_name,
+                name_types,
+                name_sources,
+                ..
+            } => Eip712FilterType::TrustedName {
+                display_name,
+                name_types,
+                name_sources,
+                signature,
+            },
+        }
+    }
+}
+
+impl Eip712FilterParams {
+    /// Filter sending `descriptor` with the CAL `signature` of its payload
+    pub fn from_descriptor(descriptor: &FilterDescriptor, signature: Vec<u8>) -> Self {
+        Self::new(descriptor.to_filter_type(signature))
+    }
+}
+
+#[cfg(feature = "filter-signatures")]
+mod signatures {
+    use std::collections::BTreeMap;
+
+    use k256::ecdsa::signature::{Signer, Verifier};
+    use k256::ecdsa::{Signature, SigningKey, VerifyingKey};
+    use sha2::{Digest, Sha224};
+
+    use super::FilterDescriptor;
+    use crate::types::{Eip712Struct, Eip712Types};
+
+    impl FilterDescriptor {
+        /// Schema hash of `types`: SHA-224 of their minified JSON, struct names sorted
+        ///
+        /// `types` must include the `EIP712Domain` definition, as in the typed
+        /// data JSON.
+        pub fn schema_hash(types: &Eip712Types) -> [u8; 28] {
+            let sorted: BTreeMap<&String, &Eip712Struct> = types.iter().collect();
+            let schema = serde_json::to_string(&sorted).expect("EIP-712 types serialize to JSON");
+            Sha224::digest(schema.as_bytes()).into()
+        }
+
+        /// Check a DER `signature` of the payload against the SEC1 `public_key`
+        pub fn verify_with_key(&self, public_key: &[u8], signature: &[u8]) -> Result<(), String> {
+            let key = VerifyingKey::from_sec1_bytes(public_key)
+                .map_err(|_| "Invalid filter signing public key".to_string())?;
+            let signature = Signature::from_der(signature)
+                .map_err(|e| format!("Invalid DER signature: {}", e))?;
+            // The device accepts high-S signatures, k256 does not
+            let signature = signature.normalize_s().unwrap_or(signature);
+
+            key.verify(&self.signable_payload(), &signature)
+                .map_err(|_| "Filter signature does not match the descriptor".to_string())
+        }
+
+        /// DER signature of the payload by a development `private_key`
+        ///
+        /// Only devices and emulators trusting that key accept the filter: use it
+        /// with the CAL test key of Speculos, never with production keys.
+        pub fn sign_with_dev_key(&self, private_key: &[u8; 32]) -> Result<Vec<u8>, String> {
+            let key = SigningKey::from_slice(private_key)
+                .map_err(|_| "Invalid filter signing private key".to_string())?;
+            let signature: Signature = key.sign(&self.signable_payload());
+            Ok(signature.to_der().as_bytes().to_vec())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptor(kind: FilterDescriptorKind) -> FilterDescriptor {
+        FilterDescriptor::new(1, [0x11; 20], [0x22; 28], kind)
+    }
+
+    fn header(prefix: u8) -> Vec<u8> {
+        let mut payload = vec![prefix, 0, 0, 0, 0, 0, 0, 0, 1];
+        payload.extend_from_slice(&[0x11; 20]);
+        payload.extend_from_slice(&[0x22; 28]);
+        payload
+    }
+
+    #[test]
+    fn test_message_info_payload() {
+        let descriptor = descriptor(FilterDescriptorKind::MessageInfo {
+            display_name: "Permit2".to_string(),
+            filters_count: 2,
+        });
+
+        let mut expected = header(183);
+        expected.push(2);
+        expected.extend_from_slice(b"Permit2");
+        assert_eq!(descriptor.signable_payload(), expected);
+        assert_eq!(descriptor.field_path(), None);
+    }
+
+    #[test]
+    fn test_field_payloads() {
+        let amount = descriptor(FilterDescriptorKind::AmountJoinValue {
+            field_path: "details.amount".to_string(),
+            display_name: "Amount".to_string(),
+            token_index: 0,
+        });
+        let mut expected = header(22);
+        expected.extend_from_slice(b"details.amountAmount");
+        expected.push(0);
+        assert_eq!(amount.signable_payload(), expected);
+
+        let token = descriptor(FilterDescriptorKind::AmountJoinToken {
+            field_path: "details.token".to_string(),
+            token_index: 0,
+        });
+        let mut expected = header(11);
+        expected.extend_from_slice(b"details.token");
+        expected.push(0);
+        assert_eq!(token.signable_payload(), expected);
+
+        let trusted_name = descriptor(FilterDescriptorKind::TrustedName {
+            field_path: "to.[].wallet".to_string(),
+            display_name: "To".to_string(),
+            name_types: vec![0x01],
+            name_sources: vec![0x02, 0x03],
+        });
+        let mut expected = header(44);
+        expected.extend_from_slice(b"to.[].walletTo");
+        expected.extend_from_slice(&[0x01, 0x02, 0x03]);
+        assert_eq!(trusted_name.signable_payload(), expected);
+    }
+
+    #[test]
+    fn test_params_from_descriptor() {
+        let descriptor = descriptor(FilterDescriptorKind::DateTime {
+            field_path: "sigDeadline".to_string(),
+            display_name: "Expires".to_string(),
+        });
+
+        assert_eq!(
+            Eip712FilterParams::from_descriptor(&descriptor, vec![0x30]),
+            Eip712FilterParams::date_time("Expires", vec![0x30])
+        );
+    }
+
+    #[cfg(feature = "filter-signatures")]
+    #[test]
+    fn test_sign_and_verify() {
+        use k256::ecdsa::SigningKey;
+
+        let private_key = [0x42; 32];
+        let public_key = SigningKey::from_slice(&private_key)
+            .unwrap()
+            .verifying_key()
+            .to_sec1_bytes();
+
+        let descriptor = descriptor(FilterDescriptorKind::RawField {
+            field_path: "contents".to_string(),
+            display_name: "Contents".to_string(),
+        });
+        let signature = descriptor.sign_with_dev_key(&private_key).unwrap();
+        assert_eq!(descriptor.verify_with_key(&public_key, &signature), Ok(()));
+
+        let mut renamed = descriptor.clone();
+        renamed.kind = FilterDescriptorKind::RawField {
+            field_path: "contents".to_string(),
+            display_name: "Message".to_string(),
+        };
+        assert!(renamed.verify_with_key(&public_key, &signature).is_err());
+    }
+
+    #[cfg(feature = "filter-signatures")]
+    #[test]
+    fn test_schema_hash() {
+        use crate::types::{Eip712Field, Eip712Struct, Eip712Types};
+
+        let mut types = Eip712Types::new();
+        types.insert(
+            "Mail".to_string(),
+            Eip712Struct::new().with_field(Eip712Field::new(
+                "contents".to_string(),
+                "string".to_string(),
+            )),
+        );
+        types.insert(
+            "EIP712Domain".to_string(),
+            Eip712Struct::new()
+                .with_field(Eip712Field::new("name".to_string(), "string".to_string())),
+        );
+
+        // SHA-224 of {"EIP712Domain":[{"name":"name","type":"string"}],"Mail":[{"name":"contents","type":"string"}]}
+        assert_eq!(
+            hex::encode(FilterDescriptor::schema_hash(&types)),
+            "e537bf245a4a0b01886c810c5404f36c3c74f53aa6bf56517abb06e5"
+        );
+    }
+}
@@ -4,8 +4,8 @@
 //!
 //! This module contains utilities for encoding EIP-712 data structures into APDU format.
 
-use crate::errors::EthAppResult;
-use crate::instructions::{p1_eip712_filtering, p2_eip712_filtering};
+use crate::errors::{EthAppError, EthAppResult};
+use crate::instructions::{length, p1_eip712_filtering, p2_eip712_filtering};
 use crate::types::{Eip712FieldDefinition, Eip712FilterParams, Eip712FilterType};
 
 // Maximum APDU payload size for a single frame (data field only)
@@ -29,8 +29,7 @@ pub fn encode_field_definition<E: std::error::Error>(
 
     // TypeNameLength and TypeName (only for custom types, when Type=0)
     if let Some(type_name) = field.field_type.type_name() {
-        data.push(type_name.len() as u8);
-        data.extend_from_slice(type_name.as_bytes());
+        push_name(&mut data, "Type name", type_name)?;
     }
 
     // TypeSize (if applicable)
@@ -50,12 +49,48 @@ pub fn encode_field_definition<E: std::error::Error>(
     }
 
     // KeyNameLength and KeyName (always present)
-    data.push(field.name.len() as u8);
-    data.extend_from_slice(field.name.as_bytes());
+    push_name(&mut data, "Field name", &field.name)?;
 
     Ok(data)
 }
 
+/// Append `name` with its 1-byte length prefix
+///
+/// The prefix counts UTF-8 bytes, not characters: a 100 character name in CJK
+/// script takes 300 bytes and does not fit.
+fn push_name<E: std::error::Error>(
+    data: &mut Vec<u8>,
+    kind: &str,
+    name: &str,
+) -> EthAppResult<(), E> {
+    let len = u8::try_from(name.len()).map_err(|_| {
+        EthAppError::Eip712StructError(format!(
+            "{} '{}' is {} bytes in UTF-8, max {}",
+            kind,
+            name,
+            name.len(),
+            u8::MAX
+        ))
+    })?;
+    data.push(len);
+    data.extend_from_slice(name.as_bytes());
+    Ok(())
+}
+
+/// Encode an array size on as few bytes as possible, big-endian
+pub fn encode_array_size<E: std::error::Error>(size: usize) -> EthAppResult<Vec<u8>, E> {
+    if let Ok(size) = u8::try_from(size) {
+        Ok(vec![size])
+    } else if let Ok(size) = u16::try_from(size) {
+        Ok(size.to_be_bytes().to_vec())
+    } else {
+        Err(EthAppError::ArrayTooLarge {
+            size,
+            max: length::MAX_EIP712_ARRAY_SIZE_U16,
+        })
+    }
+}
+
 /// Encode filter parameters for APDU
 pub fn encode_filter_params<E: std::error::Error>(
     filter_params: &Eip712FilterParams,
@@ -160,3 +195,49 @@ pub fn encode_filter_params<E: std::error::Error>(
 
     Ok((p1, p2, data))
 }
+
+#[cfg(test)]
+mod tests {
+    use ledger_sdk_transport::mock::MockError;
+
+    use super::*;
+    use crate::types::Eip712FieldType;
+
+    #[test]
+    fn test_multibyte_field_name_length() {
+        // 2 characters, 3 bytes each in UTF-8
+        let field = Eip712FieldDefinition::new(Eip712FieldType::Bool, "金额".to_string());
+        let data = encode_field_definition::<MockError>(&field).unwrap();
+        assert_eq!(data[1], 6);
+        assert_eq!(&data[2..], "金额".as_bytes());
+
+        let field = Eip712FieldDefinition::new(
+            Eip712FieldType::Custom("邮件".to_string()),
+            "from".to_string(),
+        );
+        let data = encode_field_definition::<MockError>(&field).unwrap();
+        assert_eq!(&data[1..8], &[&[6][..], "邮件".as_bytes()].concat()[..]);
+    }
+
+    #[test]
+    fn test_field_name_too_long() {
+        // 85 characters fit in a u8 but their 255 bytes are the limit
+        let field = Eip712FieldDefinition::new(Eip712FieldType::Bool, "名".repeat(85));
+        let data = encode_field_definition::<MockError>(&field).unwrap();
+        assert_eq!(data[1], 255);
+
+        let field = Eip712FieldDefinition::new(Eip712FieldType::Bool, "名".repeat(86));
+        let err = encode_field_definition::<MockError>(&field).unwrap_err();
+        assert!(matches!(
+            err,
+            EthAppError::Eip712StructError(ref msg) if msg.contains("258 bytes")
+        ));
+
+        let field = Eip712FieldDefinition::new(
+            Eip712FieldType::Custom("名".repeat(86)),
+            "from".to_string(),
+        );
+        let err = encode_field_definition::<MockError>(&field).unwrap_err();
+        assert!(matches!(err, EthAppError::Eip712StructError(_)));
+    }
+}
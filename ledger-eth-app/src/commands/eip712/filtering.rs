@@ -5,12 +5,17 @@
 //! This module contains the EIP-712 filtering APDU command implementation (0x1E).
 
 use async_trait::async_trait;
-use ledger_sdk_device_base::{App, AppExt};
+use ledger_sdk_device_base::App;
 use ledger_sdk_transport::{APDUCommand, Exchange};
+use serde::{Deserialize, Serialize};
 
+use crate::commands::eip712::descriptor::{FilterDescriptor, FilterDescriptorKind};
 use crate::commands::eip712::encoding::encode_filter_params;
-use crate::errors::{EthAppError, EthAppResult};
+use crate::commands::eip712::high_level::Eip712Converter;
+use crate::commands::eip712::structs::send_commands;
+use crate::errors::EthAppResult;
 use crate::instructions::{ins, p1_eip712_filtering, p2_eip712_filtering};
+use crate::types::Eip712TypedData;
 use crate::EthApp;
 
 // Re-export filtering types from the main types module
@@ -43,42 +48,601 @@ where
         transport: &E,
         filter_params: &Eip712FilterParams,
     ) -> EthAppResult<(), E::Error> {
-        let (p1, p2, data) = encode_filter_params::<E::Error>(filter_params)?;
-
-        let command = APDUCommand {
-            cla: Self::CLA,
-            ins: ins::EIP712_FILTERING,
-            p1,
-            p2,
-            data,
-        };
+        let command = filter_command::<E::Error>(filter_params)?;
+        send_commands(transport, &[command]).await
+    }
 
-        let response = transport
-            .exchange(&command)
-            .await
-            .map_err(|e| EthAppError::Transport(e.into()))?;
+    async fn activate_filtering(transport: &E) -> EthAppResult<(), E::Error> {
+        send_commands(transport, &[activation_command()]).await
+    }
+}
 
-        <EthApp as AppExt<E>>::handle_response_error(&response).map_err(EthAppError::Transport)?;
+/// Command sending one filter
+pub(crate) fn filter_command<E: std::error::Error>(
+    filter_params: &Eip712FilterParams,
+) -> EthAppResult<APDUCommand<Vec<u8>>, E> {
+    let (p1, p2, data) = encode_filter_params::<E>(filter_params)?;
+    Ok(APDUCommand {
+        cla: EthApp::CLA,
+        ins: ins::EIP712_FILTERING,
+        p1,
+        p2,
+        data,
+    })
+}
 
-        Ok(())
+/// Command activating filtering for the upcoming message
+pub(crate) fn activation_command() -> APDUCommand<Vec<u8>> {
+    APDUCommand {
+        cla: EthApp::CLA,
+        ins: ins::EIP712_FILTERING,
+        p1: p1_eip712_filtering::STANDARD,
+        p2: p2_eip712_filtering::ACTIVATION,
+        data: vec![],
     }
+}
 
-    async fn activate_filtering(transport: &E) -> EthAppResult<(), E::Error> {
-        let command = APDUCommand {
-            cla: Self::CLA,
-            ins: ins::EIP712_FILTERING,
-            p1: p1_eip712_filtering::STANDARD,
-            p2: p2_eip712_filtering::ACTIVATION,
-            data: vec![],
+/// Filters for one typed-data message, ready to be interleaved with its implementation
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Eip712FilterConfig {
+    /// Message info filter, sent right before the message implementation
+    pub message_info: Eip712FilterParams,
+    /// Per-field filters keyed by field path, in message traversal order
+    pub field_filters: Vec<(String, Eip712FilterParams)>,
+}
+
+impl Eip712FilterConfig {
+    /// All filters in the order the device expects them: message info, then per-field
+    pub fn params(&self) -> Vec<&Eip712FilterParams> {
+        std::iter::once(&self.message_info)
+            .chain(self.field_filters.iter().map(|(_, params)| params))
+            .collect()
+    }
+
+    /// Every filter in the order it is sent: activation, message info, then
+    /// the per-field filters in message traversal order
+    ///
+    /// The activation goes before the domain implementation and the message
+    /// info right before the message implementation; each field filter is sent
+    /// right before its field's value.
+    pub fn sequence(&self) -> Vec<Eip712FilterParams> {
+        std::iter::once(Eip712FilterParams::activation())
+            .chain(self.params().into_iter().cloned())
+            .collect()
+    }
+
+    /// Filter registered for the given field path
+    pub fn field_filter(&self, path: &str) -> Option<&Eip712FilterParams> {
+        self.field_filters
+            .iter()
+            .find(|(filter_path, _)| filter_path == path)
+            .map(|(_, params)| params)
+    }
+}
+
+/// Builds an [`Eip712FilterConfig`] for a typed-data message
+///
+/// Field filters are registered by path, using `.` between struct fields and
+/// `[]` for array elements (e.g. `"details.amount"`, `"recipients.[].wallet"`).
+#[derive(Clone, Debug)]
+pub struct Eip712FilterBuilder<'a> {
+    typed_data: &'a Eip712TypedData,
+    message_info: Option<(String, Vec<u8>)>,
+    signed_filters_count: Option<u8>,
+    field_filters: Vec<(String, Eip712FilterType)>,
+}
+
+impl<'a> Eip712FilterBuilder<'a> {
+    /// Start building filters for `typed_data`
+    pub fn new(typed_data: &'a Eip712TypedData) -> Self {
+        Eip712FilterBuilder {
+            typed_data,
+            message_info: None,
+            signed_filters_count: None,
+            field_filters: Vec::new(),
+        }
+    }
+
+    /// Set the message info shown before the filtered fields
+    pub fn message_info(mut self, display_name: &str, signature: Vec<u8>) -> Self {
+        self.message_info = Some((display_name.to_string(), signature));
+        self
+    }
+
+    /// Display a field as-is under `display_name`
+    pub fn raw_field(self, path: &str, display_name: &str, signature: Vec<u8>) -> Self {
+        self.field(
+            path,
+            Eip712FilterType::RawField {
+                display_name: display_name.to_string(),
+                signature,
+            },
+        )
+    }
+
+    /// Display a timestamp field as a date
+    pub fn date_time(self, path: &str, display_name: &str, signature: Vec<u8>) -> Self {
+        self.field(
+            path,
+            Eip712FilterType::DateTime {
+                display_name: display_name.to_string(),
+                signature,
+            },
+        )
+    }
+
+    /// Display a field as an amount of the token registered under `token_index`
+    pub fn amount(
+        self,
+        path: &str,
+        display_name: &str,
+        token_index: u8,
+        signature: Vec<u8>,
+    ) -> Self {
+        self.field(
+            path,
+            Eip712FilterType::AmountJoinValue {
+                display_name: display_name.to_string(),
+                token_index,
+                signature,
+            },
+        )
+    }
+
+    /// Mark a field as the token address for amounts using `token_index`
+    pub fn token(self, path: &str, token_index: u8, signature: Vec<u8>) -> Self {
+        self.field(
+            path,
+            Eip712FilterType::AmountJoinToken {
+                token_index,
+                signature,
+            },
+        )
+    }
+
+    /// Display the amount at `amount_path` in the token whose address is at
+    /// `token_path`, joining them under `token_index`
+    ///
+    /// Shorthand for [`Eip712FilterBuilder::token`] and [`Eip712FilterBuilder::amount`].
+    pub fn amount_join(
+        self,
+        token_path: &str,
+        amount_path: &str,
+        display_name: &str,
+        token_index: u8,
+        token_signature: Vec<u8>,
+        amount_signature: Vec<u8>,
+    ) -> Self {
+        self.token(token_path, token_index, token_signature).amount(
+            amount_path,
+            display_name,
+            token_index,
+            amount_signature,
+        )
+    }
+
+    /// Display an address field resolved through trusted names
+    pub fn trusted_name(
+        self,
+        path: &str,
+        display_name: &str,
+        name_types: Vec<u8>,
+        name_sources: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Self {
+        self.field(
+            path,
+            Eip712FilterType::TrustedName {
+                display_name: display_name.to_string(),
+                name_types,
+                name_sources,
+                signature,
+            },
+        )
+    }
+
+    /// Register the filter described by `descriptor`, signed with `signature`
+    ///
+    /// The display name, path and token index come from the descriptor, so they
+    /// match the signed payload. [`Eip712FilterBuilder::build`] checks the filter
+    /// count of a message info descriptor.
+    pub fn descriptor(mut self, descriptor: &FilterDescriptor, signature: Vec<u8>) -> Self {
+        match descriptor.field_path() {
+            Some(path) => self.field(path, descriptor.to_filter_type(signature)),
+            None => {
+                if let FilterDescriptorKind::MessageInfo {
+                    display_name,
+                    filters_count,
+                } = &descriptor.kind
+                {
+                    self.signed_filters_count = Some(*filters_count);
+                    self = self.message_info(display_name, signature);
+                }
+                self
+            }
+        }
+    }
+
+    /// Register an arbitrary per-field filter
+    pub fn field(mut self, path: &str, filter_type: Eip712FilterType) -> Self {
+        self.field_filters.push((path.to_string(), filter_type));
+        self
+    }
+
+    /// Validate the registered paths and compute the message info filter count
+    pub fn build(self) -> Result<Eip712FilterConfig, String> {
+        let (display_name, signature) = self
+            .message_info
+            .ok_or_else(|| "Filter config requires message info".to_string())?;
+
+        let leaf_paths =
+            Eip712Converter::leaf_paths(&self.typed_data.types, &self.typed_data.primary_type)?;
+
+        let mut field_filters = Vec::with_capacity(self.field_filters.len());
+        for (path, filter_type) in self.field_filters {
+            match filter_type {
+                Eip712FilterType::Activation
+                | Eip712FilterType::MessageInfo { .. }
+                | Eip712FilterType::DiscardedFilterPath(_) => {
+                    return Err(format!("Filter for '{}' is not a field filter", path));
+                }
+                _ => {}
+            }
+
+            let position = leaf_paths.iter().position(|p| *p == path).ok_or_else(|| {
+                format!(
+                    "Filter path '{}' does not name a field of '{}'",
+                    path, self.typed_data.primary_type
+                )
+            })?;
+            if field_filters.iter().any(|(_, p, _)| *p == path) {
+                return Err(format!("Duplicate filter for path '{}'", path));
+            }
+
+            field_filters.push((position, path, filter_type));
+        }
+
+        let filters_count = u8::try_from(field_filters.len())
+            .map_err(|_| format!("Too many field filters: {}", field_filters.len()))?;
+        if let Some(signed) = self.signed_filters_count.filter(|&n| n != filters_count) {
+            return Err(format!(
+                "Message info is signed for {} filters, {} registered",
+                signed, filters_count
+            ));
+        }
+
+        // The device consumes field filters as it walks the message
+        field_filters.sort_by_key(|(position, _, _)| *position);
+
+        Ok(Eip712FilterConfig {
+            message_info: Eip712FilterParams::message_info(&display_name, filters_count, signature),
+            field_filters: field_filters
+                .into_iter()
+                .map(|(_, path, filter_type)| (path, Eip712FilterParams::new(filter_type)))
+                .collect(),
+        })
+    }
+}
+
+/// Filtering descriptors for one message schema, as distributed by Ledger
+///
+/// Signatures are hex encoded. Field `format` is one of `raw` (default),
+/// `datetime`, `token`, `amount` or `trusted-name`; `token` and `amount` fields
+/// are joined through `coin_ref`.
+///
+/// ```json
+/// {
+///   "contractName": { "label": "Permit2", "signature": "3045..." },
+///   "fields": [
+///     { "path": "details.token", "format": "token", "coin_ref": 0, "signature": "3044..." },
+///     { "path": "details.amount", "label": "Amount", "format": "amount", "coin_ref": 0, "signature": "3045..." },
+///     { "path": "sigDeadline", "label": "Expires", "format": "datetime", "signature": "3045..." }
+///   ]
+/// }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Eip712FilterBundle {
+    /// Message info: name shown for the message and its signature
+    #[serde(rename = "contractName")]
+    pub contract_name: Eip712BundleDescriptor,
+    /// Per-field descriptors
+    #[serde(default)]
+    pub fields: Vec<Eip712BundleField>,
+}
+
+/// Display name and signature of a bundle entry
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Eip712BundleDescriptor {
+    pub label: String,
+    pub signature: String,
+}
+
+/// Field descriptor of an [`Eip712FilterBundle`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Eip712BundleField {
+    /// Field path, see [`Eip712FilterBuilder`]
+    pub path: String,
+    /// Display name, unused for `token` fields
+    #[serde(default)]
+    pub label: String,
+    pub signature: String,
+    #[serde(default)]
+    pub format: Option<String>,
+    /// Token index joining `token` and `amount` fields
+    #[serde(default)]
+    pub coin_ref: Option<u8>,
+    /// Accepted trusted name types (`trusted-name` only)
+    #[serde(default)]
+    pub types: Vec<u8>,
+    /// Accepted trusted name sources (`trusted-name` only)
+    #[serde(default)]
+    pub sources: Vec<u8>,
+}
+
+impl Eip712FilterBundle {
+    /// Parse a JSON filter bundle
+    pub fn from_json(json: &str) -> Result<Self, String> {
+        serde_json::from_str(json).map_err(|e| format!("Invalid filter bundle: {}", e))
+    }
+
+    /// Resolve the bundle against `typed_data`, validating every field path
+    pub fn to_config(&self, typed_data: &Eip712TypedData) -> Result<Eip712FilterConfig, String> {
+        let mut builder = Eip712FilterBuilder::new(typed_data).message_info(
+            &self.contract_name.label,
+            decode_signature(&self.contract_name.signature)?,
+        );
+
+        for field in &self.fields {
+            let signature = decode_signature(&field.signature)
+                .map_err(|e| format!("Filter for '{}': {}", field.path, e))?;
+            let coin_ref = || {
+                field
+                    .coin_ref
+                    .ok_or_else(|| format!("Filter for '{}' requires coin_ref", field.path))
+            };
+
+            builder = match field.format.as_deref().unwrap_or("raw") {
+                "raw" => builder.raw_field(&field.path, &field.label, signature),
+                "datetime" => builder.date_time(&field.path, &field.label, signature),
+                "token" => builder.token(&field.path, coin_ref()?, signature),
+                "amount" => builder.amount(&field.path, &field.label, coin_ref()?, signature),
+                "trusted-name" => builder.trusted_name(
+                    &field.path,
+                    &field.label,
+                    field.types.clone(),
+                    field.sources.clone(),
+                    signature,
+                ),
+                other => {
+                    return Err(format!(
+                        "Filter for '{}' has unknown format '{}'",
+                        field.path, other
+                    ))
+                }
+            };
+        }
+
+        builder.build()
+    }
+}
+
+fn decode_signature(signature: &str) -> Result<Vec<u8>, String> {
+    let signature = signature.trim_start_matches("0x");
+    hex::decode(signature).map_err(|e| format!("Invalid signature hex: {}", e))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Eip712Domain, Eip712Field, Eip712Struct, Eip712Types};
+
+    fn typed_data() -> Eip712TypedData {
+        let mut types = Eip712Types::new();
+        types.insert(
+            "Details".to_string(),
+            Eip712Struct::new()
+                .with_field(Eip712Field::new("token".to_string(), "address".to_string()))
+                .with_field(Eip712Field::new(
+                    "amount".to_string(),
+                    "uint160".to_string(),
+                )),
+        );
+        types.insert(
+            "Permit".to_string(),
+            Eip712Struct::new()
+                .with_field(Eip712Field::new(
+                    "details".to_string(),
+                    "Details".to_string(),
+                ))
+                .with_field(Eip712Field::new(
+                    "spender".to_string(),
+                    "address".to_string(),
+                ))
+                .with_field(Eip712Field::new(
+                    "deadline".to_string(),
+                    "uint256".to_string(),
+                )),
+        );
+
+        Eip712TypedData::new(
+            Eip712Domain::new(),
+            types,
+            "Permit".to_string(),
+            serde_json::json!({}),
+        )
+    }
+
+    #[test]
+    fn test_filter_builder_orders_and_counts() {
+        let typed_data = typed_data();
+        let config = Eip712FilterBuilder::new(&typed_data)
+            .message_info("Permit2", vec![0x01])
+            .date_time("deadline", "Expires", vec![0x02])
+            .amount("details.amount", "Amount", 0, vec![0x03])
+            .token("details.token", 0, vec![0x04])
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config.message_info.filter_type,
+            Eip712FilterType::MessageInfo {
+                display_name: "Permit2".to_string(),
+                filters_count: 3,
+                signature: vec![0x01],
+            }
+        );
+
+        // Message traversal order, not registration order
+        let paths: Vec<&str> = config
+            .field_filters
+            .iter()
+            .map(|(path, _)| path.as_str())
+            .collect();
+        assert_eq!(paths, vec!["details.token", "details.amount", "deadline"]);
+
+        let params = config.params();
+        assert_eq!(params.len(), 4);
+        assert_eq!(params[0], &config.message_info);
+        assert_eq!(
+            config.field_filter("details.amount").unwrap().filter_type,
+            Eip712FilterType::AmountJoinValue {
+                display_name: "Amount".to_string(),
+                token_index: 0,
+                signature: vec![0x03],
+            }
+        );
+    }
+
+    #[test]
+    fn test_filter_builder_from_descriptors() {
+        let typed_data = typed_data();
+        let descriptor = |kind| FilterDescriptor::new(1, [0x11; 20], [0x22; 28], kind);
+        let message_info = |filters_count| {
+            descriptor(FilterDescriptorKind::MessageInfo {
+                display_name: "Permit2".to_string(),
+                filters_count,
+            })
         };
+        let deadline = descriptor(FilterDescriptorKind::DateTime {
+            field_path: "deadline".to_string(),
+            display_name: "Expires".to_string(),
+        });
+
+        let config = Eip712FilterBuilder::new(&typed_data)
+            .descriptor(&message_info(1), vec![0x01])
+            .descriptor(&deadline, vec![0x02])
+            .build()
+            .unwrap();
+        assert_eq!(
+            config.message_info,
+            Eip712FilterParams::from_descriptor(&message_info(1), vec![0x01])
+        );
+        assert_eq!(
+            config.field_filter("deadline"),
+            Some(&Eip712FilterParams::date_time("Expires", vec![0x02]))
+        );
+
+        let err = Eip712FilterBuilder::new(&typed_data)
+            .descriptor(&message_info(2), vec![0x01])
+            .descriptor(&deadline, vec![0x02])
+            .build()
+            .unwrap_err();
+        assert!(err.contains("signed for 2 filters"), "{}", err);
+    }
+
+    #[test]
+    fn test_filter_sequence_for_two_fields() {
+        let typed_data = typed_data();
+        let config = Eip712FilterBuilder::new(&typed_data)
+            .message_info("Permit2", vec![0x01])
+            .amount_join(
+                "details.token",
+                "details.amount",
+                "Amount",
+                0,
+                vec![0x02],
+                vec![0x03],
+            )
+            .build()
+            .unwrap();
+
+        assert_eq!(
+            config.sequence(),
+            vec![
+                Eip712FilterParams::activation(),
+                Eip712FilterParams::message_info("Permit2", 2, vec![0x01]),
+                Eip712FilterParams::amount_join_token(0, vec![0x02]),
+                Eip712FilterParams::amount_join_value("Amount", 0, vec![0x03]),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_filter_bundle_from_json() {
+        let bundle = Eip712FilterBundle::from_json(
+            r#"{
+                "contractName": { "label": "Permit2", "signature": "0x01" },
+                "fields": [
+                    { "path": "deadline", "label": "Expires", "format": "datetime", "signature": "02" },
+                    { "path": "details.amount", "label": "Amount", "format": "amount", "coin_ref": 0, "signature": "03" },
+                    { "path": "details.token", "format": "token", "coin_ref": 0, "signature": "04" },
+                    { "path": "spender", "label": "Spender", "signature": "05" }
+                ]
+            }"#,
+        )
+        .unwrap();
+
+        let typed_data = typed_data();
+        let config = bundle.to_config(&typed_data).unwrap();
+        let expected = Eip712FilterBuilder::new(&typed_data)
+            .message_info("Permit2", vec![0x01])
+            .date_time("deadline", "Expires", vec![0x02])
+            .amount("details.amount", "Amount", 0, vec![0x03])
+            .token("details.token", 0, vec![0x04])
+            .raw_field("spender", "Spender", vec![0x05])
+            .build()
+            .unwrap();
+        assert_eq!(config, expected);
+
+        let mut bad = bundle.clone();
+        bad.fields[1].coin_ref = None;
+        assert!(bad.to_config(&typed_data).is_err());
+        bad.fields[1].format = Some("percentage".to_string());
+        assert!(bad
+            .to_config(&typed_data)
+            .unwrap_err()
+            .contains("percentage"));
+        assert!(Eip712FilterBundle::from_json(r#"{"fields": []}"#).is_err());
+    }
+
+    #[test]
+    fn test_filter_builder_rejects_bad_paths() {
+        let typed_data = typed_data();
+
+        let err = Eip712FilterBuilder::new(&typed_data)
+            .message_info("Permit2", vec![])
+            .raw_field("details.amonut", "Amount", vec![])
+            .build()
+            .unwrap_err();
+        assert!(err.contains("details.amonut"), "{}", err);
 
-        let response = transport
-            .exchange(&command)
-            .await
-            .map_err(|e| EthAppError::Transport(e.into()))?;
+        // Struct-typed fields are not leaves
+        assert!(Eip712FilterBuilder::new(&typed_data)
+            .message_info("Permit2", vec![])
+            .raw_field("details", "Details", vec![])
+            .build()
+            .is_err());
 
-        <EthApp as AppExt<E>>::handle_response_error(&response).map_err(EthAppError::Transport)?;
+        assert!(Eip712FilterBuilder::new(&typed_data)
+            .message_info("Permit2", vec![])
+            .raw_field("spender", "Spender", vec![])
+            .raw_field("spender", "Spender", vec![])
+            .build()
+            .is_err());
 
-        Ok(())
+        assert!(Eip712FilterBuilder::new(&typed_data)
+            .raw_field("spender", "Spender", vec![])
+            .build()
+            .is_err());
     }
 }
@@ -5,19 +5,19 @@
 //! This module provides a high-level API for EIP-712 signing that matches the viem interface,
 //! making it easy to work with standard typed data structures.
 
-use crate::commands::{Eip712StructDef, Eip712StructImpl, SignEip712Full};
+use crate::commands::eip712::plan::{execute_plan, Eip712ApduPlanner};
+use crate::commands::{Eip712FilterConfig, Eip712FilterParams};
 use crate::errors::{EthAppError, EthAppResult};
 use crate::types::{
-    Eip712ArrayLevel, Eip712Domain, Eip712Field, Eip712FieldDefinition, Eip712FieldType,
-    Eip712FieldValue, Eip712Struct, Eip712StructDefinition, Eip712StructImplementation,
-    Eip712TypedData, Eip712Types,
+    parse_chain_id_value, Eip712ArrayLevel, Eip712Domain, Eip712Field, Eip712FieldDefinition,
+    Eip712FieldType, Eip712FieldValue, Eip712Struct, Eip712StructDefinition,
+    Eip712StructImplementation, Eip712TypedData, Eip712Types, EthAddress, PathPolicy,
 };
-use crate::utils::validate_bip32_path;
-use crate::{BipPath, Eip712Filtering, EthApp};
+use crate::{BipPath, EthApp};
 use async_trait::async_trait;
 use ledger_sdk_transport::Exchange;
 use num_bigint::{BigInt, BigUint, Sign};
-use num_traits::{One, Zero};
+use num_traits::One;
 use serde_json::{from_str, Value};
 
 /// High-level EIP-712 signing trait
@@ -34,6 +34,17 @@ where
         typed_data: &Eip712TypedData,
     ) -> EthAppResult<crate::types::Signature, E::Error>;
 
+    /// Sign EIP-712 typed data, interleaving the given filters with the message implementation
+    ///
+    /// `path` is checked against `path_policy` before anything is sent.
+    async fn sign_eip712_typed_data_with_filters(
+        transport: &E,
+        path: &BipPath,
+        typed_data: &Eip712TypedData,
+        filters: Option<&Eip712FilterConfig>,
+        path_policy: PathPolicy,
+    ) -> EthAppResult<crate::types::Signature, E::Error>;
+
     /// Sign EIP-712 typed data from JSON string
     async fn sign_eip712_from_json(
         transport: &E,
@@ -42,35 +53,128 @@ where
     ) -> EthAppResult<crate::types::Signature, E::Error>;
 }
 
+/// One step of a struct implementation, in the order it is sent to the device
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Eip712ImplementationStep {
+    /// Root struct name
+    Root(String),
+    /// Size of the upcoming array
+    ArraySize(usize),
+    /// Filter for the upcoming field (or message info before the root)
+    Filter(Eip712FilterParams),
+    /// Leaf field value
+    Field(Eip712FieldValue),
+}
+
+/// Strip array suffixes from a type, returning the base type and the number of array levels
+fn split_array_type(type_str: &str) -> (&str, usize) {
+    let mut base = type_str.trim();
+    let mut levels = 0;
+    while base.ends_with(']') {
+        match base.rfind('[') {
+            Some(idx) => {
+                base = &base[..idx];
+                levels += 1;
+            }
+            None => break,
+        }
+    }
+    (base, levels)
+}
+
+/// Append a field name to a filter path
+fn join_path(prefix: &str, name: &str) -> String {
+    if prefix.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", prefix, name)
+    }
+}
+
+/// Integer given as a JSON float (e.g. `1e18`), accepted only when it is exact
+///
+/// Floats cannot hold most 256-bit values, so anything past 2^53 or with a
+/// fractional part is rejected rather than silently rounded.
+fn integral_float(value: &Value, prefix: &str, bits: u32) -> Result<Option<BigInt>, String> {
+    const MAX_EXACT: f64 = (1u64 << 53) as f64;
+    let Some(float) = value.as_f64().filter(|_| value.is_f64()) else {
+        return Ok(None);
+    };
+    if float.fract() != 0.0 || float.abs() > MAX_EXACT {
+        return Err(format!(
+            "expected {}{}, got {} which is not an exact integer, pass it as a decimal string",
+            prefix, bits, value
+        ));
+    }
+    Ok(Some(BigInt::from(float as i64)))
+}
+
+/// Left-pad big-endian bytes with zeros to the declared integer width
+fn left_pad(bytes: Vec<u8>, size_bytes: u8) -> Vec<u8> {
+    let mut out = vec![0u8; (size_bytes as usize).saturating_sub(bytes.len())];
+    out.extend(bytes);
+    out
+}
+
 /// Convert high-level EIP-712 types to low-level struct definitions
 pub struct Eip712Converter;
 
 impl Eip712Converter {
     /// Convert a high-level field type string to low-level Eip712FieldType
+    ///
+    /// Array suffixes are stripped; use [`Eip712Converter::parse_field_definition`]
+    /// to keep them.
     pub fn parse_field_type(type_str: &str) -> Result<Eip712FieldType, String> {
-        let type_str = type_str.trim();
-
-        // Handle array types (e.g., "Person[]", "uint256[2]")
-        if type_str.ends_with(']') {
-            let (base_type, array_spec) = type_str
-                .rsplit_once('[')
-                .ok_or_else(|| format!("Invalid array type format: {}", type_str))?;
+        let (base_type, _) = Self::parse_array_levels(type_str)?;
+        Self::parse_base_field_type(base_type)
+    }
 
-            let array_spec = array_spec.trim_end_matches(']');
-            let _array_level = if array_spec.is_empty() {
-                Eip712ArrayLevel::Dynamic
-            } else {
-                let size: u8 = array_spec
-                    .parse()
-                    .map_err(|_| format!("Invalid array size: {}", array_spec))?;
-                Eip712ArrayLevel::Fixed(size)
-            };
+    /// Convert a high-level field to a field definition, array levels included
+    ///
+    /// Levels are listed in the order they appear in the type string, so
+    /// `uint8[2][]` is a fixed level of 2 followed by a dynamic level.
+    pub fn parse_field_definition(
+        name: &str,
+        type_str: &str,
+    ) -> Result<Eip712FieldDefinition, String> {
+        let (base_type, levels) = Self::parse_array_levels(type_str)?;
+        let definition = levels.into_iter().fold(
+            Eip712FieldDefinition::new(Self::parse_base_field_type(base_type)?, name.to_string()),
+            Eip712FieldDefinition::with_array_level,
+        );
+        Ok(definition)
+    }
 
-            let base_field_type = Self::parse_base_field_type(base_type)?;
-            return Ok(base_field_type);
+    /// Split a type string into its base type and array levels (e.g. "Person[]", "uint256[2]")
+    fn parse_array_levels(type_str: &str) -> Result<(&str, Vec<Eip712ArrayLevel>), String> {
+        let type_str = type_str.trim();
+        let (base_type, array_count) = split_array_type(type_str);
+        if array_count == 0 {
+            return Ok((base_type, Vec::new()));
         }
 
-        Self::parse_base_field_type(type_str)
+        let suffix = &type_str[base_type.len()..];
+        let levels = suffix
+            .strip_prefix('[')
+            .and_then(|s| s.strip_suffix(']'))
+            .ok_or_else(|| format!("Invalid array type format: {}", type_str))?
+            .split("][")
+            .map(|array_spec| {
+                if array_spec.is_empty() {
+                    Ok(Eip712ArrayLevel::Dynamic)
+                } else {
+                    array_spec
+                        .parse()
+                        .map(Eip712ArrayLevel::Fixed)
+                        .map_err(|_| format!("Invalid array size: {}", array_spec))
+                }
+            })
+            .collect::<Result<Vec<_>, String>>()?;
+
+        if levels.len() != array_count {
+            return Err(format!("Invalid array type format: {}", type_str));
+        }
+        Ok((base_type, levels))
     }
 
     /// Parse base field type (non-array)
@@ -116,19 +220,64 @@ impl Eip712Converter {
         }
     }
 
+    /// Check that every custom field type names a declared struct (case-sensitive)
+    pub fn validate_type_references(types: &Eip712Types) -> Result<(), String> {
+        let mut struct_names: Vec<&String> = types.keys().collect();
+        struct_names.sort();
+
+        for struct_name in &struct_names {
+            for field in &types[*struct_name].fields {
+                let Eip712FieldType::Custom(referenced) = Self::parse_field_type(&field.r#type)?
+                else {
+                    continue;
+                };
+                if types.contains_key(&referenced) {
+                    continue;
+                }
+
+                let mut message = format!(
+                    "Field '{}' in type '{}' references undeclared type '{}'",
+                    field.name, struct_name, referenced
+                );
+                if let Some(suggestion) = Self::closest_type_name(&referenced, &struct_names) {
+                    message.push_str(&format!(" (did you mean '{}'?)", suggestion));
+                }
+                return Err(message);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Find the declared type name closest to `name`, if any is reasonably close
+    fn closest_type_name<'a>(name: &str, candidates: &[&'a String]) -> Option<&'a str> {
+        let lowered = name.to_lowercase();
+        candidates
+            .iter()
+            .map(|candidate| {
+                (
+                    edit_distance(&lowered, &candidate.to_lowercase()),
+                    candidate,
+                )
+            })
+            .filter(|(distance, _)| *distance <= (name.len() / 3).max(2))
+            .min_by_key(|(distance, _)| *distance)
+            .map(|(_, candidate)| candidate.as_str())
+    }
+
     /// Convert high-level EIP-712 types to low-level struct definitions
     pub fn convert_types_to_definitions(
         types: &Eip712Types,
     ) -> Result<Vec<Eip712StructDefinition>, String> {
+        Self::validate_type_references(types)?;
+
         let mut definitions = Vec::new();
 
         for (struct_name, struct_def) in types {
             let mut fields = Vec::new();
 
             for field in &struct_def.fields {
-                let field_type = Self::parse_field_type(&field.r#type)?;
-                let field_def = Eip712FieldDefinition::new(field_type, field.name.clone());
-                fields.push(field_def);
+                fields.push(Self::parse_field_definition(&field.name, &field.r#type)?);
             }
 
             let definition = Eip712StructDefinition {
@@ -142,6 +291,94 @@ impl Eip712Converter {
         Ok(definitions)
     }
 
+    /// Struct definitions in the order they are sent to the device
+    ///
+    /// `EIP712Domain` comes first and `primary_type` last; every other struct is
+    /// preceded by the structs its fields reference. Fails on dangling references
+    /// and on circular ones, naming the types involved.
+    pub fn ordered_definitions(
+        types: &Eip712Types,
+        primary_type: &str,
+    ) -> Result<Vec<Eip712StructDefinition>, String> {
+        Self::validate_type_references(types)?;
+        if !types.contains_key(primary_type) {
+            return Err(format!(
+                "Primary type '{}' is not declared in types",
+                primary_type
+            ));
+        }
+
+        let mut names: Vec<&str> = types.keys().map(String::as_str).collect();
+        names.sort_unstable();
+        // Visiting the primary type last keeps it at the end unless another
+        // struct references it
+        names.retain(|name| *name != primary_type && *name != "EIP712Domain");
+        names.push(primary_type);
+
+        let mut order: Vec<&str> = Vec::new();
+        if types.contains_key("EIP712Domain") {
+            order.push("EIP712Domain");
+        }
+        let mut visiting = Vec::new();
+        for name in names {
+            Self::visit_dependencies(types, name, &mut visiting, &mut order)?;
+        }
+
+        order
+            .into_iter()
+            .map(|name| {
+                let fields = types[name]
+                    .fields
+                    .iter()
+                    .map(|field| Self::parse_field_definition(&field.name, &field.r#type))
+                    .collect::<Result<Vec<_>, String>>()?;
+                Ok(Eip712StructDefinition {
+                    name: name.to_string(),
+                    fields,
+                })
+            })
+            .collect()
+    }
+
+    /// Depth-first walk appending `name` to `order` after the structs it references
+    fn visit_dependencies<'a>(
+        types: &'a Eip712Types,
+        name: &'a str,
+        visiting: &mut Vec<&'a str>,
+        order: &mut Vec<&'a str>,
+    ) -> Result<(), String> {
+        if order.contains(&name) {
+            return Ok(());
+        }
+        if let Some(start) = visiting.iter().position(|visited| *visited == name) {
+            let mut cycle = visiting[start..].to_vec();
+            cycle.push(name);
+            return Err(format!(
+                "Type '{}' references itself: {}",
+                name,
+                cycle.join(" -> ")
+            ));
+        }
+
+        visiting.push(name);
+        for field in &types[name].fields {
+            let (base_type, _) = Self::parse_array_levels(&field.r#type)?;
+            if let Some((referenced, _)) = types.get_key_value(base_type) {
+                Self::visit_dependencies(types, referenced, visiting, order)?;
+            }
+        }
+        visiting.pop();
+        order.push(name);
+        Ok(())
+    }
+
+    /// `EIP712Domain` definition for the fields set in `domain`
+    ///
+    /// See [`Eip712Domain::to_struct_definition`].
+    pub fn domain_definition(domain: &Eip712Domain) -> Eip712StructDefinition {
+        domain.to_struct_definition()
+    }
+
     /// Convert message value to field value
     pub fn convert_value_to_field_value(
         value: &Value,
@@ -149,29 +386,38 @@ impl Eip712Converter {
     ) -> Result<Eip712FieldValue, String> {
         match field_type {
             Eip712FieldType::Bool => {
-                let bool_val = value
-                    .as_bool()
-                    .ok_or_else(|| "Expected boolean value".to_string())?;
+                // Some dapps serialize booleans as strings or 0/1
+                let bool_val = match value {
+                    Value::Bool(b) => *b,
+                    Value::String(s) if s == "true" => true,
+                    Value::String(s) if s == "false" => false,
+                    Value::Number(n) if n.as_u64() == Some(1) => true,
+                    Value::Number(n) if n.as_u64() == Some(0) => false,
+                    _ => return Err(format!("expected bool, got {}", value)),
+                };
                 Ok(Eip712FieldValue::from_bool(bool_val))
             }
             Eip712FieldType::Address => {
                 let addr_str = value
                     .as_str()
-                    .ok_or_else(|| "Expected string value for address".to_string())?;
+                    .ok_or_else(|| format!("expected address, got {}", value))?;
+                let addr_str = addr_str.trim();
+                let addr_str = addr_str.strip_prefix("0X").unwrap_or(addr_str);
                 Eip712FieldValue::from_address_string(addr_str)
+                    .map_err(|e| format!("expected address: {}", e))
             }
             Eip712FieldType::String => {
                 let str_val = value
                     .as_str()
-                    .ok_or_else(|| "Expected string value".to_string())?;
+                    .ok_or_else(|| format!("expected string, got {}", value))?;
                 Ok(Eip712FieldValue::from_string(str_val))
             }
             Eip712FieldType::Uint(size) => {
-                let bytes = Self::parse_uint_to_min_be(value, *size)?;
+                let bytes = Self::parse_uint_to_be(value, *size)?;
                 Ok(Eip712FieldValue::from_bytes(bytes))
             }
             Eip712FieldType::Int(size) => {
-                let bytes = Self::parse_int_to_min_be(value, *size)?;
+                let bytes = Self::parse_int_to_be(value, *size)?;
                 Ok(Eip712FieldValue::from_bytes(bytes))
             }
             Eip712FieldType::FixedBytes(size) => {
@@ -193,19 +439,22 @@ impl Eip712Converter {
                     .map_err(|e| format!("Invalid hex string: {}", e))?;
                 Ok(Eip712FieldValue::from_bytes(bytes))
             }
-            Eip712FieldType::Custom(_) => {
-                // For custom structs, we return an empty value as the struct reference
-                Ok(Eip712FieldValue::from_struct())
-            }
+            Eip712FieldType::Custom(name) => Err(format!(
+                "'{}' is a struct; its fields are sent as values, not the struct itself",
+                name
+            )),
         }
     }
 
-    /// Parse unsigned integer (uintN) from JSON number or string into minimal big-endian bytes (with range check)
-    fn parse_uint_to_min_be(value: &Value, size_bytes: u8) -> Result<Vec<u8>, String> {
+    /// Parse unsigned integer (uintN) from JSON number or string into `N / 8` big-endian bytes (with range check)
+    fn parse_uint_to_be(value: &Value, size_bytes: u8) -> Result<Vec<u8>, String> {
         let bits: u32 = (size_bytes as u32) * 8;
         // Parse into BigUint
         let big: BigUint = if let Some(u) = value.as_u64() {
             BigUint::from(u)
+        } else if let Some(i) = integral_float(value, "uint", bits)? {
+            i.to_biguint()
+                .ok_or_else(|| format!("uint{} value out of range", bits))?
         } else if let Some(s) = value.as_str() {
             let s = s.trim();
             if s.starts_with("0x") || s.starts_with("0X") {
@@ -218,42 +467,20 @@ impl Eip712Converter {
                     .ok_or_else(|| format!("Invalid decimal string for uint{}", bits))?
             }
         } else {
-            return Err(format!(
-                "Expected number or numeric string for uint{}",
-                bits
-            ));
+            return Err(format!("expected uint{}, got {}", bits, value));
         };
 
-        // Range check: 0 <= big < 2^(bits)
-        let max = BigUint::one() << bits;
-        if big >= max {
-            return Err(format!("uint{} value out of range", bits));
-        }
-
-        // Minimal big-endian: 0 => [0x00], otherwise trim leading zeros
-        if big.is_zero() {
-            return Ok(vec![0u8]);
-        }
-        let mut out = big.to_bytes_be();
-        while out.len() > 1 && out[0] == 0 {
-            out.remove(0);
-        }
-        // Still ensure it could fit in size_bytes if needed by device constraints
-        if out.len() > size_bytes as usize {
-            return Err(format!(
-                "uint{} minimal encoding exceeds {} bytes",
-                bits, size_bytes
-            ));
-        }
-        Ok(out)
+        Eip712FieldValue::from_biguint(&big, size_bytes).map(|v| v.value)
     }
 
-    /// Parse signed integer (intN) from JSON number or string into minimal two's-complement big-endian bytes (with range check)
-    fn parse_int_to_min_be(value: &Value, size_bytes: u8) -> Result<Vec<u8>, String> {
+    /// Parse signed integer (intN) from JSON number or string into `N / 8` two's-complement big-endian bytes (with range check)
+    fn parse_int_to_be(value: &Value, size_bytes: u8) -> Result<Vec<u8>, String> {
         let bits: u32 = (size_bytes as u32) * 8;
         // Parse into BigInt
         let big: BigInt = if let Some(i) = value.as_i64() {
             BigInt::from(i)
+        } else if let Some(i) = integral_float(value, "int", bits)? {
+            i
         } else if let Some(s) = value.as_str() {
             let s = s.trim();
             // Support optional leading '-'
@@ -272,7 +499,7 @@ impl Eip712Converter {
                     .ok_or_else(|| format!("Invalid decimal string for int{}", bits))?
             }
         } else {
-            return Err(format!("Expected number or numeric string for int{}", bits));
+            return Err(format!("expected int{}, got {}", bits, value));
         };
 
         // Range: -(2^(bits-1)) ..= 2^(bits-1)-1
@@ -291,53 +518,175 @@ impl Eip712Converter {
         } else {
             big.to_biguint().unwrap()
         };
-        let mut full = as_uint.to_bytes_be();
-        // Left pad to at least 1 byte
-        if full.is_empty() {
-            full.push(0);
-        }
-        // Ensure we have at most size_bytes to start with (range already checked)
-        if full.len() > size_bytes as usize {
+        // Negative values already span all `bits` modulo 2^bits, so only zeros are padded
+        Ok(left_pad(as_uint.to_bytes_be(), size_bytes))
+    }
+
+    /// Paths of every leaf field reachable from `primary_type`, in traversal order
+    ///
+    /// Struct fields are joined with `.` and array elements appear as `[]`,
+    /// matching the paths used by filters (e.g. `"details.[].amount"`).
+    pub fn leaf_paths(types: &Eip712Types, primary_type: &str) -> Result<Vec<String>, String> {
+        let mut paths = Vec::new();
+        let mut visiting = Vec::new();
+        Self::collect_leaf_paths(types, primary_type, "", &mut visiting, &mut paths)?;
+        Ok(paths)
+    }
+
+    fn collect_leaf_paths(
+        types: &Eip712Types,
+        struct_name: &str,
+        prefix: &str,
+        visiting: &mut Vec<String>,
+        paths: &mut Vec<String>,
+    ) -> Result<(), String> {
+        let struct_def = types
+            .get(struct_name)
+            .ok_or_else(|| format!("Type '{}' not found in types", struct_name))?;
+        if visiting.iter().any(|name| name == struct_name) {
             return Err(format!(
-                "int{} minimal encoding exceeds {} bytes",
-                bits, size_bytes
+                "Recursive type '{}' has no finite paths",
+                struct_name
             ));
         }
-        // Trim redundant sign extension:
-        // For negative numbers, while first byte == 0xFF and next byte has MSB 1, drop first byte
-        // For non-negative, while first byte == 0x00 and next byte MSB 0, drop first byte
-        if big.sign() == Sign::Minus {
-            while full.len() > 1 && full[0] == 0xFF && (full[1] & 0x80) == 0x80 {
-                full.remove(0);
+        visiting.push(struct_name.to_string());
+
+        for field in &struct_def.fields {
+            let (base, levels) = split_array_type(&field.r#type);
+            let mut path = join_path(prefix, &field.name);
+            for _ in 0..levels {
+                path.push_str(".[]");
             }
-        } else {
-            while full.len() > 1 && full[0] == 0x00 && (full[1] & 0x80) == 0x00 {
-                full.remove(0);
+
+            match Self::parse_base_field_type(base)? {
+                Eip712FieldType::Custom(name) => {
+                    Self::collect_leaf_paths(types, &name, &path, visiting, paths)?
+                }
+                _ => paths.push(path),
             }
         }
-        Ok(full)
+
+        visiting.pop();
+        Ok(())
     }
 
-    /// Convert message data to struct implementation
-    pub fn convert_message_to_implementation(
+    /// Flatten a message into the ordered implementation steps sent to the device
+    ///
+    /// Nested structs are walked depth-first, arrays are preceded by their size,
+    /// and each filtered field gets its filter right before its value. When
+    /// `filters` is given, its message info comes first.
+    pub fn implementation_steps(
         message: &Value,
         primary_type: &str,
         types: &Eip712Types,
-    ) -> Result<Eip712StructImplementation, String> {
-        let struct_def = types
-            .get(primary_type)
-            .ok_or_else(|| format!("Primary type '{}' not found in types", primary_type))?;
+        filters: Option<&Eip712FilterConfig>,
+    ) -> Result<Vec<Eip712ImplementationStep>, String> {
+        let mut steps = Vec::new();
+        if let Some(filters) = filters {
+            steps.push(Eip712ImplementationStep::Filter(
+                filters.message_info.clone(),
+            ));
+        }
+        steps.push(Eip712ImplementationStep::Root(primary_type.to_string()));
+        Self::push_struct_steps(message, primary_type, types, "", filters, &mut steps)?;
+        Ok(steps)
+    }
 
-        let mut values = Vec::new();
+    fn push_struct_steps(
+        value: &Value,
+        struct_name: &str,
+        types: &Eip712Types,
+        prefix: &str,
+        filters: Option<&Eip712FilterConfig>,
+        steps: &mut Vec<Eip712ImplementationStep>,
+    ) -> Result<(), String> {
+        let struct_def = types
+            .get(struct_name)
+            .ok_or_else(|| format!("Type '{}' not found in types", struct_name))?;
 
         for field in &struct_def.fields {
-            let field_value = message
+            let path = join_path(prefix, &field.name);
+            let field_value = value
                 .get(&field.name)
-                .ok_or_else(|| format!("Field '{}' not found in message", field.name))?;
+                .ok_or_else(|| format!("message.{}: missing field", path))?;
+
+            let (base, levels) = split_array_type(&field.r#type);
+            Self::push_field_steps(field_value, base, levels, &path, types, filters, steps)?;
+        }
 
-            let field_type = Self::parse_field_type(&field.r#type)?;
-            let field_val = Self::convert_value_to_field_value(field_value, &field_type)?;
-            values.push(field_val);
+        Ok(())
+    }
+
+    fn push_field_steps(
+        value: &Value,
+        base_type: &str,
+        array_levels: usize,
+        path: &str,
+        types: &Eip712Types,
+        filters: Option<&Eip712FilterConfig>,
+        steps: &mut Vec<Eip712ImplementationStep>,
+    ) -> Result<(), String> {
+        if array_levels > 0 {
+            let items = value
+                .as_array()
+                .ok_or_else(|| format!("Expected array value for '{}'", path))?;
+            steps.push(Eip712ImplementationStep::ArraySize(items.len()));
+
+            let item_path = format!("{}.[]", path);
+            for item in items {
+                Self::push_field_steps(
+                    item,
+                    base_type,
+                    array_levels - 1,
+                    &item_path,
+                    types,
+                    filters,
+                    steps,
+                )?;
+            }
+            return Ok(());
+        }
+
+        match Self::parse_base_field_type(base_type)? {
+            Eip712FieldType::Custom(name) => {
+                Self::push_struct_steps(value, &name, types, path, filters, steps)
+            }
+            field_type => {
+                if let Some(filter) = filters.and_then(|filters| filters.field_filter(path)) {
+                    steps.push(Eip712ImplementationStep::Filter(filter.clone()));
+                }
+                let field_value = Self::convert_value_to_field_value(value, &field_type)
+                    .map_err(|e| format!("message.{}: {}", path, e))?;
+                steps.push(Eip712ImplementationStep::Field(field_value));
+                Ok(())
+            }
+        }
+    }
+
+    /// Convert message data to struct implementation
+    ///
+    /// Nested struct fields are flattened into their leaf values, in the order the
+    /// device expects them. Arrays need ARRAY size frames, which a single
+    /// implementation cannot carry: use [`Eip712Converter::implementation_steps`].
+    pub fn convert_message_to_implementation(
+        message: &Value,
+        primary_type: &str,
+        types: &Eip712Types,
+    ) -> Result<Eip712StructImplementation, String> {
+        let steps = Self::implementation_steps(message, primary_type, types, None)?;
+
+        let mut values = Vec::new();
+        for step in steps {
+            match step {
+                Eip712ImplementationStep::Root(_) => {}
+                Eip712ImplementationStep::Field(value) => values.push(value),
+                Eip712ImplementationStep::ArraySize(_) | Eip712ImplementationStep::Filter(_) => {
+                    return Err(format!(
+                        "'{}' contains arrays, use implementation_steps",
+                        primary_type
+                    ));
+                }
+            }
         }
 
         Ok(Eip712StructImplementation {
@@ -418,9 +767,8 @@ impl Eip712Converter {
         }
 
         if let Some(chain_id) = domain_obj.get("chainId") {
-            if let Some(chain_id_num) = chain_id.as_u64() {
-                domain = domain.with_chain_id(chain_id_num);
-            }
+            // Accept numbers as well as decimal or 0x-prefixed strings
+            domain = domain.with_chain_id(parse_chain_id_value(chain_id)?);
         }
 
         if let Some(verifying_contract) = domain_obj.get("verifyingContract") {
@@ -495,6 +843,136 @@ impl Eip712Converter {
     }
 }
 
+impl Eip712TypedData {
+    /// Render the domain and message as an indented tree, close to what the
+    /// device displays
+    ///
+    /// Each line is `name: value`, structs and array elements open an indented
+    /// block. Addresses are EIP-55 checksummed, integers decimal and bytes hex.
+    /// Values that do not match their declared type are shown as raw JSON.
+    pub fn human_readable(&self) -> String {
+        let mut out = String::from("EIP712Domain\n");
+        let domain = &self.domain;
+        if let Some(name) = &domain.name {
+            out.push_str(&format!("  name: {}\n", name));
+        }
+        if let Some(version) = &domain.version {
+            out.push_str(&format!("  version: {}\n", version));
+        }
+        if let Some(chain_id) = domain.chain_id {
+            out.push_str(&format!("  chainId: {}\n", chain_id));
+        }
+        if let Some(contract) = &domain.verifying_contract {
+            let value = Value::String(contract.clone());
+            out.push_str(&format!(
+                "  verifyingContract: {}\n",
+                render_atomic(&value, &Eip712FieldType::Address)
+            ));
+        }
+        if let Some(salt) = &domain.salt {
+            out.push_str(&format!("  salt: 0x{}\n", hex::encode(salt)));
+        }
+
+        out.push_str(&self.primary_type);
+        out.push('\n');
+        render_struct(&mut out, &self.types, &self.primary_type, &self.message, 1);
+        out
+    }
+}
+
+/// Append the fields of struct `name` found in `value`, one per line
+fn render_struct(out: &mut String, types: &Eip712Types, name: &str, value: &Value, depth: usize) {
+    let Some(definition) = types.get(name) else {
+        return;
+    };
+    for field in &definition.fields {
+        let field_value = value.get(&field.name).unwrap_or(&Value::Null);
+        render_field(out, types, &field.name, &field.r#type, field_value, depth);
+    }
+}
+
+/// Append `label: value`, or `label` followed by an indented block for structs and arrays
+fn render_field(
+    out: &mut String,
+    types: &Eip712Types,
+    label: &str,
+    type_str: &str,
+    value: &Value,
+    depth: usize,
+) {
+    let indent = "  ".repeat(depth);
+    // Element type of an array: everything before the outermost brackets
+    let element_type = type_str
+        .strip_suffix(']')
+        .and_then(|rest| rest.rfind('[').map(|open| &rest[..open]));
+
+    match (element_type, value) {
+        (Some(element_type), Value::Array(items)) => {
+            out.push_str(&format!("{}{}\n", indent, label));
+            for (i, item) in items.iter().enumerate() {
+                render_field(
+                    out,
+                    types,
+                    &format!("[{}]", i),
+                    element_type,
+                    item,
+                    depth + 1,
+                );
+            }
+        }
+        (None, Value::Object(_)) if types.contains_key(type_str) => {
+            out.push_str(&format!("{}{}\n", indent, label));
+            render_struct(out, types, type_str, value, depth + 1);
+        }
+        _ => {
+            let rendered = match Eip712Converter::parse_field_type(type_str) {
+                Ok(field_type) if element_type.is_none() => render_atomic(value, &field_type),
+                _ => value.to_string(),
+            };
+            out.push_str(&format!("{}{}: {}\n", indent, label, rendered));
+        }
+    }
+}
+
+/// Display form of an atomic value, raw JSON when it does not parse as `field_type`
+fn render_atomic(value: &Value, field_type: &Eip712FieldType) -> String {
+    let Ok(field_value) = Eip712Converter::convert_value_to_field_value(value, field_type) else {
+        return value.to_string();
+    };
+    let bytes = &field_value.value;
+
+    match field_type {
+        Eip712FieldType::Bool => (bytes.last() == Some(&1)).to_string(),
+        Eip712FieldType::String => String::from_utf8_lossy(bytes).into_owned(),
+        Eip712FieldType::Address => EthAddress::new(format!("0x{}", hex::encode(bytes)))
+            .map(|address| address.to_checksummed())
+            .unwrap_or_else(|_| value.to_string()),
+        Eip712FieldType::Uint(_) => BigUint::from_bytes_be(bytes).to_string(),
+        Eip712FieldType::Int(_) => BigInt::from_signed_bytes_be(bytes).to_string(),
+        Eip712FieldType::FixedBytes(_) | Eip712FieldType::DynamicBytes => {
+            format!("0x{}", hex::encode(bytes))
+        }
+        Eip712FieldType::Custom(_) => value.to_string(),
+    }
+}
+
+/// Levenshtein distance between two strings
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = prev[j] + usize::from(ca != *cb);
+            current[j + 1] = substitution.min(prev[j + 1] + 1).min(current[j] + 1);
+        }
+        prev = current;
+    }
+
+    prev[b.len()]
+}
+
 #[async_trait]
 impl<E> SignEip712TypedData<E> for EthApp
 where
@@ -506,78 +984,616 @@ where
         path: &BipPath,
         typed_data: &Eip712TypedData,
     ) -> EthAppResult<crate::types::Signature, E::Error> {
-        // Validate BIP32 path
-        validate_bip32_path(path)?;
+        Self::sign_eip712_typed_data_with_filters(
+            transport,
+            path,
+            typed_data,
+            None,
+            PathPolicy::default(),
+        )
+        .await
+    }
+
+    async fn sign_eip712_typed_data_with_filters(
+        transport: &E,
+        path: &BipPath,
+        typed_data: &Eip712TypedData,
+        filters: Option<&Eip712FilterConfig>,
+        path_policy: PathPolicy,
+    ) -> EthAppResult<crate::types::Signature, E::Error> {
+        let mut planner = Eip712ApduPlanner::new(typed_data).with_path_policy(path_policy);
+        if let Some(filters) = filters {
+            planner = planner.with_filters(filters);
+        }
+        let commands = planner.plan::<E::Error>(path)?;
+
+        execute_plan(transport, &commands).await
+    }
 
-        // Convert high-level types to low-level struct definitions
-        let struct_definitions = Eip712Converter::convert_types_to_definitions(&typed_data.types)
+    async fn sign_eip712_from_json(
+        transport: &E,
+        path: &BipPath,
+        json_str: &str,
+    ) -> EthAppResult<crate::types::Signature, E::Error> {
+        // Parse and validate JSON string
+        let typed_data = Eip712Converter::parse_json_to_typed_data(json_str)
             .map_err(EthAppError::InvalidEip712Data)?;
 
-        // Send all struct definitions in deterministic order: alphabetical by name
-        let mut defs_sorted = struct_definitions.clone();
-        defs_sorted.sort_by(|a, b| a.name.cmp(&b.name));
-        for struct_def in &defs_sorted {
-            EthApp::send_struct_definition(transport, struct_def).await?;
-        }
+        println!("typed_data: {:?}", &typed_data);
 
-        // Some Ledger firmware expect a canonical EIP712Domain value order.
-        // Build the domain implementation explicitly in the order:
-        // name, version, chainId, verifyingContract (when present)
-        let mut domain_values: Vec<Eip712FieldValue> = Vec::new();
+        // Use the existing typed data signing method
+        Self::sign_eip712_typed_data(transport, path, &typed_data).await
+    }
+}
 
-        if let Some(name) = &typed_data.domain.name {
-            domain_values.push(Eip712FieldValue::from_string(name));
-        }
-        if let Some(version) = &typed_data.domain.version {
-            domain_values.push(Eip712FieldValue::from_string(version));
-        }
-        if let Some(chain_id) = typed_data.domain.chain_id {
-            // Encode as minimal big-endian for uint256
-            let chain_id_val = serde_json::Value::Number(chain_id.into());
-            let bytes = Eip712Converter::parse_uint_to_min_be(&chain_id_val, 32)
-                .map_err(EthAppError::InvalidEip712Data)?;
-            domain_values.push(Eip712FieldValue::from_bytes(bytes));
-        }
-        if let Some(addr) = &typed_data.domain.verifying_contract {
-            let addr_val = Eip712FieldValue::from_address_string(addr)
-                .map_err(EthAppError::InvalidEip712Data)?;
-            domain_values.push(addr_val);
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::Eip712FilterBuilder;
+
+    fn types_with_reference(reference: &str) -> Eip712Types {
+        let mut types = Eip712Types::new();
+        types.insert(
+            "Permit".to_string(),
+            Eip712Struct::new()
+                .with_field(Eip712Field::new("owner".to_string(), "address".to_string())),
+        );
+        types.insert(
+            "Order".to_string(),
+            Eip712Struct::new().with_field(Eip712Field::new(
+                "permit".to_string(),
+                reference.to_string(),
+            )),
+        );
+        types
+    }
 
-        let domain_impl = Eip712StructImplementation {
-            name: "EIP712Domain".to_string(),
-            values: domain_values,
+    #[test]
+    fn test_validate_type_references_ok() {
+        let types = types_with_reference("Permit");
+        assert!(Eip712Converter::validate_type_references(&types).is_ok());
+        assert!(Eip712Converter::convert_types_to_definitions(&types).is_ok());
+    }
+
+    #[test]
+    fn test_validate_type_references_case_mismatch() {
+        let types = types_with_reference("permit");
+        let err = Eip712Converter::validate_type_references(&types).unwrap_err();
+        assert!(err.contains("undeclared type 'permit'"), "{}", err);
+        assert!(err.contains("did you mean 'Permit'?"), "{}", err);
+
+        // The converter refuses to build definitions with a dangling reference
+        assert_eq!(
+            Eip712Converter::convert_types_to_definitions(&types).unwrap_err(),
+            err
+        );
+    }
+
+    #[test]
+    fn test_validate_type_references_array_and_no_suggestion() {
+        let types = types_with_reference("permit[]");
+        let err = Eip712Converter::validate_type_references(&types).unwrap_err();
+        assert!(err.contains("did you mean 'Permit'?"), "{}", err);
+
+        let types = types_with_reference("Transfer");
+        let err = Eip712Converter::validate_type_references(&types).unwrap_err();
+        assert!(!err.contains("did you mean"), "{}", err);
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("permit", "permit"), 0);
+        assert_eq!(edit_distance("permit", "permits"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    fn group_types() -> Eip712Types {
+        let mut types = Eip712Types::new();
+        types.insert(
+            "Person".to_string(),
+            Eip712Struct::new()
+                .with_field(Eip712Field::new("name".to_string(), "string".to_string()))
+                .with_field(Eip712Field::new(
+                    "wallet".to_string(),
+                    "address".to_string(),
+                )),
+        );
+        types.insert(
+            "Group".to_string(),
+            Eip712Struct::new()
+                .with_field(Eip712Field::new("title".to_string(), "string".to_string()))
+                .with_field(Eip712Field::new(
+                    "members".to_string(),
+                    "Person[]".to_string(),
+                )),
+        );
+        types
+    }
+
+    #[test]
+    fn test_array_field_definitions_encoding() {
+        use crate::commands::eip712::encoding::encode_field_definition;
+
+        let encode = |name: &str, type_str: &str| {
+            let definition = Eip712Converter::parse_field_definition(name, type_str).unwrap();
+            encode_field_definition::<std::io::Error>(&definition).unwrap()
         };
 
-        EthApp::activate_filtering(transport).await?;
-        EthApp::send_struct_implementation(transport, &domain_impl).await?;
+        // TypeArray | bytes, no size byte, 1 dynamic level, key "proofs"
+        let mut expected = vec![0x87, 0x01, 0x00, 0x06];
+        expected.extend_from_slice(b"proofs");
+        assert_eq!(encode("proofs", "bytes[]"), expected);
+
+        // TypeArray | TypeSize | bytes32, size 32, 1 fixed level of 2, key "roots"
+        let mut expected = vec![0xC6, 0x20, 0x01, 0x01, 0x02, 0x05];
+        expected.extend_from_slice(b"roots");
+        assert_eq!(encode("roots", "bytes32[2]"), expected);
+
+        // Levels in source order: fixed 2, then dynamic
+        let mut expected = vec![0xC2, 0x01, 0x02, 0x01, 0x02, 0x00, 0x01];
+        expected.extend_from_slice(b"n");
+        assert_eq!(encode("n", "uint8[2][]"), expected);
+
+        // Plain bytes keeps a bare type descriptor
+        assert_eq!(
+            encode("data", "bytes"),
+            [&[0x07, 0x04][..], b"data"].concat()
+        );
 
-        let struct_implementation = Eip712Converter::convert_message_to_implementation(
+        assert!(Eip712Converter::parse_field_definition("x", "uint8[a]").is_err());
+        assert!(Eip712Converter::parse_field_definition("x", "uint8[2]x[]").is_err());
+
+        let mut types = Eip712Types::new();
+        types.insert(
+            "Claim".to_string(),
+            Eip712Struct::new().with_field(Eip712Field::new(
+                "proof".to_string(),
+                "bytes32[]".to_string(),
+            )),
+        );
+        let definitions = Eip712Converter::convert_types_to_definitions(&types).unwrap();
+        assert_eq!(
+            definitions[0].fields[0].array_levels,
+            vec![Eip712ArrayLevel::Dynamic]
+        );
+    }
+
+    #[test]
+    fn test_integers_are_fixed_width() {
+        let minus_one = Eip712Converter::parse_int_to_be(&serde_json::json!(-1), 32).unwrap();
+        assert_eq!(minus_one, vec![0xFF; 32]);
+
+        let min_int8 = Eip712Converter::parse_int_to_be(&serde_json::json!("-128"), 1).unwrap();
+        assert_eq!(min_int8, vec![0x80]);
+        let int16 = Eip712Converter::parse_int_to_be(&serde_json::json!(127), 2).unwrap();
+        assert_eq!(int16, vec![0x00, 0x7F]);
+        assert!(Eip712Converter::parse_int_to_be(&serde_json::json!(128), 1).is_err());
+
+        // Zero is sent at full width rather than as a single 0x00
+        let zero = Eip712Converter::parse_uint_to_be(&serde_json::json!(0), 32).unwrap();
+        assert_eq!(zero, vec![0x00; 32]);
+
+        // USDC permit: `value` is the maximum uint256, `deadline` a small uint256
+        let max = Eip712Converter::parse_uint_to_be(
+            &serde_json::json!(
+                "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+            ),
+            32,
+        )
+        .unwrap();
+        assert_eq!(max, vec![0xFF; 32]);
+        let deadline =
+            Eip712Converter::parse_uint_to_be(&serde_json::json!(1718992051), 32).unwrap();
+        assert_eq!(deadline.len(), 32);
+        assert_eq!(&deadline[28..], &1718992051u32.to_be_bytes());
+        assert!(Eip712Converter::parse_uint_to_be(&serde_json::json!("0x0100"), 1).is_err());
+    }
+
+    #[test]
+    fn test_parse_domain_chain_id_formats() {
+        for chain_id in [
+            serde_json::json!(137),
+            serde_json::json!("137"),
+            serde_json::json!("0x89"),
+        ] {
+            let domain =
+                Eip712Converter::parse_domain(&serde_json::json!({ "chainId": chain_id })).unwrap();
+            assert_eq!(domain.chain_id, Some(137));
+        }
+        assert!(
+            Eip712Converter::parse_domain(&serde_json::json!({ "chainId": "polygon" })).is_err()
+        );
+    }
+
+    #[test]
+    fn test_domain_definition_canonical_order() {
+        let domain = Eip712Domain::new()
+            .with_chain_id(1)
+            .with_name("USD Coin".to_string());
+        let definition = Eip712Converter::domain_definition(&domain);
+
+        assert_eq!(definition.name, "EIP712Domain");
+        assert_eq!(
+            definition.fields,
+            vec![
+                Eip712FieldDefinition::new(Eip712FieldType::String, "name".to_string()),
+                Eip712FieldDefinition::new(Eip712FieldType::Uint(32), "chainId".to_string()),
+            ]
+        );
+
+        let full = Eip712Converter::domain_definition(
+            &domain
+                .with_salt(vec![0; 32])
+                .with_verifying_contract("0x00".to_string())
+                .with_version("2".to_string()),
+        );
+        let names: Vec<&str> = full.fields.iter().map(|f| f.name.as_str()).collect();
+        assert_eq!(
+            names,
+            vec!["name", "version", "chainId", "verifyingContract", "salt"]
+        );
+    }
+
+    fn fixture_steps(json: &str) -> (Eip712TypedData, Vec<Eip712ImplementationStep>) {
+        let typed_data = Eip712Converter::parse_json_to_typed_data(json).unwrap();
+        Eip712Converter::convert_types_to_definitions(&typed_data.types).unwrap();
+        let steps = Eip712Converter::implementation_steps(
             &typed_data.message,
             &typed_data.primary_type,
             &typed_data.types,
+            None,
         )
-        .map_err(EthAppError::InvalidEip712Data)?;
+        .unwrap();
+        (typed_data, steps)
+    }
 
-        // Send message struct implementation
-        EthApp::send_struct_implementation(transport, &struct_implementation).await?;
+    fn field_count(steps: &[Eip712ImplementationStep]) -> usize {
+        steps
+            .iter()
+            .filter(|step| matches!(step, Eip712ImplementationStep::Field(_)))
+            .count()
+    }
 
-        // Perform the final signing
-        EthApp::sign_eip712_full(transport, path).await
+    #[test]
+    fn test_real_world_fixtures() {
+        // OpenSea Seaport listing: numbers as strings, nested struct arrays, hex salt
+        let (typed_data, steps) = fixture_steps(include_str!("fixtures/seaport_order.json"));
+        assert_eq!(typed_data.domain.chain_id, Some(1));
+        assert_eq!(field_count(&steps), 9 + 5 + 2 * 6);
+
+        // Uniswap Permit2: hex chain ID, uint160 max allowance
+        let (typed_data, steps) =
+            fixture_steps(include_str!("fixtures/permit2_permit_single.json"));
+        assert_eq!(typed_data.domain.chain_id, Some(1));
+        let fields: Vec<&Eip712FieldValue> = steps
+            .iter()
+            .filter_map(|step| match step {
+                Eip712ImplementationStep::Field(value) => Some(value),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(
+            fields[0],
+            &Eip712FieldValue::from_address_string("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48")
+                .unwrap()
+        );
+        assert_eq!(fields[1].value, vec![0xFF; 20]);
+
+        // Safe transaction: numbers as JSON numbers, empty-domain name/version
+        let (typed_data, steps) = fixture_steps(include_str!("fixtures/safe_tx.json"));
+        assert_eq!(typed_data.domain.name, None);
+        assert_eq!(field_count(&steps), 10);
     }
 
-    async fn sign_eip712_from_json(
-        transport: &E,
-        path: &BipPath,
-        json_str: &str,
-    ) -> EthAppResult<crate::types::Signature, E::Error> {
-        // Parse and validate JSON string
-        let typed_data = Eip712Converter::parse_json_to_typed_data(json_str)
-            .map_err(EthAppError::InvalidEip712Data)?;
+    fn definition_names(types: &Eip712Types, primary_type: &str) -> Result<Vec<String>, String> {
+        Eip712Converter::ordered_definitions(types, primary_type)
+            .map(|definitions| definitions.into_iter().map(|d| d.name).collect())
+    }
 
-        println!("typed_data: {:?}", &typed_data);
+    #[test]
+    fn test_definitions_in_dependency_order() {
+        // Seaport: both item types sort before "EIP712Domain" and "OrderComponents"
+        let typed_data =
+            Eip712Converter::parse_json_to_typed_data(include_str!("fixtures/seaport_order.json"))
+                .unwrap();
+        assert_eq!(
+            definition_names(&typed_data.types, "OrderComponents").unwrap(),
+            vec![
+                "EIP712Domain",
+                "ConsiderationItem",
+                "OfferItem",
+                "OrderComponents"
+            ]
+        );
+
+        // Nested references: "Asset" and "AssetType" sort before the structs
+        // using them, "Zone" after
+        let field =
+            |name: &str, r#type: &str| Eip712Field::new(name.to_string(), r#type.to_string());
+        let mut types = Eip712Types::new();
+        types.insert(
+            "Order".to_string(),
+            Eip712Struct::new()
+                .with_field(field("maker", "Party"))
+                .with_field(field("assets", "Asset[]"))
+                .with_field(field("zone", "Zone")),
+        );
+        types.insert(
+            "Asset".to_string(),
+            Eip712Struct::new()
+                .with_field(field("assetType", "AssetType"))
+                .with_field(field("value", "uint256")),
+        );
+        types.insert(
+            "AssetType".to_string(),
+            Eip712Struct::new().with_field(field("class", "bytes4")),
+        );
+        types.insert(
+            "Party".to_string(),
+            Eip712Struct::new()
+                .with_field(field("wallet", "address"))
+                .with_field(field("preferred", "Asset")),
+        );
+        types.insert(
+            "Zone".to_string(),
+            Eip712Struct::new().with_field(field("id", "uint8")),
+        );
+        assert_eq!(
+            definition_names(&types, "Order").unwrap(),
+            vec!["AssetType", "Asset", "Party", "Zone", "Order"]
+        );
+
+        // Primary type not declared
+        let err = definition_names(&types, "Listing").unwrap_err();
+        assert!(err.contains("'Listing'"), "{}", err);
+
+        // Dangling reference
+        types.insert(
+            "Zone".to_string(),
+            Eip712Struct::new().with_field(field("owner", "Party2")),
+        );
+        let err = definition_names(&types, "Order").unwrap_err();
+        assert!(err.contains("undeclared type 'Party2'"), "{}", err);
+
+        // Cycle through Party -> Asset -> AssetType -> Party
+        types.insert(
+            "Zone".to_string(),
+            Eip712Struct::new().with_field(field("id", "uint8")),
+        );
+        types.insert(
+            "AssetType".to_string(),
+            Eip712Struct::new().with_field(field("issuer", "Party")),
+        );
+        let err = definition_names(&types, "Order").unwrap_err();
+        assert!(
+            err.contains("Asset -> AssetType -> Party -> Asset"),
+            "{}",
+            err
+        );
+    }
 
-        // Use the existing typed data signing method
-        Self::sign_eip712_typed_data(transport, path, &typed_data).await
+    #[test]
+    fn test_human_readable_mail() {
+        let typed_data = Eip712Converter::parse_json_to_typed_data(
+            r#"{
+                "types": {
+                    "EIP712Domain": [
+                        { "name": "name", "type": "string" },
+                        { "name": "version", "type": "string" },
+                        { "name": "chainId", "type": "uint256" },
+                        { "name": "verifyingContract", "type": "address" }
+                    ],
+                    "Person": [
+                        { "name": "name", "type": "string" },
+                        { "name": "wallet", "type": "address" }
+                    ],
+                    "Mail": [
+                        { "name": "from", "type": "Person" },
+                        { "name": "to", "type": "Person" },
+                        { "name": "contents", "type": "string" },
+                        { "name": "amount", "type": "uint256" },
+                        { "name": "delta", "type": "int8" },
+                        { "name": "tags", "type": "bytes4[]" }
+                    ]
+                },
+                "primaryType": "Mail",
+                "domain": {
+                    "name": "Ether Mail",
+                    "version": "1",
+                    "chainId": 1,
+                    "verifyingContract": "0xcccccccccccccccccccccccccccccccccccccccc"
+                },
+                "message": {
+                    "from": { "name": "Cow", "wallet": "0xcd2a3d9f938e13cd947ec05abc7fe734df8dd826" },
+                    "to": { "name": "Bob", "wallet": "0xbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb" },
+                    "contents": "Hello, Bob!",
+                    "amount": "0x0de0b6b3a7640000",
+                    "delta": -3,
+                    "tags": ["0xdeadbeef", "0x00000001"]
+                }
+            }"#,
+        )
+        .unwrap();
+
+        let rendered = typed_data.human_readable();
+        for line in [
+            "EIP712Domain",
+            "  name: Ether Mail",
+            "  chainId: 1",
+            "  verifyingContract: 0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC",
+            "Mail",
+            "  from",
+            "    name: Cow",
+            "    wallet: 0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826",
+            "    wallet: 0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB",
+            "  contents: Hello, Bob!",
+            "  amount: 1000000000000000000",
+            "  delta: -3",
+            "  tags",
+            "    [0]: 0xdeadbeef",
+            "    [1]: 0x00000001",
+        ] {
+            assert!(
+                rendered.lines().any(|l| l == line),
+                "missing {:?} in\n{}",
+                line,
+                rendered
+            );
+        }
+        // Fields keep their declaration order
+        assert!(rendered.find("  from").unwrap() < rendered.find("  to").unwrap());
+    }
+
+    #[test]
+    fn test_permissive_values() {
+        let convert = Eip712Converter::convert_value_to_field_value;
+        for value in [
+            serde_json::json!(true),
+            serde_json::json!("true"),
+            serde_json::json!(1),
+        ] {
+            assert_eq!(
+                convert(&value, &Eip712FieldType::Bool).unwrap().value,
+                vec![1]
+            );
+        }
+        assert_eq!(
+            convert(&serde_json::json!("false"), &Eip712FieldType::Bool)
+                .unwrap()
+                .value,
+            vec![0]
+        );
+        assert!(convert(&serde_json::json!("yes"), &Eip712FieldType::Bool).is_err());
+
+        let address = convert(
+            &serde_json::json!("CD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"),
+            &Eip712FieldType::Address,
+        )
+        .unwrap();
+        assert_eq!(address.value[0], 0xCD);
+
+        // Floats within the exact f64 range are accepted, larger ones are not
+        let amount = convert(&serde_json::json!(1e15), &Eip712FieldType::Uint(32)).unwrap();
+        assert_eq!(&amount.value[24..], &1_000_000_000_000_000u64.to_be_bytes());
+        let err = convert(&serde_json::json!(1e18), &Eip712FieldType::Uint(32)).unwrap_err();
+        assert!(err.contains("decimal string"), "{}", err);
+        assert!(convert(&serde_json::json!(1.5), &Eip712FieldType::Int(32)).is_err());
+    }
+
+    #[test]
+    fn test_errors_name_field_path() {
+        let mut types = Eip712Types::new();
+        types.insert(
+            "Details".to_string(),
+            Eip712Struct::new().with_field(Eip712Field::new(
+                "deadline".to_string(),
+                "uint256".to_string(),
+            )),
+        );
+        types.insert(
+            "Order".to_string(),
+            Eip712Struct::new().with_field(Eip712Field::new(
+                "details".to_string(),
+                "Details".to_string(),
+            )),
+        );
+
+        let err = Eip712Converter::implementation_steps(
+            &serde_json::json!({ "details": { "deadline": true } }),
+            "Order",
+            &types,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, "message.details.deadline: expected uint256, got true");
+
+        let err = Eip712Converter::implementation_steps(
+            &serde_json::json!({ "details": {} }),
+            "Order",
+            &types,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, "message.details.deadline: missing field");
+    }
+
+    #[test]
+    fn test_leaf_paths() {
+        let types = group_types();
+        assert_eq!(
+            Eip712Converter::leaf_paths(&types, "Group").unwrap(),
+            vec!["title", "members.[].name", "members.[].wallet"]
+        );
+
+        let mut recursive = Eip712Types::new();
+        recursive.insert(
+            "Node".to_string(),
+            Eip712Struct::new()
+                .with_field(Eip712Field::new("next".to_string(), "Node".to_string())),
+        );
+        assert!(Eip712Converter::leaf_paths(&recursive, "Node").is_err());
+    }
+
+    #[test]
+    fn test_implementation_steps_nested_arrays_with_filters() {
+        let types = group_types();
+        let message = serde_json::json!({
+            "title": "Team",
+            "members": [
+                { "name": "Alice", "wallet": "0x0000000000000000000000000000000000000001" },
+                { "name": "Bob", "wallet": "0x0000000000000000000000000000000000000002" }
+            ]
+        });
+        let typed_data = Eip712TypedData::new(
+            Eip712Domain::new(),
+            types.clone(),
+            "Group".to_string(),
+            message.clone(),
+        );
+        let filters = Eip712FilterBuilder::new(&typed_data)
+            .message_info("Group", vec![0x01])
+            .raw_field("members.[].wallet", "Wallet", vec![0x02])
+            .build()
+            .unwrap();
+
+        let steps =
+            Eip712Converter::implementation_steps(&message, "Group", &types, Some(&filters))
+                .unwrap();
+
+        let message_info = Eip712ImplementationStep::Filter(filters.message_info.clone());
+        let wallet_filter = Eip712ImplementationStep::Filter(
+            filters.field_filter("members.[].wallet").unwrap().clone(),
+        );
+        let field = |value: Eip712FieldValue| Eip712ImplementationStep::Field(value);
+        let address = |last: u8| {
+            let mut bytes = vec![0u8; 20];
+            bytes[19] = last;
+            Eip712FieldValue::from_bytes(bytes)
+        };
+
+        assert_eq!(
+            steps,
+            vec![
+                message_info,
+                Eip712ImplementationStep::Root("Group".to_string()),
+                field(Eip712FieldValue::from_string("Team")),
+                Eip712ImplementationStep::ArraySize(2),
+                field(Eip712FieldValue::from_string("Alice")),
+                wallet_filter.clone(),
+                field(address(1)),
+                field(Eip712FieldValue::from_string("Bob")),
+                wallet_filter,
+                field(address(2)),
+            ]
+        );
+
+        // Without filters only the implementation itself is produced
+        let steps = Eip712Converter::implementation_steps(&message, "Group", &types, None).unwrap();
+        assert_eq!(
+            steps[0],
+            Eip712ImplementationStep::Root("Group".to_string())
+        );
+        assert_eq!(steps.len(), 7);
     }
 }
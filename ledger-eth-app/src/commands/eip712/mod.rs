@@ -4,15 +4,21 @@
 //!
 //! This module contains all EIP-712 related functionality organized by APDU command type.
 
+pub mod descriptor;
 pub mod encoding;
 pub mod filtering;
 pub mod high_level;
+pub mod plan;
+pub mod session;
 pub mod signing;
 pub mod structs;
 
 // Re-export all public traits and types
+pub use descriptor::*;
 pub use encoding::*;
 pub use filtering::*;
 pub use high_level::*;
+pub use plan::*;
+pub use session::*;
 pub use signing::*;
 pub use structs::*;
@@ -0,0 +1,334 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Dry run of the EIP-712 typed data signing flow
+//!
+//! [`Eip712ApduPlanner`] builds every APDU `sign_eip712_typed_data` sends, in
+//! order, without a transport. The signing flow executes the same plan, so a plan
+//! dumped while debugging a failing payload is exactly what the device receives.
+
+use ledger_sdk_transport::{APDUCommand, Exchange};
+
+use crate::commands::eip712::filtering::{activation_command, filter_command};
+use crate::commands::eip712::high_level::{Eip712Converter, Eip712ImplementationStep};
+use crate::commands::eip712::signing::{send_sign_command, sign_full_command};
+use crate::commands::eip712::structs::{
+    array_size_command, root_struct_command, send_commands, struct_definition_commands,
+    struct_field_commands, struct_implementation_commands,
+};
+use crate::commands::Eip712FilterConfig;
+use crate::errors::{EthAppError, EthAppResult};
+use crate::instructions::length;
+use crate::types::{BipPath, Eip712TypedData, PathPolicy, Signature};
+use crate::utils::validate_bip32_path_with_policy;
+
+/// Builds the APDU sequence signing a typed data message
+///
+/// The plan holds, in order: the struct definitions (referenced structs first),
+/// the filtering activation, the domain implementation, the message
+/// implementation with its array sizes and filters, and the final sign command.
+#[derive(Clone, Debug)]
+pub struct Eip712ApduPlanner<'a> {
+    typed_data: &'a Eip712TypedData,
+    filters: Option<&'a Eip712FilterConfig>,
+    max_array_size: usize,
+    path_policy: PathPolicy,
+}
+
+impl<'a> Eip712ApduPlanner<'a> {
+    /// Plan the signature of `typed_data`, without filters
+    pub fn new(typed_data: &'a Eip712TypedData) -> Self {
+        Eip712ApduPlanner {
+            typed_data,
+            filters: None,
+            max_array_size: length::MAX_EIP712_ARRAY_SIZE_U16,
+            path_policy: PathPolicy::default(),
+        }
+    }
+
+    /// Interleave `filters` with the message implementation
+    pub fn with_filters(mut self, filters: &'a Eip712FilterConfig) -> Self {
+        self.filters = Some(filters);
+        self
+    }
+
+    /// Reject arrays with more than `max` items, e.g. the limit of an older app
+    pub fn with_max_array_size(mut self, max: usize) -> Self {
+        self.max_array_size = max;
+        self
+    }
+
+    /// Set the derivation path policy
+    pub fn with_path_policy(mut self, path_policy: PathPolicy) -> Self {
+        self.path_policy = path_policy;
+        self
+    }
+
+    /// Every command signing the message with the key at `path`, in sending order
+    ///
+    /// Fails with the error the signing flow would return before sending anything.
+    pub fn plan<E: std::error::Error>(
+        &self,
+        path: &BipPath,
+    ) -> EthAppResult<Vec<APDUCommand<Vec<u8>>>, E> {
+        validate_bip32_path_with_policy(path, self.path_policy)?;
+        let typed_data = self.typed_data;
+
+        let steps = Eip712Converter::implementation_steps(
+            &typed_data.message,
+            &typed_data.primary_type,
+            &typed_data.types,
+            self.filters,
+        )
+        .map_err(EthAppError::InvalidEip712Data)?;
+
+        let mut definitions =
+            Eip712Converter::ordered_definitions(&typed_data.types, &typed_data.primary_type)
+                .map_err(EthAppError::InvalidEip712Data)?;
+        if !typed_data.types.contains_key("EIP712Domain") {
+            definitions.insert(0, typed_data.domain.to_struct_definition());
+        }
+
+        let mut commands = Vec::new();
+        for definition in &definitions {
+            commands.extend(struct_definition_commands::<E>(definition)?);
+        }
+
+        commands.push(activation_command());
+        let domain = typed_data
+            .domain
+            .to_struct_implementation()
+            .map_err(EthAppError::InvalidEip712Data)?;
+        commands.extend(struct_implementation_commands::<E>(&domain)?);
+
+        for step in &steps {
+            match step {
+                Eip712ImplementationStep::Root(name) => {
+                    commands.push(root_struct_command::<E>(name)?);
+                }
+                Eip712ImplementationStep::ArraySize(size) => {
+                    if *size > self.max_array_size {
+                        return Err(EthAppError::ArrayTooLarge {
+                            size: *size,
+                            max: self.max_array_size,
+                        });
+                    }
+                    commands.push(array_size_command::<E>(*size)?);
+                }
+                Eip712ImplementationStep::Filter(filter) => {
+                    commands.push(filter_command::<E>(filter)?);
+                }
+                Eip712ImplementationStep::Field(value) => {
+                    commands.extend(struct_field_commands(value));
+                }
+            }
+        }
+
+        commands.push(sign_full_command(path));
+        Ok(commands)
+    }
+}
+
+/// Send a plan built by [`Eip712ApduPlanner::plan`] and return the signature
+/// answering its last command
+pub(crate) async fn execute_plan<E>(
+    transport: &E,
+    commands: &[APDUCommand<Vec<u8>>],
+) -> EthAppResult<Signature, E::Error>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    let (sign, setup) = commands
+        .split_last()
+        .expect("a plan ends with its sign command");
+    send_commands(transport, setup).await?;
+    send_sign_command(transport, sign).await
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger_sdk_transport::mock::MockExchange;
+
+    use super::*;
+    use crate::commands::eip712::high_level::{Eip712Converter, SignEip712TypedData};
+    use crate::utils::encode_bip32_path;
+    use crate::EthApp;
+
+    /// USDC permit from the `sign_eip712_from_json` example
+    const USDC_PERMIT: &str = r#"{
+      "domain": {
+        "name": "USD Coin",
+        "verifyingContract": "0xa0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+        "chainId": 1,
+        "version": "2"
+      },
+      "primaryType": "Permit",
+      "message": {
+        "deadline": 1718992051,
+        "nonce": 0,
+        "spender": "0x111111125421ca6dc452d289314280a0f8842a65",
+        "owner": "0x6cbcd73cd8e8a42844662f0a0e76d7f79afd933d",
+        "value": "115792089237316195423570985008687907853269984665640564039457584007913129639935"
+      },
+      "types": {
+        "EIP712Domain": [
+          {"name": "name", "type": "string"},
+          {"name": "version", "type": "string"},
+          {"name": "chainId", "type": "uint256"},
+          {"name": "verifyingContract", "type": "address"}
+        ],
+        "Permit": [
+          {"name": "owner", "type": "address"},
+          {"name": "spender", "type": "address"},
+          {"name": "value", "type": "uint256"},
+          {"name": "nonce", "type": "uint256"},
+          {"name": "deadline", "type": "uint256"}
+        ]
+      }
+    }"#;
+
+    fn path() -> BipPath {
+        BipPath::from_string("m/44'/60'/0'/0/0").unwrap()
+    }
+
+    fn to_hex(command: &APDUCommand<Vec<u8>>) -> String {
+        let mut bytes = vec![
+            command.cla,
+            command.ins,
+            command.p1,
+            command.p2,
+            command.data.len() as u8,
+        ];
+        bytes.extend_from_slice(&command.data);
+        hex::encode(bytes)
+    }
+
+    #[test]
+    fn test_plan_usdc_permit() {
+        let typed_data = Eip712Converter::parse_json_to_typed_data(USDC_PERMIT).unwrap();
+        let plan = Eip712ApduPlanner::new(&typed_data)
+            .plan::<std::io::Error>(&path())
+            .unwrap();
+
+        let expected = [
+            // Struct definitions
+            "e01a00000c454950373132446f6d61696e",
+            "e01a00ff0605046e616d65",
+            "e01a00ff09050776657273696f6e",
+            "e01a00ff0a422007636861696e4964",
+            "e01a00ff130311766572696679696e67436f6e7472616374",
+            "e01a0000065065726d6974",
+            "e01a00ff0703056f776e6572",
+            "e01a00ff0903077370656e646572",
+            "e01a00ff0842200576616c7565",
+            "e01a00ff084220056e6f6e6365",
+            "e01a00ff0b422008646561646c696e65",
+            // Filtering activation
+            "e01e000000",
+            // Domain implementation
+            "e01c00000c454950373132446f6d61696e",
+            "e01c00ff0a000855534420436f696e",
+            "e01c00ff03000132",
+            "e01c00ff2200200000000000000000000000000000000000000000000000000000000000000001",
+            "e01c00ff160014a0b86991c6218b36c1d19d4a2e9eb0ce3606eb48",
+            // Message implementation
+            "e01c0000065065726d6974",
+            "e01c00ff1600146cbcd73cd8e8a42844662f0a0e76d7f79afd933d",
+            "e01c00ff160014111111125421ca6dc452d289314280a0f8842a65",
+            "e01c00ff220020ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff",
+            "e01c00ff2200200000000000000000000000000000000000000000000000000000000000000000",
+            "e01c00ff220020000000000000000000000000000000000000000000000000000000006675bcb3",
+            // Sign
+            "e00c000115058000002c8000003c800000000000000000000000",
+        ];
+        let plan: Vec<String> = plan.iter().map(to_hex).collect();
+        assert_eq!(plan, expected);
+    }
+
+    #[test]
+    fn test_plan_rejects_large_arrays() {
+        let typed_data = Eip712Converter::parse_json_to_typed_data(
+            r#"{
+              "domain": {"name": "Batch", "chainId": 1},
+              "primaryType": "Batch",
+              "message": {"ids": [1, 2, 3]},
+              "types": {
+                "EIP712Domain": [
+                  {"name": "name", "type": "string"},
+                  {"name": "chainId", "type": "uint256"}
+                ],
+                "Batch": [{"name": "ids", "type": "uint256[]"}]
+              }
+            }"#,
+        )
+        .unwrap();
+
+        let err = Eip712ApduPlanner::new(&typed_data)
+            .with_max_array_size(2)
+            .plan::<std::io::Error>(&path())
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            EthAppError::ArrayTooLarge { size: 3, max: 2 }
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_signing_sends_the_plan() {
+        let typed_data = Eip712Converter::parse_json_to_typed_data(USDC_PERMIT).unwrap();
+        let plan = Eip712ApduPlanner::new(&typed_data)
+            .plan::<std::io::Error>(&path())
+            .unwrap();
+
+        let transport = MockExchange::new();
+        for _ in 1..plan.len() {
+            transport.push_ok(&[]);
+        }
+        let mut signature = vec![0x1b];
+        signature.extend_from_slice(&[0x11; 64]);
+        transport.push_ok(&signature);
+
+        let signature = <EthApp as SignEip712TypedData<MockExchange>>::sign_eip712_typed_data(
+            &transport,
+            &path(),
+            &typed_data,
+        )
+        .await
+        .unwrap();
+        assert_eq!(signature.v, 0x1b);
+
+        let sent: Vec<String> = transport.sent().iter().map(to_hex).collect();
+        let plan: Vec<String> = plan.iter().map(to_hex).collect();
+        assert_eq!(sent, plan);
+    }
+
+    #[tokio::test]
+    async fn test_path_policy_override() {
+        let typed_data = Eip712Converter::parse_json_to_typed_data(USDC_PERMIT).unwrap();
+        // Testnet path: fine by default, rejected under the Ethereum standard policy
+        let path = BipPath::from_string("m/44'/1'/0'/0/0").unwrap();
+
+        let transport = MockExchange::new();
+        let err =
+            <EthApp as SignEip712TypedData<MockExchange>>::sign_eip712_typed_data_with_filters(
+                &transport,
+                &path,
+                &typed_data,
+                None,
+                PathPolicy::EthereumOnly,
+            )
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(err, EthAppError::InvalidBip32Path(ref reason) if reason.contains("Ethereum standard policy"))
+        );
+        assert!(transport.sent().is_empty());
+
+        let plan = Eip712ApduPlanner::new(&typed_data)
+            .with_path_policy(PathPolicy::Testnet)
+            .plan::<std::io::Error>(&path)
+            .unwrap();
+        let sign = plan.last().unwrap();
+        assert_eq!(sign.data, encode_bip32_path(&path));
+    }
+}
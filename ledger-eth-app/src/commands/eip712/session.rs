@@ -0,0 +1,292 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Validated EIP-712 struct sends
+//!
+//! [`Eip712Session`] records the struct definitions it sends and checks every
+//! struct implementation against them before it reaches the device, instead of
+//! surfacing a mismatch as a wrong hash or a 0x6A80 halfway through the message.
+
+use std::collections::HashMap;
+
+use ledger_sdk_transport::Exchange;
+
+use crate::errors::{EthAppError, EthAppResult};
+use crate::types::{
+    Eip712FieldType, Eip712FieldValue, Eip712StructDefinition, Eip712StructImplementation,
+};
+use crate::EthereumApp;
+
+/// Opt-in validation layer over the raw struct definition / implementation calls
+///
+/// Implementation values are the flattened leaf values of the struct, nested
+/// structs included. Array sizes are sent separately, so values after the first
+/// array field are not checked.
+#[derive(Debug)]
+pub struct Eip712Session<'a, E: Exchange> {
+    app: &'a EthereumApp<E>,
+    definitions: HashMap<String, Eip712StructDefinition>,
+}
+
+impl<'a, E> Eip712Session<'a, E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    /// Start a session on `app`
+    pub fn new(app: &'a EthereumApp<E>) -> Self {
+        Eip712Session {
+            app,
+            definitions: HashMap::new(),
+        }
+    }
+
+    /// Send a struct definition and record it for later validation
+    pub async fn send_struct_definition(
+        &mut self,
+        struct_def: &Eip712StructDefinition,
+    ) -> EthAppResult<(), E::Error> {
+        self.app.send_struct_definition(struct_def).await?;
+        self.definitions
+            .insert(struct_def.name.clone(), struct_def.clone());
+        Ok(())
+    }
+
+    /// Validate a struct implementation against the recorded definitions, then send it
+    ///
+    /// # Errors
+    ///
+    /// Returns `EthAppError::Eip712StructError` naming the struct and value index
+    /// on mismatch; nothing is sent in that case.
+    pub async fn send_struct_implementation(
+        &self,
+        struct_impl: &Eip712StructImplementation,
+    ) -> EthAppResult<(), E::Error> {
+        self.validate(struct_impl)
+            .map_err(EthAppError::Eip712StructError)?;
+        self.app.send_struct_implementation(struct_impl).await
+    }
+
+    /// Check the values of `struct_impl` against the recorded definitions
+    pub fn validate(&self, struct_impl: &Eip712StructImplementation) -> Result<(), String> {
+        let mut leaves = Vec::new();
+        let complete = self.leaf_types(&struct_impl.name, "", &mut leaves)?;
+
+        if complete && leaves.len() != struct_impl.values.len() {
+            return Err(format!(
+                "Struct '{}' expects {} values, got {}",
+                struct_impl.name,
+                leaves.len(),
+                struct_impl.values.len()
+            ));
+        }
+
+        for (index, ((path, field_type), value)) in
+            leaves.iter().zip(&struct_impl.values).enumerate()
+        {
+            check_value(field_type, value).map_err(|e| {
+                format!(
+                    "Struct '{}' field {} ({}): {}",
+                    struct_impl.name, index, path, e
+                )
+            })?;
+        }
+
+        Ok(())
+    }
+
+    /// Collect the leaf field types of `name` in send order
+    ///
+    /// Returns false if an array field cut the walk short.
+    fn leaf_types(
+        &self,
+        name: &str,
+        prefix: &str,
+        leaves: &mut Vec<(String, Eip712FieldType)>,
+    ) -> Result<bool, String> {
+        let definition = self
+            .definitions
+            .get(name)
+            .ok_or_else(|| format!("Struct '{}' was not defined in this session", name))?;
+
+        for field in &definition.fields {
+            let path = if prefix.is_empty() {
+                field.name.clone()
+            } else {
+                format!("{}.{}", prefix, field.name)
+            };
+
+            if field.is_array() {
+                return Ok(false);
+            }
+            match &field.field_type {
+                Eip712FieldType::Custom(inner) => {
+                    if !self.leaf_types(inner, &path, leaves)? {
+                        return Ok(false);
+                    }
+                }
+                field_type => leaves.push((path, field_type.clone())),
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+/// Check that an encoded value has the shape of its declared type
+fn check_value(field_type: &Eip712FieldType, value: &Eip712FieldValue) -> Result<(), String> {
+    let len = value.value.len();
+    match field_type {
+        Eip712FieldType::Address if len != 20 => {
+            Err(format!("expected 20-byte address, got {} bytes", len))
+        }
+        Eip712FieldType::Bool if len != 1 || value.value[0] > 1 => {
+            Err(format!("expected 1-byte bool, got {:02x?}", value.value))
+        }
+        Eip712FieldType::FixedBytes(size) if len != *size as usize => {
+            Err(format!("expected bytes{}, got {} bytes", size, len))
+        }
+        Eip712FieldType::Uint(size) | Eip712FieldType::Int(size) if len > *size as usize => {
+            Err(format!(
+                "{} bytes do not fit {}",
+                len,
+                field_type.to_solidity_string()
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger_sdk_transport::mock::MockExchange;
+
+    use super::*;
+    use crate::instructions::ins;
+    use crate::types::{AppConfiguration, AppVersion, ConfigFlags, Eip712FieldDefinition};
+    use crate::{App, EthApp};
+
+    fn app() -> EthereumApp<MockExchange> {
+        EthereumApp::new(MockExchange::new().with_responder(|command| {
+            let mut answer = if command.ins == ins::GET_APP_CONFIGURATION {
+                AppConfiguration {
+                    flags: ConfigFlags::from_byte(0),
+                    version: AppVersion::new(1, 12, 0),
+                }
+                .to_response_bytes()
+            } else {
+                Vec::new()
+            };
+            answer.extend_from_slice(&[0x90, 0x00]);
+            Ok(answer)
+        }))
+    }
+
+    fn definitions() -> Vec<Eip712StructDefinition> {
+        vec![
+            Eip712StructDefinition::new("Person".to_string())
+                .with_field(Eip712FieldDefinition::new(
+                    Eip712FieldType::String,
+                    "name".to_string(),
+                ))
+                .with_field(Eip712FieldDefinition::new(
+                    Eip712FieldType::Address,
+                    "wallet".to_string(),
+                )),
+            Eip712StructDefinition::new("Mail".to_string())
+                .with_field(Eip712FieldDefinition::new(
+                    Eip712FieldType::Custom("Person".to_string()),
+                    "from".to_string(),
+                ))
+                .with_field(Eip712FieldDefinition::new(
+                    Eip712FieldType::Uint(8),
+                    "nonce".to_string(),
+                ))
+                .with_field(Eip712FieldDefinition::new(
+                    Eip712FieldType::Bool,
+                    "urgent".to_string(),
+                )),
+        ]
+    }
+
+    fn mail(values: Vec<Eip712FieldValue>) -> Eip712StructImplementation {
+        Eip712StructImplementation {
+            name: "Mail".to_string(),
+            values,
+        }
+    }
+
+    fn valid_values() -> Vec<Eip712FieldValue> {
+        vec![
+            Eip712FieldValue::from_string("Cow"),
+            Eip712FieldValue::new(vec![0xCD; 20]),
+            Eip712FieldValue::new(vec![0x01, 0x00]),
+            Eip712FieldValue::new(vec![0x01]),
+        ]
+    }
+
+    #[tokio::test]
+    async fn test_session_validates_implementations() {
+        let app = app();
+        let mut session = Eip712Session::new(&app);
+        for definition in &definitions() {
+            session.send_struct_definition(definition).await.unwrap();
+        }
+
+        session
+            .send_struct_implementation(&mail(valid_values()))
+            .await
+            .unwrap();
+
+        let mut short = valid_values();
+        short.pop();
+        assert_eq!(
+            session.validate(&mail(short)).unwrap_err(),
+            "Struct 'Mail' expects 4 values, got 3"
+        );
+
+        let mut bad_address = valid_values();
+        bad_address[1] = Eip712FieldValue::new(vec![0xCD; 19]);
+        assert_eq!(
+            session.validate(&mail(bad_address)).unwrap_err(),
+            "Struct 'Mail' field 1 (from.wallet): expected 20-byte address, got 19 bytes"
+        );
+
+        let mut bad_bool = valid_values();
+        bad_bool[3] = Eip712FieldValue::new(vec![0x02]);
+        assert!(session.validate(&mail(bad_bool)).is_err());
+
+        let mut wide = valid_values();
+        wide[2] = Eip712FieldValue::new(vec![0x01; 9]);
+        let sent_before = app.transport().sent().len();
+        let err = session
+            .send_struct_implementation(&mail(wide))
+            .await
+            .unwrap_err();
+        assert!(
+            matches!(&err, EthAppError::Eip712StructError(msg) if msg.contains("field 2 (nonce)"))
+        );
+        assert_eq!(app.transport().sent().len(), sent_before);
+
+        let unknown = Eip712StructImplementation::new("Order".to_string());
+        assert!(session.validate(&unknown).is_err());
+
+        // Raw calls bypass the session: one root frame on top of the valid Mail's five
+        app.send_struct_implementation(&unknown).await.unwrap();
+        assert_eq!(
+            app.transport()
+                .count(EthApp::CLA, ins::EIP712_SEND_STRUCT_IMPLEMENTATION),
+            6
+        );
+    }
+
+    #[test]
+    fn test_check_value_widths() {
+        let value = |len| Eip712FieldValue::new(vec![0; len]);
+        assert!(check_value(&Eip712FieldType::FixedBytes(32), &value(32)).is_ok());
+        assert!(check_value(&Eip712FieldType::FixedBytes(32), &value(31)).is_err());
+        assert!(check_value(&Eip712FieldType::Int(4), &value(4)).is_ok());
+        assert!(check_value(&Eip712FieldType::Int(4), &value(5)).is_err());
+        assert!(check_value(&Eip712FieldType::String, &value(0)).is_ok());
+        assert!(check_value(&Eip712FieldType::DynamicBytes, &value(300)).is_ok());
+    }
+}
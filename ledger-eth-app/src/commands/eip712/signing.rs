@@ -8,26 +8,20 @@ use async_trait::async_trait;
 use ledger_sdk_device_base::{App, AppExt};
 use ledger_sdk_transport::{APDUCommand, Exchange};
 
-use crate::errors::{EthAppError, EthAppResult};
+use crate::errors::{
+    map_exchange_error, map_gated_error, map_ledger_error, EthAppError, EthAppResult,
+};
 use crate::instructions::{ins, length, p1_sign_eip712, p2_sign_eip712};
-use crate::types::{BipPath, SignEip712Params, Signature};
-use crate::utils::{encode_bip32_path, validate_bip32_path};
+use crate::trace;
+use crate::types::{BipPath, PathPolicy, SignEip712Params, Signature};
+use crate::utils::{encode_bip32_path, validate_bip32_path_with_policy};
 use crate::EthApp;
 
 /// Parse signature response data
-pub fn parse_signature_response<E: std::error::Error>(data: &[u8]) -> EthAppResult<Signature, E> {
-    if data.len() != 65 {
-        return Err(EthAppError::InvalidResponseData(format!(
-            "Invalid signature response length: {} bytes (expected 65)",
-            data.len()
-        )));
-    }
-    let v = data[0];
-    let r = data[1..33].to_vec();
-    let s = data[33..65].to_vec();
-
-    Signature::new(v, r, s).map_err(|e| EthAppError::InvalidSignature(e))
-}
+///
+/// Kept here for the `commands::eip712::parse_signature_response` path; the
+/// implementation is shared by every signing command.
+pub use crate::utils::parse_signature_response;
 
 /// EIP-712 full implementation trait
 #[async_trait]
@@ -37,7 +31,14 @@ where
     E::Error: std::error::Error,
 {
     /// Sign an EIP-712 message using full implementation
-    async fn sign_eip712_full(transport: &E, path: &BipPath) -> EthAppResult<Signature, E::Error>;
+    ///
+    /// `path` is checked against `path_policy` before anything is sent. The
+    /// returned `v` is always 27 or 28.
+    async fn sign_eip712_full(
+        transport: &E,
+        path: &BipPath,
+        path_policy: PathPolicy,
+    ) -> EthAppResult<Signature, E::Error>;
 }
 
 #[async_trait]
@@ -46,30 +47,49 @@ where
     E: Exchange + Send + Sync,
     E::Error: std::error::Error,
 {
-    async fn sign_eip712_full(transport: &E, path: &BipPath) -> EthAppResult<Signature, E::Error> {
+    async fn sign_eip712_full(
+        transport: &E,
+        path: &BipPath,
+        path_policy: PathPolicy,
+    ) -> EthAppResult<Signature, E::Error> {
         // Validate BIP32 path
-        validate_bip32_path(path)?;
+        validate_bip32_path_with_policy(path, path_policy)?;
 
-        let path_data = encode_bip32_path(path);
+        send_sign_command(transport, &sign_full_command(path)).await
+    }
+}
 
-        let command = APDUCommand {
-            cla: Self::CLA,
-            ins: ins::SIGN_ETH_EIP712,
-            p1: p1_sign_eip712::FIRST_CHUNK,
-            p2: p2_sign_eip712::FULL_IMPLEMENTATION,
-            data: path_data,
-        };
+/// Command signing the message sent with the full implementation
+pub(crate) fn sign_full_command(path: &BipPath) -> APDUCommand<Vec<u8>> {
+    APDUCommand {
+        cla: EthApp::CLA,
+        ins: ins::SIGN_ETH_EIP712,
+        p1: p1_sign_eip712::FIRST_CHUNK,
+        p2: p2_sign_eip712::FULL_IMPLEMENTATION,
+        data: encode_bip32_path(path),
+    }
+}
 
-        let response = transport
-            .exchange(&command)
-            .await
-            .map_err(|e| EthAppError::Transport(e.into()))?;
+/// Send a SIGN ETH EIP 712 command and parse the signature it answers
+pub(crate) async fn send_sign_command<E>(
+    transport: &E,
+    command: &APDUCommand<Vec<u8>>,
+) -> EthAppResult<Signature, E::Error>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    let response = trace::exchange(transport, command)
+        .await
+        .map_err(map_exchange_error)?;
 
-        <EthApp as AppExt<E>>::handle_response_error(&response).map_err(EthAppError::Transport)?;
+    <EthApp as AppExt<E>>::handle_response_error(&response)
+        .map_err(map_gated_error("EIP-712", map_ledger_error))?;
 
-        // Parse signature from response
-        parse_signature_response::<E::Error>(response.data())
-    }
+    // EIP-712 signatures are not EIP-155 encoded: report v as 27/28
+    parse_signature_response::<E::Error>(response.data())?
+        .normalize_v(None)
+        .map_err(EthAppError::InvalidSignature)
 }
 
 /// EIP-712 v0 signing trait (simple domain + message hash mode)
@@ -80,6 +100,8 @@ where
     E::Error: std::error::Error,
 {
     /// Sign an EIP-712 message using v0 implementation (domain hash + message hash)
+    ///
+    /// The returned `v` is always 27 or 28.
     async fn sign_eip712_v0(
         transport: &E,
         params: SignEip712Params,
@@ -97,7 +119,7 @@ where
         params: SignEip712Params,
     ) -> EthAppResult<Signature, E::Error> {
         // Validate BIP32 path
-        validate_bip32_path(&params.path)?;
+        validate_bip32_path_with_policy(&params.path, params.path_policy)?;
 
         // Validate hash sizes
         if params.domain_hash.len() != length::EIP712_DOMAIN_HASH_SIZE {
@@ -131,14 +153,45 @@ where
             data: command_data,
         };
 
-        let response = transport
-            .exchange(&command)
-            .await
-            .map_err(|e| EthAppError::Transport(e.into()))?;
+        send_sign_command(transport, &command).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger_sdk_transport::mock::MockExchange;
 
-        <EthApp as AppExt<E>>::handle_response_error(&response).map_err(EthAppError::Transport)?;
+    use super::*;
+
+    fn device_signature(v: u8) -> Vec<u8> {
+        let mut answer = vec![v];
+        answer.extend_from_slice(&[0x11; 32]);
+        answer.extend_from_slice(&[0x22; 32]);
+        answer
+    }
 
-        // Parse signature from response
-        parse_signature_response::<E::Error>(response.data())
+    #[tokio::test]
+    async fn test_v_normalized_to_27_28() {
+        let path = BipPath::ethereum_standard(0, 0);
+        let mock = MockExchange::new();
+
+        for (v, expected) in [(0, 27), (1, 28)] {
+            mock.push_ok(&device_signature(v));
+            let signature = EthApp::sign_eip712_full(&mock, &path, PathPolicy::Strict)
+                .await
+                .unwrap();
+            assert_eq!(signature.v, expected);
+
+            mock.push_ok(&device_signature(v));
+            let params = SignEip712Params::new(path.clone(), [0xAA; 32], [0xBB; 32]);
+            let signature = EthApp::sign_eip712_v0(&mock, params).await.unwrap();
+            assert_eq!(signature.v, expected);
+        }
+
+        mock.push_ok(&device_signature(37));
+        let err = EthApp::sign_eip712_full(&mock, &path, PathPolicy::Strict)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthAppError::InvalidSignature(_)));
     }
 }
@@ -8,12 +8,18 @@ use async_trait::async_trait;
 use ledger_sdk_device_base::{App, AppExt};
 use ledger_sdk_transport::{APDUCommand, Exchange};
 
-use crate::commands::eip712::encoding::{encode_field_definition, APDU_MAX_PAYLOAD};
-use crate::errors::{EthAppError, EthAppResult};
+use crate::commands::eip712::encoding::{
+    encode_array_size, encode_field_definition, APDU_MAX_PAYLOAD,
+};
+use crate::errors::{
+    map_exchange_error, map_gated_error, map_ledger_error, EthAppError, EthAppResult,
+};
 use crate::instructions::{
     ins, p1_eip712_struct_impl, p2_eip712_struct_def, p2_eip712_struct_impl,
 };
-use crate::types::{Eip712StructDefinition, Eip712StructImplementation};
+use crate::trace;
+use crate::types::{Eip712FieldValue, Eip712StructDefinition, Eip712StructImplementation};
+use crate::utils::ChunkedPayload;
 use crate::EthApp;
 
 /// EIP-712 struct definition trait
@@ -40,44 +46,8 @@ where
         transport: &E,
         struct_def: &Eip712StructDefinition,
     ) -> EthAppResult<(), E::Error> {
-        let struct_name_command = APDUCommand {
-            cla: Self::CLA,
-            ins: ins::EIP712_SEND_STRUCT_DEFINITION,
-            p1: 0x00,
-            p2: p2_eip712_struct_def::STRUCT_NAME,
-            data: struct_def.name.as_bytes(),
-        };
-
-        let response = transport
-            .exchange(&struct_name_command)
-            .await
-            .map_err(|e| EthAppError::Transport(e.into()))?;
-
-        <EthApp as AppExt<E>>::handle_response_error(&response)
-            .map_err(crate::errors::map_ledger_error)?;
-
-        // Send each field definition
-        for field in &struct_def.fields {
-            let encoded_field = encode_field_definition::<E::Error>(field)?;
-
-            let field_command = APDUCommand {
-                cla: Self::CLA,
-                ins: ins::EIP712_SEND_STRUCT_DEFINITION,
-                p1: 0x00,
-                p2: p2_eip712_struct_def::STRUCT_FIELD,
-                data: encoded_field,
-            };
-
-            let response = transport
-                .exchange(&field_command)
-                .await
-                .map_err(|e| EthAppError::Transport(e.into()))?;
-
-            <EthApp as AppExt<E>>::handle_response_error(&response)
-                .map_err(crate::errors::map_ledger_error)?;
-        }
-
-        Ok(())
+        let commands = struct_definition_commands::<E::Error>(struct_def)?;
+        send_commands(transport, &commands).await
     }
 }
 
@@ -95,7 +65,10 @@ where
     ) -> EthAppResult<(), E::Error>;
 
     /// Set array size for upcoming array fields
-    async fn set_array_size(transport: &E, size: u8) -> EthAppResult<(), E::Error>;
+    ///
+    /// Sizes up to 255 are sent as 1 byte, larger ones as 2 big-endian bytes,
+    /// which only app versions >= 1.17.0 accept.
+    async fn set_array_size(transport: &E, size: usize) -> EthAppResult<(), E::Error>;
 }
 
 #[async_trait]
@@ -108,81 +81,221 @@ where
         transport: &E,
         struct_impl: &Eip712StructImplementation,
     ) -> EthAppResult<(), E::Error> {
-        let struct_name_command = APDUCommand {
-            cla: Self::CLA,
-            ins: ins::EIP712_SEND_STRUCT_IMPLEMENTATION,
-            p1: p1_eip712_struct_impl::COMPLETE_SEND,
-            p2: p2_eip712_struct_impl::ROOT_STRUCT,
-            data: struct_impl.name.as_bytes(),
-        };
+        let commands = struct_implementation_commands::<E::Error>(struct_impl)?;
+        send_commands(transport, &commands).await
+    }
+
+    async fn set_array_size(transport: &E, size: usize) -> EthAppResult<(), E::Error> {
+        let command = array_size_command::<E::Error>(size)?;
+        send_commands(transport, &[command]).await
+    }
+}
+
+/// Struct names are sent in a single APDU and must fit its payload
+///
+/// The limit is on UTF-8 bytes, so non-ASCII names hit it with fewer characters.
+fn check_struct_name<E: std::error::Error>(name: &str) -> EthAppResult<(), E> {
+    if name.len() > APDU_MAX_PAYLOAD {
+        return Err(EthAppError::Eip712StructError(format!(
+            "Struct name is {} bytes in UTF-8, max {}",
+            name.len(),
+            APDU_MAX_PAYLOAD
+        )));
+    }
+    Ok(())
+}
+
+/// Struct name command followed by one command per field definition
+pub(crate) fn struct_definition_commands<E: std::error::Error>(
+    struct_def: &Eip712StructDefinition,
+) -> EthAppResult<Vec<APDUCommand<Vec<u8>>>, E> {
+    check_struct_name::<E>(&struct_def.name)?;
+
+    let mut commands = vec![APDUCommand {
+        cla: EthApp::CLA,
+        ins: ins::EIP712_SEND_STRUCT_DEFINITION,
+        p1: 0x00,
+        p2: p2_eip712_struct_def::STRUCT_NAME,
+        data: struct_def.name.as_bytes().to_vec(),
+    }];
+    for field in &struct_def.fields {
+        commands.push(APDUCommand {
+            cla: EthApp::CLA,
+            ins: ins::EIP712_SEND_STRUCT_DEFINITION,
+            p1: 0x00,
+            p2: p2_eip712_struct_def::STRUCT_FIELD,
+            data: encode_field_definition::<E>(field)?,
+        });
+    }
+    Ok(commands)
+}
+
+/// Root struct command followed by the commands of every field value
+pub(crate) fn struct_implementation_commands<E: std::error::Error>(
+    struct_impl: &Eip712StructImplementation,
+) -> EthAppResult<Vec<APDUCommand<Vec<u8>>>, E> {
+    let mut commands = vec![root_struct_command::<E>(&struct_impl.name)?];
+    for value in &struct_impl.values {
+        commands.extend(struct_field_commands(value));
+    }
+    Ok(commands)
+}
+
+/// Command selecting the root struct of an implementation
+pub(crate) fn root_struct_command<E: std::error::Error>(
+    name: &str,
+) -> EthAppResult<APDUCommand<Vec<u8>>, E> {
+    // The root struct name is a single COMPLETE_SEND frame, the app does not
+    // accept it split over PARTIAL_SEND frames like field values
+    check_struct_name::<E>(name)?;
+
+    Ok(APDUCommand {
+        cla: EthApp::CLA,
+        ins: ins::EIP712_SEND_STRUCT_IMPLEMENTATION,
+        p1: p1_eip712_struct_impl::COMPLETE_SEND,
+        p2: p2_eip712_struct_impl::ROOT_STRUCT,
+        data: name.as_bytes().to_vec(),
+    })
+}
 
-        let response = transport
-            .exchange(&struct_name_command)
+/// Command announcing the size of the upcoming array
+pub(crate) fn array_size_command<E: std::error::Error>(
+    size: usize,
+) -> EthAppResult<APDUCommand<Vec<u8>>, E> {
+    Ok(APDUCommand {
+        cla: EthApp::CLA,
+        ins: ins::EIP712_SEND_STRUCT_IMPLEMENTATION,
+        p1: p1_eip712_struct_impl::PARTIAL_SEND,
+        p2: p2_eip712_struct_impl::ARRAY,
+        data: encode_array_size::<E>(size)?,
+    })
+}
+
+/// Commands of a single struct field value, chunked with a 2-byte big-endian length prefix
+pub(crate) fn struct_field_commands(value: &Eip712FieldValue) -> Vec<APDUCommand<Vec<u8>>> {
+    ChunkedPayload::new(
+        EthApp::CLA,
+        ins::EIP712_SEND_STRUCT_IMPLEMENTATION,
+        &value.value,
+    )
+    .with_p1(
+        p1_eip712_struct_impl::PARTIAL_SEND,
+        p1_eip712_struct_impl::PARTIAL_SEND,
+    )
+    .with_last_p1(p1_eip712_struct_impl::COMPLETE_SEND)
+    .with_p2(p2_eip712_struct_impl::STRUCT_FIELD)
+    .with_prefix((value.value.len() as u16).to_be_bytes().to_vec())
+    .commands()
+}
+
+/// Send `commands` in order, stopping at the first error status
+pub(crate) async fn send_commands<E>(
+    transport: &E,
+    commands: &[APDUCommand<Vec<u8>>],
+) -> EthAppResult<(), E::Error>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    for command in commands {
+        let response = trace::exchange(transport, command)
             .await
-            .map_err(|e| EthAppError::Transport(e.into()))?;
+            .map_err(map_exchange_error)?;
 
         <EthApp as AppExt<E>>::handle_response_error(&response)
-            .map_err(crate::errors::map_ledger_error)?;
-
-        // Send each field value as FIELD type
-        for value in struct_impl.values.iter() {
-            // Encode field value with a 2-byte big-endian length prefix
-            let mut buffer = Vec::with_capacity(2 + value.value.len());
-            buffer.extend_from_slice(&(value.value.len() as u16).to_be_bytes());
-            buffer.extend_from_slice(&value.value);
-
-            // Chunk the buffer into APDU_MAX_PAYLOAD-sized frames
-            let mut offset = 0usize;
-            while offset < buffer.len() {
-                let end = core::cmp::min(offset + APDU_MAX_PAYLOAD, buffer.len());
-                let chunk = &buffer[offset..end];
-                let is_last_chunk = end == buffer.len();
-
-                let p1 = if is_last_chunk {
-                    p1_eip712_struct_impl::COMPLETE_SEND
-                } else {
-                    p1_eip712_struct_impl::PARTIAL_SEND
-                };
-
-                let field_command = APDUCommand {
-                    cla: Self::CLA,
-                    ins: ins::EIP712_SEND_STRUCT_IMPLEMENTATION,
-                    p1,
-                    p2: p2_eip712_struct_impl::STRUCT_FIELD,
-                    data: chunk,
-                };
-
-                let response = transport
-                    .exchange(&field_command)
-                    .await
-                    .map_err(|e| EthAppError::Transport(e.into()))?;
-
-                <EthApp as AppExt<E>>::handle_response_error(&response)
-                    .map_err(EthAppError::Transport)?;
-
-                offset = end;
-            }
-        }
-
-        Ok(())
+            .map_err(map_gated_error("EIP-712", map_ledger_error))?;
     }
+    Ok(())
+}
 
-    async fn set_array_size(transport: &E, size: u8) -> EthAppResult<(), E::Error> {
-        let command = APDUCommand {
-            cla: Self::CLA,
-            ins: ins::EIP712_SEND_STRUCT_IMPLEMENTATION,
-            p1: p1_eip712_struct_impl::PARTIAL_SEND,
-            p2: p2_eip712_struct_impl::ARRAY,
-            data: vec![size],
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ledger_sdk_transport::mock::MockExchange;
+
+    #[tokio::test]
+    async fn test_struct_name_too_long() {
+        let name = "A".repeat(300);
+        let mock = MockExchange::new();
+
+        let struct_impl = Eip712StructImplementation {
+            name: name.clone(),
+            values: Vec::new(),
         };
+        let err = EthApp::send_struct_implementation(&mock, &struct_impl)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthAppError::Eip712StructError(_)));
 
-        let response = transport
-            .exchange(&command)
+        let struct_def = Eip712StructDefinition::new(name);
+        let err = EthApp::send_struct_definition(&mock, &struct_def)
             .await
-            .map_err(|e| EthAppError::Transport(e.into()))?;
+            .unwrap_err();
+        assert!(matches!(err, EthAppError::Eip712StructError(_)));
 
-        <EthApp as AppExt<E>>::handle_response_error(&response).map_err(EthAppError::Transport)?;
+        // Rejected before anything reaches the device
+        assert!(mock.sent().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_multibyte_struct_name() {
+        let mock = MockExchange::new();
+        mock.push_ok(&[]);
+
+        // 85 characters, 255 bytes
+        let struct_def = Eip712StructDefinition::new("名".repeat(85));
+        EthApp::send_struct_definition(&mock, &struct_def)
+            .await
+            .unwrap();
+        assert_eq!(mock.sent()[0].data.len(), 255);
 
-        Ok(())
+        let struct_def = Eip712StructDefinition::new("名".repeat(86));
+        let err = EthApp::send_struct_definition(&mock, &struct_def)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            EthAppError::Eip712StructError(ref msg) if msg.contains("258 bytes")
+        ));
+        assert_eq!(mock.sent().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_array_size_encoding() {
+        let mock = MockExchange::new();
+        for size in [0, 255, 256, 65535] {
+            mock.push_ok(&[]);
+            EthApp::set_array_size(&mock, size).await.unwrap();
+        }
+        let data: Vec<Vec<u8>> = mock.sent().into_iter().map(|c| c.data).collect();
+        assert_eq!(
+            data,
+            vec![vec![0x00], vec![0xFF], vec![0x01, 0x00], vec![0xFF, 0xFF]]
+        );
+
+        let err = EthApp::set_array_size(&mock, 65536).await.unwrap_err();
+        assert_eq!(
+            err,
+            EthAppError::ArrayTooLarge {
+                size: 65536,
+                max: 65535
+            }
+        );
+        assert_eq!(mock.sent().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_struct_name_at_limit() {
+        let mock = MockExchange::new();
+        mock.push_ok(&[]);
+
+        let struct_impl = Eip712StructImplementation {
+            name: "A".repeat(APDU_MAX_PAYLOAD),
+            values: Vec::new(),
+        };
+        EthApp::send_struct_implementation(&mock, &struct_impl)
+            .await
+            .unwrap();
+        assert_eq!(mock.sent()[0].data.len(), APDU_MAX_PAYLOAD);
     }
 }
@@ -0,0 +1,182 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! GET ETH2 PUBLIC KEY and SET ETH2 WITHDRAWAL INDEX command implementations
+
+use async_trait::async_trait;
+use ledger_sdk_device_base::{App, AppExt};
+use ledger_sdk_transport::{APDUCommand, Exchange};
+
+use crate::errors::{map_ledger_error, EthAppError, EthAppResult};
+use crate::instructions::{ins, length, p1_get_eth2_key};
+use crate::trace;
+use crate::types::{BipPath, PathPolicy};
+use crate::utils::{encode_bip32_path, validate_bip32_path_with_policy};
+use crate::EthApp;
+
+#[async_trait]
+pub trait GetEth2PublicKey<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    /// Get the BLS12-381 validator public key for the given BIP 32 path
+    ///
+    /// ETH2 keys follow EIP-2334, e.g. `m/12381/3600/0/0`; other paths are
+    /// rejected under [`PathPolicy::Eth2Validator`].
+    async fn get_eth2_public_key(
+        transport: &E,
+        path: &BipPath,
+        display: bool,
+    ) -> EthAppResult<[u8; length::ETH2_PUBLIC_KEY_SIZE], E::Error>;
+}
+
+#[async_trait]
+pub trait SetEth2WithdrawalIndex<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    /// Set the index of the withdrawal key used by ETH2 deposits
+    async fn set_eth2_withdrawal_index(transport: &E, index: u32) -> EthAppResult<(), E::Error>;
+}
+
+#[async_trait]
+impl<E> GetEth2PublicKey<E> for EthApp
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    async fn get_eth2_public_key(
+        transport: &E,
+        path: &BipPath,
+        display: bool,
+    ) -> EthAppResult<[u8; length::ETH2_PUBLIC_KEY_SIZE], E::Error> {
+        validate_bip32_path_with_policy(path, PathPolicy::Eth2Validator)?;
+
+        let p1 = if display {
+            p1_get_eth2_key::DISPLAY_AND_CONFIRM
+        } else {
+            p1_get_eth2_key::RETURN_KEY
+        };
+        let command = APDUCommand {
+            cla: Self::CLA,
+            ins: ins::GET_ETH2_PUBLIC_KEY,
+            p1,
+            p2: 0x00,
+            data: encode_bip32_path(path),
+        };
+
+        let response = trace::exchange(transport, &command)
+            .await
+            .map_err(|e| EthAppError::Transport(e.into()))?;
+
+        <EthApp as AppExt<E>>::handle_response_error(&response).map_err(map_ledger_error)?;
+
+        parse_eth2_public_key(response.data())
+    }
+}
+
+#[async_trait]
+impl<E> SetEth2WithdrawalIndex<E> for EthApp
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    async fn set_eth2_withdrawal_index(transport: &E, index: u32) -> EthAppResult<(), E::Error> {
+        let command = APDUCommand {
+            cla: Self::CLA,
+            ins: ins::SET_ETH2_WITHDRAWAL_INDEX,
+            p1: 0x00,
+            p2: 0x00,
+            data: index.to_be_bytes().to_vec(),
+        };
+
+        let response = trace::exchange(transport, &command)
+            .await
+            .map_err(|e| EthAppError::Transport(e.into()))?;
+
+        <EthApp as AppExt<E>>::handle_response_error(&response).map_err(map_ledger_error)?;
+
+        Ok(())
+    }
+}
+
+/// Parse the raw 48-byte BLS public key returned by GET ETH2 PUBLIC KEY
+fn parse_eth2_public_key<E: std::error::Error>(
+    data: &[u8],
+) -> EthAppResult<[u8; length::ETH2_PUBLIC_KEY_SIZE], E> {
+    data.try_into().map_err(|_| {
+        EthAppError::InvalidResponseData(format!(
+            "Invalid ETH2 public key length: {} bytes (expected {})",
+            data.len(),
+            length::ETH2_PUBLIC_KEY_SIZE
+        ))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ledger_sdk_transport::mock::MockExchange;
+
+    fn eth2_path() -> BipPath {
+        BipPath::from_string("m/12381/3600/0/0").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_get_eth2_public_key() {
+        let mock = MockExchange::new();
+        mock.push_ok(&[0xA5; 48]).push_ok(&[0x5A; 48]);
+
+        let key = EthApp::get_eth2_public_key(&mock, &eth2_path(), false)
+            .await
+            .unwrap();
+        assert_eq!(key, [0xA5; 48]);
+        let key = EthApp::get_eth2_public_key(&mock, &eth2_path(), true)
+            .await
+            .unwrap();
+        assert_eq!(key, [0x5A; 48]);
+
+        let sent = mock.sent();
+        assert_eq!(sent[0].ins, ins::GET_ETH2_PUBLIC_KEY);
+        assert_eq!(sent[0].p1, p1_get_eth2_key::RETURN_KEY);
+        assert_eq!(sent[1].p1, p1_get_eth2_key::DISPLAY_AND_CONFIRM);
+        assert_eq!(
+            hex::encode(&sent[0].data),
+            "040000305d00000e100000000000000000"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_get_eth2_public_key_rejects_wrong_length() {
+        let mock = MockExchange::new();
+        mock.push_ok(&[0xA5; 47]).push_ok(&[0xA5; 49]);
+
+        for _ in 0..2 {
+            let err = EthApp::get_eth2_public_key(&mock, &eth2_path(), false)
+                .await
+                .unwrap_err();
+            assert!(matches!(err, EthAppError::InvalidResponseData(_)));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_set_eth2_withdrawal_index() {
+        let mock = MockExchange::new();
+        mock.push_ok(&[]).push_status(0x6A80);
+
+        EthApp::set_eth2_withdrawal_index(&mock, 0x0102_0304)
+            .await
+            .unwrap();
+        let err = EthApp::set_eth2_withdrawal_index(&mock, 7)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthAppError::DeviceStatus { sw: 0x6A80, .. }));
+
+        let sent = mock.sent();
+        assert_eq!(sent[0].ins, ins::SET_ETH2_WITHDRAWAL_INDEX);
+        assert_eq!((sent[0].p1, sent[0].p2), (0x00, 0x00));
+        assert_eq!(sent[0].data, vec![0x01, 0x02, 0x03, 0x04]);
+        assert_eq!(sent[1].data, vec![0x00, 0x00, 0x00, 0x07]);
+    }
+}
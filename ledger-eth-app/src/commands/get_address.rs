@@ -6,12 +6,13 @@ use async_trait::async_trait;
 use ledger_sdk_device_base::{App, AppExt};
 use ledger_sdk_transport::{APDUCommand, Exchange};
 
-use crate::errors::{EthAppError, EthAppResult};
+use crate::errors::{map_ledger_error, EthAppError, EthAppResult};
 use crate::instructions::{ins, p1_get_address, p2_get_address};
+use crate::trace;
 use crate::types::{GetAddressParams, PublicKeyInfo};
 use crate::utils::{
     encode_bip32_path, encode_chain_id, parse_device_address, parse_device_chain_code,
-    parse_device_public_key, validate_bip32_path,
+    parse_device_public_key, validate_bip32_path_with_policy,
 };
 use crate::EthApp;
 
@@ -39,8 +40,22 @@ where
         params: GetAddressParams,
     ) -> EthAppResult<PublicKeyInfo, E::Error> {
         // Validate BIP32 path
-        validate_bip32_path(&params.path)?;
+        validate_bip32_path_with_policy(&params.path, params.path_policy)?;
 
+        Self::get_address_validated(transport, &params).await
+    }
+}
+
+impl EthApp {
+    /// GET ETH PUBLIC ADDRESS for a path already checked against its policy
+    pub(crate) async fn get_address_validated<E>(
+        transport: &E,
+        params: &GetAddressParams,
+    ) -> EthAppResult<PublicKeyInfo, E::Error>
+    where
+        E: Exchange + Send + Sync,
+        E::Error: std::error::Error,
+    {
         // Prepare command data
         let mut data = Vec::new();
 
@@ -68,7 +83,7 @@ where
 
         // Build APDU command
         let command = APDUCommand {
-            cla: Self::CLA,
+            cla: EthApp::CLA,
             ins: ins::GET_ETH_PUBLIC_ADDRESS,
             p1,
             p2,
@@ -76,23 +91,29 @@ where
         };
 
         // Send command and get response
-        let response = transport
-            .exchange(&command)
+        let response = trace::exchange(transport, &command)
             .await
             .map_err(|e| EthAppError::Transport(e.into()))?;
 
         // Handle APDU response
-        <EthApp as AppExt<E>>::handle_response_error(&response).map_err(EthAppError::Transport)?;
+        <EthApp as AppExt<E>>::handle_response_error(&response).map_err(map_ledger_error)?;
 
         // Parse response data
-        parse_get_address_response::<E::Error>(response.data(), params.return_chain_code)
+        parse_get_address_response::<E::Error>(
+            response.data(),
+            params.return_chain_code,
+            params.strict,
+        )
     }
 }
 
 /// Parse GET ETH PUBLIC ADDRESS response data
+///
+/// Bytes after the last expected field are ignored unless `strict` is set.
 fn parse_get_address_response<E: std::error::Error>(
     data: &[u8],
     return_chain_code: bool,
+    strict: bool,
 ) -> EthAppResult<PublicKeyInfo, E> {
     let mut offset = 0;
 
@@ -105,12 +126,24 @@ fn parse_get_address_response<E: std::error::Error>(
     offset = new_offset;
 
     // Parse optional chain code
-    let (chain_code, _) = if return_chain_code {
+    let (chain_code, offset) = if return_chain_code {
         parse_device_chain_code(data, offset)?
     } else {
         (None, offset)
     };
 
+    if strict && offset < data.len() {
+        return Err(EthAppError::InvalidResponseData(format!(
+            "Unexpected {} trailing bytes after {}",
+            data.len() - offset,
+            if return_chain_code {
+                "chain code"
+            } else {
+                "address"
+            }
+        )));
+    }
+
     Ok(PublicKeyInfo {
         public_key,
         address,
@@ -136,7 +169,7 @@ mod tests {
         response_data.push(42); // address length
         response_data.extend(b"0x742d35Cc6535C244B8c80A79d5d22efeAdBA5B90");
 
-        let result = parse_get_address_response::<std::io::Error>(&response_data, false);
+        let result = parse_get_address_response::<std::io::Error>(&response_data, false, false);
         assert!(result.is_ok());
 
         let public_key_info = result.unwrap();
@@ -164,7 +197,7 @@ mod tests {
         // Chain code (32 bytes)
         response_data.extend(vec![0xAB; 32]);
 
-        let result = parse_get_address_response::<std::io::Error>(&response_data, true);
+        let result = parse_get_address_response::<std::io::Error>(&response_data, true, false);
         assert!(result.is_ok());
 
         let public_key_info = result.unwrap();
@@ -177,6 +210,31 @@ mod tests {
         assert_eq!(public_key_info.chain_code.unwrap().len(), 32);
     }
 
+    #[test]
+    fn test_parse_get_address_response_trailing_bytes() {
+        let mut response_data = vec![65];
+        response_data.extend(vec![0x04; 65]);
+        response_data.push(42);
+        response_data.extend(b"0x742d35Cc6535C244B8c80A79d5d22efeAdBA5B90");
+        response_data.extend(vec![0xAB; 32]);
+
+        // Chain code not requested: lenient parsing ignores it, strict parsing rejects it
+        let lenient = parse_get_address_response::<std::io::Error>(&response_data, false, false);
+        assert!(lenient.unwrap().chain_code.is_none());
+        let strict = parse_get_address_response::<std::io::Error>(&response_data, false, true);
+        assert!(matches!(
+            strict,
+            Err(EthAppError::InvalidResponseData(msg)) if msg.contains("32 trailing bytes")
+        ));
+
+        // Everything consumed
+        assert!(parse_get_address_response::<std::io::Error>(&response_data, true, true).is_ok());
+
+        response_data.push(0x00);
+        assert!(parse_get_address_response::<std::io::Error>(&response_data, true, false).is_ok());
+        assert!(parse_get_address_response::<std::io::Error>(&response_data, true, true).is_err());
+    }
+
     #[test]
     fn test_get_address_params() {
         let path = BipPath::ethereum_standard(0, 0);
@@ -189,5 +247,7 @@ mod tests {
         assert!(params.display);
         assert!(params.return_chain_code);
         assert_eq!(params.chain_id, Some(1));
+        assert!(!params.strict);
+        assert!(params.with_strict_parsing().strict);
     }
 }
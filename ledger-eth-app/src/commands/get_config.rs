@@ -6,8 +6,9 @@ use async_trait::async_trait;
 use ledger_sdk_device_base::{App, AppExt};
 use ledger_sdk_transport::{APDUCommand, Exchange};
 
-use crate::errors::{EthAppError, EthAppResult};
+use crate::errors::{map_ledger_error, EthAppError, EthAppResult};
 use crate::instructions::ins;
+use crate::trace;
 use crate::types::{AppConfiguration, AppVersion, ConfigFlags};
 use crate::EthApp;
 
@@ -38,13 +39,12 @@ where
         };
 
         // Send command and get response
-        let response = transport
-            .exchange(&command)
+        let response = trace::exchange(transport, &command)
             .await
             .map_err(|e| EthAppError::Transport(e.into()))?;
 
         // Handle APDU response
-        <EthApp as AppExt<E>>::handle_response_error(&response).map_err(EthAppError::Transport)?;
+        <EthApp as AppExt<E>>::handle_response_error(&response).map_err(map_ledger_error)?;
 
         // Parse response data
         parse_get_configuration_response::<E::Error>(response.data())
@@ -190,4 +190,36 @@ mod tests {
             config_flags::ARBITRARY_DATA_SIGNATURE | config_flags::TRANSACTION_CHECK_ENABLED
         );
     }
+
+    #[test]
+    fn test_to_response_bytes_round_trip() {
+        let config = AppConfiguration {
+            flags: ConfigFlags {
+                arbitrary_data_signature: true,
+                erc20_external_info: false,
+                transaction_check_enabled: false,
+                transaction_check_opt_in: true,
+            },
+            version: AppVersion {
+                major: 1,
+                minor: 14,
+                patch: 2,
+            },
+        };
+
+        let bytes = config.to_response_bytes();
+        assert_eq!(
+            bytes,
+            vec![
+                config_flags::ARBITRARY_DATA_SIGNATURE | config_flags::TRANSACTION_CHECK_OPT_IN,
+                1,
+                14,
+                2
+            ]
+        );
+        assert_eq!(
+            parse_get_configuration_response::<std::io::Error>(&bytes).unwrap(),
+            config
+        );
+    }
 }
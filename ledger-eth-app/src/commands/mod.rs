@@ -3,13 +3,115 @@
 //! Command implementations for Ethereum application
 
 pub mod eip712;
+pub mod eth2;
 pub mod get_address;
 pub mod get_config;
+pub mod privacy_operation;
+pub mod provide_network_info;
+pub mod provide_nft_info;
+pub mod provide_safe_account;
+pub mod provide_tx_simulation;
+pub mod set_plugin;
+pub mod sign_authorization;
 pub mod sign_message;
 pub mod sign_transaction;
 
 pub use eip712::*;
+pub use eth2::*;
 pub use get_address::*;
 pub use get_config::*;
+pub use privacy_operation::*;
+pub use provide_network_info::*;
+pub use provide_nft_info::*;
+pub use provide_safe_account::*;
+pub use provide_tx_simulation::*;
+pub use set_plugin::*;
+pub use sign_authorization::*;
 pub use sign_message::*;
 pub use sign_transaction::*;
+
+#[cfg(test)]
+mod tests {
+    #[tokio::test]
+    async fn test_user_rejection_from_every_command() {
+        use ledger_sdk_transport::mock::MockExchange;
+
+        use crate::types::{
+            BipPath, Eip7702Authorization, GetAddressParams, PathPolicy, PrivacyOperationParams,
+            SignEip712Params, SignMessageParams, SignTransactionParams,
+        };
+        use crate::{
+            EthApp, GetAddress, GetConfiguration, GetEth2PublicKey, PerformPrivacyOperation,
+            SetEth2WithdrawalIndex, SignAuthorization, SignEip712Full, SignEip712V0,
+            SignPersonalMessage, SignTransaction,
+        };
+
+        let path = BipPath::ethereum_standard(0, 0);
+        let eth2_path = BipPath::from_string("m/12381/3600/0/0").unwrap();
+        // Canceled (0x6982) and rejected on the device (0x6985)
+        for status in [0x6982, 0x6985] {
+            let rejecting = || {
+                let mock = MockExchange::new();
+                mock.push_status(status);
+                mock
+            };
+
+            let results = [
+                EthApp::get_address(&rejecting(), GetAddressParams::new(path.clone()))
+                    .await
+                    .map(drop),
+                EthApp::get_configuration(&rejecting()).await.map(drop),
+                EthApp::sign_transaction(
+                    &rejecting(),
+                    SignTransactionParams::new(path.clone(), vec![0xE5; 10]),
+                )
+                .await
+                .map(drop),
+                EthApp::sign_personal_message(
+                    &rejecting(),
+                    SignMessageParams::new(path.clone(), b"hello".to_vec()),
+                )
+                .await
+                .map(drop),
+                EthApp::sign_authorization(
+                    &rejecting(),
+                    &path,
+                    &Eip7702Authorization::new(1, [0x11; 20], 0),
+                    PathPolicy::default(),
+                )
+                .await
+                .map(drop),
+                EthApp::sign_eip712_v0(
+                    &rejecting(),
+                    SignEip712Params::new(path.clone(), [0x01; 32], [0x02; 32]),
+                )
+                .await
+                .map(drop),
+                EthApp::sign_eip712_full(&rejecting(), &path, PathPolicy::default())
+                    .await
+                    .map(drop),
+                EthApp::get_eth2_public_key(&rejecting(), &eth2_path, false)
+                    .await
+                    .map(drop),
+                EthApp::set_eth2_withdrawal_index(&rejecting(), 0).await,
+                EthApp::perform_privacy_operation(
+                    &rejecting(),
+                    PrivacyOperationParams::new(path.clone()),
+                )
+                .await
+                .map(drop),
+            ];
+
+            for (index, result) in results.into_iter().enumerate() {
+                let err = result.unwrap_err();
+                assert!(
+                    err.is_user_rejected(),
+                    "{:#06x}, command {}: {:?}",
+                    status,
+                    index,
+                    err
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,155 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! PERFORM PRIVACY OPERATION command implementation
+
+use async_trait::async_trait;
+use ledger_sdk_device_base::{App, AppExt};
+use ledger_sdk_transport::{APDUCommand, Exchange};
+
+use crate::errors::{map_ledger_error, EthAppError, EthAppResult};
+use crate::instructions::{ins, p1_privacy_operation, p2_privacy_operation};
+use crate::trace;
+use crate::types::PrivacyOperationParams;
+use crate::utils::{encode_bip32_path, validate_bip32_path_with_policy};
+use crate::EthApp;
+
+#[async_trait]
+pub trait PerformPrivacyOperation<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    /// Get the public encryption key or a shared secret for the given BIP 32 path
+    ///
+    /// Returns the raw bytes sent back by the device.
+    async fn perform_privacy_operation(
+        transport: &E,
+        params: PrivacyOperationParams,
+    ) -> EthAppResult<Vec<u8>, E::Error>;
+}
+
+#[async_trait]
+impl<E> PerformPrivacyOperation<E> for EthApp
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    async fn perform_privacy_operation(
+        transport: &E,
+        params: PrivacyOperationParams,
+    ) -> EthAppResult<Vec<u8>, E::Error> {
+        validate_bip32_path_with_policy(&params.path, params.path_policy)?;
+
+        let (p1, p2, data) = encode_privacy_operation(&params);
+        let command = APDUCommand {
+            cla: Self::CLA,
+            ins: ins::PERFORM_PRIVACY_OPERATION,
+            p1,
+            p2,
+            data,
+        };
+
+        let response = trace::exchange(transport, &command)
+            .await
+            .map_err(|e| EthAppError::Transport(e.into()))?;
+
+        <EthApp as AppExt<E>>::handle_response_error(&response).map_err(map_ledger_error)?;
+
+        Ok(response.data().to_vec())
+    }
+}
+
+/// P1, P2 and data for a PERFORM PRIVACY OPERATION request
+fn encode_privacy_operation(params: &PrivacyOperationParams) -> (u8, u8, Vec<u8>) {
+    let p1 = if params.display {
+        p1_privacy_operation::DISPLAY_AND_CONFIRM
+    } else {
+        p1_privacy_operation::RETURN_DATA
+    };
+
+    let mut data = encode_bip32_path(&params.path);
+    let p2 = match &params.counterparty_public_key {
+        Some(public_key) => {
+            data.extend_from_slice(public_key);
+            p2_privacy_operation::RETURN_SHARED_SECRET
+        }
+        None => p2_privacy_operation::RETURN_PUBLIC_KEY,
+    };
+
+    (p1, p2, data)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::BipPath;
+
+    #[test]
+    fn test_encode_public_key_request() {
+        let params = PrivacyOperationParams::new(BipPath::ethereum_standard(0, 0));
+        let (p1, p2, data) = encode_privacy_operation(&params);
+
+        assert_eq!(p1, p1_privacy_operation::RETURN_DATA);
+        assert_eq!(p2, p2_privacy_operation::RETURN_PUBLIC_KEY);
+        assert_eq!(
+            hex::encode(data),
+            "058000002c8000003c800000000000000000000000"
+        );
+    }
+
+    #[test]
+    fn test_encode_shared_secret_request() {
+        let params = PrivacyOperationParams::new(BipPath::ethereum_standard(0, 0))
+            .with_display()
+            .with_shared_secret([0x42; 32]);
+        let (p1, p2, data) = encode_privacy_operation(&params);
+
+        assert_eq!(p1, p1_privacy_operation::DISPLAY_AND_CONFIRM);
+        assert_eq!(p2, p2_privacy_operation::RETURN_SHARED_SECRET);
+        assert_eq!(data.len(), 21 + 32);
+        assert_eq!(&data[21..], &[0x42; 32]);
+    }
+
+    #[tokio::test]
+    async fn test_perform_privacy_operation_returns_raw_bytes() {
+        use ledger_sdk_transport::mock::MockExchange;
+
+        let mock = MockExchange::new();
+        mock.push_ok(&[0x11; 32]);
+
+        let params = PrivacyOperationParams::new(BipPath::ethereum_standard(0, 0));
+        let result = EthApp::perform_privacy_operation(&mock, params)
+            .await
+            .unwrap();
+        assert_eq!(result, vec![0x11; 32]);
+        assert_eq!(mock.sent()[0].ins, ins::PERFORM_PRIVACY_OPERATION);
+    }
+
+    #[tokio::test]
+    async fn test_path_policy_override() {
+        use crate::types::PathPolicy;
+        use ledger_sdk_transport::mock::MockExchange;
+
+        // Testnet path: fine by default, rejected under the Ethereum standard policy
+        let path = BipPath::from_string("m/44'/1'/0'/0/0").unwrap();
+        let params = PrivacyOperationParams::new(path);
+
+        let mock = MockExchange::new();
+        let err = EthApp::perform_privacy_operation(
+            &mock,
+            params.clone().with_path_policy(PathPolicy::EthereumOnly),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(err, EthAppError::InvalidBip32Path(ref reason) if reason.contains("Ethereum standard policy"))
+        );
+        assert!(mock.sent().is_empty());
+
+        mock.push_ok(&[0x11; 32]);
+        EthApp::perform_privacy_operation(&mock, params.with_path_policy(PathPolicy::Testnet))
+            .await
+            .unwrap();
+        assert_eq!(mock.sent().len(), 1);
+    }
+}
@@ -0,0 +1,151 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! PROVIDE NETWORK INFORMATION command implementation
+
+use async_trait::async_trait;
+use ledger_sdk_device_base::{App, AppExt};
+use ledger_sdk_transport::{APDUCommand, Exchange};
+
+use crate::errors::{map_gated_error, map_ledger_error, EthAppError, EthAppResult};
+use crate::instructions::{ins, p1_provide_network_info, p2_provide_network_info};
+use crate::trace;
+use crate::types::NetworkDescriptor;
+use crate::utils::chunk_length_prefixed;
+use crate::EthApp;
+
+#[async_trait]
+pub trait ProvideNetworkInformation<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    /// Provide signed network metadata so the device can display a custom chain
+    async fn provide_network_information(
+        transport: &E,
+        descriptor: &NetworkDescriptor,
+    ) -> EthAppResult<(), E::Error>;
+}
+
+#[async_trait]
+impl<E> ProvideNetworkInformation<E> for EthApp
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    async fn provide_network_information(
+        transport: &E,
+        descriptor: &NetworkDescriptor,
+    ) -> EthAppResult<(), E::Error> {
+        let tlv = descriptor.to_tlv().map_err(EthAppError::InvalidMessage)?;
+
+        let chunks = chunk_length_prefixed::<E::Error>(&tlv)?;
+
+        for (idx, chunk) in chunks.into_iter().enumerate() {
+            let p1 = if idx == 0 {
+                p1_provide_network_info::FIRST_CHUNK
+            } else {
+                p1_provide_network_info::FOLLOWING_CHUNK
+            };
+
+            let command = APDUCommand {
+                cla: Self::CLA,
+                ins: ins::PROVIDE_NETWORK_INFORMATION,
+                p1,
+                p2: p2_provide_network_info::NETWORK_CONFIGURATION,
+                data: chunk,
+            };
+
+            let response = trace::exchange(transport, &command)
+                .await
+                .map_err(|e| EthAppError::Transport(e.into()))?;
+
+            <EthApp as AppExt<E>>::handle_response_error(&response)
+                .map_err(map_gated_error("Network information", map_ledger_error))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::length;
+    use crate::instructions::network_info_tag as tag;
+
+    fn descriptor() -> NetworkDescriptor {
+        NetworkDescriptor::new(8453)
+            .with_name("Base".to_string())
+            .with_ticker("ETH".to_string())
+            .with_signature(vec![0x30, 0x02, 0xAA, 0xBB])
+    }
+
+    #[test]
+    fn test_network_descriptor_tlv() {
+        let tlv = descriptor().to_tlv().unwrap();
+
+        let mut expected = vec![
+            tag::STRUCTURE_TYPE,
+            1,
+            tag::TYPE_DYNAMIC_NETWORK,
+            tag::STRUCTURE_VERSION,
+            1,
+            tag::VERSION,
+            tag::BLOCKCHAIN_FAMILY,
+            1,
+            tag::FAMILY_ETHEREUM,
+            tag::CHAIN_ID,
+            8,
+        ];
+        expected.extend_from_slice(&8453u64.to_be_bytes());
+        expected.extend_from_slice(&[tag::NETWORK_NAME, 4]);
+        expected.extend_from_slice(b"Base");
+        expected.extend_from_slice(&[tag::TICKER, 3]);
+        expected.extend_from_slice(b"ETH");
+        expected.extend_from_slice(&[tag::DER_SIGNATURE, 4, 0x30, 0x02, 0xAA, 0xBB]);
+
+        assert_eq!(tlv, expected);
+    }
+
+    #[test]
+    fn test_network_descriptor_tlv_icon_hash() {
+        let tlv = descriptor().with_icon_hash([0x42; 32]).to_tlv().unwrap();
+
+        let mut icon_tlv = vec![tag::NETWORK_ICON_HASH, 32];
+        icon_tlv.extend_from_slice(&[0x42; 32]);
+        let position = tlv
+            .windows(icon_tlv.len())
+            .position(|w| w == icon_tlv.as_slice());
+        assert!(position.is_some());
+
+        // Signature stays last
+        assert_eq!(&tlv[tlv.len() - 6..tlv.len() - 4], &[tag::DER_SIGNATURE, 4]);
+    }
+
+    #[test]
+    fn test_network_descriptor_tlv_validation() {
+        assert!(NetworkDescriptor::new(1)
+            .with_ticker("ETH".to_string())
+            .with_signature(vec![0x30])
+            .to_tlv()
+            .is_err());
+        assert!(descriptor().with_signature(Vec::new()).to_tlv().is_err());
+        assert!(descriptor().with_name("x".repeat(128)).to_tlv().is_err());
+    }
+
+    #[test]
+    fn test_network_info_chunks() {
+        let tlv = descriptor().to_tlv().unwrap();
+        let chunks = chunk_length_prefixed::<std::io::Error>(&tlv).unwrap();
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(&chunks[0][..2], &(tlv.len() as u16).to_be_bytes());
+        assert_eq!(&chunks[0][2..], tlv.as_slice());
+
+        let large = vec![0xAB; 300];
+        let chunks = chunk_length_prefixed::<std::io::Error>(&large).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].len(), length::MAX_MESSAGE_CHUNK_SIZE);
+        assert_eq!(&chunks[0][..2], &[0x01, 0x2C]);
+        assert_eq!(chunks[1].len(), 302 - length::MAX_MESSAGE_CHUNK_SIZE);
+    }
+}
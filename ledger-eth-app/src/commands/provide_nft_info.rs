@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! PROVIDE NFT INFORMATION command implementation
+
+use async_trait::async_trait;
+use ledger_sdk_device_base::{App, AppExt};
+use ledger_sdk_transport::{APDUCommand, Exchange};
+
+use crate::errors::{map_gated_error, map_ledger_error, EthAppError, EthAppResult};
+use crate::instructions::ins;
+use crate::trace;
+use crate::types::NftCollectionInfo;
+use crate::EthApp;
+
+#[async_trait]
+pub trait ProvideNftInformation<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    /// Provide signed NFT collection metadata used to clear-sign the next transfer
+    async fn provide_nft_information(
+        transport: &E,
+        info: &NftCollectionInfo,
+    ) -> EthAppResult<(), E::Error>;
+}
+
+#[async_trait]
+impl<E> ProvideNftInformation<E> for EthApp
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    async fn provide_nft_information(
+        transport: &E,
+        info: &NftCollectionInfo,
+    ) -> EthAppResult<(), E::Error> {
+        let payload = info.to_payload().map_err(EthAppError::InvalidMessage)?;
+
+        let command = APDUCommand {
+            cla: Self::CLA,
+            ins: ins::PROVIDE_NFT_INFORMATION,
+            p1: 0x00,
+            p2: 0x00,
+            data: payload,
+        };
+
+        let response = trace::exchange(transport, &command)
+            .await
+            .map_err(|e| EthAppError::Transport(e.into()))?;
+
+        <EthApp as AppExt<E>>::handle_response_error(&response)
+            .map_err(map_gated_error("NFT information", map_ledger_error))?;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger_sdk_transport::mock::MockExchange;
+
+    use super::*;
+    use crate::instructions::nft;
+
+    /// Bored Ape Yacht Club on mainnet
+    fn info() -> NftCollectionInfo {
+        let address: [u8; 20] = hex::decode("bc4ca0eda7647a8ab7c2061c2e118a18a936f13d")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        NftCollectionInfo::new("BoredApeYachtClub".to_string(), address, 1)
+            .with_signature(vec![0x30, 0x04, 0x02, 0x01, 0x01, 0x02])
+    }
+
+    #[test]
+    fn test_nft_info_payload() {
+        let payload = info().to_payload().unwrap();
+
+        let expected = [
+            "01",                                       // type
+            "01",                                       // version
+            "11",                                       // name length
+            "426f7265644170655961636874436c7562",       // "BoredApeYachtClub"
+            "bc4ca0eda7647a8ab7c2061c2e118a18a936f13d", // contract address
+            "0000000000000001",                         // chain ID
+            "01",                                       // key ID
+            "01",                                       // algorithm
+            "06",                                       // signature length
+            "300402010102",                             // signature
+        ]
+        .concat();
+        assert_eq!(hex::encode(payload), expected);
+    }
+
+    #[test]
+    fn test_nft_info_payload_validation() {
+        assert!(info().with_signature(Vec::new()).to_payload().is_err());
+
+        let mut unnamed = info();
+        unnamed.collection_name.clear();
+        assert!(unnamed.to_payload().is_err());
+
+        // Does not fit a single APDU
+        assert!(info().with_signature(vec![0x30; 220]).to_payload().is_err());
+
+        let payload = info().with_key_id(nft::KEY_ID_TEST).to_payload().unwrap();
+        assert_eq!(payload[3 + 17 + 20 + 8], nft::KEY_ID_TEST);
+    }
+
+    #[tokio::test]
+    async fn test_provide_nft_information_apdu() {
+        let mock = MockExchange::new();
+        mock.push_ok(&[]);
+
+        EthApp::provide_nft_information(&mock, &info())
+            .await
+            .unwrap();
+
+        let sent = mock.sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(
+            (sent[0].ins, sent[0].p1, sent[0].p2),
+            (ins::PROVIDE_NFT_INFORMATION, 0x00, 0x00)
+        );
+        assert_eq!(sent[0].data, info().to_payload().unwrap());
+    }
+}
@@ -0,0 +1,218 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! PROVIDE SAFE ACCOUNT command implementation
+
+use async_trait::async_trait;
+use ledger_sdk_device_base::{App, AppExt};
+use ledger_sdk_transport::{APDUCommand, Exchange};
+
+use crate::errors::{map_gated_error, map_ledger_error, EthAppError, EthAppResult};
+use crate::instructions::{ins, p1_provide_safe_account, p2_provide_safe_account};
+use crate::trace;
+use crate::types::SafeAccountInfo;
+use crate::utils::chunk_length_prefixed;
+use crate::EthApp;
+
+#[async_trait]
+pub trait ProvideSafeAccount<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    /// Provide the signed Safe account context shown when signing for a Safe
+    ///
+    /// Sends the Safe descriptor, then the signer descriptor.
+    async fn provide_safe_account(
+        transport: &E,
+        info: &SafeAccountInfo,
+    ) -> EthAppResult<(), E::Error>;
+}
+
+#[async_trait]
+impl<E> ProvideSafeAccount<E> for EthApp
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    async fn provide_safe_account(
+        transport: &E,
+        info: &SafeAccountInfo,
+    ) -> EthAppResult<(), E::Error> {
+        let descriptors = [
+            (
+                p2_provide_safe_account::SAFE_DESCRIPTOR,
+                info.to_safe_descriptor_tlv()
+                    .map_err(EthAppError::InvalidMessage)?,
+            ),
+            (
+                p2_provide_safe_account::SIGNER_DESCRIPTOR,
+                info.to_signer_descriptor_tlv()
+                    .map_err(EthAppError::InvalidMessage)?,
+            ),
+        ];
+
+        for (p2, tlv) in descriptors {
+            let chunks = chunk_length_prefixed::<E::Error>(&tlv)?;
+
+            for (idx, chunk) in chunks.into_iter().enumerate() {
+                let p1 = if idx == 0 {
+                    p1_provide_safe_account::FIRST_CHUNK
+                } else {
+                    p1_provide_safe_account::FOLLOWING_CHUNK
+                };
+
+                let command = APDUCommand {
+                    cla: Self::CLA,
+                    ins: ins::PROVIDE_SAFE_ACCOUNT,
+                    p1,
+                    p2,
+                    data: chunk,
+                };
+
+                let response = trace::exchange(transport, &command)
+                    .await
+                    .map_err(|e| EthAppError::Transport(e.into()))?;
+
+                <EthApp as AppExt<E>>::handle_response_error(&response).map_err(
+                    map_gated_error("Safe account information", map_ledger_error),
+                )?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger_sdk_transport::mock::MockExchange;
+
+    use super::*;
+    use crate::instructions::safe_account_tag as tag;
+
+    fn info(owners: usize) -> SafeAccountInfo {
+        let owners = (0..owners).map(|i| [i as u8 + 1; 20]).collect();
+        SafeAccountInfo::new([0x5A; 20], 2, owners)
+            .with_challenge(0xDEADBEEF)
+            .with_signature(vec![0x30, 0x02, 0xAA, 0xBB])
+            .with_signers_signature(vec![0x30, 0x02, 0xCC, 0xDD])
+    }
+
+    #[test]
+    fn test_safe_descriptor_tlv() {
+        let tlv = info(3).to_safe_descriptor_tlv().unwrap();
+
+        let mut expected = vec![
+            tag::STRUCTURE_TYPE,
+            1,
+            tag::TYPE_SAFE_ACCOUNT,
+            tag::STRUCTURE_VERSION,
+            1,
+            tag::VERSION,
+            tag::CHALLENGE,
+            4,
+            0xDE,
+            0xAD,
+            0xBE,
+            0xEF,
+            tag::ADDRESS,
+            20,
+        ];
+        expected.extend_from_slice(&[0x5A; 20]);
+        expected.extend_from_slice(&[tag::THRESHOLD, 2, 0x00, 0x02]);
+        expected.extend_from_slice(&[tag::SIGNERS_COUNT, 2, 0x00, 0x03]);
+        expected.extend_from_slice(&[tag::ROLE, 1, tag::ROLE_SIGNER]);
+        expected.extend_from_slice(&[tag::DER_SIGNATURE, 4, 0x30, 0x02, 0xAA, 0xBB]);
+
+        assert_eq!(tlv, expected);
+    }
+
+    #[test]
+    fn test_signer_descriptor_tlv() {
+        let tlv = info(2).to_signer_descriptor_tlv().unwrap();
+
+        let mut expected = vec![
+            tag::STRUCTURE_TYPE,
+            1,
+            tag::TYPE_SIGNER,
+            tag::STRUCTURE_VERSION,
+            1,
+            tag::VERSION,
+            tag::CHALLENGE,
+            4,
+            0xDE,
+            0xAD,
+            0xBE,
+            0xEF,
+        ];
+        for owner in [[0x01; 20], [0x02; 20]] {
+            expected.extend_from_slice(&[tag::ADDRESS, 20]);
+            expected.extend_from_slice(&owner);
+        }
+        expected.extend_from_slice(&[tag::DER_SIGNATURE, 4, 0x30, 0x02, 0xCC, 0xDD]);
+
+        assert_eq!(tlv, expected);
+    }
+
+    #[test]
+    fn test_safe_account_validation() {
+        assert!(info(1).to_safe_descriptor_tlv().is_err()); // threshold 2 of 1
+        assert!(info(0).to_signer_descriptor_tlv().is_err());
+        assert!(info(3)
+            .with_signature(Vec::new())
+            .to_safe_descriptor_tlv()
+            .is_err());
+        assert!(info(3)
+            .with_signers_signature(Vec::new())
+            .to_signer_descriptor_tlv()
+            .is_err());
+
+        let proposer = info(3)
+            .with_role(tag::ROLE_PROPOSER)
+            .to_safe_descriptor_tlv()
+            .unwrap();
+        let role = proposer
+            .windows(3)
+            .position(|w| w[..2] == [tag::ROLE, 1])
+            .unwrap();
+        assert_eq!(proposer[role + 2], tag::ROLE_PROPOSER);
+    }
+
+    #[tokio::test]
+    async fn test_provide_safe_account_apdus() {
+        let mock = MockExchange::new();
+        mock.push_ok(&[]).push_ok(&[]).push_ok(&[]);
+
+        // 12 owners do not fit one APDU
+        let info = info(12);
+        EthApp::provide_safe_account(&mock, &info).await.unwrap();
+
+        let sent: Vec<(u8, u8, u8)> = mock.sent().iter().map(|c| (c.ins, c.p1, c.p2)).collect();
+        assert_eq!(
+            sent,
+            vec![
+                (
+                    ins::PROVIDE_SAFE_ACCOUNT,
+                    p1_provide_safe_account::FIRST_CHUNK,
+                    p2_provide_safe_account::SAFE_DESCRIPTOR
+                ),
+                (
+                    ins::PROVIDE_SAFE_ACCOUNT,
+                    p1_provide_safe_account::FIRST_CHUNK,
+                    p2_provide_safe_account::SIGNER_DESCRIPTOR
+                ),
+                (
+                    ins::PROVIDE_SAFE_ACCOUNT,
+                    p1_provide_safe_account::FOLLOWING_CHUNK,
+                    p2_provide_safe_account::SIGNER_DESCRIPTOR
+                ),
+            ]
+        );
+
+        let signers = info.to_signer_descriptor_tlv().unwrap();
+        assert_eq!(
+            &mock.sent()[1].data[..2],
+            &(signers.len() as u16).to_be_bytes()
+        );
+    }
+}
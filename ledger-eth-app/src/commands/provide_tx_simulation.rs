@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! PROVIDE TX SIMULATION command implementation
+
+use async_trait::async_trait;
+use ledger_sdk_device_base::{App, AppExt};
+use ledger_sdk_transport::{APDUCommand, Exchange};
+
+use crate::errors::{map_gated_error, map_ledger_error, EthAppError, EthAppResult};
+use crate::instructions::{ins, p1_provide_tx_simulation};
+use crate::trace;
+use crate::types::TxSimulationResult;
+use crate::utils::chunk_length_prefixed;
+use crate::EthApp;
+
+#[async_trait]
+pub trait ProvideTxSimulation<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    /// Provide a signed simulation result for the Transaction Check warnings
+    ///
+    /// A transaction simulation goes between storing the transaction
+    /// (`TransactionMode::StoreOnly`) and starting its flow
+    /// (`TransactionMode::StartFlow`); message simulations right before signing.
+    /// This order is not checked here, [`crate::EthereumApp::provide_tx_simulation`]
+    /// enforces it.
+    async fn provide_tx_simulation(
+        transport: &E,
+        simulation: &TxSimulationResult,
+    ) -> EthAppResult<(), E::Error>;
+}
+
+#[async_trait]
+impl<E> ProvideTxSimulation<E> for EthApp
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    async fn provide_tx_simulation(
+        transport: &E,
+        simulation: &TxSimulationResult,
+    ) -> EthAppResult<(), E::Error> {
+        let tlv = simulation.to_tlv().map_err(EthAppError::InvalidMessage)?;
+        let chunks = chunk_length_prefixed::<E::Error>(&tlv)?;
+
+        for (idx, chunk) in chunks.into_iter().enumerate() {
+            let p1 = if idx == 0 {
+                p1_provide_tx_simulation::FIRST_CHUNK
+            } else {
+                p1_provide_tx_simulation::FOLLOWING_CHUNK
+            };
+
+            let command = APDUCommand {
+                cla: Self::CLA,
+                ins: ins::PROVIDE_TX_SIMULATION,
+                p1,
+                p2: 0x00,
+                data: chunk,
+            };
+
+            let response = trace::exchange(transport, &command)
+                .await
+                .map_err(|e| EthAppError::Transport(e.into()))?;
+
+            <EthApp as AppExt<E>>::handle_response_error(&response)
+                .map_err(map_gated_error("Transaction simulation", map_ledger_error))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger_sdk_transport::mock::MockExchange;
+
+    use super::*;
+    use crate::instructions::tx_simulation_tag as tag;
+
+    fn simulation() -> TxSimulationResult {
+        TxSimulationResult::new([0xAB; 32], 1, [0x11; 20], tag::RISK_WARNING)
+            .with_category(0x03)
+            .with_provider_message("Drainer".to_string())
+            .with_tiny_url("w3c.io/x".to_string())
+            .with_signature(vec![0x30, 0x02, 0xAA, 0xBB])
+    }
+
+    #[test]
+    fn test_tx_simulation_tlv() {
+        let tlv = simulation().to_tlv().unwrap();
+
+        let mut expected = vec![
+            tag::STRUCTURE_TYPE,
+            1,
+            tag::TYPE_TX_SIMULATION,
+            tag::STRUCTURE_VERSION,
+            1,
+            tag::VERSION,
+            tag::ADDRESS,
+            20,
+        ];
+        expected.extend_from_slice(&[0x11; 20]);
+        expected.extend_from_slice(&[tag::CHAIN_ID, 8, 0, 0, 0, 0, 0, 0, 0, 1]);
+        expected.extend_from_slice(&[tag::TX_HASH, 32]);
+        expected.extend_from_slice(&[0xAB; 32]);
+        expected.extend_from_slice(&[tag::NORMALIZED_RISK, 1, tag::RISK_WARNING]);
+        expected.extend_from_slice(&[tag::NORMALIZED_CATEGORY, 1, 0x03]);
+        expected.extend_from_slice(&[tag::PROVIDER_MSG, 7]);
+        expected.extend_from_slice(b"Drainer");
+        expected.extend_from_slice(&[tag::TINY_URL, 8]);
+        expected.extend_from_slice(b"w3c.io/x");
+        expected.extend_from_slice(&[tag::SIMULATION_TYPE, 1, tag::SIMULATION_TRANSACTION]);
+        expected.extend_from_slice(&[tag::DER_SIGNATURE, 4, 0x30, 0x02, 0xAA, 0xBB]);
+
+        assert_eq!(tlv, expected);
+    }
+
+    #[test]
+    fn test_tx_simulation_validation() {
+        assert!(simulation().with_signature(Vec::new()).to_tlv().is_err());
+        assert!(TxSimulationResult::new([0; 32], 1, [0; 20], 3)
+            .with_signature(vec![0x30, 0x00])
+            .to_tlv()
+            .is_err());
+        assert!(simulation()
+            .with_provider_message("x".repeat(128))
+            .to_tlv()
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_provide_tx_simulation_apdus() {
+        let mock = MockExchange::new();
+        mock.push_ok(&[]).push_ok(&[]);
+
+        // A long provider message and URL do not fit one APDU
+        let simulation = simulation()
+            .with_provider_message("m".repeat(120))
+            .with_tiny_url("u".repeat(100));
+        EthApp::provide_tx_simulation(&mock, &simulation)
+            .await
+            .unwrap();
+
+        let sent = mock.sent();
+        let p1s: Vec<u8> = sent.iter().map(|c| c.p1).collect();
+        assert_eq!(
+            p1s,
+            vec![
+                p1_provide_tx_simulation::FIRST_CHUNK,
+                p1_provide_tx_simulation::FOLLOWING_CHUNK
+            ]
+        );
+        assert!(sent.iter().all(|c| c.ins == ins::PROVIDE_TX_SIMULATION));
+
+        let tlv = simulation.to_tlv().unwrap();
+        assert_eq!(&sent[0].data[..2], &(tlv.len() as u16).to_be_bytes());
+    }
+}
@@ -0,0 +1,176 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! SET PLUGIN and SET EXTERNAL PLUGIN command implementations
+
+use async_trait::async_trait;
+use ledger_sdk_device_base::{App, AppExt};
+use ledger_sdk_transport::{APDUCommand, Exchange};
+
+use crate::errors::{map_ledger_error, EthAppError, EthAppResult};
+use crate::instructions::ins;
+use crate::trace;
+use crate::types::PluginDescriptor;
+use crate::EthApp;
+
+#[async_trait]
+pub trait SetPlugin<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    /// Select the plugin used to clear-sign the next transaction
+    async fn set_plugin(transport: &E, descriptor: &PluginDescriptor)
+        -> EthAppResult<(), E::Error>;
+}
+
+#[async_trait]
+pub trait SetExternalPlugin<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    /// Select an external plugin (legacy descriptor format) for the next transaction
+    async fn set_external_plugin(
+        transport: &E,
+        descriptor: &PluginDescriptor,
+    ) -> EthAppResult<(), E::Error>;
+}
+
+#[async_trait]
+impl<E> SetPlugin<E> for EthApp
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    async fn set_plugin(
+        transport: &E,
+        descriptor: &PluginDescriptor,
+    ) -> EthAppResult<(), E::Error> {
+        let payload = descriptor
+            .to_set_plugin_payload()
+            .map_err(EthAppError::InvalidMessage)?;
+
+        send_plugin_command(transport, ins::SET_PLUGIN, payload).await
+    }
+}
+
+#[async_trait]
+impl<E> SetExternalPlugin<E> for EthApp
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    async fn set_external_plugin(
+        transport: &E,
+        descriptor: &PluginDescriptor,
+    ) -> EthAppResult<(), E::Error> {
+        let payload = descriptor
+            .to_external_plugin_payload()
+            .map_err(EthAppError::InvalidMessage)?;
+
+        send_plugin_command(transport, ins::SET_EXTERNAL_PLUGIN, payload).await
+    }
+}
+
+async fn send_plugin_command<E>(
+    transport: &E,
+    ins: u8,
+    payload: Vec<u8>,
+) -> EthAppResult<(), E::Error>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    let command = APDUCommand {
+        cla: EthApp::CLA,
+        ins,
+        p1: 0x00,
+        p2: 0x00,
+        data: payload,
+    };
+
+    let response = trace::exchange(transport, &command)
+        .await
+        .map_err(|e| EthAppError::Transport(e.into()))?;
+
+    <EthApp as AppExt<E>>::handle_response_error(&response).map_err(map_ledger_error)?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::plugin;
+
+    /// Paraswap `simpleSwap` on the Augustus swapper
+    fn descriptor() -> PluginDescriptor {
+        let address: [u8; 20] = hex::decode("def171fe48cf0115b1d80b88dc8eab59176fee57")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        PluginDescriptor::new("Paraswap".to_string(), address, [0x54, 0xe3, 0xf3, 0x1b])
+            .with_signature(vec![0x30, 0x04, 0x02, 0x01, 0x01, 0x02])
+    }
+
+    #[test]
+    fn test_set_plugin_payload() {
+        let payload = descriptor().to_set_plugin_payload().unwrap();
+
+        let expected = [
+            "01",                                       // type
+            "01",                                       // version
+            "08",                                       // name length
+            "5061726173776170",                         // "Paraswap"
+            "def171fe48cf0115b1d80b88dc8eab59176fee57", // contract address
+            "54e3f31b",                                 // selector
+            "0000000000000001",                         // chain ID
+            "02",                                       // key ID
+            "01",                                       // algorithm
+            "06",                                       // signature length
+            "300402010102",                             // signature
+        ]
+        .concat();
+        assert_eq!(hex::encode(payload), expected);
+    }
+
+    #[test]
+    fn test_set_external_plugin_payload() {
+        let payload = descriptor().to_external_plugin_payload().unwrap();
+
+        let expected = [
+            "08",
+            "5061726173776170",
+            "def171fe48cf0115b1d80b88dc8eab59176fee57",
+            "54e3f31b",
+            "300402010102",
+        ]
+        .concat();
+        assert_eq!(hex::encode(payload), expected);
+    }
+
+    #[test]
+    fn test_plugin_payload_validation() {
+        assert!(descriptor()
+            .with_signature(Vec::new())
+            .to_set_plugin_payload()
+            .is_err());
+
+        let mut unnamed = descriptor();
+        unnamed.plugin_name.clear();
+        assert!(unnamed.to_external_plugin_payload().is_err());
+
+        // Does not fit a single APDU
+        assert!(descriptor()
+            .with_signature(vec![0x30; 255])
+            .to_set_plugin_payload()
+            .is_err());
+
+        let test_key = descriptor()
+            .with_key_id(plugin::KEY_ID_TEST)
+            .with_chain_id(137);
+        let payload = test_key.to_set_plugin_payload().unwrap();
+        assert_eq!(&payload[35..43], &137u64.to_be_bytes());
+        assert_eq!(payload[43], plugin::KEY_ID_TEST);
+    }
+}
@@ -0,0 +1,184 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! SIGN EIP 7702 AUTHORIZATION command implementation
+
+use async_trait::async_trait;
+use ledger_sdk_device_base::{App, AppExt};
+use ledger_sdk_transport::{APDUCommand, Exchange};
+
+use crate::errors::{
+    map_exchange_error, map_gated_error, map_ledger_error, EthAppError, EthAppResult,
+};
+use crate::instructions::{ins, p1_sign_eip7702};
+use crate::trace;
+use crate::types::{BipPath, Eip7702Authorization, Eip7702Signature, PathPolicy};
+use crate::utils::{encode_bip32_path, validate_bip32_path_with_policy};
+use crate::EthApp;
+
+#[async_trait]
+pub trait SignAuthorization<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    /// Sign an EIP-7702 authorization delegating the account to a contract
+    ///
+    /// `path` is checked against `path_policy` before anything is sent.
+    async fn sign_authorization(
+        transport: &E,
+        path: &BipPath,
+        authorization: &Eip7702Authorization,
+        path_policy: PathPolicy,
+    ) -> EthAppResult<Eip7702Signature, E::Error>;
+}
+
+#[async_trait]
+impl<E> SignAuthorization<E> for EthApp
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    async fn sign_authorization(
+        transport: &E,
+        path: &BipPath,
+        authorization: &Eip7702Authorization,
+        path_policy: PathPolicy,
+    ) -> EthAppResult<Eip7702Signature, E::Error> {
+        validate_bip32_path_with_policy(path, path_policy)?;
+
+        // At most 41 bytes of path and 45 of TLV: a single first chunk
+        let command = APDUCommand {
+            cla: Self::CLA,
+            ins: ins::SIGN_EIP7702_AUTHORIZATION,
+            p1: p1_sign_eip7702::FIRST_CHUNK,
+            p2: 0x00,
+            data: encode_authorization(path, authorization),
+        };
+
+        let response = trace::exchange(transport, &command)
+            .await
+            .map_err(map_exchange_error)?;
+
+        <EthApp as AppExt<E>>::handle_response_error_signature(&response)
+            .map_err(map_gated_error("EIP-7702", map_ledger_error))?;
+
+        Eip7702Signature::from_response(authorization.clone(), response.data())
+            .map_err(EthAppError::InvalidSignature)
+    }
+}
+
+/// SIGN EIP 7702 AUTHORIZATION payload: the BIP32 path, the 2-byte TLV length,
+/// then the authorization TLV
+fn encode_authorization(path: &BipPath, authorization: &Eip7702Authorization) -> Vec<u8> {
+    let tlv = authorization.to_tlv();
+
+    let mut data = encode_bip32_path(path);
+    data.extend_from_slice(&(tlv.len() as u16).to_be_bytes());
+    data.extend_from_slice(&tlv);
+    data
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ledger_sdk_transport::mock::MockExchange;
+
+    fn authorization() -> Eip7702Authorization {
+        Eip7702Authorization::new(1, [0x11; 20], 7)
+    }
+
+    #[test]
+    fn test_encode_authorization() {
+        let data = encode_authorization(&BipPath::ethereum_standard(0, 0), &authorization());
+
+        let path_len = 21;
+        let tlv = authorization().to_tlv();
+        assert_eq!(&data[path_len..path_len + 2], &[0x00, 0x2D]);
+        assert_eq!(&data[path_len + 2..], tlv.as_slice());
+        assert_eq!(data.len(), path_len + 2 + 45);
+    }
+
+    #[tokio::test]
+    async fn test_sign_authorization() {
+        let mut answer = vec![0x01];
+        answer.extend_from_slice(&[0xAA; 32]);
+        answer.extend_from_slice(&[0xBB; 32]);
+
+        let mock = MockExchange::new();
+        mock.push_ok(&answer);
+
+        let signature = EthApp::sign_authorization(
+            &mock,
+            &BipPath::ethereum_standard(0, 0),
+            &authorization(),
+            PathPolicy::Strict,
+        )
+        .await
+        .unwrap();
+        assert_eq!(signature.authorization, authorization());
+        assert_eq!(signature.y_parity, 1);
+
+        let sent = mock.sent();
+        assert_eq!(sent[0].ins, ins::SIGN_EIP7702_AUTHORIZATION);
+        assert_eq!(sent[0].p1, p1_sign_eip7702::FIRST_CHUNK);
+    }
+
+    #[tokio::test]
+    async fn test_sign_authorization_rejects_short_answer() {
+        let mock = MockExchange::new();
+        mock.push_ok(&[0x01; 64]);
+
+        let err = EthApp::sign_authorization(
+            &mock,
+            &BipPath::ethereum_standard(0, 0),
+            &authorization(),
+            PathPolicy::Strict,
+        )
+        .await
+        .unwrap_err();
+        assert!(matches!(err, EthAppError::InvalidSignature(_)));
+    }
+
+    #[tokio::test]
+    async fn test_sign_authorization_on_older_app() {
+        let mock = MockExchange::new();
+        mock.push_status(0x6D00);
+
+        let err = EthApp::sign_authorization(
+            &mock,
+            &BipPath::ethereum_standard(0, 0),
+            &authorization(),
+            PathPolicy::Strict,
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(
+            err,
+            EthAppError::FeatureNotSupported("EIP-7702".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_path_policy_override() {
+        // Testnet path: fine by default, rejected under the Ethereum standard policy
+        let path = BipPath::from_string("m/44'/1'/0'/0/0").unwrap();
+
+        let mock = MockExchange::new();
+        let err =
+            EthApp::sign_authorization(&mock, &path, &authorization(), PathPolicy::EthereumOnly)
+                .await
+                .unwrap_err();
+        assert!(
+            matches!(err, EthAppError::InvalidBip32Path(ref reason) if reason.contains("Ethereum standard policy"))
+        );
+        assert!(mock.sent().is_empty());
+
+        let mut answer = vec![0x00];
+        answer.extend_from_slice(&[0xAA; 64]);
+        mock.push_ok(&answer);
+        EthApp::sign_authorization(&mock, &path, &authorization(), PathPolicy::Testnet)
+            .await
+            .unwrap();
+        assert_eq!(mock.sent().len(), 1);
+    }
+}
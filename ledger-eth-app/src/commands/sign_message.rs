@@ -4,12 +4,16 @@
 
 use async_trait::async_trait;
 use ledger_sdk_device_base::{App, AppExt};
-use ledger_sdk_transport::{APDUCommand, Exchange};
+use ledger_sdk_transport::Exchange;
+use tokio::io::{AsyncRead, AsyncReadExt};
 
-use crate::errors::{EthAppError, EthAppResult};
+use crate::errors::{map_exchange_error, map_ledger_error, EthAppError, EthAppResult};
 use crate::instructions::{ins, length, p1_sign_message};
-use crate::types::{SignMessageParams, Signature};
-use crate::utils::{chunk_data, encode_bip32_path, validate_bip32_path};
+use crate::trace;
+use crate::types::{BipPath, SignMessageParams, SignMessageStreamParams, Signature};
+use crate::utils::{
+    encode_bip32_path, parse_signature_response, validate_bip32_path_with_policy, ChunkedPayload,
+};
 use crate::EthApp;
 
 #[async_trait]
@@ -23,6 +27,18 @@ where
         transport: &E,
         params: SignMessageParams,
     ) -> EthAppResult<Signature, E::Error>;
+
+    /// Sign a personal message read from `reader` without buffering it in memory
+    ///
+    /// Exactly `params.len` bytes are read and sent in 255-byte APDUs; the length
+    /// is announced to the device in the first chunk, so it must match the message.
+    async fn sign_personal_message_streaming<R>(
+        transport: &E,
+        params: SignMessageStreamParams,
+        reader: R,
+    ) -> EthAppResult<Signature, E::Error>
+    where
+        R: AsyncRead + Unpin + Send;
 }
 
 #[async_trait]
@@ -36,107 +52,121 @@ where
         params: SignMessageParams,
     ) -> EthAppResult<Signature, E::Error> {
         // Validate BIP32 path
-        validate_bip32_path(&params.path)?;
+        validate_bip32_path_with_policy(&params.path, params.path_policy)?;
 
         // Check message size
-        if params.message.is_empty() {
-            return Err(EthAppError::InvalidMessage(
-                "Message cannot be empty".to_string(),
-            ));
-        }
-
-        // Calculate maximum chunk size for message data
-        // First chunk includes: path_len(1) + path_indices(path.len()*4) + message_len(4)
-        let path_data = encode_bip32_path(&params.path);
-        let first_chunk_overhead = path_data.len() + 4; // +4 for message length
+        check_message_size::<E::Error>(params.message.len())?;
 
-        if first_chunk_overhead >= length::MAX_MESSAGE_CHUNK_SIZE {
-            return Err(EthAppError::InvalidBip32Path(
-                "BIP32 path too long for message signing".to_string(),
-            ));
+        let payload = message_payload(&params.path, params.message.len() as u32, &params.message)?;
+        let commands = payload.commands();
+        let (last, first) = commands.split_last().expect("at least one chunk");
+        for command in first {
+            let response = trace::exchange(transport, command)
+                .await
+                .map_err(map_exchange_error)?;
+            <EthApp as AppExt<E>>::handle_response_error(&response).map_err(map_ledger_error)?;
         }
 
-        let first_chunk_message_size = length::MAX_MESSAGE_CHUNK_SIZE - first_chunk_overhead;
-        let subsequent_chunk_size = length::MAX_MESSAGE_CHUNK_SIZE;
-
-        // Split message into chunks
-        let (first_message_chunk, remaining_message) =
-            if params.message.len() <= first_chunk_message_size {
-                (params.message.as_slice(), &[][..])
-            } else {
-                (
-                    &params.message[..first_chunk_message_size],
-                    &params.message[first_chunk_message_size..],
-                )
-            };
-
-        let remaining_chunks = chunk_data(remaining_message, subsequent_chunk_size);
-
-        // Send first chunk with path and message length
-        let mut first_chunk_data = Vec::new();
-        first_chunk_data.extend_from_slice(&path_data);
-        first_chunk_data.extend_from_slice(&(params.message.len() as u32).to_be_bytes());
-        first_chunk_data.extend_from_slice(first_message_chunk);
-
-        let first_command = APDUCommand {
-            cla: Self::CLA,
-            ins: ins::SIGN_ETH_PERSONAL_MESSAGE,
-            p1: p1_sign_message::FIRST_DATA_BLOCK,
-            p2: 0x00,
-            data: first_chunk_data,
-        };
-
-        let mut response = transport
-            .exchange(&first_command)
+        // The last chunk is answered with the signature once the user approves
+        let response = trace::exchange(transport, last)
             .await
-            .map_err(|e| EthAppError::Transport(e.into()))?;
-
-        <EthApp as AppExt<E>>::handle_response_error(&response).map_err(EthAppError::Transport)?;
-
-        // Send remaining chunks
-        for chunk in remaining_chunks {
-            let command = APDUCommand {
-                cla: Self::CLA,
-                ins: ins::SIGN_ETH_PERSONAL_MESSAGE,
-                p1: p1_sign_message::SUBSEQUENT_DATA_BLOCK,
-                p2: 0x00,
-                data: chunk,
-            };
-
-            response = transport
-                .exchange(&command)
-                .await
-                .map_err(|e| EthAppError::Transport(e.into()))?;
+            .map_err(map_exchange_error)?;
+        <EthApp as AppExt<E>>::handle_response_error_signature(&response)
+            .map_err(map_ledger_error)?;
+
+        parse_signature_response::<E::Error>(response.data())
+    }
 
-            <EthApp as AppExt<E>>::handle_response_error_signature(&response)
-                .map_err(EthAppError::Transport)?;
+    async fn sign_personal_message_streaming<R>(
+        transport: &E,
+        params: SignMessageStreamParams,
+        mut reader: R,
+    ) -> EthAppResult<Signature, E::Error>
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        validate_bip32_path_with_policy(&params.path, params.path_policy)?;
+
+        let message_len = u32::try_from(params.len).map_err(|_| EthAppError::MessageTooLarge {
+            size: usize::try_from(params.len).unwrap_or(usize::MAX),
+            max: length::MAX_PERSONAL_MESSAGE_SIZE,
+        })?;
+        check_message_size::<E::Error>(message_len as usize)?;
+        let payload = message_payload(&params.path, message_len, &[])?;
+
+        // Read each chunk only once the previous one was accepted
+        let mut frames = payload
+            .chunk_sizes(message_len as usize)
+            .enumerate()
+            .peekable();
+        let mut chunk = Vec::with_capacity(length::MAX_MESSAGE_CHUNK_SIZE);
+        while let Some((index, size)) = frames.next() {
+            chunk.resize(size, 0);
+            reader.read_exact(&mut chunk).await.map_err(|e| {
+                EthAppError::InvalidMessage(format!("Failed to read message: {}", e))
+            })?;
+            let last = frames.peek().is_none();
+
+            let response = trace::exchange(transport, &payload.frame(index, &chunk, last))
+                .await
+                .map_err(map_exchange_error)?;
+            if last {
+                <EthApp as AppExt<E>>::handle_response_error_signature(&response)
+                    .map_err(map_ledger_error)?;
+                return parse_signature_response::<E::Error>(response.data());
+            }
+            <EthApp as AppExt<E>>::handle_response_error(&response).map_err(map_ledger_error)?;
         }
 
-        // Parse signature from final response
-        parse_signature_response::<E::Error>(response.data())
+        unreachable!("a payload has at least one frame")
     }
 }
 
-/// Parse signature response data
-fn parse_signature_response<E: std::error::Error>(data: &[u8]) -> EthAppResult<Signature, E> {
-    if data.len() != 65 {
-        return Err(EthAppError::InvalidResponseData(format!(
-            "Invalid signature response length: {} bytes (expected 65)",
-            data.len()
-        )));
+/// SIGN ETH PERSONAL MESSAGE framing of a `message_len`-byte message
+///
+/// First chunk includes: path_len(1) + path_indices(path.len()*4) + message_len(4).
+/// `message` is empty when the message is streamed.
+fn message_payload<'a, E: std::error::Error>(
+    path: &BipPath,
+    message_len: u32,
+    message: &'a [u8],
+) -> EthAppResult<ChunkedPayload<'a>, E> {
+    let mut prefix = encode_bip32_path(path);
+    prefix.extend_from_slice(&message_len.to_be_bytes());
+    let payload = ChunkedPayload::new(EthApp::CLA, ins::SIGN_ETH_PERSONAL_MESSAGE, message)
+        .with_p1(
+            p1_sign_message::FIRST_DATA_BLOCK,
+            p1_sign_message::SUBSEQUENT_DATA_BLOCK,
+        )
+        .with_prefix(prefix);
+
+    if payload.first_frame_capacity().is_none() {
+        return Err(EthAppError::InvalidBip32Path(
+            "BIP32 path too long for message signing".to_string(),
+        ));
     }
+    Ok(payload)
+}
 
-    let v = data[0];
-    let r = data[1..33].to_vec();
-    let s = data[33..65].to_vec();
-
-    Signature::new(v, r, s).map_err(|e| EthAppError::InvalidSignature(e))
+/// Messages must be non-empty and at most [`length::MAX_PERSONAL_MESSAGE_SIZE`] bytes
+fn check_message_size<E: std::error::Error>(len: usize) -> EthAppResult<(), E> {
+    if len == 0 {
+        return Err(EthAppError::InvalidMessage(
+            "Message cannot be empty".to_string(),
+        ));
+    }
+    if len > length::MAX_PERSONAL_MESSAGE_SIZE {
+        return Err(EthAppError::MessageTooLarge {
+            size: len,
+            max: length::MAX_PERSONAL_MESSAGE_SIZE,
+        });
+    }
+    Ok(())
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::types::BipPath;
 
     #[test]
     fn test_parse_signature_response() {
@@ -191,4 +221,109 @@ mod tests {
         let first_chunk_message_size = length::MAX_MESSAGE_CHUNK_SIZE - first_chunk_overhead;
         assert_eq!(first_chunk_message_size, 255 - 17); // 238 bytes for message in first chunk
     }
+
+    #[tokio::test]
+    async fn test_sign_personal_message_streaming() {
+        use ledger_sdk_transport::mock::MockExchange;
+
+        let path = BipPath::ethereum_standard(0, 0);
+        let message: Vec<u8> = (0..600u32).map(|i| i as u8).collect();
+
+        let mock = MockExchange::new();
+        mock.push_ok(&[]).push_ok(&[]);
+        let mut signature = vec![0x1c];
+        signature.extend_from_slice(&[0xAA; 32]);
+        signature.extend_from_slice(&[0xBB; 32]);
+        mock.push_ok(&signature);
+
+        let reader = std::io::Cursor::new(message.clone());
+        let result = EthApp::sign_personal_message_streaming(
+            &mock,
+            SignMessageStreamParams::new(path.clone(), message.len() as u64),
+            reader,
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.v, 0x1c);
+        assert_eq!(result.r, vec![0xAA; 32]);
+
+        let sent = mock.sent();
+        let p1s: Vec<u8> = sent.iter().map(|c| c.p1).collect();
+        assert_eq!(
+            p1s,
+            vec![
+                p1_sign_message::FIRST_DATA_BLOCK,
+                p1_sign_message::SUBSEQUENT_DATA_BLOCK,
+                p1_sign_message::SUBSEQUENT_DATA_BLOCK,
+            ]
+        );
+        // Path (21 bytes) + length (4 bytes) + 230 bytes, then 255, then the remaining 115
+        let path_len = encode_bip32_path(&path).len();
+        assert_eq!(&sent[0].data[path_len..path_len + 4], &600u32.to_be_bytes());
+        let sizes: Vec<usize> = sent.iter().map(|c| c.data.len()).collect();
+        assert_eq!(sizes, vec![255, 255, 115]);
+        let streamed: Vec<u8> = std::iter::once(&sent[0].data[path_len + 4..])
+            .chain(sent[1..].iter().map(|c| c.data.as_slice()))
+            .flatten()
+            .copied()
+            .collect();
+        assert_eq!(streamed, message);
+
+        // A reader shorter than the declared length fails before the last chunk is sent
+        let mock = MockExchange::new();
+        mock.push_ok(&[]);
+        let reader = std::io::Cursor::new(vec![0u8; 300]);
+        let params = SignMessageStreamParams::new(path.clone(), 600);
+        let err = EthApp::sign_personal_message_streaming(&mock, params, reader)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthAppError::InvalidMessage(_)));
+        assert_eq!(mock.sent().len(), 1);
+    }
+
+    #[test]
+    fn test_check_message_size() {
+        let max = length::MAX_PERSONAL_MESSAGE_SIZE;
+        assert!(check_message_size::<std::io::Error>(1).is_ok());
+        assert!(check_message_size::<std::io::Error>(max).is_ok());
+        assert!(matches!(
+            check_message_size::<std::io::Error>(0),
+            Err(EthAppError::InvalidMessage(_))
+        ));
+        assert!(matches!(
+            check_message_size::<std::io::Error>(max + 1),
+            Err(EthAppError::MessageTooLarge { size, max: limit }) if size == max + 1 && limit == max
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_message_size_limit() {
+        use ledger_sdk_transport::mock::MockExchange;
+
+        let path = BipPath::new(vec![0x8000002C, 0x8000003C, 0x80000000, 0, 0]).unwrap();
+        let max = length::MAX_PERSONAL_MESSAGE_SIZE;
+
+        // At the limit: a 230-byte first chunk after the path and length, then full ones
+        let frames = 1 + (max - 230).div_ceil(length::MAX_MESSAGE_CHUNK_SIZE);
+        let mock = MockExchange::new();
+        for _ in 0..frames - 1 {
+            mock.push_ok(&[]);
+        }
+        mock.push_ok(&[0x1b; 65]);
+        let params = SignMessageParams::new(path.clone(), vec![0xAB; max]);
+        EthApp::sign_personal_message(&mock, params).await.unwrap();
+        assert_eq!(mock.sent().len(), frames);
+
+        // One byte over: rejected before anything is sent
+        let mock = MockExchange::new();
+        let params = SignMessageParams::new(path, vec![0xAB; max + 1]);
+        let err = EthApp::sign_personal_message(&mock, params)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            EthAppError::MessageTooLarge { size, max: limit } if size == max + 1 && limit == max
+        ));
+        assert!(mock.sent().is_empty());
+    }
 }
@@ -1,15 +1,28 @@
 // SPDX-License-Identifier: Apache-2.0
 
 //! SIGN ETH TRANSACTION command implementation
+//!
+//! ## Review on device
+//!
+//! The Ethereum app has no intermediate "review in progress" status that needs a
+//! continuation APDU. Every chunk but the last is acknowledged with 0x9000, and the
+//! exchange carrying the last chunk only completes once the user approves (signature)
+//! or rejects (0x6985) the transaction, however many screens the review takes.
+//! A long review is therefore just a slow exchange: the HID transport waits up to
+//! `LEDGER_TIMEOUT` for it. Any other status aborts the flow and is returned as is;
+//! nothing is retried or resumed.
 
 use async_trait::async_trait;
 use ledger_sdk_device_base::{App, AppExt};
 use ledger_sdk_transport::{APDUCommand, Exchange};
 
-use crate::errors::{EthAppError, EthAppResult};
+use crate::errors::{map_exchange_error, map_ledger_error, EthAppError, EthAppResult};
 use crate::instructions::{ins, length, p1_sign_transaction, p2_sign_transaction};
+use crate::trace;
 use crate::types::{SignTransactionParams, Signature};
-use crate::utils::{chunk_data, encode_bip32_path, validate_bip32_path};
+use crate::utils::{
+    encode_bip32_path, parse_signature_response, validate_bip32_path_with_policy, ChunkedPayload,
+};
 use crate::EthApp;
 
 /// Transaction processing mode
@@ -18,6 +31,9 @@ pub enum TransactionMode {
     /// Process transaction data and start signing flow immediately
     ProcessAndStart,
     /// Store transaction data only, don't start signing flow
+    ///
+    /// Every chunk, the last one included, must be acknowledged with an empty
+    /// 0x9000 response; any other status is returned as an error.
     StoreOnly,
     /// Start signing flow using previously stored data
     StartFlow,
@@ -79,7 +95,7 @@ where
         mode: TransactionMode,
     ) -> EthAppResult<Option<Signature>, E::Error> {
         // Validate BIP32 path
-        validate_bip32_path(&params.path)?;
+        validate_bip32_path_with_policy(&params.path, params.path_policy)?;
 
         // Check transaction data size
         if params.transaction_data.is_empty() {
@@ -87,6 +103,13 @@ where
                 "Transaction data cannot be empty".to_string(),
             ));
         }
+        let max = params.max_transaction_size();
+        if params.transaction_data.len() > max {
+            return Err(EthAppError::TransactionTooLarge {
+                size: params.transaction_data.len(),
+                max,
+            });
+        }
 
         match mode {
             TransactionMode::StartFlow => {
@@ -99,13 +122,12 @@ where
                     data: Vec::new(),
                 };
 
-                let response = transport
-                    .exchange(&command)
+                let response = trace::exchange(transport, &command)
                     .await
-                    .map_err(|e| EthAppError::Transport(e.into()))?;
+                    .map_err(map_exchange_error)?;
 
                 <EthApp as AppExt<E>>::handle_response_error_signature(&response)
-                    .map_err(EthAppError::Transport)?;
+                    .map_err(map_ledger_error)?;
 
                 let signature = parse_signature_response::<E::Error>(response.data())?;
                 return Ok(Some(signature));
@@ -118,6 +140,46 @@ where
     }
 }
 
+impl SignTransactionParams {
+    /// Largest transaction, in bytes, that can be signed with this path
+    ///
+    /// `MAX_TRANSACTION_CHUNKS` APDUs, minus the BIP32 path carried by the first one.
+    pub fn max_transaction_size(&self) -> usize {
+        (length::MAX_TRANSACTION_CHUNKS * length::MAX_MESSAGE_CHUNK_SIZE)
+            .saturating_sub(encode_bip32_path(&self.path).len())
+    }
+
+    /// APDU sequence a `sign_transaction` call will send, without sending it
+    ///
+    /// Intended for audit logging: the first command carries the BIP32 path,
+    /// the following ones the remaining transaction data.
+    pub fn plan(&self) -> Vec<APDUCommand<Vec<u8>>> {
+        transaction_commands(self, TransactionMode::ProcessAndStart)
+    }
+}
+
+/// Split transaction data into SIGN ETH TRANSACTION commands
+///
+/// First chunk includes: path_len(1) + path_indices(path.len()*4) + tx data.
+/// `params.chain_id` is client-side metadata and never sent.
+fn transaction_commands(
+    params: &SignTransactionParams,
+    mode: TransactionMode,
+) -> Vec<APDUCommand<Vec<u8>>> {
+    ChunkedPayload::new(
+        EthApp::CLA,
+        ins::SIGN_ETH_TRANSACTION,
+        &params.transaction_data,
+    )
+    .with_p1(
+        p1_sign_transaction::FIRST_DATA_BLOCK,
+        p1_sign_transaction::SUBSEQUENT_DATA_BLOCK,
+    )
+    .with_p2(mode.to_p2())
+    .with_prefix(encode_bip32_path(&params.path))
+    .commands()
+}
+
 impl EthApp {
     async fn process_transaction_data<E>(
         transport: &E,
@@ -128,110 +190,53 @@ impl EthApp {
         E: Exchange + Send + Sync,
         E::Error: std::error::Error,
     {
-        let path_data = encode_bip32_path(&params.path);
-
-        // Calculate maximum chunk size for transaction data
-        // First chunk includes: path_len(1) + path_indices(path.len()*4)
-        let first_chunk_overhead = path_data.len();
-
-        if first_chunk_overhead >= length::MAX_MESSAGE_CHUNK_SIZE {
+        if encode_bip32_path(&params.path).len() >= length::MAX_MESSAGE_CHUNK_SIZE {
             return Err(EthAppError::InvalidBip32Path(
                 "BIP32 path too long for transaction signing".to_string(),
             ));
         }
 
-        let first_chunk_tx_size = length::MAX_MESSAGE_CHUNK_SIZE - first_chunk_overhead;
-        let subsequent_chunk_size = length::MAX_MESSAGE_CHUNK_SIZE;
-
-        // Split transaction into chunks
-        let (first_tx_chunk, remaining_tx) = if params.transaction_data.len() <= first_chunk_tx_size
-        {
-            (params.transaction_data.as_slice(), &[][..])
-        } else {
-            (
-                &params.transaction_data[..first_chunk_tx_size],
-                &params.transaction_data[first_chunk_tx_size..],
-            )
-        };
+        let commands = transaction_commands(&params, mode);
+        let last_index = commands.len() - 1;
+        let mut signature_data = Vec::new();
 
-        let remaining_chunks = chunk_data(remaining_tx, subsequent_chunk_size);
-
-        // Send first chunk with path
-        let mut first_chunk_data = Vec::new();
-        first_chunk_data.extend_from_slice(&path_data);
-        first_chunk_data.extend_from_slice(first_tx_chunk);
-
-        let first_command = APDUCommand {
-            cla: Self::CLA,
-            ins: ins::SIGN_ETH_TRANSACTION,
-            p1: p1_sign_transaction::FIRST_DATA_BLOCK,
-            p2: mode.to_p2(),
-            data: first_chunk_data,
-        };
-
-        let mut response = transport
-            .exchange(&first_command)
-            .await
-            .map_err(|e| EthAppError::Transport(e.into()))?;
-
-        // Handle response (no signature expected yet at this stage)
-        <EthApp as AppExt<E>>::handle_response_error(&response).map_err(EthAppError::Transport)?;
-
-        // Send remaining chunks
-        for (i, chunk) in remaining_chunks.iter().enumerate() {
-            let command = APDUCommand {
-                cla: Self::CLA,
-                ins: ins::SIGN_ETH_TRANSACTION,
-                p1: p1_sign_transaction::SUBSEQUENT_DATA_BLOCK,
-                p2: mode.to_p2(),
-                data: chunk.clone(),
-            };
-
-            response = transport
-                .exchange(&command)
+        for (i, command) in commands.iter().enumerate() {
+            let response = trace::exchange(transport, command)
                 .await
-                .map_err(|e| EthAppError::Transport(e.into()))?;
+                .map_err(map_exchange_error)?;
 
-            // Only check for signature on the last chunk if not store-only mode
-            if mode == TransactionMode::StoreOnly {
-                <EthApp as AppExt<E>>::handle_response_error(&response)
-                    .map_err(EthAppError::Transport)?;
-            } else if i == remaining_chunks.len() - 1 {
-                // Last chunk - expect signature
+            // Only the last subsequent chunk carries the signature, unless store-only mode
+            if i > 0 && i == last_index && mode != TransactionMode::StoreOnly {
                 <EthApp as AppExt<E>>::handle_response_error_signature(&response)
-                    .map_err(EthAppError::Transport)?;
+                    .map_err(map_ledger_error)?;
             } else {
                 <EthApp as AppExt<E>>::handle_response_error(&response)
-                    .map_err(EthAppError::Transport)?;
+                    .map_err(map_ledger_error)?;
+            }
+
+            if i == last_index {
+                signature_data = response.data().to_vec();
             }
         }
 
         // Parse signature from final response if not store-only mode
         if mode == TransactionMode::StoreOnly {
+            // Storing is acknowledged with a bare 0x9000; anything else means the
+            // device did not just buffer the whole transaction
+            if !signature_data.is_empty() {
+                return Err(EthAppError::InvalidResponseData(format!(
+                    "Transaction may not be fully stored: unexpected {} bytes after the last chunk",
+                    signature_data.len()
+                )));
+            }
             Ok(None)
         } else {
-            let signature = parse_signature_response::<E::Error>(response.data())?;
+            let signature = parse_signature_response::<E::Error>(&signature_data)?;
             Ok(Some(signature))
         }
     }
 }
 
-/// Parse signature response data
-fn parse_signature_response<E: std::error::Error>(data: &[u8]) -> EthAppResult<Signature, E> {
-    if data.len() != 65 {
-        return Err(EthAppError::InvalidResponseData(format!(
-            "Invalid signature response length: {} bytes (expected 65)",
-            data.len()
-        )));
-    }
-
-    let v = data[0];
-    let r = data[1..33].to_vec();
-    let s = data[33..65].to_vec();
-
-    Signature::new(v, r, s).map_err(|e| EthAppError::InvalidSignature(e))
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -306,4 +311,218 @@ mod tests {
         let first_chunk_tx_size = length::MAX_MESSAGE_CHUNK_SIZE - first_chunk_overhead;
         assert_eq!(first_chunk_tx_size, 255 - 21); // 234 bytes for tx data in first chunk
     }
+
+    #[test]
+    fn test_plan_chunks() {
+        let path = BipPath::ethereum_standard(0, 0);
+        let path_data = encode_bip32_path(&path);
+        let tx_data: Vec<u8> = (0..600u32).map(|i| i as u8).collect();
+        let plan = SignTransactionParams::new(path, tx_data.clone()).plan();
+
+        // 234 bytes in the first chunk, then 255 + 111
+        assert_eq!(plan.len(), 3);
+        assert_eq!(plan[0].p1, p1_sign_transaction::FIRST_DATA_BLOCK);
+        assert_eq!(plan[0].p2, p2_sign_transaction::PROCESS_AND_START);
+        assert_eq!(&plan[0].data[..path_data.len()], path_data.as_slice());
+        assert_eq!(&plan[0].data[path_data.len()..], &tx_data[..234]);
+        assert_eq!(plan[0].data.len(), length::MAX_MESSAGE_CHUNK_SIZE);
+
+        assert!(plan[1..]
+            .iter()
+            .all(|c| c.p1 == p1_sign_transaction::SUBSEQUENT_DATA_BLOCK
+                && c.ins == ins::SIGN_ETH_TRANSACTION));
+        assert_eq!(plan[1].data.len(), 255);
+        assert_eq!(plan[2].data, tx_data[489..].to_vec());
+    }
+
+    #[test]
+    fn test_plan_single_chunk() {
+        let path = BipPath::ethereum_standard(0, 0);
+        let plan = SignTransactionParams::new(path.clone(), vec![0xf8, 0x6c]).plan();
+
+        assert_eq!(plan.len(), 1);
+        assert_eq!(plan[0].cla, EthApp::CLA);
+        let mut expected = encode_bip32_path(&path);
+        expected.extend_from_slice(&[0xf8, 0x6c]);
+        assert_eq!(plan[0].data, expected);
+    }
+
+    #[test]
+    fn test_plan_ignores_chain_id() {
+        let path = BipPath::ethereum_standard(0, 0);
+        let tx_data: Vec<u8> = (0..600u32).map(|i| i as u8).collect();
+
+        let params = SignTransactionParams::new(path.clone(), tx_data.clone());
+        let with_chain_id = params.clone().with_chain_id(137);
+        let data = |plan: Vec<APDUCommand<Vec<u8>>>| -> Vec<Vec<u8>> {
+            plan.into_iter().map(|command| command.data).collect()
+        };
+        assert_eq!(data(with_chain_id.plan()), data(params.plan()));
+        assert_eq!(
+            with_chain_id.max_transaction_size(),
+            params.max_transaction_size()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_transaction_chunks_through_mock() {
+        use ledger_sdk_transport::mock::MockExchange;
+
+        let path = BipPath::new(vec![0x8000002C, 0x8000003C, 0x80000000, 0, 0]).unwrap();
+        let tx_data: Vec<u8> = (0..600u32).map(|i| i as u8).collect();
+
+        let mock = MockExchange::new();
+        mock.push_ok(&[]).push_ok(&[]);
+        let mut signature = vec![0x25];
+        signature.extend_from_slice(&[0xAA; 32]);
+        signature.extend_from_slice(&[0xBB; 32]);
+        mock.push_ok(&signature);
+
+        let result = EthApp::sign_transaction(&mock, SignTransactionParams::new(path, tx_data))
+            .await
+            .unwrap();
+        assert_eq!(result.v, 0x25);
+        assert_eq!(result.r, vec![0xAA; 32]);
+        assert_eq!(result.s, vec![0xBB; 32]);
+
+        let sent = mock.sent();
+        let p1s: Vec<u8> = sent.iter().map(|c| c.p1).collect();
+        let sizes: Vec<usize> = sent.iter().map(|c| c.data.len()).collect();
+        assert_eq!(
+            p1s,
+            vec![
+                p1_sign_transaction::FIRST_DATA_BLOCK,
+                p1_sign_transaction::SUBSEQUENT_DATA_BLOCK,
+                p1_sign_transaction::SUBSEQUENT_DATA_BLOCK,
+            ]
+        );
+        // Path (21 bytes) + 234 bytes, then 255, then the remaining 111
+        assert_eq!(sizes, vec![21 + 234, 255, 111]);
+        assert_eq!(mock.remaining(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_status_during_review_is_not_resumed() {
+        use ledger_sdk_transport::mock::MockExchange;
+
+        let path = BipPath::new(vec![0x8000002C, 0x8000003C, 0x80000000, 0, 0]).unwrap();
+        let tx_data = vec![0xAB; 300];
+
+        // Long review: the last chunk answers with the signature only after approval
+        let mock = MockExchange::new();
+        mock.push_ok(&[]);
+        mock.push_ok(&[0x25; 65]);
+        let signature = EthApp::sign_transaction(
+            &mock,
+            SignTransactionParams::new(path.clone(), tx_data.clone()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(signature.v, 0x25);
+        assert_eq!(mock.sent().len(), 2);
+
+        // Rejection (or any other status) ends the flow without a continuation APDU
+        let mock = MockExchange::new();
+        mock.push_ok(&[]);
+        mock.push_status(0x6985);
+        mock.push_ok(&[0x25; 65]);
+        let err = EthApp::sign_transaction(&mock, SignTransactionParams::new(path, tx_data))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthAppError::UserRejected));
+        assert_eq!(mock.sent().len(), 2);
+        assert_eq!(mock.remaining(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_store_only_checks_last_chunk() {
+        use ledger_sdk_transport::mock::MockExchange;
+
+        let path = BipPath::new(vec![0x8000002C, 0x8000003C, 0x80000000, 0, 0]).unwrap();
+        let params = SignTransactionParams::new(path, vec![0xAB; 300]);
+
+        let mock = MockExchange::new();
+        mock.push_ok(&[]).push_ok(&[]);
+        let result =
+            EthApp::sign_transaction_with_mode(&mock, params.clone(), TransactionMode::StoreOnly)
+                .await
+                .unwrap();
+        assert!(result.is_none());
+        assert!(mock
+            .sent()
+            .iter()
+            .all(|c| c.p2 == p2_sign_transaction::STORE_ONLY));
+
+        // Error status on the last store chunk
+        let mock = MockExchange::new();
+        mock.push_ok(&[]).push_status(0x6A80);
+        let err =
+            EthApp::sign_transaction_with_mode(&mock, params.clone(), TransactionMode::StoreOnly)
+                .await
+                .unwrap_err();
+        assert!(matches!(err, EthAppError::DeviceStatus { sw: 0x6A80, .. }));
+        assert_eq!(mock.sent().len(), 2);
+
+        // Success status carrying data instead of a bare acknowledgement
+        let mock = MockExchange::new();
+        mock.push_ok(&[]).push_ok(&[0x25; 65]);
+        let err = EthApp::sign_transaction_with_mode(&mock, params, TransactionMode::StoreOnly)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthAppError::InvalidResponseData(_)));
+    }
+
+    #[tokio::test]
+    async fn test_transaction_size_limit() {
+        use ledger_sdk_transport::mock::MockExchange;
+
+        let path = BipPath::new(vec![0x8000002C, 0x8000003C, 0x80000000, 0, 0]).unwrap();
+        let max = SignTransactionParams::new(path.clone(), Vec::new()).max_transaction_size();
+        assert_eq!(max, 255 * 255 - 21);
+
+        // At the limit: exactly MAX_TRANSACTION_CHUNKS full APDUs
+        let mock = MockExchange::new();
+        for _ in 0..length::MAX_TRANSACTION_CHUNKS - 1 {
+            mock.push_ok(&[]);
+        }
+        mock.push_ok(&[0x25; 65]);
+        let params = SignTransactionParams::new(path.clone(), vec![0xAB; max]);
+        EthApp::sign_transaction(&mock, params).await.unwrap();
+        assert_eq!(mock.sent().len(), length::MAX_TRANSACTION_CHUNKS);
+
+        // One byte over: rejected before anything is sent
+        let mock = MockExchange::new();
+        let params = SignTransactionParams::new(path, vec![0xAB; max + 1]);
+        let err = EthApp::sign_transaction(&mock, params).await.unwrap_err();
+        assert_eq!(err, EthAppError::TransactionTooLarge { size: max + 1, max });
+        assert!(mock.sent().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_path_policy_override() {
+        use crate::types::PathPolicy;
+        use ledger_sdk_transport::mock::MockExchange;
+
+        // Testnet path: fine by default, rejected under the Ethereum standard policy
+        let path = BipPath::from_string("m/44'/1'/0'/0/0").unwrap();
+        let params = SignTransactionParams::new(path, vec![0xAB; 10]);
+
+        let mock = MockExchange::new();
+        let err = EthApp::sign_transaction(
+            &mock,
+            params.clone().with_path_policy(PathPolicy::EthereumOnly),
+        )
+        .await
+        .unwrap_err();
+        assert!(
+            matches!(err, EthAppError::InvalidBip32Path(ref reason) if reason.contains("Ethereum standard policy"))
+        );
+        assert!(mock.sent().is_empty());
+
+        mock.push_ok(&[0x25; 65]);
+        EthApp::sign_transaction(&mock, params.with_path_policy(PathPolicy::Testnet))
+            .await
+            .unwrap();
+        assert_eq!(mock.sent().len(), 1);
+    }
 }
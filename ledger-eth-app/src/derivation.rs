@@ -0,0 +1,112 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Off-device public key derivation
+//!
+//! Available with the `derivation` feature. Derives the non-hardened children of
+//! an [`ExtendedPublicKey`] (BIP32 public child key derivation), so wallet
+//! discovery fetches the account key once with
+//! [`crate::EthereumApp::get_account_xpub`] instead of one APDU per address.
+
+use hmac::{Hmac, Mac};
+use k256::elliptic_curve::{sec1::ToEncodedPoint, PrimeField};
+use k256::{FieldBytes, ProjectivePoint, PublicKey, Scalar};
+use sha2::Sha512;
+
+use crate::types::{BipPath, ExtendedPublicKey};
+use crate::utils::keccak256;
+
+/// Indices from this value on are hardened and need the private key
+const HARDENED: u32 = 0x8000_0000;
+
+impl ExtendedPublicKey {
+    /// Extended public key of the non-hardened child `index`
+    ///
+    /// Fails for hardened indices and, with negligible probability, for an index
+    /// BIP32 declares invalid; skip to the next index in that case.
+    pub fn child(&self, index: u32) -> Result<ExtendedPublicKey, String> {
+        if index >= HARDENED {
+            return Err(format!(
+                "Cannot derive hardened child {}' from a public key",
+                index - HARDENED
+            ));
+        }
+        let parent = PublicKey::from_sec1_bytes(&self.public_key)
+            .map_err(|_| "Invalid extended public key".to_string())?;
+
+        let mut mac = Hmac::<Sha512>::new_from_slice(&self.chain_code)
+            .expect("HMAC accepts keys of any length");
+        mac.update(parent.to_encoded_point(true).as_bytes());
+        mac.update(&index.to_be_bytes());
+        let output = mac.finalize().into_bytes();
+        let (tweak, chain_code) = output.split_at(32);
+
+        // A tweak at or above the curve order makes the index invalid
+        let tweak: [u8; 32] = tweak.try_into().expect("32 bytes");
+        let tweak = Option::<Scalar>::from(Scalar::from_repr(FieldBytes::from(tweak)))
+            .ok_or_else(|| format!("Child {} is invalid, use the next index", index))?;
+        let point = ProjectivePoint::GENERATOR * tweak + parent.to_projective();
+        let child = PublicKey::from_affine(point.to_affine())
+            .map_err(|_| format!("Child {} is invalid, use the next index", index))?;
+
+        let mut indices = self.path.indices.clone();
+        indices.push(index);
+        Ok(ExtendedPublicKey {
+            path: BipPath::new(indices)?,
+            public_key: child.to_encoded_point(false).as_bytes().to_vec(),
+            chain_code: chain_code
+                .try_into()
+                .expect("HMAC-SHA512 output is 64 bytes"),
+        })
+    }
+
+    /// Ethereum address of the non-hardened child `index`
+    pub fn derive_child(&self, index: u32) -> Result<[u8; 20], String> {
+        let child = self.child(index)?;
+        // Skip the 0x04 uncompressed point tag
+        let hash = keccak256(&child.public_key[1..]);
+        Ok(hash[12..].try_into().expect("20 bytes"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `m/44'/60'/0'` of the "abandon ... about" BIP39 test mnemonic
+    fn account_xpub() -> ExtendedPublicKey {
+        ExtendedPublicKey {
+            path: BipPath::from_string("m/44'/60'/0'").unwrap(),
+            public_key: hex::decode(
+                "04eae4b876a8696134b868f88cc2f51f715f2dbedb7446b8e6edf3d4541c4eb67b\
+                 61ed8eb62af1d433cd11b4f59923ac1f87f328c5673396ee55acc6195d92b320",
+            )
+            .unwrap(),
+            chain_code: hex::decode(
+                "d882718b7a42806803eeb17f7483f20620611adb88fc943c898dc5aba94c2819",
+            )
+            .unwrap()
+            .try_into()
+            .unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_derive_child_known_vector() {
+        let external = account_xpub().child(0).unwrap();
+        assert_eq!(external.path.to_string(), "m/44'/60'/0'/0");
+
+        // Well-known first address of the test mnemonic
+        let address = external.derive_child(0).unwrap();
+        assert_eq!(
+            hex::encode(address),
+            "9858effd232b4033e47d90003d41ec34ecaeda94"
+        );
+        assert_eq!(
+            external.child(0).unwrap().path,
+            BipPath::ethereum_standard(0, 0)
+        );
+
+        let err = account_xpub().child(HARDENED).unwrap_err();
+        assert!(err.contains("hardened"), "{}", err);
+    }
+}
@@ -0,0 +1,42 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! APDU payload encoders
+//!
+//! The commands build their payloads with these functions. They are collected
+//! here for callers assembling their own flows on top of
+//! [`EthereumApp::raw_exchange`](crate::EthereumApp::raw_exchange), or checking
+//! what is sent to the device.
+//!
+//! Encoding a BIP32 path, as sent by GET ETH PUBLIC ADDRESS and the signing
+//! commands:
+//!
+//! ```rust
+//! use ledger_sdk_eth_app::encoding::encode_bip32_path;
+//! use ledger_sdk_eth_app::BipPath;
+//!
+//! let path = BipPath::from_string("m/44'/60'/0'/0/0").unwrap();
+//! assert_eq!(
+//!     hex::encode(encode_bip32_path(&path)),
+//!     "058000002c8000003c800000000000000000000000"
+//! );
+//! ```
+//!
+//! Encoding an EIP-712 struct field for EIP712 SEND STRUCT DEFINITION:
+//!
+//! ```rust
+//! use ledger_sdk_eth_app::encoding::encode_field_definition;
+//! use ledger_sdk_eth_app::{Eip712FieldDefinition, Eip712FieldType};
+//!
+//! let field = Eip712FieldDefinition::new(Eip712FieldType::Uint(32), "amount".to_string());
+//! let data = encode_field_definition::<std::io::Error>(&field).unwrap();
+//! // type descriptor (uint with size), size in bytes, then the length-prefixed name
+//! assert_eq!(hex::encode(data), "422006616d6f756e74");
+//! ```
+
+pub use crate::commands::eip712::encoding::{
+    encode_array_size, encode_field_definition, encode_filter_params, APDU_MAX_PAYLOAD,
+};
+pub use crate::utils::{
+    chunk_data, chunk_length_prefixed, decode_bip32_path, decode_chain_id, encode_bip32_path,
+    encode_chain_id, ChunkedPayload,
+};
@@ -2,7 +2,7 @@
 
 //! Error types for Ethereum application
 
-use ledger_sdk_device_base::LedgerAppError;
+use ledger_sdk_device_base::{LedgerAppError, StatusWord};
 use thiserror::Error;
 
 /// Ethereum application specific errors
@@ -32,6 +32,10 @@ pub enum EthAppError<E: std::error::Error> {
     #[error("Message data too large: {size} bytes (max {max})")]
     MessageTooLarge { size: usize, max: usize },
 
+    /// EIP-712 array has more items than the app accepts
+    #[error("EIP-712 array too large: {size} items (max {max})")]
+    ArrayTooLarge { size: usize, max: usize },
+
     /// Invalid transaction format
     #[error("Invalid transaction format: {0}")]
     InvalidTransaction(String),
@@ -84,6 +88,33 @@ pub enum EthAppError<E: std::error::Error> {
     #[error("Unsupported version: {0}")]
     UnsupportedVersion(String),
 
+    /// Another app than the expected one is open on the device
+    #[error("Wrong app open: expected {expected}, found {found}")]
+    WrongApp { expected: String, found: String },
+
+    /// The device is switching apps and re-enumerating; reopen the transport and retry
+    #[error("App switch in progress: reopen the transport and retry")]
+    AppSwitchPending,
+
+    /// The device is locked (status 0x5515), e.g. its screen locked mid-review
+    #[error("Device locked: unlock it and retry")]
+    DeviceLocked,
+
+    /// The device disconnected mid-operation and lost its state
+    ///
+    /// Retriable: restart the whole operation, from its first command, once the
+    /// transport has reconnected.
+    #[error("Device disconnected: restart the operation")]
+    Disconnected,
+
+    /// One path of a batch failed; the paths before it succeeded
+    #[error("Path {index} ({path}) failed: {}", .error.to_string())]
+    BatchItem {
+        index: usize,
+        path: String,
+        error: Box<EthAppError<E>>,
+    },
+
     /// Device returned a specific status word
     #[error("Device status 0x{sw:04X}: {description}")]
     DeviceStatus { sw: u16, description: String },
@@ -92,18 +123,39 @@ pub enum EthAppError<E: std::error::Error> {
 impl<E: std::error::Error> EthAppError<E> {
     /// Check if error is due to user rejection
     pub fn is_user_rejected(&self) -> bool {
-        matches!(self, EthAppError::UserRejected)
+        matches!(self.item_error(), EthAppError::UserRejected)
+    }
+
+    /// Check if the device was locked; unlock it and retry
+    pub fn is_device_locked(&self) -> bool {
+        matches!(self.item_error(), EthAppError::DeviceLocked)
+    }
+
+    /// Check if the device disconnected; restart the operation to retry
+    pub fn is_disconnected(&self) -> bool {
+        matches!(self.item_error(), EthAppError::Disconnected)
     }
 
     /// Check if error is due to transport/communication issues
     pub fn is_transport_error(&self) -> bool {
-        matches!(self, EthAppError::Transport(_))
+        matches!(self.item_error(), EthAppError::Transport(_))
+    }
+
+    /// Underlying transport error, if this error came from the transport
+    ///
+    /// Lets callers inspect transport specific details (e.g. a `LedgerHIDError`)
+    /// without matching through [`LedgerAppError`].
+    pub fn transport_error(&self) -> Option<&E> {
+        match self.item_error() {
+            EthAppError::Transport(LedgerAppError::TransportError(err)) => Some(err),
+            _ => None,
+        }
     }
 
     /// Check if error is due to invalid input parameters
     pub fn is_invalid_input(&self) -> bool {
         matches!(
-            self,
+            self.item_error(),
             EthAppError::InvalidBip32Path(_)
                 | EthAppError::InvalidAddress(_)
                 | EthAppError::InvalidSignature(_)
@@ -112,6 +164,14 @@ impl<E: std::error::Error> EthAppError<E> {
                 | EthAppError::InvalidChainId(_)
         )
     }
+
+    /// Error of the failed batch item, or this error outside batches
+    fn item_error(&self) -> &Self {
+        match self {
+            EthAppError::BatchItem { error, .. } => error.item_error(),
+            other => other,
+        }
+    }
 }
 
 /// Result type alias for Ethereum application operations
@@ -120,33 +180,66 @@ pub type EthAppResult<T, E> = Result<T, EthAppError<E>>;
 /// Map LedgerAppError to Ethereum app specific error with SW decoding when possible
 pub fn map_ledger_error<E: std::error::Error>(err: LedgerAppError<E>) -> EthAppError<E> {
     match err {
-        // User cancel / security status not satisfied
-        LedgerAppError::AppSpecific(0x6982, _) => EthAppError::UserRejected,
-        LedgerAppError::Unknown(0x6982) => EthAppError::UserRejected,
+        // User cancel / security status not satisfied, or rejected on the device
+        LedgerAppError::Status(StatusWord::SecurityStatusNotSatisfied | StatusWord::DenyByUser) => {
+            EthAppError::UserRejected
+        }
+        LedgerAppError::Status(StatusWord::DeviceLocked) => EthAppError::DeviceLocked,
 
         // Map known ETH app status words to descriptions
-        LedgerAppError::AppSpecific(sw, _) | LedgerAppError::Unknown(sw) => {
-            EthAppError::DeviceStatus {
-                sw,
-                description: describe_eth_status(sw).to_string(),
-            }
-        }
+        LedgerAppError::Status(status) => EthAppError::DeviceStatus {
+            sw: status.code(),
+            description: describe_eth_status(status.code()).to_string(),
+        },
 
         // Fallback: treat as transport-layer app error
         other => EthAppError::Transport(other),
     }
 }
 
+/// Map a transport error of a signing flow, singling out a disconnected device
+///
+/// Transports report a disconnection with an `std::io::Error` of kind
+/// `NotConnected` as source, like `LedgerHIDError::Disconnected`.
+pub(crate) fn map_exchange_error<E: std::error::Error>(err: E) -> EthAppError<E> {
+    let disconnected = err
+        .source()
+        .and_then(|source| source.downcast_ref::<std::io::Error>())
+        .is_some_and(|source| source.kind() == std::io::ErrorKind::NotConnected);
+    if disconnected {
+        EthAppError::Disconnected
+    } else {
+        EthAppError::Transport(err.into())
+    }
+}
+
+/// Map the `0x6D00` an older app answers to a command it does not know to
+/// `EthAppError::FeatureNotSupported(feature)`
+///
+/// For version-gated commands only; other errors are mapped with `fallback`.
+pub(crate) fn map_gated_error<E: std::error::Error>(
+    feature: &'static str,
+    fallback: fn(LedgerAppError<E>) -> EthAppError<E>,
+) -> impl Fn(LedgerAppError<E>) -> EthAppError<E> {
+    move |err| match err {
+        LedgerAppError::Status(StatusWord::InsNotSupported) => {
+            EthAppError::FeatureNotSupported(feature.to_string())
+        }
+        other => fallback(other),
+    }
+}
+
 /// ETH app specific status word descriptions (subset per spec)
-fn describe_eth_status(sw: u16) -> &'static str {
+pub(crate) fn describe_eth_status(sw: u16) -> &'static str {
     match sw {
+        0x5515 => "Device locked",
         0x6001 => "Mode check fail",
         0x6501 => "TransactionType not supported",
         0x6502 => "Output buffer too small for chainId conversion",
         0x6982 => "Security status not satisfied (Canceled by user)",
         0x6983 => "Wrong Data length",
         0x6984 => "Plugin not installed",
-        0x6985 => "Condition not satisfied",
+        0x6985 => "Condition not satisfied (Rejected by user)",
         0x6A00 => "Error without info",
         0x6A80 => "Invalid data",
         0x6A84 => "Insufficient memory",
@@ -161,3 +254,92 @@ fn describe_eth_status(sw: u16) -> &'static str {
         _ => "Unknown status",
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use ledger_sdk_transport::mock::MockError;
+
+    use super::*;
+
+    /// Transport error caused by an I/O error, like `MockError::Disconnected`
+    #[derive(Debug, Error)]
+    #[error("transport I/O error")]
+    struct IoTransportError(#[source] std::io::Error);
+
+    #[test]
+    fn test_transport_error() {
+        let err: EthAppError<MockError> =
+            EthAppError::Transport(LedgerAppError::TransportError(MockError::NoResponse));
+        assert_eq!(err.transport_error(), Some(&MockError::NoResponse));
+
+        let err: EthAppError<MockError> = EthAppError::Transport(LedgerAppError::InvalidSignature);
+        assert!(err.transport_error().is_none());
+        assert!(EthAppError::<MockError>::UserRejected
+            .transport_error()
+            .is_none());
+    }
+
+    #[test]
+    fn test_map_ledger_error_status() {
+        let err = map_ledger_error::<MockError>(LedgerAppError::Status(
+            StatusWord::SecurityStatusNotSatisfied,
+        ));
+        assert!(err.is_user_rejected());
+
+        let err = map_ledger_error::<MockError>(LedgerAppError::Status(StatusWord::from(0x6985)));
+        assert!(err.is_user_rejected());
+
+        let err = map_ledger_error::<MockError>(LedgerAppError::Status(StatusWord::Other(0x6501)));
+        assert!(matches!(
+            err,
+            EthAppError::DeviceStatus { sw: 0x6501, ref description }
+                if description == "TransactionType not supported"
+        ));
+
+        let err = map_ledger_error::<MockError>(LedgerAppError::Status(StatusWord::from(0x5515)));
+        assert!(err.is_device_locked());
+
+        let err = map_ledger_error::<MockError>(LedgerAppError::InvalidSignature);
+        assert!(err.is_transport_error());
+    }
+
+    #[test]
+    fn test_batch_item_classification() {
+        let batch = |error| EthAppError::<MockError>::BatchItem {
+            index: 1,
+            path: "m/44'/60'/0'/0/1".to_string(),
+            error: Box::new(error),
+        };
+        assert!(batch(EthAppError::UserRejected).is_user_rejected());
+        assert!(batch(EthAppError::DeviceLocked).is_device_locked());
+        assert!(batch(EthAppError::Disconnected).is_disconnected());
+        assert!(batch(EthAppError::InvalidBip32Path(String::new())).is_invalid_input());
+        assert!(!batch(EthAppError::DeviceLocked).is_user_rejected());
+    }
+
+    #[test]
+    fn test_map_exchange_error() {
+        let err = map_exchange_error(IoTransportError(std::io::Error::new(
+            std::io::ErrorKind::NotConnected,
+            "No such device",
+        )));
+        assert!(err.is_disconnected());
+
+        let err = map_exchange_error(IoTransportError(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            "garbled frame",
+        )));
+        assert!(err.is_transport_error());
+        assert!(map_exchange_error(MockError::NoResponse).is_transport_error());
+    }
+
+    #[test]
+    fn test_map_gated_error() {
+        let map = map_gated_error::<MockError>("Safe account information", EthAppError::Transport);
+        assert!(matches!(
+            map(LedgerAppError::Status(StatusWord::InsNotSupported)),
+            EthAppError::FeatureNotSupported(ref feature) if feature == "Safe account information"
+        ));
+        assert!(map(LedgerAppError::Status(StatusWord::Other(0x6A80))).is_transport_error());
+    }
+}
@@ -0,0 +1,306 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Local EIP-712 hashing
+//!
+//! Available with the `hashing` feature. Computes the domain separator and the
+//! `hashStruct` of the message of an [`Eip712TypedData`] (EIP-712 `encodeType`,
+//! `encodeData` and keccak256), so apps without full EIP-712 support can still
+//! sign typed data with [`crate::SignEip712V0`].
+
+use std::collections::BTreeSet;
+
+use serde_json::Value;
+
+use crate::commands::eip712::high_level::Eip712Converter;
+use crate::types::{
+    BipPath, Eip712Field, Eip712FieldType, Eip712Struct, Eip712TypedData, Eip712Types,
+    SignEip712Params,
+};
+use crate::utils::{eip712_domain_separator, keccak256};
+
+impl Eip712TypedData {
+    /// `hashStruct` of the domain, following the `EIP712Domain` type if given
+    ///
+    /// Without an `EIP712Domain` type, the domain fields that are set are hashed
+    /// in their canonical order.
+    pub fn domain_separator(&self) -> Result<[u8; 32], String> {
+        match self.types.get("EIP712Domain") {
+            Some(domain_type) => eip712_domain_separator(&self.domain, domain_type),
+            None => eip712_domain_separator(&self.domain, &self.default_domain_type()),
+        }
+    }
+
+    /// `hashStruct` of the message as an instance of the primary type
+    pub fn struct_hash(&self) -> Result<[u8; 32], String> {
+        hash_struct(&self.types, &self.primary_type, &self.message)
+    }
+
+    /// Parameters signing the typed data with the key at `path` in v0 mode
+    pub fn to_v0_params(&self, path: BipPath) -> Result<SignEip712Params, String> {
+        Ok(SignEip712Params::new(
+            path,
+            self.domain_separator()?,
+            self.struct_hash()?,
+        ))
+    }
+
+    /// `EIP712Domain` type of the fields set in the domain
+    fn default_domain_type(&self) -> Eip712Struct {
+        let domain = &self.domain;
+        [
+            (domain.name.is_some(), "name", "string"),
+            (domain.version.is_some(), "version", "string"),
+            (domain.chain_id.is_some(), "chainId", "uint256"),
+            (
+                domain.verifying_contract.is_some(),
+                "verifyingContract",
+                "address",
+            ),
+            (domain.salt.is_some(), "salt", "bytes32"),
+        ]
+        .into_iter()
+        .filter(|(present, _, _)| *present)
+        .fold(Eip712Struct::new(), |domain_type, (_, name, r#type)| {
+            domain_type.with_field(Eip712Field::new(name.to_string(), r#type.to_string()))
+        })
+    }
+}
+
+/// `keccak256(typeHash ‖ encodeData(value))` of the struct `name`
+fn hash_struct(types: &Eip712Types, name: &str, value: &Value) -> Result<[u8; 32], String> {
+    let definition = types
+        .get(name)
+        .ok_or_else(|| format!("Unknown struct type '{}'", name))?;
+
+    let mut encoded = keccak256(encode_type(types, name)?.as_bytes()).to_vec();
+    for field in &definition.fields {
+        let field_value = value
+            .get(&field.name)
+            .ok_or_else(|| format!("Missing value for {}.{}", name, field.name))?;
+        let word = encode_field(types, &field.r#type, field_value)
+            .map_err(|e| format!("{}.{}: {}", name, field.name, e))?;
+        encoded.extend_from_slice(&word);
+    }
+
+    Ok(keccak256(&encoded))
+}
+
+/// `encodeType`: the struct followed by the structs it references, sorted by name
+fn encode_type(types: &Eip712Types, name: &str) -> Result<String, String> {
+    let mut dependencies = BTreeSet::new();
+    collect_dependencies(types, name, &mut dependencies)?;
+    dependencies.remove(name);
+
+    let mut encoded = String::new();
+    for struct_name in std::iter::once(name).chain(dependencies) {
+        let fields = types[struct_name]
+            .fields
+            .iter()
+            .map(|field| format!("{} {}", field.r#type, field.name))
+            .collect::<Vec<_>>()
+            .join(",");
+        encoded.push_str(&format!("{}({})", struct_name, fields));
+    }
+
+    Ok(encoded)
+}
+
+/// Insert `name` and every struct it references, directly or not, into `found`
+fn collect_dependencies<'a>(
+    types: &'a Eip712Types,
+    name: &str,
+    found: &mut BTreeSet<&'a str>,
+) -> Result<(), String> {
+    let (name, definition) = types
+        .get_key_value(name)
+        .ok_or_else(|| format!("Unknown struct type '{}'", name))?;
+    if !found.insert(name.as_str()) {
+        return Ok(());
+    }
+
+    for field in &definition.fields {
+        let base_type = field.r#type.split('[').next().unwrap_or_default().trim();
+        if types.contains_key(base_type) {
+            collect_dependencies(types, base_type, found)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// 32-byte `encodeData` word of a value of type `type_str`
+fn encode_field(types: &Eip712Types, type_str: &str, value: &Value) -> Result<[u8; 32], String> {
+    let type_str = type_str.trim();
+
+    // Arrays hash the concatenated encoding of their items, one level at a time
+    if let Some(item_type) = type_str
+        .strip_suffix(']')
+        .and_then(|type_str| type_str.rsplit_once('[').map(|(item_type, _)| item_type))
+    {
+        let items = value
+            .as_array()
+            .ok_or_else(|| format!("expected array, got {}", value))?;
+        let mut encoded = Vec::with_capacity(items.len() * 32);
+        for item in items {
+            encoded.extend_from_slice(&encode_field(types, item_type, item)?);
+        }
+        return Ok(keccak256(&encoded));
+    }
+
+    if types.contains_key(type_str) {
+        return hash_struct(types, type_str, value);
+    }
+
+    let field_type = Eip712Converter::parse_field_type(type_str)?;
+    let bytes = Eip712Converter::convert_value_to_field_value(value, &field_type)?.value;
+
+    let mut word = [0u8; 32];
+    match field_type {
+        Eip712FieldType::String | Eip712FieldType::DynamicBytes => word = keccak256(&bytes),
+        Eip712FieldType::FixedBytes(_) => word[..bytes.len()].copy_from_slice(&bytes),
+        Eip712FieldType::Int(_) => {
+            // Sign-extend negative values
+            if bytes.first().is_some_and(|byte| byte & 0x80 != 0) {
+                word = [0xFF; 32];
+            }
+            word[32 - bytes.len()..].copy_from_slice(&bytes);
+        }
+        Eip712FieldType::Uint(_) | Eip712FieldType::Address | Eip712FieldType::Bool => {
+            word[32 - bytes.len()..].copy_from_slice(&bytes);
+        }
+        Eip712FieldType::Custom(name) => {
+            return Err(format!("Unknown struct type '{}'", name));
+        }
+    }
+
+    Ok(word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The `Mail` example of the EIP-712 specification
+    const MAIL: &str = r#"{
+      "types": {
+        "EIP712Domain": [
+          {"name": "name", "type": "string"},
+          {"name": "version", "type": "string"},
+          {"name": "chainId", "type": "uint256"},
+          {"name": "verifyingContract", "type": "address"}
+        ],
+        "Person": [
+          {"name": "name", "type": "string"},
+          {"name": "wallet", "type": "address"}
+        ],
+        "Mail": [
+          {"name": "from", "type": "Person"},
+          {"name": "to", "type": "Person"},
+          {"name": "contents", "type": "string"}
+        ]
+      },
+      "primaryType": "Mail",
+      "domain": {
+        "name": "Ether Mail",
+        "version": "1",
+        "chainId": 1,
+        "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+      },
+      "message": {
+        "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+        "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+        "contents": "Hello, Bob!"
+      }
+    }"#;
+
+    fn mail() -> Eip712TypedData {
+        Eip712Converter::parse_json_to_typed_data(MAIL).unwrap()
+    }
+
+    #[test]
+    fn test_mail_encode_type() {
+        let typed_data = mail();
+        assert_eq!(
+            encode_type(&typed_data.types, "Mail").unwrap(),
+            "Mail(Person from,Person to,string contents)Person(string name,address wallet)"
+        );
+        assert_eq!(
+            hex::encode(keccak256(
+                encode_type(&typed_data.types, "Mail").unwrap().as_bytes()
+            )),
+            "a0cedeb2dc280ba39b857546d74f5549c3a1d7bdc2dd96bf881f76108e23dac2"
+        );
+    }
+
+    #[test]
+    fn test_mail_hashes() {
+        let typed_data = mail();
+        let domain_separator = typed_data.domain_separator().unwrap();
+        let struct_hash = typed_data.struct_hash().unwrap();
+        assert_eq!(
+            hex::encode(domain_separator),
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+        );
+        assert_eq!(
+            hex::encode(struct_hash),
+            "c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e"
+        );
+
+        let mut digest = vec![0x19, 0x01];
+        digest.extend_from_slice(&domain_separator);
+        digest.extend_from_slice(&struct_hash);
+        assert_eq!(
+            hex::encode(keccak256(&digest)),
+            "be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2"
+        );
+    }
+
+    #[test]
+    fn test_domain_separator_without_domain_type() {
+        let mut typed_data = mail();
+        typed_data.types.remove("EIP712Domain");
+        assert_eq!(
+            hex::encode(typed_data.domain_separator().unwrap()),
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+        );
+    }
+
+    #[test]
+    fn test_to_v0_params() {
+        let typed_data = mail();
+        let params = typed_data
+            .to_v0_params(BipPath::ethereum_standard(0, 0))
+            .unwrap();
+        assert_eq!(params.domain_hash, typed_data.domain_separator().unwrap());
+        assert_eq!(params.message_hash, typed_data.struct_hash().unwrap());
+    }
+
+    #[test]
+    fn test_encode_atomic_and_array_fields() {
+        let types = Eip712Types::new();
+
+        let word = encode_field(&types, "int8", &serde_json::json!(-1)).unwrap();
+        assert_eq!(word, [0xFF; 32]);
+
+        let word = encode_field(&types, "bytes4", &serde_json::json!("0xdeadbeef")).unwrap();
+        assert_eq!(&word[..4], &[0xDE, 0xAD, 0xBE, 0xEF]);
+        assert_eq!(&word[4..], &[0; 28]);
+
+        let word = encode_field(&types, "uint8[]", &serde_json::json!([1, 2])).unwrap();
+        let mut items = [0u8; 64];
+        items[31] = 1;
+        items[63] = 2;
+        assert_eq!(word, keccak256(&items));
+    }
+
+    #[test]
+    fn test_missing_field_value() {
+        let mut typed_data = mail();
+        typed_data.message["to"]
+            .as_object_mut()
+            .unwrap()
+            .remove("wallet");
+        let err = typed_data.struct_hash().unwrap_err();
+        assert!(err.contains("Person.wallet"), "{}", err);
+    }
+}
@@ -184,6 +184,201 @@ pub mod p2_eip712_filtering {
     pub const RAW_FIELD: u8 = 0xFF;
 }
 
+/// P1 parameter constants for PROVIDE NETWORK INFORMATION
+pub mod p1_provide_network_info {
+    /// First chunk (carries the 2-byte total payload length)
+    pub const FIRST_CHUNK: u8 = 0x01;
+    /// Following chunk
+    pub const FOLLOWING_CHUNK: u8 = 0x00;
+}
+
+/// P2 parameter constants for PROVIDE NETWORK INFORMATION
+pub mod p2_provide_network_info {
+    /// Network configuration descriptor
+    pub const NETWORK_CONFIGURATION: u8 = 0x00;
+    /// Network icon bitmap
+    pub const NETWORK_ICON: u8 = 0x01;
+}
+
+/// TLV tags of the dynamic network descriptor
+pub mod network_info_tag {
+    /// Structure type
+    pub const STRUCTURE_TYPE: u8 = 0x01;
+    /// Structure version
+    pub const STRUCTURE_VERSION: u8 = 0x02;
+    /// Chain ID
+    pub const CHAIN_ID: u8 = 0x23;
+    /// Native currency ticker
+    pub const TICKER: u8 = 0x24;
+    /// DER encoded signature over the descriptor
+    pub const DER_SIGNATURE: u8 = 0x15;
+    /// Blockchain family
+    pub const BLOCKCHAIN_FAMILY: u8 = 0x51;
+    /// Network name
+    pub const NETWORK_NAME: u8 = 0x52;
+    /// SHA-256 of the network icon
+    pub const NETWORK_ICON_HASH: u8 = 0x53;
+
+    /// STRUCTURE_TYPE value for a dynamic network descriptor
+    pub const TYPE_DYNAMIC_NETWORK: u8 = 0x08;
+    /// Supported STRUCTURE_VERSION value
+    pub const VERSION: u8 = 0x01;
+    /// BLOCKCHAIN_FAMILY value for Ethereum
+    pub const FAMILY_ETHEREUM: u8 = 0x01;
+}
+
+/// P1 parameter constants for SIGN EIP 7702 AUTHORIZATION
+pub mod p1_sign_eip7702 {
+    /// First chunk (carries the BIP32 path and the 2-byte TLV length)
+    pub const FIRST_CHUNK: u8 = 0x01;
+    /// Following chunk
+    pub const FOLLOWING_CHUNK: u8 = 0x00;
+}
+
+/// TLV tags of the EIP-7702 authorization
+pub mod eip7702_tag {
+    /// Structure version
+    pub const STRUCT_VERSION: u8 = 0x00;
+    /// Address of the contract the account delegates to
+    pub const DELEGATE_ADDR: u8 = 0x01;
+    /// Chain ID the authorization is valid on, 0 for any chain
+    pub const CHAIN_ID: u8 = 0x02;
+    /// Account nonce
+    pub const NONCE: u8 = 0x03;
+
+    /// Supported STRUCT_VERSION value
+    pub const VERSION: u8 = 0x01;
+}
+
+/// P1 parameter constants for PROVIDE SAFE ACCOUNT
+pub mod p1_provide_safe_account {
+    /// First chunk (carries the 2-byte total payload length)
+    pub const FIRST_CHUNK: u8 = 0x01;
+    /// Following chunk
+    pub const FOLLOWING_CHUNK: u8 = 0x00;
+}
+
+/// P2 parameter constants for PROVIDE SAFE ACCOUNT
+pub mod p2_provide_safe_account {
+    /// Safe account descriptor (address, threshold, signers count)
+    pub const SAFE_DESCRIPTOR: u8 = 0x00;
+    /// Signer descriptor (owner addresses)
+    pub const SIGNER_DESCRIPTOR: u8 = 0x01;
+}
+
+/// TLV tags of the Safe account and signer descriptors
+pub mod safe_account_tag {
+    /// Structure type
+    pub const STRUCTURE_TYPE: u8 = 0x01;
+    /// Structure version
+    pub const STRUCTURE_VERSION: u8 = 0x02;
+    /// Challenge returned by GET CHALLENGE
+    pub const CHALLENGE: u8 = 0x12;
+    /// DER encoded signature over the descriptor
+    pub const DER_SIGNATURE: u8 = 0x15;
+    /// Safe address (Safe descriptor) or owner address (signer descriptor)
+    pub const ADDRESS: u8 = 0x22;
+    /// Number of confirmations required
+    pub const THRESHOLD: u8 = 0xA0;
+    /// Number of owners
+    pub const SIGNERS_COUNT: u8 = 0xA1;
+    /// Role of the device's account in the Safe
+    pub const ROLE: u8 = 0xA2;
+
+    /// STRUCTURE_TYPE value for a Safe account descriptor
+    pub const TYPE_SAFE_ACCOUNT: u8 = 0x27;
+    /// STRUCTURE_TYPE value for a signer descriptor
+    pub const TYPE_SIGNER: u8 = 0x28;
+    /// Supported STRUCTURE_VERSION value
+    pub const VERSION: u8 = 0x01;
+
+    /// ROLE value for an owner of the Safe
+    pub const ROLE_SIGNER: u8 = 0x00;
+    /// ROLE value for a proposer that is not an owner
+    pub const ROLE_PROPOSER: u8 = 0x01;
+}
+
+/// P1 parameter constants for PROVIDE TX SIMULATION
+pub mod p1_provide_tx_simulation {
+    /// First chunk (carries the 2-byte total payload length)
+    pub const FIRST_CHUNK: u8 = 0x01;
+    /// Following chunk
+    pub const FOLLOWING_CHUNK: u8 = 0x00;
+}
+
+/// TLV tags of the transaction simulation result
+pub mod tx_simulation_tag {
+    /// Structure type
+    pub const STRUCTURE_TYPE: u8 = 0x01;
+    /// Structure version
+    pub const STRUCTURE_VERSION: u8 = 0x02;
+    /// DER encoded signature over the result
+    pub const DER_SIGNATURE: u8 = 0x15;
+    /// Address of the account the transaction is sent from
+    pub const ADDRESS: u8 = 0x22;
+    /// Chain ID of the simulated transaction
+    pub const CHAIN_ID: u8 = 0x23;
+    /// Hash of the simulated transaction
+    pub const TX_HASH: u8 = 0x27;
+    /// Normalized risk score
+    pub const NORMALIZED_RISK: u8 = 0x80;
+    /// Normalized risk category
+    pub const NORMALIZED_CATEGORY: u8 = 0x81;
+    /// Message from the simulation provider
+    pub const PROVIDER_MSG: u8 = 0x82;
+    /// Short URL to the provider's report
+    pub const TINY_URL: u8 = 0x83;
+    /// What was simulated (transaction, typed data, personal message)
+    pub const SIMULATION_TYPE: u8 = 0x84;
+
+    /// STRUCTURE_TYPE value for a transaction simulation result
+    pub const TYPE_TX_SIMULATION: u8 = 0x09;
+    /// Supported STRUCTURE_VERSION value
+    pub const VERSION: u8 = 0x01;
+
+    /// NORMALIZED_RISK value for a benign transaction
+    pub const RISK_BENIGN: u8 = 0x00;
+    /// NORMALIZED_RISK value for a transaction worth a warning
+    pub const RISK_WARNING: u8 = 0x01;
+    /// NORMALIZED_RISK value for a malicious transaction
+    pub const RISK_MALICIOUS: u8 = 0x02;
+
+    /// SIMULATION_TYPE value for a transaction
+    pub const SIMULATION_TRANSACTION: u8 = 0x00;
+    /// SIMULATION_TYPE value for EIP-712 typed data
+    pub const SIMULATION_TYPED_DATA: u8 = 0x01;
+    /// SIMULATION_TYPE value for a personal message
+    pub const SIMULATION_PERSONAL_MESSAGE: u8 = 0x02;
+}
+
+/// Field values for SET PLUGIN descriptors
+pub mod plugin {
+    /// Descriptor type for an Ethereum plugin
+    pub const TYPE_ETH_PLUGIN: u8 = 0x01;
+    /// Supported descriptor version
+    pub const VERSION: u8 = 0x01;
+    /// Key ID of the Ledger test key
+    pub const KEY_ID_TEST: u8 = 0x00;
+    /// Key ID of the Ledger production key
+    pub const KEY_ID_PROD: u8 = 0x02;
+    /// ECDSA over secp256k1 with SHA-256
+    pub const ALGORITHM_ECDSA_SHA256: u8 = 0x01;
+}
+
+/// Field values for PROVIDE NFT INFORMATION descriptors
+pub mod nft {
+    /// Descriptor type for an NFT collection
+    pub const TYPE_COLLECTION: u8 = 0x01;
+    /// Supported descriptor version
+    pub const VERSION: u8 = 0x01;
+    /// Key ID of the Ledger test key
+    pub const KEY_ID_TEST: u8 = 0x00;
+    /// Key ID of the Ledger production key
+    pub const KEY_ID_PROD: u8 = 0x01;
+    /// ECDSA over secp256k1 with SHA-256
+    pub const ALGORITHM_ECDSA_SHA256: u8 = 0x01;
+}
+
 /// Data length constants
 pub mod length {
     /// Maximum BIP 32 derivation path depth
@@ -202,10 +397,33 @@ pub mod length {
     pub const SIGNATURE_V_SIZE: usize = 1;
     /// Maximum message chunk size for chunked operations
     pub const MAX_MESSAGE_CHUNK_SIZE: usize = 255;
+    /// Maximum number of SIGN ETH TRANSACTION APDUs sent for one transaction
+    ///
+    /// The app parses the transaction as it streams in, so this is a client-side
+    /// ceiling (about 64 KiB, above the largest contract creation payloads) that
+    /// catches runaway inputs before the device is involved.
+    pub const MAX_TRANSACTION_CHUNKS: usize = 255;
+    /// Maximum personal message size
+    ///
+    /// The app hashes the message as it streams in, so its only bound is the
+    /// 4-byte length prefix. This client-side ceiling (64 KiB, 258 APDUs with a
+    /// 5-level path) is far above sign-in and off-chain order messages, and
+    /// stops runaway inputs before the user is asked to review them on device.
+    pub const MAX_PERSONAL_MESSAGE_SIZE: usize = 64 * 1024;
     /// Size of EIP-712 domain hash
     pub const EIP712_DOMAIN_HASH_SIZE: usize = 32;
     /// Size of EIP-712 message hash
     pub const EIP712_MESSAGE_HASH_SIZE: usize = 32;
+    /// Size of network icon hash
+    pub const NETWORK_ICON_HASH_SIZE: usize = 32;
+    /// Size of an ETH2 BLS12-381 public key
+    pub const ETH2_PUBLIC_KEY_SIZE: usize = 48;
+    /// Size of a contract method selector
+    pub const METHOD_SELECTOR_SIZE: usize = 4;
+    /// Largest EIP-712 array sent as a 1-byte size
+    pub const MAX_EIP712_ARRAY_SIZE_U8: usize = u8::MAX as usize;
+    /// Largest EIP-712 array sent as a 2-byte size
+    pub const MAX_EIP712_ARRAY_SIZE_U16: usize = u16::MAX as usize;
 }
 
 /// App configuration flags
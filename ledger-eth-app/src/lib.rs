@@ -15,21 +15,94 @@
 //! - **Type Safety**: Strongly typed parameters and responses
 //! - **Async/Await**: Fully async API using async-trait
 //!
+//! ## Command traits
 //!
+//! [`EthereumApp`] covers every command with inherent methods. The traits behind
+//! them are re-exported here and implemented for the [`EthApp`] marker, taking any
+//! [`Exchange`] as first argument, for callers managing the transport themselves:
+//!
+//! - [`GetAddress`], [`GetConfiguration`], [`PerformPrivacyOperation`]
+//! - [`GetEth2PublicKey`], [`SetEth2WithdrawalIndex`]
+//! - [`SignTransaction`], [`SignPersonalMessage`], [`SignAuthorization`]
+//! - [`SignEip712V0`], [`SignEip712Full`], [`SignEip712TypedData`],
+//!   [`Eip712StructDef`], [`Eip712StructImpl`], [`Eip712Filtering`]
+//! - [`ProvideNetworkInformation`], [`ProvideNftInformation`], [`ProvideSafeAccount`],
+//!   [`ProvideTxSimulation`], [`SetPlugin`], [`SetExternalPlugin`]
+//!
+//! ```rust
+//! use ledger_sdk_eth_app::{EthApp, GetConfiguration};
+//! use ledger_sdk_transport::mock::MockExchange;
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! // Any `Exchange`: `TransportNativeHID`, `TransportSpeculos`, ...
+//! let transport = MockExchange::new();
+//! transport.push_ok(&[0x00, 1, 12, 0]);
+//!
+//! let configuration = EthApp::get_configuration(&transport).await.unwrap();
+//! assert_eq!(configuration.version.minor, 12);
+//! # }
+//! ```
+//!
+//! ## Feature flags
+//!
+//! - `blocking`: adds `blocking::BlockingEthereumApp`, a synchronous wrapper for
+//!   callers without an async runtime
+//! - `recovery`: adds the `recover` module, recovering the signer address of a
+//!   returned [`Signature`] to check it against [`EthereumApp::get_address`]
+//! - `derivation`: adds the `derivation` module, deriving the addresses below an
+//!   [`ExtendedPublicKey`] from [`EthereumApp::get_account_xpub`] off-device
+//! - `apdu-trace`: logs every exchanged command header with its status word and
+//!   Ethereum app description at debug level through `tracing`
+//! - `hashing`: adds `Eip712TypedData::domain_separator`, `struct_hash` and
+//!   `to_v0_params`, hashing typed data locally for [`SignEip712V0`]
+//! - `filter-signatures`: adds [`FilterDescriptor`] schema hashing, signature
+//!   verification and signing with a development key
+//!
+
+use std::{
+    future::Future,
+    ops::{Deref, Range},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    },
+    time::{Duration, Instant},
+};
 
 use async_trait::async_trait;
-use ledger_sdk_device_base::App;
-use ledger_sdk_transport::Exchange;
+use ledger_sdk_device_base::{
+    App, AppExt, LedgerAppError, StatusWord, Version, DASHBOARD_APP_NAME,
+};
+use ledger_sdk_transport::{
+    wait_until_unlocked, APDUAnswer, APDUCommand, Exchange, ExchangeGuard, ExchangeLogger,
+    ExchangeTimeouts, UnlockWaitExchange, DEFAULT_UNLOCK_POLL_INTERVAL,
+};
+use tokio::io::AsyncRead;
 
 // Re-export all public types and traits
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod chains;
 pub mod commands;
+#[cfg(feature = "derivation")]
+pub mod derivation;
+pub mod encoding;
 pub mod errors;
+#[cfg(feature = "hashing")]
+mod hashing;
 pub mod instructions;
+#[cfg(feature = "recovery")]
+pub mod recover;
+mod trace;
+pub mod tx;
 pub mod types;
 pub mod utils;
 
+pub use chains::*;
 pub use commands::*;
 pub use errors::*;
+pub use tx::*;
 pub use types::*;
 
 /// Ethereum app marker implementing `App` trait CLA.
@@ -41,25 +114,171 @@ impl App for EthApp {
     const CLA: u8 = 0xE0;
 }
 
+/// Delay between configuration queries while waiting for the app to start
+const APP_SWITCH_POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+impl EthApp {
+    /// Name of the Ethereum app, as reported by `get_app_info`
+    pub const APP_NAME: &'static str = "Ethereum";
+
+    /// Whether the device waits for the user before answering this command
+    ///
+    /// Signing commands, EIP-712 field values (reviewed on the device as they
+    /// arrive) and anything asking to display and confirm count as interactive.
+    /// Signing chunks are all treated as interactive since the prompt only
+    /// follows the last one.
+    pub fn requires_confirmation(cla: u8, ins: u8, p1: u8, _p2: u8) -> bool {
+        use instructions::*;
+
+        if cla != Self::CLA {
+            return false;
+        }
+        match ins {
+            ins::SIGN_ETH_TRANSACTION
+            | ins::SIGN_ETH_PERSONAL_MESSAGE
+            | ins::SIGN_ETH_EIP712
+            | ins::EIP712_SEND_STRUCT_IMPLEMENTATION
+            | ins::SIGN_EIP7702_AUTHORIZATION => true,
+            ins::GET_ETH_PUBLIC_ADDRESS => p1 == p1_get_address::DISPLAY_AND_CONFIRM,
+            ins::GET_ETH2_PUBLIC_KEY => p1 == p1_get_eth2_key::DISPLAY_AND_CONFIRM,
+            ins::PERFORM_PRIVACY_OPERATION => p1 == p1_privacy_operation::DISPLAY_AND_CONFIRM,
+            _ => false,
+        }
+    }
+
+    /// Transport timeouts classifying commands with [`EthApp::requires_confirmation`]
+    ///
+    /// `protocol` bounds commands answered without user interaction, `confirmation`
+    /// the ones waiting for the user.
+    pub fn exchange_timeouts(protocol: Duration, confirmation: Duration) -> ExchangeTimeouts {
+        ExchangeTimeouts::new(protocol, confirmation, Self::requires_confirmation)
+    }
+}
+
 /// High-level Ethereum application client
 ///
 /// This struct provides a convenient interface for all Ethereum application operations.
 /// It wraps the transport layer and provides type-safe methods for interacting with
 /// the Ledger device.
+///
+/// Share it between tasks behind an `Arc`: each method has exclusive access to
+/// the device while it runs, so the APDUs of concurrent calls are never
+/// interleaved. See [`EthereumApp::exclusive`] for flows spanning several calls.
 #[derive(Debug)]
 pub struct EthereumApp<E: Exchange> {
-    transport: E,
+    transport: ExchangeGuard<E>,
+    /// Last configuration read from the device
+    configuration: RwLock<Option<AppConfiguration>>,
+    /// Whether version checks may reuse `configuration`
+    cache_configuration: bool,
+    /// Whether a transaction was stored with `TransactionMode::StoreOnly` and
+    /// its signing flow not started yet
+    transaction_stored: AtomicBool,
 }
 
 impl<E: Exchange> EthereumApp<E> {
     /// Create a new Ethereum application client
     pub fn new(transport: E) -> Self {
-        Self { transport }
+        Self {
+            transport: ExchangeGuard::new(transport),
+            configuration: RwLock::new(None),
+            cache_configuration: true,
+            transaction_stored: AtomicBool::new(false),
+        }
+    }
+
+    /// Enable or disable caching of the app configuration (enabled by default)
+    ///
+    /// With caching disabled every version-gated call queries the device again,
+    /// which is only useful if the app may be swapped behind this client's back.
+    pub fn with_configuration_cache(mut self, enabled: bool) -> Self {
+        self.cache_configuration = enabled;
+        self
     }
 
     /// Get a reference to the underlying transport
     pub fn transport(&self) -> &E {
-        &self.transport
+        self.transport.inner()
+    }
+
+    /// Run `f` with exclusive access to the device
+    ///
+    /// Calls on this app from other tasks wait until `f` completes, so a flow
+    /// spanning several calls, e.g. storing a transaction, providing its
+    /// simulation and starting its signing flow, cannot be interleaved. Calls
+    /// made by `f` must run on the calling task, one after the other.
+    pub async fn exclusive<F: Future>(&self, f: F) -> F::Output {
+        self.transport.with_session(f).await
+    }
+
+    /// Run `operation` on the transport with exclusive access to the device
+    async fn locked<'a, F, Fut>(&'a self, operation: F) -> Fut::Output
+    where
+        F: FnOnce(&'a E) -> Fut,
+        Fut: Future,
+    {
+        self.transport
+            .with_session(operation(self.transport.inner()))
+            .await
+    }
+
+    /// Log every APDU exchanged with the device through `tracing`
+    ///
+    /// Status words are logged with their Ethereum app description.
+    /// Payloads are logged at trace level; wrap the transport in an
+    /// [`ExchangeLogger`] with redaction enabled to keep them out of the logs.
+    pub fn with_logging(self) -> EthereumApp<ExchangeLogger<E>>
+    where
+        E: Send + Sync,
+        E::Error: std::fmt::Display + Send,
+    {
+        EthereumApp {
+            transport: ExchangeGuard::new(
+                ExchangeLogger::new(self.transport.into_inner())
+                    .with_status_description(errors::describe_eth_status),
+            ),
+            configuration: self.configuration,
+            cache_configuration: self.cache_configuration,
+            transaction_stored: self.transaction_stored,
+        }
+    }
+
+    /// Wait up to `timeout` for the user to unlock the device when a command is
+    /// rejected with `0x5515`, then send it again
+    ///
+    /// Keeps long signing flows going when the device auto-locks while the user
+    /// reads the review. Once `timeout` elapses the command fails with
+    /// `EthAppError::DeviceLocked`. The device is checked every
+    /// [`DEFAULT_UNLOCK_POLL_INTERVAL`], see
+    /// [`EthereumApp::with_unlock_wait_interval`] to change it.
+    pub fn with_unlock_wait(self, timeout: Duration) -> EthereumApp<UnlockWaitExchange<E>>
+    where
+        E: Send + Sync,
+        E::Error: Send,
+    {
+        self.with_unlock_wait_interval(timeout, DEFAULT_UNLOCK_POLL_INTERVAL)
+    }
+
+    /// Same as [`EthereumApp::with_unlock_wait`], checking the device every
+    /// `poll_interval`
+    pub fn with_unlock_wait_interval(
+        self,
+        timeout: Duration,
+        poll_interval: Duration,
+    ) -> EthereumApp<UnlockWaitExchange<E>>
+    where
+        E: Send + Sync,
+        E::Error: Send,
+    {
+        EthereumApp {
+            transport: ExchangeGuard::new(
+                UnlockWaitExchange::new(self.transport.into_inner(), timeout)
+                    .with_poll_interval(poll_interval),
+            ),
+            configuration: self.configuration,
+            cache_configuration: self.cache_configuration,
+            transaction_stored: self.transaction_stored,
+        }
     }
 }
 
@@ -88,6 +307,62 @@ where
     }
 }
 
+#[async_trait]
+impl<E> ProvideNetworkInformation<E> for EthereumApp<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    async fn provide_network_information(
+        transport: &E,
+        descriptor: &NetworkDescriptor,
+    ) -> EthAppResult<(), E::Error> {
+        EthApp::provide_network_information(transport, descriptor).await
+    }
+}
+
+#[async_trait]
+impl<E> ProvideNftInformation<E> for EthereumApp<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    async fn provide_nft_information(
+        transport: &E,
+        info: &NftCollectionInfo,
+    ) -> EthAppResult<(), E::Error> {
+        EthApp::provide_nft_information(transport, info).await
+    }
+}
+
+#[async_trait]
+impl<E> ProvideSafeAccount<E> for EthereumApp<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    async fn provide_safe_account(
+        transport: &E,
+        info: &SafeAccountInfo,
+    ) -> EthAppResult<(), E::Error> {
+        EthApp::provide_safe_account(transport, info).await
+    }
+}
+
+#[async_trait]
+impl<E> ProvideTxSimulation<E> for EthereumApp<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::error::Error,
+{
+    async fn provide_tx_simulation(
+        transport: &E,
+        simulation: &TxSimulationResult,
+    ) -> EthAppResult<(), E::Error> {
+        EthApp::provide_tx_simulation(transport, simulation).await
+    }
+}
+
 #[async_trait]
 impl<E> SignPersonalMessage<E> for EthereumApp<E>
 where
@@ -100,6 +375,17 @@ where
     ) -> EthAppResult<Signature, E::Error> {
         EthApp::sign_personal_message(transport, params).await
     }
+
+    async fn sign_personal_message_streaming<R>(
+        transport: &E,
+        params: SignMessageStreamParams,
+        reader: R,
+    ) -> EthAppResult<Signature, E::Error>
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        EthApp::sign_personal_message_streaming(transport, params, reader).await
+    }
 }
 
 #[async_trait]
@@ -144,8 +430,12 @@ where
     E: Exchange + Send + Sync,
     E::Error: std::error::Error,
 {
-    async fn sign_eip712_full(transport: &E, path: &BipPath) -> EthAppResult<Signature, E::Error> {
-        EthApp::sign_eip712_full(transport, path).await
+    async fn sign_eip712_full(
+        transport: &E,
+        path: &BipPath,
+        path_policy: PathPolicy,
+    ) -> EthAppResult<Signature, E::Error> {
+        EthApp::sign_eip712_full(transport, path, path_policy).await
     }
 }
 
@@ -176,7 +466,7 @@ where
         EthApp::send_struct_implementation(transport, struct_impl).await
     }
 
-    async fn set_array_size(transport: &E, size: u8) -> EthAppResult<(), E::Error> {
+    async fn set_array_size(transport: &E, size: usize) -> EthAppResult<(), E::Error> {
         EthApp::set_array_size(transport, size).await
     }
 }
@@ -204,6 +494,98 @@ where
     E: Exchange + Send + Sync,
     E::Error: std::error::Error,
 {
+    /// Send a raw APDU to the device
+    ///
+    /// Escape hatch for commands this crate does not wrap yet. The answer is
+    /// returned as-is: its status word is not checked, only transport failures
+    /// are reported as errors.
+    ///
+    /// # Arguments
+    ///
+    /// * `command` - APDU to send, e.g. with [`EthApp::CLA`] and an [`instructions::ins`] code
+    ///
+    /// # Errors
+    ///
+    /// Returns `EthAppError::Transport` if the exchange itself fails
+    ///
+    pub async fn raw_exchange<I>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> EthAppResult<APDUAnswer<E::AnswerType>, E::Error>
+    where
+        I: Deref<Target = [u8]> + Send + Sync,
+    {
+        trace::exchange(&self.transport, command)
+            .await
+            .map_err(|e| EthAppError::Transport(e.into()))
+    }
+
+    /// Send a raw APDU and check its status word
+    ///
+    /// Like [`EthereumApp::raw_exchange`], but returns the answer payload and
+    /// maps a failing status word to the same errors as the wrapped commands.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EthAppError::UserRejected` or `EthAppError::DeviceStatus` if the
+    /// device answers with an error status word
+    pub async fn exchange_raw(
+        &self,
+        cla: u8,
+        ins: u8,
+        p1: u8,
+        p2: u8,
+        data: &[u8],
+    ) -> EthAppResult<Vec<u8>, E::Error> {
+        let command = APDUCommand {
+            cla,
+            ins,
+            p1,
+            p2,
+            data,
+        };
+        let response = self.raw_exchange(&command).await?;
+        <EthApp as AppExt<E>>::handle_response_error(&response).map_err(map_ledger_error)?;
+        Ok(response.data().to_vec())
+    }
+
+    /// Send a raw APDU given as hex, see [`EthereumApp::exchange_raw`]
+    ///
+    /// The frame is `CLA INS P1 P2`, optionally followed by the data length and
+    /// the data, e.g. `"e006000000"` for GET APP CONFIGURATION. A `0x` prefix is
+    /// accepted.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EthAppError::HexError` for invalid hex and
+    /// `EthAppError::InvalidMessage` for a frame shorter than its header or
+    /// whose length byte does not match its data
+    pub async fn exchange_raw_hex(&self, apdu: &str) -> EthAppResult<Vec<u8>, E::Error> {
+        let apdu = apdu.strip_prefix("0x").unwrap_or(apdu);
+        let frame = hex::decode(apdu).map_err(|e| EthAppError::HexError(e.to_string()))?;
+
+        let data = match frame.len() {
+            0..=3 => {
+                return Err(EthAppError::InvalidMessage(format!(
+                    "APDU frame too short: {} bytes, header is 4",
+                    frame.len()
+                )));
+            }
+            4 => &[][..],
+            _ if frame[4] as usize != frame.len() - 5 => {
+                return Err(EthAppError::InvalidMessage(format!(
+                    "APDU length byte is {} but {} data bytes follow",
+                    frame[4],
+                    frame.len() - 5
+                )));
+            }
+            _ => &frame[5..],
+        };
+
+        self.exchange_raw(frame[0], frame[1], frame[2], frame[3], data)
+            .await
+    }
+
     /// Get Ethereum public address for the given BIP 32 path
     ///
     /// # Arguments
@@ -219,7 +601,140 @@ where
         &self,
         params: GetAddressParams,
     ) -> EthAppResult<PublicKeyInfo, E::Error> {
-        EthApp::get_address(&self.transport, params).await
+        self.locked(|transport| EthApp::get_address(transport, params))
+            .await
+    }
+
+    /// Get the addresses of consecutive derivation paths, e.g. for account discovery
+    ///
+    /// The last index of `base` is replaced by each index of `range`, keeping its
+    /// hardened flag: `m/44'/60'/0'/0/0` with `0..5` covers `m/44'/60'/0'/0/0` to
+    /// `m/44'/60'/0'/0/4`. The paths are fetched with
+    /// [`EthereumApp::get_addresses`], holding the device for the whole range.
+    ///
+    /// Leave `display` false for discovery; with it set the user has to confirm
+    /// every address on the device.
+    ///
+    /// # Returns
+    ///
+    /// The addresses paired with their index, in `range` order.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EthAppError::InvalidBip32Path` for an invalid `base` or `range`
+    /// Returns `EthAppError::BatchItem` for the first path the device failed
+    pub async fn get_address_range(
+        &self,
+        base: BipPath,
+        range: Range<u32>,
+        display: bool,
+    ) -> EthAppResult<Vec<(u32, PublicKeyInfo)>, E::Error> {
+        const HARDENED: u32 = 0x8000_0000;
+
+        let mut params = GetAddressParams::new(base);
+        params.display = display;
+        utils::validate_bip32_path_with_policy(&params.path, params.path_policy)?;
+        if range.end > HARDENED {
+            return Err(EthAppError::InvalidBip32Path(format!(
+                "Index range {:?} exceeds the largest index {}",
+                range,
+                HARDENED - 1
+            )));
+        }
+        let last = params.path.indices.len() - 1;
+        let hardened = params.path.indices[last] & HARDENED;
+
+        let paths: Vec<BipPath> = range
+            .clone()
+            .map(|index| {
+                let mut path = params.path.clone();
+                path.indices[last] = hardened | index;
+                path
+            })
+            .collect();
+        let addresses = self.get_addresses(paths, params).await?;
+        Ok(range.zip(addresses).collect())
+    }
+
+    /// Get the addresses of `paths`, in order, e.g. for account discovery
+    ///
+    /// `options` applies to every path (display, chain code, chain id, strict
+    /// parsing and path policy); its own path is ignored. All paths are
+    /// validated before the first APDU, and the device is held for the whole
+    /// batch so other callers cannot interleave commands.
+    ///
+    /// For many addresses of one account, [`EthereumApp::get_account_xpub`] with
+    /// the `derivation` feature needs a single APDU.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EthAppError::BatchItem` with the index of the first path that
+    /// failed validation or retrieval
+    pub async fn get_addresses<P>(
+        &self,
+        paths: P,
+        options: GetAddressParams,
+    ) -> EthAppResult<Vec<PublicKeyInfo>, E::Error>
+    where
+        P: IntoIterator<Item = BipPath>,
+    {
+        let batch_error = |index: usize, path: &BipPath, error| EthAppError::BatchItem {
+            index,
+            path: path.to_string(),
+            error: Box::new(error),
+        };
+
+        let paths: Vec<BipPath> = paths.into_iter().collect();
+        for (index, path) in paths.iter().enumerate() {
+            utils::validate_bip32_path_with_policy(path, options.path_policy)
+                .map_err(|error| batch_error(index, path, error))?;
+        }
+
+        self.locked(|transport| async move {
+            let mut params = options;
+            let mut addresses = Vec::with_capacity(paths.len());
+            for (index, path) in paths.into_iter().enumerate() {
+                params.path = path;
+                let info = EthApp::get_address_validated(transport, &params)
+                    .await
+                    .map_err(|error| batch_error(index, &params.path, error))?;
+                addresses.push(info);
+            }
+            Ok(addresses)
+        })
+        .await
+    }
+
+    /// Extended public key of the account node `m/44'/60'/account'`
+    ///
+    /// Fetches the public key and chain code once, without display. With the
+    /// `derivation` feature the account's addresses are then derived off-device,
+    /// e.g. `xpub.child(0)?.derive_child(i)` for `m/44'/60'/account'/0/i`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EthAppError::InvalidBip32Path` if `account` is 2^31 or more
+    /// Returns `EthAppError::InvalidResponseData` if the device omits the chain code
+    pub async fn get_account_xpub(
+        &self,
+        account: u32,
+    ) -> EthAppResult<ExtendedPublicKey, E::Error> {
+        const HARDENED: u32 = 0x8000_0000;
+
+        if account >= HARDENED {
+            return Err(EthAppError::InvalidBip32Path(format!(
+                "Account index too large: {}",
+                account
+            )));
+        }
+        let path = BipPath::new(vec![0x8000002C, 0x8000003C, HARDENED | account])
+            .map_err(EthAppError::InvalidBip32Path)?;
+
+        let info = self
+            .get_address(GetAddressParams::new(path.clone()).with_chain_code())
+            .await?;
+        ExtendedPublicKey::from_public_key_info(path, &info)
+            .map_err(EthAppError::InvalidResponseData)
     }
 
     /// Get Ethereum application configuration
@@ -228,7 +743,194 @@ where
     ///
     ///
     pub async fn get_configuration(&self) -> EthAppResult<AppConfiguration, E::Error> {
-        EthApp::get_configuration(&self.transport).await
+        self.refresh_configuration().await
+    }
+
+    /// Query the application configuration and replace the cached copy
+    pub async fn refresh_configuration(&self) -> EthAppResult<AppConfiguration, E::Error> {
+        let config = self
+            .locked(|transport| EthApp::get_configuration(transport))
+            .await?;
+        *self
+            .configuration
+            .write()
+            .expect("configuration cache poisoned") = Some(config.clone());
+        Ok(config)
+    }
+
+    /// Application configuration, served from cache when available
+    ///
+    /// Used by the version-gated methods so a multi-step flow only queries the
+    /// device once.
+    pub async fn app_configuration(&self) -> EthAppResult<AppConfiguration, E::Error> {
+        if self.cache_configuration {
+            let cached = self
+                .configuration
+                .read()
+                .expect("configuration cache poisoned")
+                .clone();
+            if let Some(config) = cached {
+                return Ok(config);
+            }
+        }
+
+        self.refresh_configuration().await
+    }
+
+    /// Make sure the Ethereum app is the one open on the device
+    ///
+    /// Checks the open app with `get_app_info`. From the dashboard, and if `open`
+    /// is set, asks it to open the Ethereum app (the user may have to confirm),
+    /// then polls GET APP CONFIGURATION until the app answers or `timeout` elapses.
+    /// The configuration cache is refreshed on success.
+    ///
+    /// Switching apps makes the device re-enumerate. Transports that don't survive
+    /// it (HID) fail with `EthAppError::AppSwitchPending`: reopen the transport and
+    /// call this again. The same error is returned after quitting another open app.
+    ///
+    /// # Arguments
+    ///
+    /// * `open` - Open the app if needed instead of failing with `EthAppError::WrongApp`
+    /// * `timeout` - How long to wait for the app to answer once opened
+    ///
+    /// # Errors
+    ///
+    /// Returns `EthAppError::WrongApp` if another app is open and `open` is false
+    /// Returns `EthAppError::AppSwitchPending` while the device switches apps
+    /// Returns `EthAppError::DeviceStatus` if the dashboard refused to open the app
+    /// (e.g. not installed or rejected by the user)
+    ///
+    pub async fn ensure_app_open(
+        &self,
+        open: bool,
+        timeout: Duration,
+    ) -> EthAppResult<(), E::Error> {
+        let info = self
+            .locked(|transport| <EthApp as AppExt<E>>::get_app_info(transport))
+            .await
+            .map_err(map_ledger_error)?;
+        if info.app_name == EthApp::APP_NAME {
+            return Ok(());
+        }
+        if !open {
+            return Err(EthAppError::WrongApp {
+                expected: EthApp::APP_NAME.to_string(),
+                found: info.app_name,
+            });
+        }
+
+        if info.app_name != DASHBOARD_APP_NAME {
+            return match self
+                .locked(|transport| <EthApp as AppExt<E>>::quit_app(transport))
+                .await
+            {
+                Ok(()) | Err(LedgerAppError::TransportError(_)) => {
+                    Err(EthAppError::AppSwitchPending)
+                }
+                Err(err) => Err(map_ledger_error(err)),
+            };
+        }
+
+        // The answer is often lost to the re-enumeration
+        match self
+            .locked(|transport| <EthApp as AppExt<E>>::open_app(transport, EthApp::APP_NAME))
+            .await
+        {
+            Ok(()) | Err(LedgerAppError::TransportError(_)) => {}
+            Err(err) => return Err(map_ledger_error(err)),
+        }
+
+        let deadline = Instant::now() + timeout;
+        loop {
+            if self.refresh_configuration().await.is_ok() {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(EthAppError::AppSwitchPending);
+            }
+            tokio::time::sleep(APP_SWITCH_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Wait until the device is unlocked, for at most `timeout`
+    ///
+    /// Polls the device while it answers `0x5515`, e.g. before resuming a flow
+    /// that failed with `EthAppError::DeviceLocked`. See
+    /// [`EthereumApp::with_unlock_wait`] to resend rejected commands automatically.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EthAppError::DeviceLocked` if the device is still locked after `timeout`
+    pub async fn wait_until_ready(&self, timeout: Duration) -> EthAppResult<(), E::Error> {
+        let unlocked = self
+            .locked(|transport| {
+                wait_until_unlocked(transport, timeout, DEFAULT_UNLOCK_POLL_INTERVAL)
+            })
+            .await
+            .map_err(map_exchange_error)?;
+        if unlocked {
+            Ok(())
+        } else {
+            Err(EthAppError::DeviceLocked)
+        }
+    }
+
+    /// Ethereum app version, as reported by GET APP CONFIGURATION
+    ///
+    /// This is the version the `supports_*` gates are checked against. Served from
+    /// the configuration cache when available.
+    pub async fn app_version(&self) -> EthAppResult<AppVersion, E::Error> {
+        Ok(self.app_configuration().await?.version)
+    }
+
+    /// Version answered to the generic GET VERSION command (INS 0x00)
+    ///
+    /// Depending on the target this is the BOLOS/OS version rather than the
+    /// Ethereum app version; use [`EthereumApp::app_version`] for feature checks.
+    pub async fn bolos_version(&self) -> EthAppResult<Version, E::Error> {
+        self.locked(|transport| <EthApp as AppExt<E>>::get_version(transport))
+            .await
+            .map_err(map_ledger_error)
+    }
+
+    /// Check which SDK features the connected device and app support
+    ///
+    /// Queries GET DEVICE INFO, GET APP INFO and, when the Ethereum app is open,
+    /// GET APP CONFIGURATION (refreshing the configuration cache). Apps usually
+    /// don't answer GET DEVICE INFO, which only the dashboard implements; the
+    /// report then lacks the device model and leaves the EIP-712 features
+    /// unknown. Complete it with [`CompatibilityReport::with_model`], e.g. with
+    /// the model of `ledger_sdk_transport_hid::LedgerDeviceInfo`.
+    ///
+    /// # Errors
+    ///
+    /// Returns transport errors, and `EthAppError::DeviceLocked` on a locked device
+    pub async fn check_compatibility(&self) -> EthAppResult<CompatibilityReport, E::Error> {
+        let device_info = match self
+            .locked(|transport| <EthApp as AppExt<E>>::get_device_info(transport))
+            .await
+        {
+            Ok(info) => Some(info),
+            Err(LedgerAppError::Status(
+                StatusWord::InsNotSupported | StatusWord::ClaNotSupported,
+            )) => None,
+            Err(err) => return Err(map_ledger_error(err)),
+        };
+        let app_info = self
+            .locked(|transport| <EthApp as AppExt<E>>::get_app_info(transport))
+            .await
+            .map_err(map_ledger_error)?;
+        let configuration = if app_info.app_name == EthApp::APP_NAME {
+            Some(self.refresh_configuration().await?)
+        } else {
+            None
+        };
+
+        Ok(CompatibilityReport::new(
+            device_info,
+            app_info,
+            configuration,
+        ))
     }
 
     /// Sign an Ethereum personal message
@@ -245,45 +947,349 @@ where
         &self,
         params: SignMessageParams,
     ) -> EthAppResult<Signature, E::Error> {
-        EthApp::sign_personal_message(&self.transport, params).await
+        self.locked(|transport| EthApp::sign_personal_message(transport, params))
+            .await
+    }
+
+    /// Sign an Ethereum personal message streamed from an async reader
+    ///
+    /// Useful for large messages that should not be buffered in memory.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - BIP32 path and total message length; exactly `len` bytes are read
+    /// * `reader` - Source of the message bytes
+    pub async fn sign_personal_message_streaming<R>(
+        &self,
+        params: SignMessageStreamParams,
+        reader: R,
+    ) -> EthAppResult<Signature, E::Error>
+    where
+        R: AsyncRead + Unpin + Send,
+    {
+        self.locked(|transport| EthApp::sign_personal_message_streaming(transport, params, reader))
+            .await
+    }
+
+    /// Sign an Ethereum transaction
+    ///
+    /// Signs a transaction using the provided RLP-encoded transaction data.
+    /// The transaction details will be displayed on the device for user confirmation.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Parameters including BIP32 path and RLP-encoded transaction data
+    pub async fn sign_transaction(
+        &self,
+        params: SignTransactionParams,
+    ) -> EthAppResult<Signature, E::Error> {
+        self.transaction_stored.store(false, Ordering::SeqCst);
+        self.locked(|transport| EthApp::sign_transaction(transport, params))
+            .await
+    }
+
+    /// Sign an Ethereum transaction with specific processing mode
+    ///
+    /// Provides fine-grained control over transaction processing, allowing for
+    /// operations like storing transaction data without immediate signing.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - Parameters including BIP32 path and RLP-encoded transaction data
+    /// * `mode` - Processing mode (ProcessAndStart, StoreOnly, or StartFlow)
+    ///
+    /// # Returns
+    ///
+    /// Returns `Some(Signature)` for modes that produce a signature, or `None` for store-only mode.
+    ///
+    /// A transaction simulation for the stored transaction is provided between
+    /// `StoreOnly` and `StartFlow`, see [`EthereumApp::provide_tx_simulation`].
+    pub async fn sign_transaction_with_mode(
+        &self,
+        params: SignTransactionParams,
+        mode: commands::sign_transaction::TransactionMode,
+    ) -> EthAppResult<Option<Signature>, E::Error> {
+        use commands::sign_transaction::TransactionMode;
+
+        self.transaction_stored.store(false, Ordering::SeqCst);
+        let signature = self
+            .locked(|transport| EthApp::sign_transaction_with_mode(transport, params, mode))
+            .await?;
+        if mode == TransactionMode::StoreOnly {
+            self.transaction_stored.store(true, Ordering::SeqCst);
+        }
+        Ok(signature)
+    }
+
+    /// Sign an EIP-7702 authorization delegating the account to a contract
+    ///
+    /// **Version Requirements**: Requires app version >= 1.15.0
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - BIP32 path of the delegating account
+    /// * `authorization` - Chain ID, delegate contract address and account nonce
+    /// * `path_policy` - Checks applied to `path` before sending
+    ///
+    /// # Errors
+    ///
+    /// Returns `EthAppError::UnsupportedVersion` if app version is below 1.15.0
+    /// Returns `EthAppError::InvalidSignature` if the device answer is not a
+    /// 65-byte `y_parity || r || s` signature.
+    pub async fn sign_authorization(
+        &self,
+        path: &BipPath,
+        authorization: &Eip7702Authorization,
+        path_policy: PathPolicy,
+    ) -> EthAppResult<Eip7702Signature, E::Error> {
+        let config = self.app_configuration().await?;
+        if !config.version.supports_eip7702() {
+            return Err(EthAppError::UnsupportedVersion(format!(
+                "EIP-7702 authorization signing requires app version >= 1.15.0, found {}",
+                config.version
+            )));
+        }
+
+        self.locked(|transport| {
+            EthApp::sign_authorization(transport, path, authorization, path_policy)
+        })
+        .await
+    }
+
+    /// Get the BLS12-381 validator public key for an EIP-2334 path
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - BIP32 path, e.g. `m/12381/3600/0/0`
+    /// * `display` - Whether to show the key on the device and wait for approval
+    pub async fn get_eth2_public_key(
+        &self,
+        path: &BipPath,
+        display: bool,
+    ) -> EthAppResult<[u8; instructions::length::ETH2_PUBLIC_KEY_SIZE], E::Error> {
+        self.locked(|transport| EthApp::get_eth2_public_key(transport, path, display))
+            .await
+    }
+
+    /// Set the index of the withdrawal key used by ETH2 deposits
+    ///
+    /// # Arguments
+    ///
+    /// * `index` - Withdrawal key index, `m/12381/3600/index/0`
+    pub async fn set_eth2_withdrawal_index(&self, index: u32) -> EthAppResult<(), E::Error> {
+        self.locked(|transport| EthApp::set_eth2_withdrawal_index(transport, index))
+            .await
+    }
+
+    /// Get the public encryption key or a shared secret
+    ///
+    /// Returns the X25519 public encryption key for the path, or the secret shared
+    /// with a counterparty when the params carry its public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `params` - BIP32 path, display flag and optional counterparty public key
+    pub async fn perform_privacy_operation(
+        &self,
+        params: PrivacyOperationParams,
+    ) -> EthAppResult<Vec<u8>, E::Error> {
+        self.locked(|transport| EthApp::perform_privacy_operation(transport, params))
+            .await
+    }
+
+    /// Select the plugin used to clear-sign the next transaction
+    ///
+    /// Must be sent right before the transaction it applies to.
+    ///
+    /// # Arguments
+    ///
+    /// * `descriptor` - Signed plugin descriptor (name, contract, selector, chain ID)
+    pub async fn set_plugin(&self, descriptor: &PluginDescriptor) -> EthAppResult<(), E::Error> {
+        self.locked(|transport| EthApp::set_plugin(transport, descriptor))
+            .await
+    }
+
+    /// Select an external plugin using the legacy descriptor format
+    ///
+    /// # Arguments
+    ///
+    /// * `descriptor` - Signed plugin descriptor (name, contract, selector)
+    pub async fn set_external_plugin(
+        &self,
+        descriptor: &PluginDescriptor,
+    ) -> EthAppResult<(), E::Error> {
+        self.locked(|transport| EthApp::set_external_plugin(transport, descriptor))
+            .await
+    }
+
+    /// Sign a transaction built with one of the [`tx`] builders
+    ///
+    /// Encodes the transaction (including the type prefix for typed transactions)
+    /// and signs it like [`EthereumApp::sign_transaction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - BIP32 derivation path for the signing key
+    /// * `transaction` - Legacy, EIP-2930 or EIP-1559 transaction
+    pub async fn sign_typed_transaction<T: UnsignedTransaction>(
+        &self,
+        path: &BipPath,
+        transaction: &T,
+    ) -> EthAppResult<Signature, E::Error> {
+        let params = SignTransactionParams::new(path.clone(), transaction.to_sign_payload());
+        self.sign_transaction(params).await
+    }
+
+    /// Provide signed network information for a custom chain
+    ///
+    /// Must be sent before signing on non-mainnet chains so the device displays
+    /// the right network name and ticker.
+    ///
+    /// **Version Requirements**: Requires app version >= 1.14.0
+    ///
+    /// # Arguments
+    ///
+    /// * `descriptor` - Signed network descriptor (chain ID, name, ticker)
+    ///
+    /// # Errors
+    ///
+    /// Returns `EthAppError::UnsupportedVersion` if app version is below 1.14.0
+    ///
+    pub async fn provide_network_information(
+        &self,
+        descriptor: &NetworkDescriptor,
+    ) -> EthAppResult<(), E::Error> {
+        let config = self.app_configuration().await?;
+        if !config.version.supports_network_information() {
+            return Err(EthAppError::UnsupportedVersion(format!(
+                "Network information requires app version >= 1.14.0, found {}",
+                config.version
+            )));
+        }
+
+        self.locked(|transport| EthApp::provide_network_information(transport, descriptor))
+            .await
+    }
+
+    /// Provide signed NFT collection information
+    ///
+    /// Must be sent before signing an ERC-721 / ERC-1155 transfer so the device can
+    /// display the collection name instead of a raw contract address.
+    ///
+    /// **Version Requirements**: Requires app version >= 1.9.18
+    ///
+    /// # Arguments
+    ///
+    /// * `info` - Signed collection descriptor (name, contract, chain ID)
+    ///
+    /// # Errors
+    ///
+    /// Returns `EthAppError::UnsupportedVersion` if app version is below 1.9.18
+    ///
+    pub async fn provide_nft_information(
+        &self,
+        info: &NftCollectionInfo,
+    ) -> EthAppResult<(), E::Error> {
+        let config = self.app_configuration().await?;
+        if !config.version.supports_nft_information() {
+            return Err(EthAppError::UnsupportedVersion(format!(
+                "NFT information requires app version >= 1.9.18, found {}",
+                config.version
+            )));
+        }
+
+        self.locked(|transport| EthApp::provide_nft_information(transport, info))
+            .await
     }
 
-    /// Sign an Ethereum transaction
+    /// Provide the signed context of a Safe (multisig) account
     ///
-    /// Signs a transaction using the provided RLP-encoded transaction data.
-    /// The transaction details will be displayed on the device for user confirmation.
+    /// Lets the device show the Safe address and threshold when signing Safe
+    /// transactions or messages. The descriptors must be signed over the current
+    /// device challenge.
+    ///
+    /// **Version Requirements**: Requires app version >= 1.16.0
     ///
     /// # Arguments
     ///
-    /// * `params` - Parameters including BIP32 path and RLP-encoded transaction data
+    /// * `info` - Safe address, threshold, owners and provider signatures
     ///
+    /// # Errors
     ///
-    pub async fn sign_transaction(
-        &self,
-        params: SignTransactionParams,
-    ) -> EthAppResult<Signature, E::Error> {
-        EthApp::sign_transaction(&self.transport, params).await
+    /// Returns `EthAppError::UnsupportedVersion` if app version is below 1.16.0
+    ///
+    pub async fn provide_safe_account(&self, info: &SafeAccountInfo) -> EthAppResult<(), E::Error> {
+        let config = self.app_configuration().await?;
+        if !config.version.supports_safe_account() {
+            return Err(EthAppError::UnsupportedVersion(format!(
+                "Safe account information requires app version >= 1.16.0, found {}",
+                config.version
+            )));
+        }
+
+        self.locked(|transport| EthApp::provide_safe_account(transport, info))
+            .await
     }
 
-    /// Sign an Ethereum transaction with specific processing mode
+    /// Whether the user enabled Transaction Check in the app settings
+    ///
+    /// Read from the cached configuration; call [`EthereumApp::refresh_configuration`]
+    /// first to pick up a setting changed since.
+    pub async fn transaction_check_enabled(&self) -> EthAppResult<bool, E::Error> {
+        Ok(self
+            .app_configuration()
+            .await?
+            .flags
+            .transaction_check_enabled)
+    }
+
+    /// Provide a signed transaction simulation result
     ///
-    /// Provides fine-grained control over transaction processing, allowing for
-    /// operations like storing transaction data without immediate signing.
+    /// Lets the device show Transaction Check warnings for the transaction or
+    /// message signed next. Only sent when the user enabled Transaction Check,
+    /// see [`EthereumApp::transaction_check_enabled`].
+    ///
+    /// The device binds a transaction simulation to the hash of the stored
+    /// transaction, so it goes between storing the transaction and starting its
+    /// signing flow:
+    ///
+    /// 1. [`EthereumApp::sign_transaction_with_mode`] with `TransactionMode::StoreOnly`
+    /// 2. `provide_tx_simulation`
+    /// 3. [`EthereumApp::sign_transaction_with_mode`] with `TransactionMode::StartFlow`
+    ///
+    /// Typed data and personal message simulations are sent right before signing.
     ///
     /// # Arguments
     ///
-    /// * `params` - Parameters including BIP32 path and RLP-encoded transaction data
-    /// * `mode` - Processing mode (ProcessAndStart, StoreOnly, or StartFlow)
+    /// * `simulation` - Simulation result signed by the provider
     ///
-    /// # Returns
+    /// # Errors
     ///
-    /// Returns `Some(Signature)` for modes that produce a signature, or `None` for store-only mode.
-    pub async fn sign_transaction_with_mode(
+    /// Returns `EthAppError::FeatureNotSupported` if Transaction Check is disabled
+    /// Returns `EthAppError::InvalidTransaction` for a transaction simulation when
+    /// no transaction is stored
+    ///
+    pub async fn provide_tx_simulation(
         &self,
-        params: SignTransactionParams,
-        mode: commands::sign_transaction::TransactionMode,
-    ) -> EthAppResult<Option<Signature>, E::Error> {
-        EthApp::sign_transaction_with_mode(&self.transport, params, mode).await
+        simulation: &TxSimulationResult,
+    ) -> EthAppResult<(), E::Error> {
+        use instructions::tx_simulation_tag::SIMULATION_TRANSACTION;
+
+        if !self.transaction_check_enabled().await? {
+            return Err(EthAppError::FeatureNotSupported(
+                "Transaction Check is disabled in the app settings".to_string(),
+            ));
+        }
+        if simulation.simulation_type == SIMULATION_TRANSACTION
+            && !self.transaction_stored.load(Ordering::SeqCst)
+        {
+            return Err(EthAppError::InvalidTransaction(
+                "No stored transaction for this simulation: store it with TransactionMode::StoreOnly first, then start the flow with TransactionMode::StartFlow".to_string(),
+            ));
+        }
+
+        self.locked(|transport| EthApp::provide_tx_simulation(transport, simulation))
+            .await
     }
 
     /// Sign an EIP-712 message using v0 implementation (domain hash + message hash)
@@ -306,7 +1312,7 @@ where
         params: SignEip712Params,
     ) -> EthAppResult<Signature, E::Error> {
         // Check version requirement for EIP-712 v0 (>= 1.5.0)
-        let config = self.get_configuration().await?;
+        let config = self.app_configuration().await?;
         if !config.version.supports_eip712_v0() {
             return Err(EthAppError::UnsupportedVersion(format!(
                 "EIP-712 v0 requires app version >= 1.5.0, found {}",
@@ -314,7 +1320,8 @@ where
             )));
         }
 
-        EthApp::sign_eip712_v0(&self.transport, params).await
+        self.locked(|transport| EthApp::sign_eip712_v0(transport, params))
+            .await
     }
 
     /// Sign an EIP-712 message using full implementation
@@ -328,14 +1335,19 @@ where
     /// # Arguments
     ///
     /// * `path` - BIP32 derivation path for the signing key
+    /// * `path_policy` - Checks applied to `path` before sending
     ///
     /// # Errors
     ///
     /// Returns `EthAppError::UnsupportedVersion` if app version is below 1.9.19
     ///
-    pub async fn sign_eip712_full(&self, path: &BipPath) -> EthAppResult<Signature, E::Error> {
+    pub async fn sign_eip712_full(
+        &self,
+        path: &BipPath,
+        path_policy: PathPolicy,
+    ) -> EthAppResult<Signature, E::Error> {
         // Check version requirement for EIP-712 full (>= 1.9.19)
-        let config = self.get_configuration().await?;
+        let config = self.app_configuration().await?;
         if !config.version.supports_eip712_full() {
             return Err(EthAppError::UnsupportedVersion(format!(
                 "EIP-712 full implementation requires app version >= 1.9.19, found {}",
@@ -343,7 +1355,17 @@ where
             )));
         }
 
-        EthApp::sign_eip712_full(&self.transport, path).await
+        self.locked(|transport| EthApp::sign_eip712_full(transport, path, path_policy))
+            .await
+    }
+
+    /// Start an [`Eip712Session`] validating struct implementations against
+    /// the definitions sent through it
+    ///
+    /// Run the session in [`EthereumApp::exclusive`] when the app is shared
+    /// between tasks.
+    pub fn eip712_session(&self) -> Eip712Session<'_, E> {
+        Eip712Session::new(self)
     }
 
     /// Send EIP-712 struct definition to the device
@@ -366,7 +1388,7 @@ where
         struct_def: &Eip712StructDefinition,
     ) -> EthAppResult<(), E::Error> {
         // Check version requirement for EIP-712 full implementation
-        let config = self.get_configuration().await?;
+        let config = self.app_configuration().await?;
         if !config.version.supports_eip712_full() {
             return Err(EthAppError::UnsupportedVersion(format!(
                 "EIP-712 struct definitions require app version >= 1.9.19, found {}",
@@ -374,7 +1396,8 @@ where
             )));
         }
 
-        EthApp::send_struct_definition(&self.transport, struct_def).await
+        self.locked(|transport| EthApp::send_struct_definition(transport, struct_def))
+            .await
     }
 
     /// Send EIP-712 struct implementation to the device
@@ -398,7 +1421,7 @@ where
         struct_impl: &Eip712StructImplementation,
     ) -> EthAppResult<(), E::Error> {
         // Check version requirement for EIP-712 full implementation
-        let config = self.get_configuration().await?;
+        let config = self.app_configuration().await?;
         if !config.version.supports_eip712_full() {
             return Err(EthAppError::UnsupportedVersion(format!(
                 "EIP-712 struct implementations require app version >= 1.9.19, found {}",
@@ -406,7 +1429,8 @@ where
             )));
         }
 
-        EthApp::send_struct_implementation(&self.transport, struct_impl).await
+        self.locked(|transport| EthApp::send_struct_implementation(transport, struct_impl))
+            .await
     }
 
     /// Set array size for upcoming array fields in EIP-712 implementation
@@ -415,23 +1439,29 @@ where
     ///
     /// # Arguments
     ///
-    /// * `size` - The size of the array
+    /// * `size` - The size of the array; above 255 requires app version >= 1.17.0
     ///
     /// # Errors
     ///
     /// Returns `EthAppError::UnsupportedVersion` if app version is below 1.9.19
+    /// Returns `EthAppError::ArrayTooLarge` if the app does not accept `size` items
     ///
-    pub async fn set_array_size(&self, size: u8) -> EthAppResult<(), E::Error> {
+    pub async fn set_array_size(&self, size: usize) -> EthAppResult<(), E::Error> {
         // Check version requirement for EIP-712 full implementation
-        let config = self.get_configuration().await?;
+        let config = self.app_configuration().await?;
         if !config.version.supports_eip712_full() {
             return Err(EthAppError::UnsupportedVersion(format!(
                 "EIP-712 array operations require app version >= 1.9.19, found {}",
                 config.version
             )));
         }
+        let max = config.version.max_eip712_array_size();
+        if size > max {
+            return Err(EthAppError::ArrayTooLarge { size, max });
+        }
 
-        EthApp::set_array_size(&self.transport, size).await
+        self.locked(|transport| EthApp::set_array_size(transport, size))
+            .await
     }
 
     /// Send EIP-712 filtering configuration
@@ -453,7 +1483,7 @@ where
         filter_params: &Eip712FilterParams,
     ) -> EthAppResult<(), E::Error> {
         // Check version requirement for EIP-712 full implementation
-        let config = self.get_configuration().await?;
+        let config = self.app_configuration().await?;
         if !config.version.supports_eip712_full() {
             return Err(EthAppError::UnsupportedVersion(format!(
                 "EIP-712 filtering requires app version >= 1.9.19, found {}",
@@ -461,7 +1491,8 @@ where
             )));
         }
 
-        EthApp::send_filter_config(&self.transport, filter_params).await
+        self.locked(|transport| EthApp::send_filter_config(transport, filter_params))
+            .await
     }
 
     /// Activate EIP-712 filtering on the device
@@ -476,7 +1507,7 @@ where
     ///
     pub async fn activate_filtering(&self) -> EthAppResult<(), E::Error> {
         // Check version requirement for EIP-712 full implementation
-        let config = self.get_configuration().await?;
+        let config = self.app_configuration().await?;
         if !config.version.supports_eip712_full() {
             return Err(EthAppError::UnsupportedVersion(format!(
                 "EIP-712 filtering requires app version >= 1.9.19, found {}",
@@ -484,7 +1515,8 @@ where
             )));
         }
 
-        EthApp::activate_filtering(&self.transport).await
+        self.locked(|transport| EthApp::activate_filtering(transport))
+            .await
     }
 
     /// Sign EIP-712 typed data using the high-level API (matching viem interface)
@@ -502,11 +1534,17 @@ where
     ///
     /// # Example
     ///
-    /// ```rust,ignore
-    /// use ledger_eth_app::{Eip712Domain, Eip712Field, Eip712Struct, Eip712Types, Eip712TypedData};
+    /// ```rust
+    /// use ledger_sdk_eth_app::{
+    ///     BipPath, Eip712Domain, Eip712Field, Eip712Struct, Eip712TypedData, Eip712Types,
+    ///     EthereumApp,
+    /// };
     /// use serde_json::json;
-    /// use std::collections::HashMap;
+    /// # use ledger_sdk_eth_app::instructions::ins;
+    /// # use ledger_sdk_transport::mock::MockExchange;
     ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
     /// let domain = Eip712Domain::new()
     ///     .with_name("Ether Mail".to_string())
     ///     .with_version("1".to_string())
@@ -519,6 +1557,14 @@ where
     ///         .with_field(Eip712Field::new("name".to_string(), "string".to_string()))
     ///         .with_field(Eip712Field::new("wallet".to_string(), "address".to_string())),
     /// );
+    /// types.insert(
+    ///     "Mail".to_string(),
+    ///     Eip712Struct::new()
+    ///         .with_field(Eip712Field::new("from".to_string(), "Person".to_string()))
+    ///         .with_field(Eip712Field::new("to".to_string(), "Person".to_string()))
+    ///         .with_field(Eip712Field::new("contents".to_string(), "string".to_string())),
+    /// );
+    /// assert!(types.contains_key("Mail"));
     ///
     /// let message = json!({
     ///     "from": {
@@ -533,7 +1579,22 @@ where
     /// });
     ///
     /// let typed_data = Eip712TypedData::new(domain, types, "Mail".to_string(), message);
-    /// // let signature = app.sign_eip712_typed_data(&path, &typed_data).await?;
+    ///
+    /// # // Stands in for a device running Ethereum app 1.12.0
+    /// # let transport = MockExchange::new().with_responder(|command| {
+    /// #     let mut answer = match command.ins {
+    /// #         ins::GET_APP_CONFIGURATION => vec![0x00, 1, 12, 0],
+    /// #         ins::SIGN_ETH_EIP712 => [&[0x1b][..], &[0x11; 64][..]].concat(),
+    /// #         _ => Vec::new(),
+    /// #     };
+    /// #     answer.extend_from_slice(&[0x90, 0x00]);
+    /// #     Ok(answer)
+    /// # });
+    /// let app = EthereumApp::new(transport);
+    /// let path = BipPath::from_string("m/44'/60'/0'/0/0").unwrap();
+    /// let signature = app.sign_eip712_typed_data(&path, &typed_data).await.unwrap();
+    /// assert_eq!(signature.v, 27);
+    /// # }
     /// ```
     ///
     /// # Errors
@@ -544,17 +1605,97 @@ where
         &self,
         path: &BipPath,
         typed_data: &Eip712TypedData,
+    ) -> EthAppResult<crate::types::Signature, E::Error> {
+        self.sign_eip712_typed_data_with_filters(path, typed_data, None, PathPolicy::default())
+            .await
+    }
+
+    /// Sign EIP-712 typed data with display filters
+    ///
+    /// The filters are usually built with [`Eip712FilterBuilder`], which validates
+    /// each field path against the typed data. The message info filter is sent
+    /// before the message root and every field filter right before its field.
+    /// `path` is checked against `path_policy` before anything is sent.
+    ///
+    /// **Version Requirements**: Requires app version >= 1.9.19
+    ///
+    /// # Errors
+    ///
+    /// Returns `EthAppError::UnsupportedVersion` if app version is below 1.9.19
+    /// Returns `EthAppError::ArrayTooLarge` if an array has more items than the app accepts
+    pub async fn sign_eip712_typed_data_with_filters(
+        &self,
+        path: &BipPath,
+        typed_data: &Eip712TypedData,
+        filters: Option<&Eip712FilterConfig>,
+        path_policy: PathPolicy,
     ) -> EthAppResult<crate::types::Signature, E::Error> {
         // Check version requirement for EIP-712 full implementation
-        let config = self.get_configuration().await?;
+        let config = self.app_configuration().await?;
         if !config.version.supports_eip712_full() {
             return Err(EthAppError::UnsupportedVersion(format!(
                 "EIP-712 typed data signing requires app version >= 1.9.19, found {}",
                 config.version
             )));
         }
+        self.sign_planned_eip712(path, typed_data, filters, path_policy, &config.version)
+            .await
+    }
+
+    /// Plan the typed data signature within the array sizes `version` accepts,
+    /// then send the plan
+    async fn sign_planned_eip712(
+        &self,
+        path: &BipPath,
+        typed_data: &Eip712TypedData,
+        filters: Option<&Eip712FilterConfig>,
+        path_policy: PathPolicy,
+        version: &AppVersion,
+    ) -> EthAppResult<crate::types::Signature, E::Error> {
+        let mut planner = commands::eip712::Eip712ApduPlanner::new(typed_data)
+            .with_max_array_size(version.max_eip712_array_size())
+            .with_path_policy(path_policy);
+        if let Some(filters) = filters {
+            planner = planner.with_filters(filters);
+        }
+        let commands = planner.plan::<E::Error>(path)?;
 
-        EthApp::sign_eip712_typed_data(&self.transport, path, typed_data).await
+        self.locked(|transport| commands::eip712::plan::execute_plan(transport, &commands))
+            .await
+    }
+
+    /// Sign EIP-712 typed data with filters from a Ledger filtering bundle
+    ///
+    /// Resolves the bundle against `typed_data` (see [`Eip712FilterBundle::to_config`])
+    /// and signs like [`EthereumApp::sign_eip712_typed_data_with_filters`].
+    ///
+    /// **Version Requirements**: Requires app version >= 1.9.19
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - BIP32 derivation path for the signing key
+    /// * `typed_data` - Message to sign
+    /// * `bundle` - Filtering descriptors for the message schema
+    ///
+    /// # Errors
+    ///
+    /// Returns `EthAppError::Eip712FilterError` if the bundle does not match the typed data
+    pub async fn sign_eip712_with_filters(
+        &self,
+        path: &BipPath,
+        typed_data: &Eip712TypedData,
+        bundle: &Eip712FilterBundle,
+    ) -> EthAppResult<crate::types::Signature, E::Error> {
+        let filters = bundle
+            .to_config(typed_data)
+            .map_err(EthAppError::Eip712FilterError)?;
+        self.sign_eip712_typed_data_with_filters(
+            path,
+            typed_data,
+            Some(&filters),
+            PathPolicy::default(),
+        )
+        .await
     }
 
     /// Sign EIP-712 typed data from JSON string
@@ -572,7 +1713,9 @@ where
     ///
     /// # Example
     ///
-    /// ```rust,ignore
+    /// ```rust
+    /// use ledger_sdk_eth_app::Eip712Converter;
+    ///
     /// let json_str = r#"{
     ///   "domain": {
     ///     "name": "USD Coin",
@@ -605,6 +1748,11 @@ where
     ///   }
     /// }"#;
     ///
+    /// // The same parsing and validation `sign_eip712_from_json` applies
+    /// let typed_data = Eip712Converter::parse_json_to_typed_data(json_str).unwrap();
+    /// assert_eq!(typed_data.primary_type, "Permit");
+    /// assert_eq!(typed_data.domain.chain_id, Some(1));
+    ///
     /// // let signature = app.sign_eip712_from_json(&path, json_str).await?;
     /// ```
     ///
@@ -619,7 +1767,7 @@ where
         json_str: &str,
     ) -> EthAppResult<crate::types::Signature, E::Error> {
         // Check version requirement for EIP-712 full implementation
-        let config = self.get_configuration().await?;
+        let config = self.app_configuration().await?;
         if !config.version.supports_eip712_full() {
             return Err(EthAppError::UnsupportedVersion(format!(
                 "EIP-712 JSON signing requires app version >= 1.9.19, found {}",
@@ -627,6 +1775,1272 @@ where
             )));
         }
 
-        EthApp::sign_eip712_from_json(&self.transport, path, json_str).await
+        let typed_data = Eip712Converter::parse_json_to_typed_data(json_str)
+            .map_err(EthAppError::InvalidEip712Data)?;
+        self.sign_planned_eip712(
+            path,
+            &typed_data,
+            None,
+            PathPolicy::default(),
+            &config.version,
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ledger_sdk_transport::mock::MockExchange;
+
+    use super::*;
+    use crate::instructions::{ins, p2_eip712_filtering, p2_eip712_struct_impl};
+
+    /// Ethereum app 1.12.0 answering every command successfully
+    fn mock_device() -> MockExchange {
+        MockExchange::new().with_responder(|command| {
+            let mut answer = match command.ins {
+                ins::GET_APP_CONFIGURATION => AppConfiguration {
+                    flags: ConfigFlags::from_byte(0),
+                    version: AppVersion {
+                        major: 1,
+                        minor: 12,
+                        patch: 0,
+                    },
+                }
+                .to_response_bytes(),
+                ins::SIGN_ETH_EIP712 | ins::SIGN_ETH_TRANSACTION => {
+                    let mut signature = vec![0x1b];
+                    signature.extend_from_slice(&[0x11; 32]);
+                    signature.extend_from_slice(&[0x22; 32]);
+                    signature
+                }
+                _ => Vec::new(),
+            };
+            answer.extend_from_slice(&[0x90, 0x00]);
+            Ok(answer)
+        })
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_calls_are_not_interleaved() {
+        use crate::instructions::p1_sign_message;
+        use ledger_sdk_transport::mock::DelayingExchange;
+        use std::sync::Arc;
+
+        let mock = MockExchange::new().with_responder(|command| {
+            let mut answer = match command.ins {
+                ins::GET_ETH_PUBLIC_ADDRESS => {
+                    let mut data = vec![65];
+                    data.extend_from_slice(&[0x04; 65]);
+                    data.push(42);
+                    data.extend_from_slice(b"0x742d35Cc6535C244B8c80A79d5d22efeAdBA5B90");
+                    data
+                }
+                ins::SIGN_ETH_PERSONAL_MESSAGE => vec![0x1b; 65],
+                _ => Vec::new(),
+            };
+            answer.extend_from_slice(&[0x90, 0x00]);
+            Ok(answer)
+        });
+        // Every exchange yields, letting the other tasks run in between
+        let app = Arc::new(EthereumApp::new(DelayingExchange::new(
+            mock,
+            Duration::from_millis(1),
+        )));
+
+        let tasks: Vec<_> = (0..8)
+            .map(|i| {
+                let app = app.clone();
+                tokio::spawn(async move {
+                    if i % 2 == 0 {
+                        app.get_address(GetAddressParams::new(path()))
+                            .await
+                            .map(|_| ())
+                    } else {
+                        let params = SignMessageParams::new(path(), vec![0xAB; 600]);
+                        app.sign_personal_message(params).await.map(|_| ())
+                    }
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap().unwrap();
+        }
+
+        // Each 600-byte message takes 3 APDUs, sent back to back
+        let sent = app.transport().inner().sent();
+        assert_eq!(sent.len(), 4 + 4 * 3);
+        let mut i = 0;
+        while i < sent.len() {
+            if sent[i].ins == ins::SIGN_ETH_PERSONAL_MESSAGE {
+                assert_eq!(sent[i].p1, p1_sign_message::FIRST_DATA_BLOCK);
+                for command in &sent[i + 1..i + 3] {
+                    assert_eq!(
+                        (command.ins, command.p1),
+                        (
+                            ins::SIGN_ETH_PERSONAL_MESSAGE,
+                            p1_sign_message::SUBSEQUENT_DATA_BLOCK
+                        )
+                    );
+                }
+                i += 3;
+            } else {
+                i += 1;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_exchange_timeouts() {
+        let timeouts = EthApp::exchange_timeouts(Duration::from_secs(2), Duration::from_secs(300));
+        let app = EthereumApp::new(mock_device());
+
+        app.get_configuration().await.unwrap();
+        let _ = app.get_address(GetAddressParams::new(path())).await;
+        let _ = app
+            .get_address(GetAddressParams::new(path()).with_display())
+            .await;
+        let _ = app
+            .sign_personal_message(SignMessageParams::new(path(), b"hello".to_vec()))
+            .await;
+
+        let sent = app.transport().sent();
+        let used: Vec<_> = sent
+            .iter()
+            .map(|command| timeouts.for_command(command).as_secs())
+            .collect();
+        assert_eq!(used, vec![2, 2, 300, 300]);
+    }
+
+    fn typed_data() -> Eip712TypedData {
+        let mut types = Eip712Types::new();
+        types.insert(
+            "EIP712Domain".to_string(),
+            Eip712Struct::new()
+                .with_field(Eip712Field::new("name".to_string(), "string".to_string()))
+                .with_field(Eip712Field::new(
+                    "chainId".to_string(),
+                    "uint256".to_string(),
+                )),
+        );
+        types.insert(
+            "Mail".to_string(),
+            Eip712Struct::new().with_field(Eip712Field::new(
+                "contents".to_string(),
+                "string".to_string(),
+            )),
+        );
+
+        Eip712TypedData::new(
+            Eip712Domain::new()
+                .with_name("Ether Mail".to_string())
+                .with_chain_id(1),
+            types,
+            "Mail".to_string(),
+            serde_json::json!({ "contents": "Hello, Bob!" }),
+        )
+    }
+
+    fn path() -> BipPath {
+        BipPath::from_string("m/44'/60'/0'/0/0").unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_configuration_queried_once_across_typed_data_flow() {
+        let app = EthereumApp::new(mock_device());
+        let typed_data = typed_data();
+        let definitions = Eip712Converter::convert_types_to_definitions(&typed_data.types).unwrap();
+
+        for definition in &definitions {
+            app.send_struct_definition(definition).await.unwrap();
+        }
+        app.activate_filtering().await.unwrap();
+        app.send_struct_implementation(&Eip712StructImplementation {
+            name: "Mail".to_string(),
+            values: vec![Eip712FieldValue::from_string("Hello, Bob!")],
+        })
+        .await
+        .unwrap();
+        app.sign_eip712_full(&path(), PathPolicy::default())
+            .await
+            .unwrap();
+        app.sign_eip712_typed_data(&path(), &typed_data)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            app.transport()
+                .count(EthApp::CLA, ins::GET_APP_CONFIGURATION),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_missing_domain_type_is_derived() {
+        use crate::instructions::p2_eip712_struct_def;
+
+        let mut typed_data = typed_data();
+        typed_data.types.remove("EIP712Domain");
+        let app = EthereumApp::new(mock_device());
+        app.sign_eip712_typed_data(&path(), &typed_data)
+            .await
+            .unwrap();
+
+        let sent = app.transport().sent();
+        let definition: Vec<&[u8]> = sent
+            .iter()
+            .filter(|c| c.ins == ins::EIP712_SEND_STRUCT_DEFINITION)
+            .skip_while(|c| c.p2 != p2_eip712_struct_def::STRUCT_NAME || c.data != b"EIP712Domain")
+            .skip(1)
+            .take_while(|c| c.p2 == p2_eip712_struct_def::STRUCT_FIELD)
+            .map(|c| &c.data[..])
+            .collect();
+        assert_eq!(
+            definition,
+            vec![&b"\x05\x04name"[..], &b"\x42\x20\x07chainId"[..]]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_raw_exchange() {
+        use ledger_sdk_transport::mock::MockError;
+
+        let device = MockExchange::new();
+        device
+            .push_answer(vec![0xAA, 0x6D, 0x00])
+            .push_error(MockError::Transport("unplugged".to_string()));
+        let app = EthereumApp::new(device);
+        let command = APDUCommand {
+            cla: EthApp::CLA,
+            ins: ins::GET_CHALLENGE,
+            p1: 0x00,
+            p2: 0x00,
+            data: vec![0x01],
+        };
+
+        // Status words are left to the caller
+        let answer = app.raw_exchange(&command).await.unwrap();
+        assert_eq!(answer.split(), (&[0xAA][..], 0x6D00));
+        assert_eq!(app.transport().sent()[0].data, vec![0x01]);
+
+        let err = app.raw_exchange(&command).await.unwrap_err();
+        assert!(matches!(
+            err.transport_error(),
+            Some(MockError::Transport(msg)) if msg == "unplugged"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_exchange_raw_hex() {
+        let device = MockExchange::new();
+        device
+            .push_ok(&[0x00, 1, 12, 0])
+            .push_status(0x6985)
+            .push_status(0x6A80);
+        let app = EthereumApp::new(device);
+
+        let data = app.exchange_raw_hex("0xe006000000").await.unwrap();
+        assert_eq!(data, vec![0x00, 1, 12, 0]);
+        let sent = app.transport().sent();
+        assert_eq!(
+            (sent[0].cla, sent[0].ins, sent[0].p1, sent[0].p2),
+            (0xE0, ins::GET_APP_CONFIGURATION, 0x00, 0x00)
+        );
+        assert!(sent[0].data.is_empty());
+
+        // Status words map to the same errors as the wrapped commands
+        let err = app.exchange_raw_hex("e0060000").await.unwrap_err();
+        assert!(err.is_user_rejected());
+        let err = app
+            .exchange_raw(0xE0, ins::SIGN_ETH_PERSONAL_MESSAGE, 0x00, 0x00, &[0x01])
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthAppError::DeviceStatus { sw: 0x6A80, .. }));
+
+        // Malformed frames are not sent
+        for (apdu, hex_error) in [
+            ("e00600zz", true),
+            ("e0060", true),
+            ("e00600", false),
+            ("e00600000201", false),
+            ("e0060000010102", false),
+        ] {
+            let err = app.exchange_raw_hex(apdu).await.unwrap_err();
+            if hex_error {
+                assert!(matches!(err, EthAppError::HexError(_)), "{}", apdu);
+            } else {
+                assert!(matches!(err, EthAppError::InvalidMessage(_)), "{}", apdu);
+            }
+        }
+        assert_eq!(app.transport().sent().len(), 3);
+    }
+
+    /// GET APP INFO answer for the open app
+    fn app_info(name: &str) -> Vec<u8> {
+        let mut data = vec![0x01, name.len() as u8];
+        data.extend_from_slice(name.as_bytes());
+        data.extend_from_slice(&[0x05, b'1', b'.', b'0', b'.', b'0', 0x01, 0x02]);
+        data
+    }
+
+    #[tokio::test]
+    async fn test_ensure_app_open_from_dashboard() {
+        use ledger_sdk_transport::mock::MockError;
+
+        let device = MockExchange::new();
+        device
+            .push_ok(&app_info("BOLOS"))
+            // Device re-enumerates while opening the app
+            .push_error(MockError::Transport("disconnected".to_string()))
+            .push_status(0x6E00)
+            .push_ok(
+                &AppConfiguration {
+                    flags: ConfigFlags::from_byte(0),
+                    version: AppVersion::new(1, 13, 0),
+                }
+                .to_response_bytes(),
+            );
+        let app = EthereumApp::new(device);
+        app.ensure_app_open(true, Duration::from_secs(5))
+            .await
+            .unwrap();
+
+        let sent = app.transport().sent();
+        let headers: Vec<(u8, u8)> = sent.iter().map(|c| (c.cla, c.ins)).collect();
+        assert_eq!(
+            headers,
+            vec![(0xB0, 0x01), (0xE0, 0xD8), (0xE0, 0x06), (0xE0, 0x06)]
+        );
+        assert_eq!(sent[1].data, b"Ethereum");
+        // Configuration cached by the poll
+        assert_eq!(app.app_version().await.unwrap(), AppVersion::new(1, 13, 0));
+        assert_eq!(app.transport().sent().len(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_ensure_app_open_other_apps() {
+        // Already open
+        let device = MockExchange::new();
+        device.push_ok(&app_info("Ethereum"));
+        let app = EthereumApp::new(device);
+        app.ensure_app_open(true, Duration::ZERO).await.unwrap();
+        assert_eq!(app.transport().sent().len(), 1);
+
+        // Another app, opening not allowed
+        let device = MockExchange::new();
+        device.push_ok(&app_info("Bitcoin"));
+        let app = EthereumApp::new(device);
+        let err = app
+            .ensure_app_open(false, Duration::ZERO)
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            EthAppError::WrongApp { ref found, .. } if found == "Bitcoin"
+        ));
+
+        // Another app is quit first, then the caller reconnects
+        let device = MockExchange::new();
+        device.push_ok(&app_info("Bitcoin")).push_ok(&[]);
+        let app = EthereumApp::new(device);
+        let err = app.ensure_app_open(true, Duration::ZERO).await.unwrap_err();
+        assert!(matches!(err, EthAppError::AppSwitchPending));
+        assert_eq!(app.transport().sent()[1].ins, 0xA7);
+
+        // App not answering before the timeout
+        let device = MockExchange::new().with_responder(|_| Ok(vec![0x6E, 0x00]));
+        device.push_ok(&app_info("BOLOS")).push_ok(&[]);
+        let app = EthereumApp::new(device);
+        let err = app.ensure_app_open(true, Duration::ZERO).await.unwrap_err();
+        assert!(matches!(err, EthAppError::AppSwitchPending));
+
+        // Opening refused by the user on the dashboard
+        let device = MockExchange::new();
+        device.push_ok(&app_info("BOLOS")).push_status(0x5501);
+        let app = EthereumApp::new(device);
+        let err = app.ensure_app_open(true, Duration::ZERO).await.unwrap_err();
+        assert!(matches!(err, EthAppError::DeviceStatus { sw: 0x5501, .. }));
+    }
+
+    #[tokio::test]
+    async fn test_refresh_and_disabled_configuration_cache() {
+        let app = EthereumApp::new(mock_device());
+        app.app_configuration().await.unwrap();
+        app.refresh_configuration().await.unwrap();
+        app.app_configuration().await.unwrap();
+        assert_eq!(
+            app.transport()
+                .count(EthApp::CLA, ins::GET_APP_CONFIGURATION),
+            2
+        );
+
+        let app = EthereumApp::new(mock_device()).with_configuration_cache(false);
+        app.activate_filtering().await.unwrap();
+        app.activate_filtering().await.unwrap();
+        assert_eq!(
+            app.transport()
+                .count(EthApp::CLA, ins::GET_APP_CONFIGURATION),
+            2
+        );
+    }
+
+    #[tokio::test]
+    async fn test_nested_structs_send_no_empty_fields() {
+        let app = EthereumApp::new(mock_device());
+        let typed_data = Eip712Converter::parse_json_to_typed_data(
+            &serde_json::json!({
+                "types": {
+                    "EIP712Domain": [
+                        {"name": "name", "type": "string"},
+                        {"name": "chainId", "type": "uint256"}
+                    ],
+                    "Person": [
+                        {"name": "name", "type": "string"},
+                        {"name": "wallet", "type": "address"}
+                    ],
+                    "Mail": [
+                        {"name": "from", "type": "Person"},
+                        {"name": "to", "type": "Person"},
+                        {"name": "contents", "type": "string"}
+                    ]
+                },
+                "primaryType": "Mail",
+                "domain": {"name": "Ether Mail", "chainId": 1},
+                "message": {
+                    "from": {"name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"},
+                    "to": {"name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB"},
+                    "contents": "Hello, Bob!"
+                }
+            })
+            .to_string(),
+        )
+        .unwrap();
+
+        app.sign_eip712_typed_data(&path(), &typed_data)
+            .await
+            .unwrap();
+
+        let fields: Vec<Vec<u8>> = app
+            .transport()
+            .sent()
+            .into_iter()
+            .filter(|command| {
+                command.ins == ins::EIP712_SEND_STRUCT_IMPLEMENTATION
+                    && command.p2 == p2_eip712_struct_impl::STRUCT_FIELD
+            })
+            .map(|command| command.data)
+            .collect();
+
+        // Domain name + chainId, then from.{name, wallet}, to.{name, wallet}, contents
+        assert_eq!(fields.len(), 7);
+        assert!(fields.iter().all(|data| data[..] != [0x00, 0x00]));
+    }
+
+    #[tokio::test]
+    async fn test_typed_data_filters_interleaved_with_implementation() {
+        let app = EthereumApp::new(mock_device());
+        let typed_data = typed_data();
+        let filters = Eip712FilterBuilder::new(&typed_data)
+            .message_info("Mail", vec![0xAA; 72])
+            .raw_field("contents", "Contents", vec![0xBB; 72])
+            .build()
+            .unwrap();
+
+        app.sign_eip712_typed_data_with_filters(
+            &path(),
+            &typed_data,
+            Some(&filters),
+            PathPolicy::default(),
+        )
+        .await
+        .unwrap();
+
+        // (ins, p2) of the filtering and message implementation commands, in order
+        let sent: Vec<(u8, u8)> = app
+            .transport()
+            .sent()
+            .iter()
+            .filter(|command| {
+                command.ins == ins::EIP712_FILTERING
+                    || command.ins == ins::EIP712_SEND_STRUCT_IMPLEMENTATION
+            })
+            .map(|command| (command.ins, command.p2))
+            .collect();
+
+        assert_eq!(
+            sent,
+            vec![
+                (ins::EIP712_FILTERING, p2_eip712_filtering::ACTIVATION),
+                // domain root + name + chainId
+                (
+                    ins::EIP712_SEND_STRUCT_IMPLEMENTATION,
+                    p2_eip712_struct_impl::ROOT_STRUCT
+                ),
+                (
+                    ins::EIP712_SEND_STRUCT_IMPLEMENTATION,
+                    p2_eip712_struct_impl::STRUCT_FIELD
+                ),
+                (
+                    ins::EIP712_SEND_STRUCT_IMPLEMENTATION,
+                    p2_eip712_struct_impl::STRUCT_FIELD
+                ),
+                (ins::EIP712_FILTERING, p2_eip712_filtering::MESSAGE_INFO),
+                (
+                    ins::EIP712_SEND_STRUCT_IMPLEMENTATION,
+                    p2_eip712_struct_impl::ROOT_STRUCT
+                ),
+                (ins::EIP712_FILTERING, p2_eip712_filtering::RAW_FIELD),
+                (
+                    ins::EIP712_SEND_STRUCT_IMPLEMENTATION,
+                    p2_eip712_struct_impl::STRUCT_FIELD
+                ),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_typed_transaction_sends_type_prefix() {
+        let app = EthereumApp::new(mock_device());
+        let tx = Eip1559Transaction {
+            chain_id: 1,
+            nonce: 1,
+            to: Some([0x35; 20]),
+            ..Default::default()
+        };
+
+        app.sign_typed_transaction(&path(), &tx).await.unwrap();
+
+        let sent = app.transport().sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].ins, ins::SIGN_ETH_TRANSACTION);
+        // Path (1 + 5 * 4 bytes) followed by the payload
+        assert_eq!(&sent[0].data[21..], &tx.to_sign_payload()[..]);
+        assert_eq!(sent[0].data[21], EIP1559_TX_TYPE);
+    }
+
+    #[tokio::test]
+    async fn test_transaction_chain_id_not_sent() {
+        let tx = vec![0xf8, 0x6c];
+        let mut first_chunk = crate::utils::encode_bip32_path(&path());
+        first_chunk.extend_from_slice(&tx);
+
+        // No chain ID
+        let app = EthereumApp::new(mock_device());
+        app.sign_transaction(SignTransactionParams::new(path(), tx.clone()))
+            .await
+            .unwrap();
+        let sent = app.transport().sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].data, first_chunk);
+
+        // Chain ID set: same payload, no version query
+        let app = EthereumApp::new(mock_device());
+        let params = SignTransactionParams::new(path(), tx).with_chain_id(137);
+        app.sign_transaction(params).await.unwrap();
+        let sent = app.transport().sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].data, first_chunk);
+    }
+
+    #[tokio::test]
+    async fn test_commands_with_shared_answer_buffers() {
+        use std::sync::Arc;
+
+        let mock = MockExchange::<Arc<[u8]>>::default();
+        mock.push_ok(
+            &AppConfiguration {
+                flags: ConfigFlags::from_byte(0),
+                version: AppVersion::new(1, 12, 0),
+            }
+            .to_response_bytes(),
+        )
+        .push_ok(&[0x1b; 65]);
+        let app = EthereumApp::new(mock);
+
+        assert_eq!(app.app_version().await.unwrap(), AppVersion::new(1, 12, 0));
+        let signature = app
+            .sign_transaction(SignTransactionParams::new(path(), vec![0xf8, 0x6c]))
+            .await
+            .unwrap();
+        assert_eq!(signature.v, 0x1b);
+    }
+
+    #[tokio::test]
+    async fn test_get_address_range() {
+        // Address derived from the last path index
+        let mock = MockExchange::new().with_responder(|command| {
+            let index = command.data[command.data.len() - 1];
+            let mut answer = vec![65];
+            answer.extend_from_slice(&[0x04; 65]);
+            answer.push(40);
+            answer.extend_from_slice(format!("{:040x}", index).as_bytes());
+            answer.extend_from_slice(&[0x90, 0x00]);
+            Ok(answer)
+        });
+        let app = EthereumApp::new(mock);
+
+        let addresses = app.get_address_range(path(), 3..6, false).await.unwrap();
+        let found: Vec<(u32, String)> = addresses
+            .into_iter()
+            .map(|(index, info)| (index, info.address.address))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                (3, format!("0x{:040x}", 3)),
+                (4, format!("0x{:040x}", 4)),
+                (5, format!("0x{:040x}", 5)),
+            ]
+        );
+        let sent = app.transport().sent();
+        assert_eq!(sent.len(), 3);
+        assert!(sent
+            .iter()
+            .all(|c| c.p1 == crate::instructions::p1_get_address::RETURN_ADDRESS));
+        let mut expected = crate::utils::encode_bip32_path(&path());
+        *expected.last_mut().unwrap() = 5;
+        assert_eq!(sent[2].data, expected);
+
+        // Hardened last index stays hardened; invalid base or range sends nothing
+        let hardened = BipPath::from_string("m/44'/60'/0'").unwrap();
+        app.get_address_range(hardened, 1..2, false).await.unwrap();
+        let sent = app.transport().sent();
+        assert_eq!(&sent[3].data[9..13], &[0x80, 0x00, 0x00, 0x01]);
+
+        let unhardened = BipPath::from_string("m/44'/60'/0").unwrap();
+        let err = app
+            .get_address_range(unhardened, 0..2, false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthAppError::InvalidBip32Path(_)));
+        let err = app
+            .get_address_range(path(), 0..0x8000_0001, false)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthAppError::InvalidBip32Path(_)));
+        assert_eq!(app.transport().sent().len(), 4);
+    }
+
+    /// GET ADDRESS answer for `address`, with a placeholder public key
+    fn address_answer(address: &str) -> Vec<u8> {
+        let mut answer = vec![65];
+        answer.extend_from_slice(&[0x04; 65]);
+        answer.push(40);
+        answer.extend_from_slice(address.as_bytes());
+        answer
+    }
+
+    #[tokio::test]
+    async fn test_get_addresses() {
+        let mock = MockExchange::new().with_responder(|command| {
+            let index = command.data[command.data.len() - 1];
+            if index == 7 {
+                return Ok(vec![0x6A, 0x80]);
+            }
+            let mut answer = address_answer(&format!("{:040x}", index));
+            answer.extend_from_slice(&[0x90, 0x00]);
+            Ok(answer)
+        });
+        let app = EthereumApp::new(mock);
+        let paths = |indices: &[u32]| -> Vec<BipPath> {
+            indices
+                .iter()
+                .map(|&i| BipPath::ethereum_standard(0, i))
+                .collect()
+        };
+
+        let addresses = app
+            .get_addresses(paths(&[5, 2, 9]), GetAddressParams::new(path()))
+            .await
+            .unwrap();
+        let found: Vec<String> = addresses
+            .into_iter()
+            .map(|info| info.address.address)
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                format!("0x{:040x}", 5),
+                format!("0x{:040x}", 2),
+                format!("0x{:040x}", 9)
+            ]
+        );
+        assert_eq!(app.transport().sent().len(), 3);
+
+        // The device failing a path names its index
+        let err = app
+            .get_addresses(paths(&[1, 7, 3]), GetAddressParams::new(path()))
+            .await
+            .unwrap_err();
+        let EthAppError::BatchItem {
+            index,
+            path: failed,
+            error,
+        } = err
+        else {
+            panic!("expected BatchItem, got {:?}", err);
+        };
+        assert_eq!((index, failed.as_str()), (1, "m/44'/60'/0'/0/7"));
+        assert!(matches!(
+            *error,
+            EthAppError::DeviceStatus { sw: 0x6A80, .. }
+        ));
+        assert_eq!(app.transport().sent().len(), 5);
+
+        // An invalid path fails the batch before anything is sent
+        let mut invalid = paths(&[0, 1]);
+        invalid.push(BipPath::from_string("m/44'/60'/0").unwrap());
+        let err = app
+            .get_addresses(invalid, GetAddressParams::new(path()))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthAppError::BatchItem { index: 2, .. }));
+        assert_eq!(app.transport().sent().len(), 5);
+    }
+
+    /// Local derivation agrees with the device on the "abandon ... about" mnemonic
+    #[cfg(feature = "derivation")]
+    #[tokio::test]
+    async fn test_derived_addresses_match_device() {
+        let device_addresses = [
+            "9858effd232b4033e47d90003d41ec34ecaeda94",
+            "6fac4d18c912343bf86fa7049364dd4e424ab9c0",
+            "b6716976a3ebe8d39aceb04372f22ff8e6802d7a",
+        ];
+        let device = MockExchange::new();
+        let mut account = vec![65];
+        account.extend_from_slice(
+            &hex::decode(
+                "04eae4b876a8696134b868f88cc2f51f715f2dbedb7446b8e6edf3d4541c4eb67b\
+                 61ed8eb62af1d433cd11b4f59923ac1f87f328c5673396ee55acc6195d92b320",
+            )
+            .unwrap(),
+        );
+        account.push(40);
+        account.extend_from_slice(&[b'0'; 40]);
+        account.extend_from_slice(
+            &hex::decode("d882718b7a42806803eeb17f7483f20620611adb88fc943c898dc5aba94c2819")
+                .unwrap(),
+        );
+        device.push_ok(&account);
+        for address in device_addresses {
+            device.push_ok(&address_answer(address));
+        }
+        let app = EthereumApp::new(device);
+
+        let external = app.get_account_xpub(0).await.unwrap().child(0).unwrap();
+        let paths = (0..3).map(|i| BipPath::ethereum_standard(0, i));
+        let from_device = app
+            .get_addresses(paths, GetAddressParams::new(path()))
+            .await
+            .unwrap();
+
+        for (i, info) in from_device.iter().enumerate() {
+            let derived = external.derive_child(i as u32).unwrap();
+            assert_eq!(
+                info.address.address,
+                format!("0x{}", hex::encode(derived)),
+                "index {}",
+                i
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_account_xpub() {
+        let device = MockExchange::new();
+        let mut answer = vec![65];
+        answer.extend_from_slice(&[0x04; 65]);
+        answer.push(40);
+        answer.extend_from_slice("9858effd232b4033e47d90003d41ec34ecaeda94".as_bytes());
+        answer.extend_from_slice(&[0xCC; 32]);
+        device.push_ok(&answer);
+        let app = EthereumApp::new(device);
+
+        let xpub = app.get_account_xpub(3).await.unwrap();
+        assert_eq!(xpub.path.to_string(), "m/44'/60'/3'");
+        assert_eq!(xpub.public_key, vec![0x04; 65]);
+        assert_eq!(xpub.chain_code, [0xCC; 32]);
+
+        let sent = app.transport().sent();
+        assert_eq!(
+            sent[0].p1,
+            crate::instructions::p1_get_address::RETURN_ADDRESS
+        );
+        assert_eq!(
+            sent[0].p2,
+            crate::instructions::p2_get_address::RETURN_CHAIN_CODE
+        );
+        assert_eq!(sent[0].data, crate::utils::encode_bip32_path(&xpub.path));
+
+        let err = app.get_account_xpub(0x8000_0000).await.unwrap_err();
+        assert!(matches!(err, EthAppError::InvalidBip32Path(_)));
+        assert_eq!(app.transport().sent().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_signing_resumes_after_unlock() {
+        let params = SignTransactionParams::new(path(), vec![0xAB; 300]);
+
+        // Device locks before the last chunk, then the user unlocks it
+        let device = MockExchange::new();
+        device
+            .push_ok(&[])
+            .push_status(0x5515)
+            .push_status(0x5515)
+            .push_ok(&app_info("Ethereum"))
+            .push_ok(&[0x25; 65]);
+        let app = EthereumApp::new(device)
+            .with_unlock_wait_interval(Duration::from_secs(5), Duration::from_millis(1));
+
+        let signature = app.sign_transaction(params.clone()).await.unwrap();
+        assert_eq!(signature.v, 0x25);
+        let sent = app.transport().inner().sent();
+        let ins: Vec<u8> = sent.iter().map(|c| c.ins).collect();
+        assert_eq!(
+            ins,
+            vec![
+                ins::SIGN_ETH_TRANSACTION,
+                ins::SIGN_ETH_TRANSACTION,
+                0x01,
+                0x01,
+                ins::SIGN_ETH_TRANSACTION,
+            ]
+        );
+        assert_eq!(sent[4].data, sent[1].data);
+
+        // Without waiting, the locked device surfaces as its own error
+        let device = MockExchange::new();
+        device.push_ok(&[]).push_status(0x5515);
+        let app = EthereumApp::new(device);
+        let err = app.sign_transaction(params).await.unwrap_err();
+        assert!(err.is_device_locked());
+
+        let device = MockExchange::new();
+        device.push_status(0x5515).push_ok(&app_info("Ethereum"));
+        let app = EthereumApp::new(device);
+        // The last sleep is capped to the time left
+        app.wait_until_ready(Duration::from_millis(10))
+            .await
+            .unwrap();
+        assert_eq!(app.transport().remaining(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_app_version_uses_configuration() {
+        let app = EthereumApp::new(mock_device());
+
+        let version = app.app_version().await.unwrap();
+        assert_eq!((version.major, version.minor, version.patch), (1, 12, 0));
+        assert!(version.supports_eip712_full());
+
+        app.app_version().await.unwrap();
+        let sent = app.transport().sent();
+        assert_eq!(sent.len(), 1);
+        assert_eq!(sent[0].ins, ins::GET_APP_CONFIGURATION);
+    }
+
+    /// GET DEVICE INFO answer for `target_id`
+    fn device_info(target_id: u32) -> Vec<u8> {
+        let mut data = target_id.to_be_bytes().to_vec();
+        data.extend_from_slice(&[0x05, b'2', b'.', b'2', b'.', b'3']);
+        data.extend_from_slice(&[0x04, 0x8C, 0x00, 0x00, 0x00]);
+        data.extend_from_slice(&[0x04, b'2', b'.', b'3', 0x00]);
+        data
+    }
+
+    #[tokio::test]
+    async fn test_check_compatibility() {
+        use ledger_sdk_device_base::DeviceModel;
+
+        // Ethereum app open: GET DEVICE INFO is not answered
+        let device = MockExchange::new();
+        device
+            .push_status(0x6D00)
+            .push_ok(&app_info("Ethereum"))
+            .push_ok(
+                &AppConfiguration {
+                    flags: ConfigFlags::from_byte(0),
+                    version: AppVersion::new(1, 15, 0),
+                }
+                .to_response_bytes(),
+            );
+        let app = EthereumApp::new(device);
+        let report = app.check_compatibility().await.unwrap();
+        assert_eq!(report.model, None);
+        assert_eq!(report.app_version(), Some(&AppVersion::new(1, 15, 0)));
+        // Unknown model: the app could be the Nano S build
+        assert_eq!((report.eip712_full, report.eip712_filtering), (None, None));
+        assert!(report.eip7702);
+        let report = report.with_model(DeviceModel::NanoX);
+        assert_eq!(
+            (report.eip712_full, report.eip712_filtering),
+            (Some(true), Some(true))
+        );
+        // Configuration cached for the version gates
+        app.app_version().await.unwrap();
+        assert_eq!(app.transport().sent().len(), 3);
+
+        // Dashboard of a Nano X
+        let device = MockExchange::new();
+        device
+            .push_ok(&device_info(0x3300_0004))
+            .push_ok(&app_info("BOLOS"));
+        let app = EthereumApp::new(device);
+        let report = app.check_compatibility().await.unwrap();
+        assert_eq!(report.model, Some(DeviceModel::NanoX));
+        assert_eq!(report.configuration, None);
+        assert_eq!(
+            (report.eip712_full, report.eip712_filtering),
+            (Some(false), Some(false))
+        );
+        assert!(!report.eip7702);
+        assert_eq!(app.transport().sent().len(), 2);
+
+        // Locked device
+        let device = MockExchange::new();
+        device.push_status(0x5515);
+        let app = EthereumApp::new(device);
+        let err = app.check_compatibility().await.unwrap_err();
+        assert!(err.is_device_locked());
+    }
+
+    #[test]
+    fn test_compatibility_report_nano_s() {
+        use ledger_sdk_device_base::{parse_app_info, DeviceInfo, DeviceModel};
+
+        let info = DeviceInfo {
+            target_id: [0x31, 0x10, 0x00, 0x04],
+            se_version: "2.1.0".to_string(),
+            flag: vec![0x8C, 0x00, 0x00, 0x00],
+            mcu_version: "1.12".to_string(),
+        };
+        let app_info = parse_app_info::<std::io::Error>(&app_info("Ethereum")).unwrap();
+        let configuration = AppConfiguration {
+            flags: ConfigFlags::from_byte(0),
+            version: AppVersion::new(1, 10, 3),
+        };
+
+        // The Nano S app has no full EIP-712 support whatever its version
+        let report =
+            CompatibilityReport::new(Some(info), app_info.clone(), Some(configuration.clone()));
+        assert_eq!(report.model, Some(DeviceModel::NanoS));
+        assert_eq!(
+            (report.eip712_full, report.eip712_filtering),
+            (Some(false), Some(false))
+        );
+        assert!(!report.eip7702);
+
+        // Inside the app the model has to come from the transport
+        let report = CompatibilityReport::new(None, app_info, Some(configuration));
+        assert_eq!(report.eip712_full, None);
+        let report = report.with_model(DeviceModel::NanoS);
+        assert_eq!(
+            (report.eip712_full, report.eip712_filtering),
+            (Some(false), Some(false))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sign_with_filter_bundle() {
+        let app = EthereumApp::new(mock_device());
+        let bundle = Eip712FilterBundle::from_json(
+            r#"{
+                "contractName": { "label": "Mail", "signature": "aaaa" },
+                "fields": [{ "path": "contents", "label": "Contents", "signature": "bbbb" }]
+            }"#,
+        )
+        .unwrap();
+
+        app.sign_eip712_with_filters(&path(), &typed_data(), &bundle)
+            .await
+            .unwrap();
+
+        let filters: Vec<(u8, Vec<u8>)> = app
+            .transport()
+            .sent()
+            .into_iter()
+            .filter(|command| command.ins == ins::EIP712_FILTERING)
+            .map(|command| (command.p2, command.data))
+            .collect();
+        assert_eq!(filters.len(), 3);
+        assert_eq!(filters[0].0, p2_eip712_filtering::ACTIVATION);
+        assert_eq!(filters[1].0, p2_eip712_filtering::MESSAGE_INFO);
+        assert!(filters[1].1.ends_with(&[0x02, 0xAA, 0xAA]));
+        assert_eq!(filters[2].0, p2_eip712_filtering::RAW_FIELD);
+        assert!(filters[2].1.starts_with(b"\x08Contents"));
+
+        let bad_bundle = Eip712FilterBundle::from_json(
+            r#"{ "contractName": { "label": "Mail", "signature": "aa" },
+                 "fields": [{ "path": "body", "label": "Body", "signature": "bb" }] }"#,
+        )
+        .unwrap();
+        let err = app
+            .sign_eip712_with_filters(&path(), &typed_data(), &bad_bundle)
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthAppError::Eip712FilterError(_)));
+    }
+
+    #[tokio::test]
+    async fn test_with_logging_keeps_configuration_cache() {
+        let app = EthereumApp::new(mock_device());
+        app.app_version().await.unwrap();
+
+        let app = app.with_logging();
+        app.app_version().await.unwrap();
+        app.sign_eip712_full(&path(), PathPolicy::default())
+            .await
+            .unwrap();
+        // Only the signing APDU went through the logger
+        assert_eq!(app.transport().inner().sent().len(), 2);
+        assert_eq!(
+            app.transport()
+                .inner()
+                .count(EthApp::CLA, ins::GET_APP_CONFIGURATION),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_array_size_limit_follows_version() {
+        let app = EthereumApp::new(mock_device());
+        app.set_array_size(255).await.unwrap();
+        assert_eq!(
+            app.set_array_size(256).await.unwrap_err(),
+            EthAppError::ArrayTooLarge {
+                size: 256,
+                max: 255
+            }
+        );
+
+        // Checked before any part of the message is sent
+        let values: Vec<String> = (0..256).map(|i| (i % 10).to_string()).collect();
+        let json = format!(
+            r#"{{
+                "types": {{
+                    "EIP712Domain": [{{"name": "name", "type": "string"}}],
+                    "Batch": [{{"name": "amounts", "type": "uint8[]"}}]
+                }},
+                "primaryType": "Batch",
+                "domain": {{"name": "Batcher"}},
+                "message": {{"amounts": [{}]}}
+            }}"#,
+            values.join(",")
+        );
+        let sent_before = app.transport().sent().len();
+        let err = app.sign_eip712_from_json(&path(), &json).await.unwrap_err();
+        assert!(matches!(err, EthAppError::ArrayTooLarge { size: 256, .. }));
+        assert_eq!(app.transport().sent().len(), sent_before);
+
+        let device = MockExchange::new().with_responder(|command| {
+            let mut answer = if command.ins == ins::GET_APP_CONFIGURATION {
+                AppConfiguration {
+                    flags: ConfigFlags::from_byte(0),
+                    version: AppVersion::new(1, 17, 0),
+                }
+                .to_response_bytes()
+            } else {
+                Vec::new()
+            };
+            answer.extend_from_slice(&[0x90, 0x00]);
+            Ok(answer)
+        });
+        let app = EthereumApp::new(device);
+        app.set_array_size(65535).await.unwrap();
+        assert_eq!(
+            app.transport().sent().last().unwrap().data,
+            vec![0xFF, 0xFF]
+        );
+        assert!(app.set_array_size(65536).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_tx_simulation_requires_transaction_check() {
+        let simulation =
+            TxSimulationResult::new([0xAB; 32], 1, [0x11; 20], 0).with_signature(vec![0x30, 0x00]);
+
+        let app = EthereumApp::new(mock_device());
+        assert!(!app.transaction_check_enabled().await.unwrap());
+        let err = app.provide_tx_simulation(&simulation).await.unwrap_err();
+        assert!(matches!(err, EthAppError::FeatureNotSupported(_)));
+        assert_eq!(app.transport().sent().len(), 1);
+
+        let device = MockExchange::new();
+        device.push_ok(
+            &AppConfiguration {
+                flags: ConfigFlags::from_byte(
+                    crate::instructions::config_flags::TRANSACTION_CHECK_ENABLED,
+                ),
+                version: AppVersion::new(1, 16, 0),
+            }
+            .to_response_bytes(),
+        );
+        device.push_ok(&[]);
+        let app = EthereumApp::new(device);
+        app.provide_tx_simulation(
+            &simulation.clone().with_simulation_type(
+                crate::instructions::tx_simulation_tag::SIMULATION_TYPED_DATA,
+            ),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            app.transport()
+                .count(EthApp::CLA, ins::PROVIDE_TX_SIMULATION),
+            1
+        );
+    }
+
+    #[tokio::test]
+    async fn test_tx_simulation_ordering() {
+        use commands::sign_transaction::TransactionMode;
+
+        let simulation =
+            TxSimulationResult::new([0xAB; 32], 1, [0x11; 20], 0).with_signature(vec![0x30, 0x00]);
+        let params = SignTransactionParams::new(path(), vec![0xE8; 40]);
+
+        let device = MockExchange::new();
+        device.push_ok(
+            &AppConfiguration {
+                flags: ConfigFlags::from_byte(
+                    crate::instructions::config_flags::TRANSACTION_CHECK_ENABLED,
+                ),
+                version: AppVersion::new(1, 16, 0),
+            }
+            .to_response_bytes(),
+        );
+        let app = EthereumApp::new(device);
+
+        // Nothing stored yet
+        let err = app.provide_tx_simulation(&simulation).await.unwrap_err();
+        assert!(matches!(
+            err,
+            EthAppError::InvalidTransaction(ref msg) if msg.contains("StoreOnly")
+        ));
+
+        // Store, simulate, start the flow
+        app.transport().push_ok(&[]);
+        app.sign_transaction_with_mode(params.clone(), TransactionMode::StoreOnly)
+            .await
+            .unwrap();
+        app.transport().push_ok(&[]);
+        app.provide_tx_simulation(&simulation).await.unwrap();
+        let mut signature = vec![0x1b];
+        signature.extend_from_slice(&[0x11; 64]);
+        signature.extend_from_slice(&[0x90, 0x00]);
+        app.transport().push_answer(signature);
+        app.sign_transaction_with_mode(params, TransactionMode::StartFlow)
+            .await
+            .unwrap();
+
+        let ins_sent: Vec<u8> = app.transport().sent().iter().map(|c| c.ins).collect();
+        assert_eq!(
+            ins_sent,
+            vec![
+                ins::GET_APP_CONFIGURATION,
+                ins::SIGN_ETH_TRANSACTION,
+                ins::PROVIDE_TX_SIMULATION,
+                ins::SIGN_ETH_TRANSACTION
+            ]
+        );
+
+        // The flow consumed the stored transaction
+        let err = app.provide_tx_simulation(&simulation).await.unwrap_err();
+        assert!(matches!(err, EthAppError::InvalidTransaction(_)));
+        assert_eq!(app.transport().remaining(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_nft_information_version_gate() {
+        let info = NftCollectionInfo::new("Collection".to_string(), [0x11; 20], 1)
+            .with_signature(vec![0x30, 0x00]);
+
+        let app = EthereumApp::new(mock_device());
+        app.provide_nft_information(&info).await.unwrap();
+        assert_eq!(
+            app.transport()
+                .count(EthApp::CLA, ins::PROVIDE_NFT_INFORMATION),
+            1
+        );
+
+        let device = MockExchange::new();
+        device.push_ok(
+            &AppConfiguration {
+                flags: ConfigFlags::from_byte(0),
+                version: AppVersion::new(1, 9, 17),
+            }
+            .to_response_bytes(),
+        );
+        let app = EthereumApp::new(device);
+        let err = app.provide_nft_information(&info).await.unwrap_err();
+        assert!(matches!(err, EthAppError::UnsupportedVersion(_)));
+        assert_eq!(app.transport().sent().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_safe_account_version_gate() {
+        let info = SafeAccountInfo::new([0x5A; 20], 1, vec![[0x01; 20]])
+            .with_signature(vec![0x30, 0x00])
+            .with_signers_signature(vec![0x30, 0x00]);
+
+        // Mock device runs 1.12.0
+        let app = EthereumApp::new(mock_device());
+        let err = app.provide_safe_account(&info).await.unwrap_err();
+        assert!(matches!(err, EthAppError::UnsupportedVersion(_)));
+        assert_eq!(
+            app.transport()
+                .count(EthApp::CLA, ins::PROVIDE_SAFE_ACCOUNT),
+            0
+        );
+    }
+
+    #[tokio::test]
+    async fn test_authorization_version_gate() {
+        let authorization = Eip7702Authorization::new(1, [0x11; 20], 0);
+
+        // Mock device runs 1.12.0
+        let app = EthereumApp::new(mock_device());
+        let err = app
+            .sign_authorization(&path(), &authorization, PathPolicy::default())
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EthAppError::UnsupportedVersion(_)));
+        assert_eq!(
+            app.transport()
+                .count(EthApp::CLA, ins::SIGN_EIP7702_AUTHORIZATION),
+            0
+        );
+
+        let device = MockExchange::new();
+        device
+            .push_ok(
+                &AppConfiguration {
+                    flags: ConfigFlags::from_byte(0),
+                    version: AppVersion::new(1, 15, 0),
+                }
+                .to_response_bytes(),
+            )
+            .push_ok(&[0x01; 65]);
+        let app = EthereumApp::new(device);
+        let signature = app
+            .sign_authorization(&path(), &authorization, PathPolicy::default())
+            .await
+            .unwrap();
+        assert_eq!(signature.y_parity, 0x01);
+    }
+
+    #[tokio::test]
+    async fn test_bolos_version_uses_generic_command() {
+        let device = MockExchange::new();
+        // mode, major, minor, patch
+        device.push_ok(&[0x00, 2, 2, 3]);
+        let app = EthereumApp::new(device);
+
+        let version = app.bolos_version().await.unwrap();
+        assert_eq!((version.major, version.minor, version.patch), (2, 2, 3));
+
+        let sent = app.transport().sent();
+        assert_eq!((sent[0].cla, sent[0].ins), (EthApp::CLA, 0x00));
     }
 }
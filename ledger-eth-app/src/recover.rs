@@ -0,0 +1,191 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Signer address recovery
+//!
+//! Available with the `recovery` feature. Lets callers check locally that the
+//! device signed with the key of the address returned by GET ETH PUBLIC ADDRESS.
+
+use k256::ecdsa::{RecoveryId, Signature as EcdsaSignature, VerifyingKey};
+use sha3::{Digest, Keccak256};
+
+pub use crate::utils::keccak256;
+
+use crate::types::{EthAddress, SignMessageParams, Signature};
+
+/// EIP-191 personal message hash:
+/// `keccak256("\x19Ethereum Signed Message:\n" || len(message) || message)`
+pub fn personal_message_hash(message: &[u8]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(format!("\x19Ethereum Signed Message:\n{}", message.len()));
+    hasher.update(message);
+    hasher.finalize().into()
+}
+
+/// EIP-712 signing hash: `keccak256("\x19\x01" || domain_hash || message_hash)`
+pub fn eip712_hash(domain_hash: &[u8; 32], message_hash: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update([0x19, 0x01]);
+    hasher.update(domain_hash);
+    hasher.update(message_hash);
+    hasher.finalize().into()
+}
+
+/// EIP-55 checksummed address of a public key
+pub fn public_key_to_address(key: &VerifyingKey) -> EthAddress {
+    let point = key.to_encoded_point(false);
+    // Skip the 0x04 uncompressed point tag
+    let hash = keccak256(&point.as_bytes()[1..]);
+    checksum_address(&hash[12..])
+}
+
+/// EIP-55 checksummed form of a 20-byte address
+fn checksum_address(address: &[u8]) -> EthAddress {
+    let lower = EthAddress {
+        address: format!("0x{}", hex::encode(address)),
+    };
+    EthAddress {
+        address: lower.to_checksummed(),
+    }
+}
+
+impl Signature {
+    /// Recover the address that signed `message` with SIGN ETH PERSONAL MESSAGE
+    pub fn recover_personal(&self, message: &[u8]) -> Result<EthAddress, String> {
+        self.recover_prehash(&personal_message_hash(message))
+    }
+
+    /// Recover the address that signed an EIP-712 message from its domain and
+    /// message hashes, as sent in the v0 flow
+    pub fn recover_eip712(
+        &self,
+        domain_hash: &[u8; 32],
+        message_hash: &[u8; 32],
+    ) -> Result<EthAddress, String> {
+        self.recover_prehash(&eip712_hash(domain_hash, message_hash))
+    }
+
+    /// Recover the address that signed a 32-byte hash
+    ///
+    /// `v` must be 0/1 or 27/28; EIP-155 transaction `v` values have to be
+    /// normalized by the caller first.
+    pub fn recover_prehash(&self, hash: &[u8; 32]) -> Result<EthAddress, String> {
+        let parity = match self.v {
+            0 | 1 => self.v,
+            27 | 28 => self.v - 27,
+            v => return Err(format!("Unsupported recovery value v = {}", v)),
+        };
+        let recovery_id =
+            RecoveryId::from_byte(parity).ok_or_else(|| "Invalid recovery id".to_string())?;
+
+        let signature = EcdsaSignature::from_scalars(
+            <[u8; 32]>::try_from(self.r.as_slice()).map_err(|_| "Invalid r length")?,
+            <[u8; 32]>::try_from(self.s.as_slice()).map_err(|_| "Invalid s length")?,
+        )
+        .map_err(|e| format!("Invalid signature: {}", e))?;
+
+        let key = VerifyingKey::recover_from_prehash(hash, &signature, recovery_id)
+            .map_err(|e| format!("Recovery failed: {}", e))?;
+        Ok(public_key_to_address(&key))
+    }
+}
+
+impl SignMessageParams {
+    /// EIP-191 hash the device signs for this message, see [`personal_message_hash`]
+    ///
+    /// Pass it to [`Signature::recover_prehash`] to check the returned signature.
+    pub fn eip191_hash(&self) -> [u8; 32] {
+        personal_message_hash(&self.message)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn signature(v: u8, r: &str, s: &str) -> Signature {
+        Signature::new(v, hex::decode(r).unwrap(), hex::decode(s).unwrap()).unwrap()
+    }
+
+    fn hash(hex_str: &str) -> [u8; 32] {
+        hex::decode(hex_str).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_recover_personal_known_vector() {
+        // web3.js `accounts.sign("Some data", 0x4c0883a6...)`
+        assert_eq!(
+            personal_message_hash(b"Some data"),
+            hash("1da44b586eb0729ff70a73c326926f6ed5a25f5b056e7f47fbc6e58d86871655")
+        );
+
+        let signature = signature(
+            0x1c,
+            "b91467e570a6466aa9e9876cbcd013baba02900b8979d43fe208a4a4f339f5fd",
+            "6007e74cd82e037b800186422fc2da167c747ef045e5d18a5f5d4300f8e1a029",
+        );
+        assert_eq!(
+            signature.recover_personal(b"Some data").unwrap().address,
+            "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23"
+        );
+        assert_ne!(
+            signature.recover_personal(b"Other data").unwrap().address,
+            "0x2c7536E3605D9C16a7a3D7b1898e529396a65c23"
+        );
+    }
+
+    #[test]
+    fn test_recover_eip712_known_vector() {
+        // Mail example from the EIP-712 specification, signed by keccak256("cow")
+        let domain_hash = hash("f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f");
+        let message_hash = hash("c52c0ee5d84264471806290a3f2c4cecfc5490626bf912d01f240d7a274b371e");
+        let signature = signature(
+            28,
+            "4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d",
+            "07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562",
+        );
+
+        assert_eq!(
+            signature
+                .recover_eip712(&domain_hash, &message_hash)
+                .unwrap()
+                .address,
+            "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826"
+        );
+    }
+
+    #[test]
+    fn test_recover_rejects_eip155_v() {
+        let signature = Signature::new(37, vec![0x11; 32], vec![0x22; 32]).unwrap();
+        assert!(signature.recover_prehash(&[0; 32]).is_err());
+    }
+
+    #[test]
+    fn test_personal_message_hash_vectors() {
+        // Same values as ethers-rs `hash_message`
+        let path = crate::types::BipPath::ethereum_standard(0, 0);
+        let cases = [
+            (
+                "Hello World",
+                "a1de988600a42c4b4ab089b619297c17d53cffae5d5120d82d8a92d0bb3b78f2",
+            ),
+            (
+                "héllo wörld",
+                "c0e106c473d00fbb6310fe337d41489c46889c4cc6ca39b76702ce7d90d62983",
+            ),
+            (
+                "こんにちは",
+                "dc0823221878132a64a0cbbe349ba3474bb7bef0c388a83c036a83805f28e3a5",
+            ),
+        ];
+        for (text, expected) in cases {
+            let params = SignMessageParams::from_text(path.clone(), text);
+            assert_eq!(params.eip191_hash(), hash(expected), "{}", text);
+        }
+
+        let params = SignMessageParams::from_hex(path, "0xdeadbeef").unwrap();
+        assert_eq!(
+            params.eip191_hash(),
+            hash("d1c7f1a06a4f9a535077e50ad23244ce2c6ae443fcd412965226f3df5d28eaaa")
+        );
+    }
+}
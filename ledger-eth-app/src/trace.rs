@@ -0,0 +1,108 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! APDU tracing
+//!
+//! Commands exchange their APDUs through [`exchange`], which logs each command
+//! header with the answered status word at debug level when the `apdu-trace`
+//! feature is enabled, and compiles down to a plain exchange otherwise.
+
+use std::ops::Deref;
+
+use ledger_sdk_transport::{APDUAnswer, APDUCommand, Exchange};
+
+/// Exchange `command`, then trace it with the answered status word
+pub(crate) async fn exchange<E, I>(
+    transport: &E,
+    command: &APDUCommand<I>,
+) -> Result<APDUAnswer<E::AnswerType>, E::Error>
+where
+    E: Exchange + Send + Sync,
+    I: Deref<Target = [u8]> + Send + Sync,
+{
+    let response = transport.exchange(command).await?;
+    trace_apdu(command, response.retcode());
+    Ok(response)
+}
+
+/// Log an exchanged command and its decoded status word at debug level
+#[cfg(feature = "apdu-trace")]
+fn trace_apdu<I: Deref<Target = [u8]>>(command: &APDUCommand<I>, sw: u16) {
+    tracing::debug!(
+        cla = format_args!("0x{:02X}", command.cla),
+        ins = format_args!("0x{:02X}", command.ins),
+        p1 = format_args!("0x{:02X}", command.p1),
+        p2 = format_args!("0x{:02X}", command.p2),
+        sw = format_args!("0x{:04X}", sw),
+        status = crate::errors::describe_eth_status(sw),
+        "ETH APDU"
+    );
+}
+
+/// No-op without the `apdu-trace` feature
+#[cfg(not(feature = "apdu-trace"))]
+fn trace_apdu<I: Deref<Target = [u8]>>(_command: &APDUCommand<I>, _sw: u16) {}
+
+#[cfg(all(test, feature = "apdu-trace"))]
+mod tests {
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    use ledger_sdk_transport::mock::MockExchange;
+
+    use crate::types::{BipPath, GetAddressParams, SignTransactionParams};
+    use crate::{EthApp, GetAddress, GetConfiguration, SignTransaction};
+
+    /// Log output shared with the test
+    #[derive(Clone, Default)]
+    struct Captured(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Captured {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_every_command_is_traced() {
+        let captured = Captured::default();
+        let writer = captured.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(tracing::Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(move || writer.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let path = BipPath::ethereum_standard(0, 0);
+        let device = MockExchange::new();
+        device.push_ok(&[0x00, 1, 12, 0]).push_status(0x6985);
+        EthApp::get_configuration(&device).await.unwrap();
+        EthApp::sign_transaction(
+            &device,
+            SignTransactionParams::new(path.clone(), vec![0xE5]),
+        )
+        .await
+        .unwrap_err();
+        device.push_status(0x6A80);
+        EthApp::get_address(&device, GetAddressParams::new(path))
+            .await
+            .unwrap_err();
+
+        let logs = String::from_utf8(captured.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = logs.lines().collect();
+        assert_eq!(lines.len(), 3, "{}", logs);
+        assert!(lines[0].contains("ins=0x06") && lines[0].contains("sw=0x9000"));
+        assert!(lines[1].contains("ins=0x04") && lines[1].contains("sw=0x6985"));
+        assert!(lines[2].contains("ins=0x02") && lines[2].contains("sw=0x6A80"));
+        assert!(
+            lines[2].contains(r#"status="Invalid data""#),
+            "{}",
+            lines[2]
+        );
+    }
+}
@@ -0,0 +1,302 @@
+// SPDX-License-Identifier: Apache-2.0
+
+//! Unsigned transaction builders
+//!
+//! This module encodes legacy (EIP-155), EIP-2930 and EIP-1559 transactions into the
+//! exact payload the Ethereum app expects for SIGN ETH TRANSACTION, so callers do not
+//! need their own RLP encoder or have to remember the typed-transaction prefix.
+
+/// Contract address and storage slots accessed by a transaction (EIP-2930)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccessListItem {
+    /// Accessed contract address
+    pub address: [u8; 20],
+    /// Accessed storage keys
+    pub storage_keys: Vec<[u8; 32]>,
+}
+
+/// Transaction access list (EIP-2930)
+pub type AccessList = Vec<AccessListItem>;
+
+/// Transaction that can be serialized for signing on the device
+pub trait UnsignedTransaction {
+    /// Bytes sent to the device: the optional type prefix followed by the RLP payload
+    fn to_sign_payload(&self) -> Vec<u8>;
+}
+
+/// Legacy transaction, signed with EIP-155 replay protection
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LegacyTransaction {
+    /// Sender nonce
+    pub nonce: u64,
+    /// Gas price in wei
+    pub gas_price: u128,
+    /// Gas limit
+    pub gas_limit: u64,
+    /// Recipient, `None` for contract creation
+    pub to: Option<[u8; 20]>,
+    /// Value in wei
+    pub value: u128,
+    /// Call data
+    pub data: Vec<u8>,
+    /// Chain ID
+    pub chain_id: u64,
+}
+
+/// EIP-2930 transaction (type 0x01)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Eip2930Transaction {
+    /// Chain ID
+    pub chain_id: u64,
+    /// Sender nonce
+    pub nonce: u64,
+    /// Gas price in wei
+    pub gas_price: u128,
+    /// Gas limit
+    pub gas_limit: u64,
+    /// Recipient, `None` for contract creation
+    pub to: Option<[u8; 20]>,
+    /// Value in wei
+    pub value: u128,
+    /// Call data
+    pub data: Vec<u8>,
+    /// Access list
+    pub access_list: AccessList,
+}
+
+/// EIP-1559 transaction (type 0x02)
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Eip1559Transaction {
+    /// Chain ID
+    pub chain_id: u64,
+    /// Sender nonce
+    pub nonce: u64,
+    /// Maximum priority fee per gas in wei
+    pub max_priority_fee_per_gas: u128,
+    /// Maximum fee per gas in wei
+    pub max_fee_per_gas: u128,
+    /// Gas limit
+    pub gas_limit: u64,
+    /// Recipient, `None` for contract creation
+    pub to: Option<[u8; 20]>,
+    /// Value in wei
+    pub value: u128,
+    /// Call data
+    pub data: Vec<u8>,
+    /// Access list
+    pub access_list: AccessList,
+}
+
+/// EIP-2930 transaction type prefix
+pub const EIP2930_TX_TYPE: u8 = 0x01;
+/// EIP-1559 transaction type prefix
+pub const EIP1559_TX_TYPE: u8 = 0x02;
+
+impl UnsignedTransaction for LegacyTransaction {
+    fn to_sign_payload(&self) -> Vec<u8> {
+        // EIP-155: chain ID followed by empty r and s
+        rlp_list(&[
+            rlp_uint(self.nonce.into()),
+            rlp_uint(self.gas_price),
+            rlp_uint(self.gas_limit.into()),
+            rlp_address(&self.to),
+            rlp_uint(self.value),
+            rlp_bytes(&self.data),
+            rlp_uint(self.chain_id.into()),
+            rlp_uint(0),
+            rlp_uint(0),
+        ])
+    }
+}
+
+impl UnsignedTransaction for Eip2930Transaction {
+    fn to_sign_payload(&self) -> Vec<u8> {
+        let mut payload = vec![EIP2930_TX_TYPE];
+        payload.extend(rlp_list(&[
+            rlp_uint(self.chain_id.into()),
+            rlp_uint(self.nonce.into()),
+            rlp_uint(self.gas_price),
+            rlp_uint(self.gas_limit.into()),
+            rlp_address(&self.to),
+            rlp_uint(self.value),
+            rlp_bytes(&self.data),
+            rlp_access_list(&self.access_list),
+        ]));
+        payload
+    }
+}
+
+impl UnsignedTransaction for Eip1559Transaction {
+    fn to_sign_payload(&self) -> Vec<u8> {
+        let mut payload = vec![EIP1559_TX_TYPE];
+        payload.extend(rlp_list(&[
+            rlp_uint(self.chain_id.into()),
+            rlp_uint(self.nonce.into()),
+            rlp_uint(self.max_priority_fee_per_gas),
+            rlp_uint(self.max_fee_per_gas),
+            rlp_uint(self.gas_limit.into()),
+            rlp_address(&self.to),
+            rlp_uint(self.value),
+            rlp_bytes(&self.data),
+            rlp_access_list(&self.access_list),
+        ]));
+        payload
+    }
+}
+
+/// RLP length header for a string (`offset` 0x80) or a list (`offset` 0xC0)
+fn rlp_header(len: usize, offset: u8) -> Vec<u8> {
+    if len < 56 {
+        return vec![offset + len as u8];
+    }
+    let len_bytes = len.to_be_bytes();
+    let skip = len_bytes.iter().take_while(|&&b| b == 0).count();
+    let mut header = vec![offset + 55 + (len_bytes.len() - skip) as u8];
+    header.extend_from_slice(&len_bytes[skip..]);
+    header
+}
+
+fn rlp_bytes(bytes: &[u8]) -> Vec<u8> {
+    if bytes.len() == 1 && bytes[0] < 0x80 {
+        return bytes.to_vec();
+    }
+    let mut encoded = rlp_header(bytes.len(), 0x80);
+    encoded.extend_from_slice(bytes);
+    encoded
+}
+
+/// Integers are encoded big-endian without leading zeros (zero is the empty string)
+fn rlp_uint(value: u128) -> Vec<u8> {
+    let bytes = value.to_be_bytes();
+    let skip = bytes.iter().take_while(|&&b| b == 0).count();
+    rlp_bytes(&bytes[skip..])
+}
+
+fn rlp_address(address: &Option<[u8; 20]>) -> Vec<u8> {
+    match address {
+        Some(address) => rlp_bytes(address),
+        None => rlp_bytes(&[]),
+    }
+}
+
+fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let body_len = items.iter().map(Vec::len).sum();
+    let mut encoded = rlp_header(body_len, 0xC0);
+    for item in items {
+        encoded.extend_from_slice(item);
+    }
+    encoded
+}
+
+fn rlp_access_list(access_list: &[AccessListItem]) -> Vec<u8> {
+    let items: Vec<Vec<u8>> = access_list
+        .iter()
+        .map(|item| {
+            let keys: Vec<Vec<u8>> = item.storage_keys.iter().map(|k| rlp_bytes(k)).collect();
+            rlp_list(&[rlp_bytes(&item.address), rlp_list(&keys)])
+        })
+        .collect();
+    rlp_list(&items)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn address(hex_str: &str) -> [u8; 20] {
+        hex::decode(hex_str).unwrap().try_into().unwrap()
+    }
+
+    #[test]
+    fn test_legacy_eip155_vector() {
+        // EIP-155 example transaction
+        let tx = LegacyTransaction {
+            nonce: 9,
+            gas_price: 20_000_000_000,
+            gas_limit: 21_000,
+            to: Some([0x35; 20]),
+            value: 1_000_000_000_000_000_000,
+            data: Vec::new(),
+            chain_id: 1,
+        };
+        assert_eq!(
+            hex::encode(tx.to_sign_payload()),
+            "ec098504a817c800825208943535353535353535353535353535353535353535880de0b6b3a764000080018080"
+        );
+    }
+
+    #[test]
+    fn test_eip2930_vector() {
+        let data = hex::decode("200200001525000000000b69ffb300000000557b933a7c2c45672b610f8954a3deb39a51a8cae53ec727dbdeb9e2d5456c3be40cff031ab40a55724d5c9c618a2152e99a45649a3b8cf198321f46720b722f4ec38f99ba3bb1303258d2e816e6a95b25647e01bd0967c1b9599fa3521939871d1d0888").unwrap();
+        let tx = Eip2930Transaction {
+            chain_id: 1,
+            nonce: 9215,
+            gas_price: 43_000_000_000,
+            gas_limit: 1_000_000,
+            to: Some(address("0000000000a8fb09af944ab3baf7a9b3e1ab29d8")),
+            value: 0,
+            data: data.clone(),
+            access_list: [
+                "724d5c9c618a2152e99a45649a3b8cf198321f46",
+                "720b722f4ec38f99ba3bb1303258d2e816e6a95b",
+                "25647e01bd0967c1b9599fa3521939871d1d0888",
+            ]
+            .iter()
+            .map(|a| AccessListItem {
+                address: address(a),
+                storage_keys: Vec::new(),
+            })
+            .collect(),
+        };
+
+        let expected = format!(
+            "01f8e3018223ff850a02ffee00830f4240940000000000a8fb09af944ab3baf7a9b3e1ab29d880b876{}f845d694724d5c9c618a2152e99a45649a3b8cf198321f46c0d694720b722f4ec38f99ba3bb1303258d2e816e6a95bc0d69425647e01bd0967c1b9599fa3521939871d1d0888c0",
+            hex::encode(&data)
+        );
+        assert_eq!(hex::encode(tx.to_sign_payload()), expected);
+    }
+
+    #[test]
+    fn test_eip1559_vector() {
+        // WETH deposit
+        let tx = Eip1559Transaction {
+            chain_id: 1,
+            nonce: 479,
+            max_priority_fee_per_gas: 82_760_620_419,
+            max_fee_per_gas: 82_760_620_419,
+            gas_limit: 27_938,
+            to: Some(address("c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2")),
+            value: 3_116_727_153_828_861_708,
+            data: hex::decode("d0e30db0").unwrap(),
+            access_list: Vec::new(),
+        };
+        assert_eq!(
+            hex::encode(tx.to_sign_payload()),
+            "02f7018201df851344ead983851344ead983826d2294c02aaa39b223fe8d0a0e5c4f27ead9083c756cc2882b40d6d551c8970c84d0e30db0c0"
+        );
+    }
+
+    #[test]
+    fn test_contract_creation_and_storage_keys() {
+        let tx = Eip1559Transaction {
+            chain_id: 1,
+            data: vec![0x60],
+            access_list: vec![AccessListItem {
+                address: [0x11; 20],
+                storage_keys: vec![[0x22; 32]],
+            }],
+            ..Default::default()
+        };
+        let payload = tx.to_sign_payload();
+
+        // Zero fields and empty `to` are empty strings, single-byte data encodes as itself
+        assert_eq!(
+            &payload[..11],
+            &[0x02, 0xF8, 0x42, 0x01, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x60]
+        );
+        // [[address, [key]]]
+        assert_eq!(&payload[11..15], &[0xF8, 0x38, 0xF7, 0x94]);
+        assert_eq!(&payload[35..37], &[0xE1, 0xA0]);
+        assert_eq!(payload.len(), 69);
+    }
+}
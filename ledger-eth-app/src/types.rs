@@ -2,7 +2,9 @@
 
 //! Core data types for Ethereum application
 
-use serde::{Deserialize, Serialize};
+use ledger_sdk_device_base::{AppInfo, DeviceInfo, DeviceModel};
+use num_bigint::BigUint;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use std::collections::HashMap;
 use std::fmt;
 
@@ -81,6 +83,27 @@ impl BipPath {
         }
     }
 
+    /// Create a BIP44 path for a known chain alias: m/44'/coin_type'/account'/0/address_index
+    ///
+    /// Aliases (e.g. "eth", "polygon", "bsc") are resolved with [`crate::chains::chain_by_alias`].
+    pub fn from_alias(chain: &str, account: u32, address_index: u32) -> Result<Self, String> {
+        let info = crate::chains::chain_by_alias(chain)
+            .ok_or_else(|| format!("Unknown chain alias: {}", chain))?;
+        if account >= 0x80000000 {
+            return Err(format!("Account index too large: {}", account));
+        }
+
+        Ok(BipPath {
+            indices: vec![
+                0x8000002C,
+                0x80000000 | info.coin_type,
+                0x80000000 | account,
+                0,
+                address_index,
+            ],
+        })
+    }
+
     /// Get the encoded length for APDU
     pub fn encoded_len(&self) -> usize {
         1 + self.indices.len() * crate::instructions::length::BIP32_INDEX_SIZE
@@ -117,9 +140,55 @@ impl EthAddress {
         if address.len() != 42 {
             return Err("Ethereum address must be 42 characters long".to_string());
         }
+        if let Some(c) = address[2..].chars().find(|c| !c.is_ascii_hexdigit()) {
+            return Err(format!("Invalid character '{}' in Ethereum address", c));
+        }
         Ok(EthAddress { address })
     }
 
+    /// Create a new Ethereum address whose casing must match its EIP-55 checksum
+    ///
+    /// [`EthAddress::new`] accepts any casing; use this for user input, where
+    /// a typo would otherwise go unnoticed.
+    pub fn new_checksummed(address: String) -> Result<Self, String> {
+        let address = Self::new(address)?;
+        if !address.is_valid_checksum() {
+            return Err(format!(
+                "Invalid EIP-55 checksum for {}, expected {}",
+                address.address,
+                address.to_checksummed()
+            ));
+        }
+        Ok(address)
+    }
+
+    /// Get the EIP-55 mixed-case form of the address
+    pub fn to_checksummed(&self) -> String {
+        let lower = self.without_prefix().to_ascii_lowercase();
+        let hash = crate::utils::keccak256(lower.as_bytes());
+
+        let checksummed: String = lower
+            .chars()
+            .enumerate()
+            .map(|(i, c)| {
+                // Uppercase letters whose nibble in the hash of the lowercase form is >= 8
+                let nibble = (hash[i / 2] >> (if i % 2 == 0 { 4 } else { 0 })) & 0x0F;
+                if nibble >= 8 {
+                    c.to_ascii_uppercase()
+                } else {
+                    c
+                }
+            })
+            .collect();
+
+        format!("0x{}", checksummed)
+    }
+
+    /// Whether the casing of the address matches its EIP-55 checksum
+    pub fn is_valid_checksum(&self) -> bool {
+        self.without_prefix() == &self.to_checksummed()[2..]
+    }
+
     /// Get the address without 0x prefix
     pub fn without_prefix(&self) -> &str {
         &self.address[2..]
@@ -148,6 +217,37 @@ pub struct PublicKeyInfo {
     pub chain_code: Option<Vec<u8>>,
 }
 
+/// Public key and chain code of a derivation node (BIP32 extended public key)
+///
+/// Enough to derive the node's non-hardened children off-device, see
+/// `ExtendedPublicKey::derive_child` with the `derivation` feature.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ExtendedPublicKey {
+    /// Path of the node
+    pub path: BipPath,
+    /// Uncompressed public key (65 bytes)
+    pub public_key: Vec<u8>,
+    /// Chain code (32 bytes)
+    pub chain_code: [u8; 32],
+}
+
+impl ExtendedPublicKey {
+    /// Build from a GET ETH PUBLIC ADDRESS answer requested with a chain code
+    pub fn from_public_key_info(path: BipPath, info: &PublicKeyInfo) -> Result<Self, String> {
+        let chain_code = info
+            .chain_code
+            .as_deref()
+            .ok_or("No chain code in the public key information")?;
+        Ok(ExtendedPublicKey {
+            path,
+            public_key: info.public_key.clone(),
+            chain_code: chain_code
+                .try_into()
+                .map_err(|_| format!("Invalid chain code length: {}", chain_code.len()))?,
+        })
+    }
+}
+
 /// Signature result from signing operations
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct Signature {
@@ -171,7 +271,8 @@ impl Signature {
         Ok(Signature { v, r, s })
     }
 
-    /// Get the signature in DER format
+    /// Get the signature as `v || r || s`
+    #[deprecated(note = "not DER encoded, emits v || r || s; use `to_rsv` instead")]
     pub fn to_der(&self) -> Vec<u8> {
         let mut result = Vec::new();
         result.push(self.v);
@@ -179,6 +280,52 @@ impl Signature {
         result.extend_from_slice(&self.s);
         result
     }
+
+    /// Serialize as the 65-byte `r || s || v` form expected by Ethereum tooling
+    pub fn to_rsv(&self) -> [u8; 65] {
+        let mut result = [0u8; 65];
+        result[..32].copy_from_slice(&self.r);
+        result[32..64].copy_from_slice(&self.s);
+        result[64] = self.v;
+        result
+    }
+
+    /// Parse a 65-byte `r || s || v` signature
+    pub fn from_rsv(bytes: &[u8; 65]) -> Result<Self, String> {
+        Signature::new(bytes[64], bytes[..32].to_vec(), bytes[32..64].to_vec())
+    }
+
+    /// `0x`-prefixed hex of the `r || s || v` form, as consumed by ethers/web3
+    pub fn to_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.to_rsv()))
+    }
+
+    /// Return the signature with `v` as 27 or 28
+    ///
+    /// Accepts a bare recovery id (0/1), an already normalized 27/28, or, when
+    /// `chain_id` is given, an EIP-155 `v` (`chain_id * 2 + 35 + parity`) as
+    /// returned by the device for transactions, i.e. truncated to its low byte.
+    /// Message and EIP-712 signatures are not EIP-155 encoded: pass `None`.
+    pub fn normalize_v(&self, chain_id: Option<u64>) -> Result<Self, String> {
+        let parity = match (self.v, chain_id) {
+            (0 | 1, _) => self.v,
+            (27 | 28, _) => self.v - 27,
+            (v, Some(chain_id)) => {
+                let base = (chain_id.wrapping_mul(2).wrapping_add(35) & 0xFF) as u8;
+                match v.wrapping_sub(base) {
+                    parity @ (0 | 1) => parity,
+                    _ => return Err(format!("Invalid v {} for chain ID {}", self.v, chain_id)),
+                }
+            }
+            (v, None) => return Err(format!("Invalid v {} (expected 0, 1, 27 or 28)", v)),
+        };
+
+        Ok(Signature {
+            v: 27 + parity,
+            r: self.r.clone(),
+            s: self.s.clone(),
+        })
+    }
 }
 
 /// Application configuration information
@@ -190,6 +337,20 @@ pub struct AppConfiguration {
     pub version: AppVersion,
 }
 
+impl AppConfiguration {
+    /// Encode as a GET APP CONFIGURATION response payload: flags || major || minor || patch
+    ///
+    /// Inverse of the response parser; mostly useful to script mock devices.
+    pub fn to_response_bytes(&self) -> Vec<u8> {
+        vec![
+            self.flags.to_byte(),
+            self.version.major,
+            self.version.minor,
+            self.version.patch,
+        ]
+    }
+}
+
 /// Configuration flags for the Ethereum application
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
 pub struct ConfigFlags {
@@ -279,6 +440,40 @@ impl AppVersion {
             || (self.major == 1 && self.minor == 9 && self.patch >= 19)
     }
 
+    /// Check if this version supports PROVIDE NETWORK INFORMATION (>= 1.14.0)
+    pub fn supports_network_information(&self) -> bool {
+        self.is_at_least(&AppVersion::new(1, 14, 0))
+    }
+
+    /// Check if this version supports PROVIDE NFT INFORMATION (>= 1.9.18)
+    pub fn supports_nft_information(&self) -> bool {
+        self.is_at_least(&AppVersion::new(1, 9, 18))
+    }
+
+    /// Check if this version supports PROVIDE SAFE ACCOUNT (>= 1.16.0)
+    pub fn supports_safe_account(&self) -> bool {
+        self.is_at_least(&AppVersion::new(1, 16, 0))
+    }
+
+    /// Check if this version supports SIGN EIP-7702 AUTHORIZATION (>= 1.15.0)
+    pub fn supports_eip7702(&self) -> bool {
+        self.is_at_least(&AppVersion::new(1, 15, 0))
+    }
+
+    /// Check if this version accepts 2-byte EIP-712 array sizes (>= 1.17.0)
+    pub fn supports_eip712_wide_arrays(&self) -> bool {
+        self.is_at_least(&AppVersion::new(1, 17, 0))
+    }
+
+    /// Largest EIP-712 array this version accepts
+    pub fn max_eip712_array_size(&self) -> usize {
+        if self.supports_eip712_wide_arrays() {
+            crate::instructions::length::MAX_EIP712_ARRAY_SIZE_U16
+        } else {
+            crate::instructions::length::MAX_EIP712_ARRAY_SIZE_U8
+        }
+    }
+
     /// Compare with another version
     pub fn compare(&self, other: &AppVersion) -> std::cmp::Ordering {
         use std::cmp::Ordering;
@@ -301,6 +496,165 @@ impl AppVersion {
     }
 }
 
+/// What the connected device and app support, from
+/// [`crate::EthereumApp::check_compatibility`]
+///
+/// The Nano S build of the Ethereum app leaves out full EIP-712 whatever its
+/// version, so the EIP-712 features are `None` (unknown) when the app version
+/// supports them but the model is unknown. Apps don't answer GET DEVICE INFO, so
+/// with the Ethereum app open the model usually has to come from the transport,
+/// see [`CompatibilityReport::with_model`].
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompatibilityReport {
+    /// Device info, `None` when the open app does not answer GET DEVICE INFO
+    pub device_info: Option<DeviceInfo>,
+    /// Device model, from the device info or [`CompatibilityReport::with_model`]
+    pub model: Option<DeviceModel>,
+    /// Open app, `BOLOS` on the dashboard
+    pub app_info: AppInfo,
+    /// Ethereum app configuration, `None` when another app is open
+    pub configuration: Option<AppConfiguration>,
+    /// Full EIP-712 signing (struct definitions and implementations)
+    pub eip712_full: Option<bool>,
+    /// EIP-712 filtering, e.g. for clear signing with [`crate::FilterDescriptor`]
+    pub eip712_filtering: Option<bool>,
+    /// EIP-7702 authorization signing
+    pub eip7702: bool,
+}
+
+impl CompatibilityReport {
+    /// Work out the supported features from what the device answered
+    pub fn new(
+        device_info: Option<DeviceInfo>,
+        app_info: AppInfo,
+        configuration: Option<AppConfiguration>,
+    ) -> Self {
+        let model = device_info.as_ref().map(DeviceInfo::model);
+        let mut report = CompatibilityReport {
+            device_info,
+            model,
+            app_info,
+            configuration,
+            eip712_full: None,
+            eip712_filtering: None,
+            eip7702: false,
+        };
+        report.evaluate();
+        report
+    }
+
+    /// Set the model when the device did not report it, e.g. from the USB
+    /// product ID the transport opened
+    pub fn with_model(mut self, model: DeviceModel) -> Self {
+        self.model = Some(model);
+        self.evaluate();
+        self
+    }
+
+    /// Ethereum app version, `None` when another app is open
+    pub fn app_version(&self) -> Option<&AppVersion> {
+        self.configuration.as_ref().map(|config| &config.version)
+    }
+
+    fn evaluate(&mut self) {
+        let version = self.configuration.as_ref().map(|config| &config.version);
+        let eip712_full = if !version.is_some_and(AppVersion::supports_eip712_full) {
+            Some(false)
+        } else {
+            match self.model {
+                Some(DeviceModel::NanoS) => Some(false),
+                None | Some(DeviceModel::Unknown) => None,
+                Some(_) => Some(true),
+            }
+        };
+
+        self.eip712_full = eip712_full;
+        // Filters apply to full EIP-712 signing and arrived with it
+        self.eip712_filtering = eip712_full;
+        self.eip7702 = version.is_some_and(AppVersion::supports_eip7702);
+    }
+}
+
+/// Parameters for PERFORM PRIVACY OPERATION command
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrivacyOperationParams {
+    /// BIP32 derivation path
+    pub path: BipPath,
+    /// Whether to display the result on device and require confirmation
+    pub display: bool,
+    /// Counterparty X25519 public key; when set the shared secret is returned
+    /// instead of the public encryption key
+    pub counterparty_public_key: Option<[u8; 32]>,
+    /// How strictly the derivation path is checked before sending
+    pub path_policy: PathPolicy,
+}
+
+impl PrivacyOperationParams {
+    /// Create parameters returning the public encryption key for `path`
+    pub fn new(path: BipPath) -> Self {
+        PrivacyOperationParams {
+            path,
+            display: false,
+            counterparty_public_key: None,
+            path_policy: PathPolicy::default(),
+        }
+    }
+
+    /// Set the derivation path policy
+    pub fn with_path_policy(mut self, path_policy: PathPolicy) -> Self {
+        self.path_policy = path_policy;
+        self
+    }
+
+    /// Enable display and confirmation on device
+    pub fn with_display(mut self) -> Self {
+        self.display = true;
+        self
+    }
+
+    /// Derive the shared secret with the given counterparty public key
+    pub fn with_shared_secret(mut self, counterparty_public_key: [u8; 32]) -> Self {
+        self.counterparty_public_key = Some(counterparty_public_key);
+        self
+    }
+}
+
+/// Client-side checks applied to a derivation path before it is sent
+///
+/// Every policy enforces a depth between 1 and 10; the device applies its own
+/// restrictions on top. Commands default to [`PathPolicy::Strict`], except the
+/// ETH2 ones which default to [`PathPolicy::Eth2Validator`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PathPolicy {
+    /// Any path, but `m/44'/60'/...` paths must use a hardened account
+    #[default]
+    Strict,
+    /// Ethereum standard paths only: `m/44'/60'/account'/...`, covering Ledger
+    /// Live (`m/44'/60'/x'/0/0`), the legacy `m/44'/60'/x'/y` layout and MEW
+    /// style `m/44'/60'/0'`
+    EthereumOnly,
+    /// EIP-2334 validator keys: `m/12381/3600/i/0` (withdrawal) and
+    /// `m/12381/3600/i/0/0` (signing), unhardened
+    Eth2Validator,
+    /// Testnet paths using the generic coin type: `m/44'/1'/account'/...`
+    Testnet,
+    /// Depth checks only, e.g. for other coin types or unhardened accounts
+    Permissive,
+}
+
+impl fmt::Display for PathPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            PathPolicy::Strict => "strict",
+            PathPolicy::EthereumOnly => "Ethereum standard",
+            PathPolicy::Eth2Validator => "ETH2 validator",
+            PathPolicy::Testnet => "testnet",
+            PathPolicy::Permissive => "permissive",
+        };
+        f.write_str(name)
+    }
+}
+
 /// Parameters for GET ETH PUBLIC ADDRESS command
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct GetAddressParams {
@@ -312,6 +666,10 @@ pub struct GetAddressParams {
     pub return_chain_code: bool,
     /// Optional chain ID for validation
     pub chain_id: Option<u64>,
+    /// Reject responses with bytes left after the last expected field
+    pub strict: bool,
+    /// How strictly the derivation path is checked before sending
+    pub path_policy: PathPolicy,
 }
 
 impl GetAddressParams {
@@ -322,6 +680,8 @@ impl GetAddressParams {
             display: false,
             return_chain_code: false,
             chain_id: None,
+            strict: false,
+            path_policy: PathPolicy::default(),
         }
     }
 
@@ -342,6 +702,20 @@ impl GetAddressParams {
         self.chain_id = Some(chain_id);
         self
     }
+
+    /// Set the derivation path policy
+    pub fn with_path_policy(mut self, path_policy: PathPolicy) -> Self {
+        self.path_policy = path_policy;
+        self
+    }
+
+    /// Fail on unexpected trailing bytes in the response instead of ignoring them
+    ///
+    /// Useful to detect response format drift between app versions.
+    pub fn with_strict_parsing(mut self) -> Self {
+        self.strict = true;
+        self
+    }
 }
 
 /// Parameters for SIGN ETH TRANSACTION command
@@ -351,6 +725,13 @@ pub struct SignTransactionParams {
     pub path: BipPath,
     /// RLP-encoded transaction data
     pub transaction_data: Vec<u8>,
+    /// Chain ID of the transaction, kept as client-side metadata
+    ///
+    /// Never sent to the device: the app reads the chain ID from the RLP data,
+    /// which is also the one signed.
+    pub chain_id: Option<u64>,
+    /// How strictly the derivation path is checked before sending
+    pub path_policy: PathPolicy,
 }
 
 impl SignTransactionParams {
@@ -359,8 +740,22 @@ impl SignTransactionParams {
         SignTransactionParams {
             path,
             transaction_data,
+            chain_id: None,
+            path_policy: PathPolicy::default(),
         }
     }
+
+    /// Set the derivation path policy
+    pub fn with_path_policy(mut self, path_policy: PathPolicy) -> Self {
+        self.path_policy = path_policy;
+        self
+    }
+
+    /// Record the transaction's chain ID, see [`SignTransactionParams::chain_id`]
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
 }
 
 /// Parameters for SIGN ETH PERSONAL MESSAGE command
@@ -370,12 +765,168 @@ pub struct SignMessageParams {
     pub path: BipPath,
     /// Message data to sign
     pub message: Vec<u8>,
+    /// How strictly the derivation path is checked before sending
+    pub path_policy: PathPolicy,
 }
 
 impl SignMessageParams {
     /// Create new parameters for signing a personal message
     pub fn new(path: BipPath, message: Vec<u8>) -> Self {
-        SignMessageParams { path, message }
+        SignMessageParams {
+            path,
+            message,
+            path_policy: PathPolicy::default(),
+        }
+    }
+
+    /// Set the derivation path policy
+    pub fn with_path_policy(mut self, path_policy: PathPolicy) -> Self {
+        self.path_policy = path_policy;
+        self
+    }
+
+    /// Sign the UTF-8 bytes of `text`
+    pub fn from_text(path: BipPath, text: &str) -> Self {
+        Self::new(path, text.as_bytes().to_vec())
+    }
+
+    /// Sign the bytes of a hex string, with or without `0x` prefix
+    ///
+    /// The bytes are signed as they are: a 32-byte hash passed here is still
+    /// prefixed and hashed again, as EIP-191 requires.
+    pub fn from_hex(path: BipPath, hex_str: &str) -> Result<Self, String> {
+        let hex_str = hex_str.strip_prefix("0x").unwrap_or(hex_str);
+        let message = hex::decode(hex_str).map_err(|e| format!("Invalid hex: {}", e))?;
+        Ok(Self::new(path, message))
+    }
+}
+
+/// Parameters for SIGN ETH PERSONAL MESSAGE with the message read from a stream
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SignMessageStreamParams {
+    /// BIP32 derivation path
+    pub path: BipPath,
+    /// Message length, announced to the device; exactly this many bytes are read
+    pub len: u64,
+    /// How strictly the derivation path is checked before sending
+    pub path_policy: PathPolicy,
+}
+
+impl SignMessageStreamParams {
+    /// Create new parameters for signing a `len`-byte streamed message
+    pub fn new(path: BipPath, len: u64) -> Self {
+        SignMessageStreamParams {
+            path,
+            len,
+            path_policy: PathPolicy::default(),
+        }
+    }
+
+    /// Set the derivation path policy
+    pub fn with_path_policy(mut self, path_policy: PathPolicy) -> Self {
+        self.path_policy = path_policy;
+        self
+    }
+}
+
+/// EIP-7702 authorization tuple, without its signature
+///
+/// Delegates the signing account's code to `address` on `chain_id`, or on every
+/// chain when `chain_id` is 0.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Eip7702Authorization {
+    /// Chain ID the authorization is valid on
+    pub chain_id: u64,
+    /// Delegate contract address
+    pub address: [u8; 20],
+    /// Nonce of the signing account
+    pub nonce: u64,
+}
+
+impl Eip7702Authorization {
+    /// Create a new authorization
+    pub fn new(chain_id: u64, address: [u8; 20], nonce: u64) -> Self {
+        Eip7702Authorization {
+            chain_id,
+            address,
+            nonce,
+        }
+    }
+
+    /// Encode the authorization TLV sent to the device
+    pub fn to_tlv(&self) -> Vec<u8> {
+        use crate::instructions::eip7702_tag as tag;
+
+        let mut tlv = Vec::new();
+        for (tag, value) in [
+            (tag::STRUCT_VERSION, &[tag::VERSION][..]),
+            (tag::DELEGATE_ADDR, &self.address[..]),
+            (tag::CHAIN_ID, &self.chain_id.to_be_bytes()[..]),
+            (tag::NONCE, &self.nonce.to_be_bytes()[..]),
+        ] {
+            tlv.push(tag);
+            tlv.push(value.len() as u8);
+            tlv.extend_from_slice(value);
+        }
+        tlv
+    }
+}
+
+/// Signed EIP-7702 authorization
+///
+/// Unlike transaction signatures, the authorization signature carries the bare
+/// y parity (0 or 1) rather than a `v` value.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Eip7702Signature {
+    /// The signed authorization
+    pub authorization: Eip7702Authorization,
+    /// Parity of the y coordinate of the signature point (0 or 1)
+    pub y_parity: u8,
+    /// Signature component r (32 bytes)
+    pub r: Vec<u8>,
+    /// Signature component s (32 bytes)
+    pub s: Vec<u8>,
+}
+
+impl Eip7702Signature {
+    /// Parse the device answer to SIGN EIP 7702 AUTHORIZATION for `authorization`
+    ///
+    /// The answer is `v || r || s`; `v` is the y parity, also accepted in its
+    /// legacy 27/28 form.
+    pub fn from_response(authorization: Eip7702Authorization, data: &[u8]) -> Result<Self, String> {
+        use crate::instructions::length::{SIGNATURE_COMPONENT_SIZE, SIGNATURE_V_SIZE};
+
+        let expected = SIGNATURE_V_SIZE + 2 * SIGNATURE_COMPONENT_SIZE;
+        if data.len() != expected {
+            return Err(format!(
+                "Invalid EIP-7702 signature length: {} bytes (expected {})",
+                data.len(),
+                expected
+            ));
+        }
+
+        let y_parity = match data[0] {
+            parity @ (0 | 1) => parity,
+            v @ (27 | 28) => v - 27,
+            v => return Err(format!("Invalid EIP-7702 y parity: {}", v)),
+        };
+        let (r, s) = data[SIGNATURE_V_SIZE..].split_at(SIGNATURE_COMPONENT_SIZE);
+
+        Ok(Eip7702Signature {
+            authorization,
+            y_parity,
+            r: r.to_vec(),
+            s: s.to_vec(),
+        })
+    }
+
+    /// Signature as a [`Signature`] with `v` set to the y parity
+    pub fn to_signature(&self) -> Signature {
+        Signature {
+            v: self.y_parity,
+            r: self.r.clone(),
+            s: self.s.clone(),
+        }
     }
 }
 
@@ -397,6 +948,8 @@ pub struct SignEip712Params {
     pub domain_hash: [u8; 32],
     /// Message hash (32 bytes)
     pub message_hash: [u8; 32],
+    /// How strictly the derivation path is checked before sending
+    pub path_policy: PathPolicy,
 }
 
 impl SignEip712Params {
@@ -406,8 +959,15 @@ impl SignEip712Params {
             path,
             domain_hash,
             message_hash,
+            path_policy: PathPolicy::default(),
         }
     }
+
+    /// Set the derivation path policy
+    pub fn with_path_policy(mut self, path_policy: PathPolicy) -> Self {
+        self.path_policy = path_policy;
+        self
+    }
 }
 
 /// EIP-712 field type enumeration
@@ -463,6 +1023,22 @@ impl Eip712FieldType {
             _ => None,
         }
     }
+
+    /// Solidity name of the type, as used in EIP-712 type strings
+    ///
+    /// Inverse of [`crate::Eip712Converter::parse_field_type`] for non-array types.
+    pub fn to_solidity_string(&self) -> String {
+        match self {
+            Eip712FieldType::Custom(name) => name.clone(),
+            Eip712FieldType::Int(size) => format!("int{}", *size as u32 * 8),
+            Eip712FieldType::Uint(size) => format!("uint{}", *size as u32 * 8),
+            Eip712FieldType::Address => "address".to_string(),
+            Eip712FieldType::Bool => "bool".to_string(),
+            Eip712FieldType::String => "string".to_string(),
+            Eip712FieldType::FixedBytes(size) => format!("bytes{}", size),
+            Eip712FieldType::DynamicBytes => "bytes".to_string(),
+        }
+    }
 }
 
 /// EIP-712 array level type
@@ -605,14 +1181,33 @@ impl Eip712FieldValue {
     }
 
     /// Create from a uint value with specific size
-    pub fn from_uint_sized(size: u8, value: u64) -> Self {
-        let mut bytes = vec![0u8; size as usize];
-        let value_bytes = value.to_be_bytes();
-        let start = bytes.len().saturating_sub(value_bytes.len());
-        let copy_len = (bytes.len() - start).min(value_bytes.len());
-        bytes[start..start + copy_len]
-            .copy_from_slice(&value_bytes[value_bytes.len() - copy_len..]);
-        Eip712FieldValue { value: bytes }
+    ///
+    /// Integer values are sent at their declared width (`uintN` takes `N / 8`
+    /// bytes, big-endian), which is what the device hashes. Fails if the value
+    /// does not fit in `size` bytes.
+    pub fn from_uint_sized(size: u8, value: u128) -> Result<Self, String> {
+        Self::from_biguint(&BigUint::from(value), size)
+    }
+
+    /// Create from an arbitrary precision uint, left-padded to `size_bytes`
+    ///
+    /// This is the encoding used for `Eip712FieldType::Uint(size_bytes)` fields.
+    /// Fails if the value does not fit in `uint{size_bytes * 8}`.
+    pub fn from_biguint(value: &BigUint, size_bytes: u8) -> Result<Self, String> {
+        let bytes = value.to_bytes_be();
+        if bytes.len() > size_bytes as usize {
+            return Err(format!("uint{} value out of range", size_bytes as u32 * 8));
+        }
+        let mut padded = vec![0u8; size_bytes as usize - bytes.len()];
+        padded.extend(bytes);
+        Ok(Eip712FieldValue { value: padded })
+    }
+
+    /// Create a uint256 from a decimal string (e.g. a max-allowance permit value)
+    pub fn from_decimal_str(value: &str) -> Result<Self, String> {
+        let big = BigUint::parse_bytes(value.trim().as_bytes(), 10)
+            .ok_or_else(|| format!("Invalid decimal string: '{}'", value))?;
+        Self::from_biguint(&big, 32)
     }
 
     /// Create from a uint32 value (4 bytes)
@@ -648,20 +1243,26 @@ impl Eip712FieldValue {
         Ok(Eip712FieldValue { value: bytes })
     }
 
-    /// Create a reference to a nested struct (empty value for struct references)
-    pub fn from_struct() -> Self {
-        Eip712FieldValue { value: vec![] }
-    }
-
     /// Create from an int value with specific size
-    pub fn from_int_sized(size: u8, value: i64) -> Self {
-        let mut bytes = vec![0u8; size as usize];
+    ///
+    /// Encoded like `intN` fields: `size` bytes of big-endian two's complement,
+    /// sign-extended. Fails if the value does not fit in `int{size * 8}`.
+    pub fn from_int_sized(size: u8, value: i64) -> Result<Self, String> {
+        let fill = if value < 0 { 0xFF } else { 0x00 };
         let value_bytes = value.to_be_bytes();
-        let start = bytes.len().saturating_sub(value_bytes.len());
-        let copy_len = (bytes.len() - start).min(value_bytes.len());
-        bytes[start..start + copy_len]
-            .copy_from_slice(&value_bytes[value_bytes.len() - copy_len..]);
-        Eip712FieldValue { value: bytes }
+        let (dropped, kept) = value_bytes.split_at(value_bytes.len().saturating_sub(size as usize));
+        // Dropped bytes and the new sign bit must all repeat the value's sign
+        let fits = dropped.iter().all(|&byte| byte == fill)
+            && kept
+                .first()
+                .is_some_and(|&byte| (byte & 0x80 != 0) == (value < 0));
+        if !fits {
+            return Err(format!("int{} value out of range", size as u32 * 8));
+        }
+
+        let mut bytes = vec![fill; (size as usize).saturating_sub(kept.len())];
+        bytes.extend_from_slice(kept);
+        Ok(Eip712FieldValue { value: bytes })
     }
 
     /// Create from bytes
@@ -744,70 +1345,855 @@ pub struct Eip712FilterParams {
     pub discarded: bool,
 }
 
-// ============================================================================
-// High-level EIP-712 Types (matching viem interface)
-// ============================================================================
+impl Eip712FilterParams {
+    /// Wrap a filter type, not discarded
+    pub fn new(filter_type: Eip712FilterType) -> Self {
+        Eip712FilterParams {
+            filter_type,
+            discarded: false,
+        }
+    }
 
-/// EIP-712 domain separator
+    /// Filtering activation, sent before the domain implementation
+    pub fn activation() -> Self {
+        Self::new(Eip712FilterType::Activation)
+    }
+
+    /// Path of a field filtered in an array that turned out to be empty
+    pub fn discarded_filter_path(path: &str) -> Self {
+        Self::new(Eip712FilterType::DiscardedFilterPath(path.to_string()))
+    }
+
+    /// Message info, sent right before the message implementation
+    pub fn message_info(display_name: &str, filters_count: u8, signature: Vec<u8>) -> Self {
+        Self::new(Eip712FilterType::MessageInfo {
+            display_name: display_name.to_string(),
+            filters_count,
+            signature,
+        })
+    }
+
+    /// Address field resolved through trusted names
+    pub fn trusted_name(
+        display_name: &str,
+        name_types: Vec<u8>,
+        name_sources: Vec<u8>,
+        signature: Vec<u8>,
+    ) -> Self {
+        Self::new(Eip712FilterType::TrustedName {
+            display_name: display_name.to_string(),
+            name_types,
+            name_sources,
+            signature,
+        })
+    }
+
+    /// Timestamp field displayed as a date
+    pub fn date_time(display_name: &str, signature: Vec<u8>) -> Self {
+        Self::new(Eip712FilterType::DateTime {
+            display_name: display_name.to_string(),
+            signature,
+        })
+    }
+
+    /// Token address for amounts joined under `token_index`
+    pub fn amount_join_token(token_index: u8, signature: Vec<u8>) -> Self {
+        Self::new(Eip712FilterType::AmountJoinToken {
+            token_index,
+            signature,
+        })
+    }
+
+    /// Amount of the token joined under `token_index`
+    pub fn amount_join_value(display_name: &str, token_index: u8, signature: Vec<u8>) -> Self {
+        Self::new(Eip712FilterType::AmountJoinValue {
+            display_name: display_name.to_string(),
+            token_index,
+            signature,
+        })
+    }
+
+    /// Field displayed as-is
+    pub fn raw_field(display_name: &str, signature: Vec<u8>) -> Self {
+        Self::new(Eip712FilterType::RawField {
+            display_name: display_name.to_string(),
+            signature,
+        })
+    }
+
+    /// Mark the filter as applying to a discarded (empty array) path
+    pub fn discarded(mut self) -> Self {
+        self.discarded = true;
+        self
+    }
+}
+
+/// Signed network metadata for PROVIDE NETWORK INFORMATION
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
-pub struct Eip712Domain {
-    /// Domain name
-    pub name: Option<String>,
-    /// Domain version
-    pub version: Option<String>,
+pub struct NetworkDescriptor {
     /// Chain ID
-    pub chain_id: Option<u64>,
-    /// Verifying contract address
-    pub verifying_contract: Option<String>,
-    /// Salt (optional)
-    pub salt: Option<Vec<u8>>,
+    pub chain_id: u64,
+    /// Network name shown on device
+    pub name: String,
+    /// Native currency ticker
+    pub ticker: String,
+    /// Optional SHA-256 of the network icon, if covered by the signature
+    pub icon_hash: Option<[u8; 32]>,
+    /// DER encoded signature over the descriptor
+    pub signature: Vec<u8>,
 }
 
-impl Eip712Domain {
-    /// Create a new domain
-    pub fn new() -> Self {
-        Eip712Domain {
-            name: None,
-            version: None,
-            chain_id: None,
-            verifying_contract: None,
-            salt: None,
+impl NetworkDescriptor {
+    /// Create a new descriptor for the given chain
+    pub fn new(chain_id: u64) -> Self {
+        NetworkDescriptor {
+            chain_id,
+            name: String::new(),
+            ticker: String::new(),
+            icon_hash: None,
+            signature: Vec::new(),
         }
     }
 
-    /// Set the domain name
+    /// Set the network name
     pub fn with_name(mut self, name: String) -> Self {
-        self.name = Some(name);
+        self.name = name;
         self
     }
 
-    /// Set the domain version
-    pub fn with_version(mut self, version: String) -> Self {
-        self.version = Some(version);
+    /// Set the native currency ticker
+    pub fn with_ticker(mut self, ticker: String) -> Self {
+        self.ticker = ticker;
         self
     }
 
-    /// Set the chain ID
-    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
-        self.chain_id = Some(chain_id);
+    /// Set the network icon hash
+    pub fn with_icon_hash(mut self, icon_hash: [u8; 32]) -> Self {
+        self.icon_hash = Some(icon_hash);
         self
     }
 
-    /// Set the verifying contract address
-    pub fn with_verifying_contract(mut self, address: String) -> Self {
-        self.verifying_contract = Some(address);
+    /// Set the descriptor signature
+    pub fn with_signature(mut self, signature: Vec<u8>) -> Self {
+        self.signature = signature;
         self
     }
 
-    /// Set the salt
-    pub fn with_salt(mut self, salt: Vec<u8>) -> Self {
-        self.salt = Some(salt);
-        self
+    /// Encode the descriptor as the TLV payload expected by the device
+    pub fn to_tlv(&self) -> Result<Vec<u8>, String> {
+        use crate::instructions::network_info_tag as tag;
+
+        if self.name.is_empty() {
+            return Err("Network name cannot be empty".to_string());
+        }
+        if self.ticker.is_empty() {
+            return Err("Network ticker cannot be empty".to_string());
+        }
+        if self.signature.is_empty() {
+            return Err("Network descriptor must be signed".to_string());
+        }
+
+        let mut tlv = Vec::new();
+        let mut push = |t: u8, value: &[u8]| -> Result<(), String> {
+            // Single byte DER-style length
+            if value.len() > 0x7F {
+                return Err(format!(
+                    "Network descriptor field 0x{:02X} too long: {} bytes (max 127)",
+                    t,
+                    value.len()
+                ));
+            }
+            tlv.push(t);
+            tlv.push(value.len() as u8);
+            tlv.extend_from_slice(value);
+            Ok(())
+        };
+
+        push(tag::STRUCTURE_TYPE, &[tag::TYPE_DYNAMIC_NETWORK])?;
+        push(tag::STRUCTURE_VERSION, &[tag::VERSION])?;
+        push(tag::BLOCKCHAIN_FAMILY, &[tag::FAMILY_ETHEREUM])?;
+        push(tag::CHAIN_ID, &self.chain_id.to_be_bytes())?;
+        push(tag::NETWORK_NAME, self.name.as_bytes())?;
+        push(tag::TICKER, self.ticker.as_bytes())?;
+        if let Some(icon_hash) = &self.icon_hash {
+            push(tag::NETWORK_ICON_HASH, icon_hash)?;
+        }
+        push(tag::DER_SIGNATURE, &self.signature)?;
+
+        Ok(tlv)
     }
 }
 
-impl Default for Eip712Domain {
-    fn default() -> Self {
+/// Signed plugin descriptor for SET PLUGIN / SET EXTERNAL PLUGIN
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PluginDescriptor {
+    /// Descriptor type and version (SET PLUGIN only)
+    pub type_version: (u8, u8),
+    /// Plugin name, as registered on the device
+    pub plugin_name: String,
+    /// Contract the plugin handles
+    pub contract_address: [u8; 20],
+    /// Method selector the plugin handles
+    pub method_selector: [u8; 4],
+    /// Chain ID (SET PLUGIN only)
+    pub chain_id: u64,
+    /// Signing key ID (SET PLUGIN only)
+    pub key_id: u8,
+    /// Signature algorithm (SET PLUGIN only)
+    pub algorithm: u8,
+    /// DER encoded signature over the descriptor
+    pub signature: Vec<u8>,
+}
+
+impl PluginDescriptor {
+    /// Create a new descriptor for a contract method, signed with the production key
+    pub fn new(plugin_name: String, contract_address: [u8; 20], method_selector: [u8; 4]) -> Self {
+        use crate::instructions::plugin;
+
+        PluginDescriptor {
+            type_version: (plugin::TYPE_ETH_PLUGIN, plugin::VERSION),
+            plugin_name,
+            contract_address,
+            method_selector,
+            chain_id: 1,
+            key_id: plugin::KEY_ID_PROD,
+            algorithm: plugin::ALGORITHM_ECDSA_SHA256,
+            signature: Vec::new(),
+        }
+    }
+
+    /// Set the chain ID
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    /// Set the signing key ID
+    pub fn with_key_id(mut self, key_id: u8) -> Self {
+        self.key_id = key_id;
+        self
+    }
+
+    /// Set the signature algorithm
+    pub fn with_algorithm(mut self, algorithm: u8) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Set the descriptor signature
+    pub fn with_signature(mut self, signature: Vec<u8>) -> Self {
+        self.signature = signature;
+        self
+    }
+
+    /// Encode the SET PLUGIN payload:
+    /// type | version | name length | name | address | selector | chain ID | key ID |
+    /// algorithm | signature length | signature
+    pub fn to_set_plugin_payload(&self) -> Result<Vec<u8>, String> {
+        self.validate()?;
+        let signature_len = u8::try_from(self.signature.len())
+            .map_err(|_| format!("Plugin signature too long: {} bytes", self.signature.len()))?;
+
+        let mut payload = vec![self.type_version.0, self.type_version.1];
+        payload.push(self.plugin_name.len() as u8);
+        payload.extend_from_slice(self.plugin_name.as_bytes());
+        payload.extend_from_slice(&self.contract_address);
+        payload.extend_from_slice(&self.method_selector);
+        payload.extend_from_slice(&self.chain_id.to_be_bytes());
+        payload.push(self.key_id);
+        payload.push(self.algorithm);
+        payload.push(signature_len);
+        payload.extend_from_slice(&self.signature);
+
+        Self::check_payload_len(payload)
+    }
+
+    /// Encode the SET EXTERNAL PLUGIN payload:
+    /// name length | name | address | selector | signature
+    pub fn to_external_plugin_payload(&self) -> Result<Vec<u8>, String> {
+        self.validate()?;
+
+        let mut payload = vec![self.plugin_name.len() as u8];
+        payload.extend_from_slice(self.plugin_name.as_bytes());
+        payload.extend_from_slice(&self.contract_address);
+        payload.extend_from_slice(&self.method_selector);
+        payload.extend_from_slice(&self.signature);
+
+        Self::check_payload_len(payload)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.plugin_name.is_empty() {
+            return Err("Plugin name cannot be empty".to_string());
+        }
+        if self.plugin_name.len() > u8::MAX as usize {
+            return Err(format!(
+                "Plugin name too long: {} bytes (max 255)",
+                self.plugin_name.len()
+            ));
+        }
+        if self.signature.is_empty() {
+            return Err("Plugin descriptor must be signed".to_string());
+        }
+        Ok(())
+    }
+
+    fn check_payload_len(payload: Vec<u8>) -> Result<Vec<u8>, String> {
+        if payload.len() > crate::instructions::length::MAX_MESSAGE_CHUNK_SIZE {
+            return Err(format!(
+                "Plugin descriptor too long: {} bytes (max {})",
+                payload.len(),
+                crate::instructions::length::MAX_MESSAGE_CHUNK_SIZE
+            ));
+        }
+        Ok(payload)
+    }
+}
+
+/// Signed NFT collection descriptor for PROVIDE NFT INFORMATION
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct NftCollectionInfo {
+    /// Descriptor type and version
+    pub type_version: (u8, u8),
+    /// Collection name shown on device
+    pub collection_name: String,
+    /// ERC-721 / ERC-1155 contract address
+    pub contract_address: [u8; 20],
+    /// Chain ID the contract is deployed on
+    pub chain_id: u64,
+    /// Signing key ID
+    pub key_id: u8,
+    /// Signature algorithm
+    pub algorithm: u8,
+    /// DER encoded signature over the descriptor
+    pub signature: Vec<u8>,
+}
+
+impl NftCollectionInfo {
+    /// Create a new descriptor for a collection, signed with the production key
+    pub fn new(collection_name: String, contract_address: [u8; 20], chain_id: u64) -> Self {
+        use crate::instructions::nft;
+
+        NftCollectionInfo {
+            type_version: (nft::TYPE_COLLECTION, nft::VERSION),
+            collection_name,
+            contract_address,
+            chain_id,
+            key_id: nft::KEY_ID_PROD,
+            algorithm: nft::ALGORITHM_ECDSA_SHA256,
+            signature: Vec::new(),
+        }
+    }
+
+    /// Set the signing key ID
+    pub fn with_key_id(mut self, key_id: u8) -> Self {
+        self.key_id = key_id;
+        self
+    }
+
+    /// Set the signature algorithm
+    pub fn with_algorithm(mut self, algorithm: u8) -> Self {
+        self.algorithm = algorithm;
+        self
+    }
+
+    /// Set the descriptor signature
+    pub fn with_signature(mut self, signature: Vec<u8>) -> Self {
+        self.signature = signature;
+        self
+    }
+
+    /// Encode the PROVIDE NFT INFORMATION payload:
+    /// type | version | name length | name | address | chain ID | key ID |
+    /// algorithm | signature length | signature
+    pub fn to_payload(&self) -> Result<Vec<u8>, String> {
+        use crate::instructions::length::MAX_MESSAGE_CHUNK_SIZE;
+
+        if self.collection_name.is_empty() {
+            return Err("Collection name cannot be empty".to_string());
+        }
+        let name_len = u8::try_from(self.collection_name.len()).map_err(|_| {
+            format!(
+                "Collection name too long: {} bytes (max 255)",
+                self.collection_name.len()
+            )
+        })?;
+        if self.signature.is_empty() {
+            return Err("NFT descriptor must be signed".to_string());
+        }
+        let signature_len = u8::try_from(self.signature.len())
+            .map_err(|_| format!("NFT signature too long: {} bytes", self.signature.len()))?;
+
+        let mut payload = vec![self.type_version.0, self.type_version.1, name_len];
+        payload.extend_from_slice(self.collection_name.as_bytes());
+        payload.extend_from_slice(&self.contract_address);
+        payload.extend_from_slice(&self.chain_id.to_be_bytes());
+        payload.push(self.key_id);
+        payload.push(self.algorithm);
+        payload.push(signature_len);
+        payload.extend_from_slice(&self.signature);
+
+        if payload.len() > MAX_MESSAGE_CHUNK_SIZE {
+            return Err(format!(
+                "NFT descriptor too long: {} bytes (max {})",
+                payload.len(),
+                MAX_MESSAGE_CHUNK_SIZE
+            ));
+        }
+        Ok(payload)
+    }
+}
+
+/// Signed Safe (multisig) account context for PROVIDE SAFE ACCOUNT
+///
+/// Sent as two TLV descriptors: the Safe descriptor (address, threshold, owners
+/// count, role) and the signer descriptor (owner addresses), each signed by the
+/// provider over the device challenge.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SafeAccountInfo {
+    /// Descriptor structure version
+    pub version: u8,
+    /// Challenge returned by the device, covered by both signatures
+    pub challenge: u32,
+    /// Safe contract address
+    pub safe_address: [u8; 20],
+    /// Number of owner confirmations required
+    pub threshold: u16,
+    /// Owner addresses
+    pub owners: Vec<[u8; 20]>,
+    /// Role of the device's account in the Safe
+    pub role: u8,
+    /// DER encoded signature over the Safe descriptor
+    pub signature: Vec<u8>,
+    /// DER encoded signature over the signer descriptor
+    pub signers_signature: Vec<u8>,
+}
+
+impl SafeAccountInfo {
+    /// Create a new Safe account context where the device's account is an owner
+    pub fn new(safe_address: [u8; 20], threshold: u16, owners: Vec<[u8; 20]>) -> Self {
+        use crate::instructions::safe_account_tag as tag;
+
+        SafeAccountInfo {
+            version: tag::VERSION,
+            challenge: 0,
+            safe_address,
+            threshold,
+            owners,
+            role: tag::ROLE_SIGNER,
+            signature: Vec::new(),
+            signers_signature: Vec::new(),
+        }
+    }
+
+    /// Set the device challenge the descriptors were signed over
+    pub fn with_challenge(mut self, challenge: u32) -> Self {
+        self.challenge = challenge;
+        self
+    }
+
+    /// Set the role of the device's account
+    pub fn with_role(mut self, role: u8) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// Set the Safe descriptor signature
+    pub fn with_signature(mut self, signature: Vec<u8>) -> Self {
+        self.signature = signature;
+        self
+    }
+
+    /// Set the signer descriptor signature
+    pub fn with_signers_signature(mut self, signature: Vec<u8>) -> Self {
+        self.signers_signature = signature;
+        self
+    }
+
+    /// Encode the Safe descriptor TLV
+    pub fn to_safe_descriptor_tlv(&self) -> Result<Vec<u8>, String> {
+        use crate::instructions::safe_account_tag as tag;
+
+        self.validate()?;
+        if self.signature.is_empty() {
+            return Err("Safe descriptor must be signed".to_string());
+        }
+
+        let mut tlv = Vec::new();
+        let mut push = |tag, value: &[u8]| push_short_tlv(&mut tlv, "Safe", tag, value);
+        push(tag::STRUCTURE_TYPE, &[tag::TYPE_SAFE_ACCOUNT])?;
+        push(tag::STRUCTURE_VERSION, &[self.version])?;
+        push(tag::CHALLENGE, &self.challenge.to_be_bytes())?;
+        push(tag::ADDRESS, &self.safe_address)?;
+        push(tag::THRESHOLD, &self.threshold.to_be_bytes())?;
+        push(
+            tag::SIGNERS_COUNT,
+            &(self.owners.len() as u16).to_be_bytes(),
+        )?;
+        push(tag::ROLE, &[self.role])?;
+        push(tag::DER_SIGNATURE, &self.signature)?;
+        Ok(tlv)
+    }
+
+    /// Encode the signer descriptor TLV, one ADDRESS entry per owner
+    pub fn to_signer_descriptor_tlv(&self) -> Result<Vec<u8>, String> {
+        use crate::instructions::safe_account_tag as tag;
+
+        self.validate()?;
+        if self.signers_signature.is_empty() {
+            return Err("Signer descriptor must be signed".to_string());
+        }
+
+        let mut tlv = Vec::new();
+        let mut push = |tag, value: &[u8]| push_short_tlv(&mut tlv, "Safe", tag, value);
+        push(tag::STRUCTURE_TYPE, &[tag::TYPE_SIGNER])?;
+        push(tag::STRUCTURE_VERSION, &[self.version])?;
+        push(tag::CHALLENGE, &self.challenge.to_be_bytes())?;
+        for owner in &self.owners {
+            push(tag::ADDRESS, owner)?;
+        }
+        push(tag::DER_SIGNATURE, &self.signers_signature)?;
+        Ok(tlv)
+    }
+
+    fn validate(&self) -> Result<(), String> {
+        if self.owners.is_empty() {
+            return Err("Safe must have at least one owner".to_string());
+        }
+        if u16::try_from(self.owners.len()).is_err() {
+            return Err(format!("Too many Safe owners: {}", self.owners.len()));
+        }
+        if self.threshold == 0 || self.threshold as usize > self.owners.len() {
+            return Err(format!(
+                "Safe threshold {} out of range for {} owners",
+                self.threshold,
+                self.owners.len()
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// Transaction simulation result for the on-device Transaction Check warnings
+///
+/// Produced and signed by a simulation provider for one transaction (or
+/// message) and sent with PROVIDE TX SIMULATION before it is signed. Only
+/// useful when [`ConfigFlags::transaction_check_enabled`] is set.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TxSimulationResult {
+    /// Structure version
+    pub version: u8,
+    /// Hash of the simulated transaction (or message)
+    pub tx_hash: [u8; 32],
+    /// Chain ID of the transaction
+    pub chain_id: u64,
+    /// Address of the account the transaction is sent from
+    pub address: [u8; 20],
+    /// Normalized risk score (benign, warning or malicious)
+    pub risk: u8,
+    /// Normalized risk category, provider defined
+    pub category: u8,
+    /// Message from the simulation provider shown on the device
+    pub provider_message: String,
+    /// Short URL to the provider's full report
+    pub tiny_url: String,
+    /// What was simulated (transaction, typed data, personal message)
+    pub simulation_type: u8,
+    /// DER encoded provider signature over the result
+    pub signature: Vec<u8>,
+}
+
+impl TxSimulationResult {
+    /// Create a new transaction simulation result
+    pub fn new(tx_hash: [u8; 32], chain_id: u64, address: [u8; 20], risk: u8) -> Self {
+        use crate::instructions::tx_simulation_tag as tag;
+
+        TxSimulationResult {
+            version: tag::VERSION,
+            tx_hash,
+            chain_id,
+            address,
+            risk,
+            category: 0,
+            provider_message: String::new(),
+            tiny_url: String::new(),
+            simulation_type: tag::SIMULATION_TRANSACTION,
+            signature: Vec::new(),
+        }
+    }
+
+    /// Set the risk category
+    pub fn with_category(mut self, category: u8) -> Self {
+        self.category = category;
+        self
+    }
+
+    /// Set the provider message
+    pub fn with_provider_message(mut self, message: String) -> Self {
+        self.provider_message = message;
+        self
+    }
+
+    /// Set the report URL
+    pub fn with_tiny_url(mut self, url: String) -> Self {
+        self.tiny_url = url;
+        self
+    }
+
+    /// Set what was simulated
+    pub fn with_simulation_type(mut self, simulation_type: u8) -> Self {
+        self.simulation_type = simulation_type;
+        self
+    }
+
+    /// Set the provider signature
+    pub fn with_signature(mut self, signature: Vec<u8>) -> Self {
+        self.signature = signature;
+        self
+    }
+
+    /// Encode the simulation result TLV
+    pub fn to_tlv(&self) -> Result<Vec<u8>, String> {
+        use crate::instructions::tx_simulation_tag as tag;
+
+        if self.risk > tag::RISK_MALICIOUS {
+            return Err(format!("Invalid normalized risk: {}", self.risk));
+        }
+        if self.signature.is_empty() {
+            return Err("Transaction simulation result must be signed".to_string());
+        }
+
+        let mut tlv = Vec::new();
+        let mut push =
+            |tag, value: &[u8]| push_short_tlv(&mut tlv, "Transaction simulation", tag, value);
+        push(tag::STRUCTURE_TYPE, &[tag::TYPE_TX_SIMULATION])?;
+        push(tag::STRUCTURE_VERSION, &[self.version])?;
+        push(tag::ADDRESS, &self.address)?;
+        push(tag::CHAIN_ID, &self.chain_id.to_be_bytes())?;
+        push(tag::TX_HASH, &self.tx_hash)?;
+        push(tag::NORMALIZED_RISK, &[self.risk])?;
+        push(tag::NORMALIZED_CATEGORY, &[self.category])?;
+        push(tag::PROVIDER_MSG, self.provider_message.as_bytes())?;
+        push(tag::TINY_URL, self.tiny_url.as_bytes())?;
+        push(tag::SIMULATION_TYPE, &[self.simulation_type])?;
+        push(tag::DER_SIGNATURE, &self.signature)?;
+        Ok(tlv)
+    }
+}
+
+/// Append a TLV entry with a single byte DER-style length
+fn push_short_tlv(
+    tlv: &mut Vec<u8>,
+    descriptor: &str,
+    tag: u8,
+    value: &[u8],
+) -> Result<(), String> {
+    if value.len() > 0x7F {
+        return Err(format!(
+            "{} descriptor field 0x{:02X} too long: {} bytes (max 127)",
+            descriptor,
+            tag,
+            value.len()
+        ));
+    }
+    tlv.push(tag);
+    tlv.push(value.len() as u8);
+    tlv.extend_from_slice(value);
+    Ok(())
+}
+
+// ============================================================================
+// High-level EIP-712 Types (matching viem interface)
+// ============================================================================
+
+/// EIP-712 domain separator
+///
+/// (De)serializes as the standard `domain` JSON object: camelCase keys, absent
+/// fields omitted, `chainId` read from a number or a decimal/0x string, `salt`
+/// as a 0x-prefixed hex string.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip712Domain {
+    /// Domain name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    /// Domain version
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    /// Chain ID
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        deserialize_with = "deserialize_chain_id"
+    )]
+    pub chain_id: Option<u64>,
+    /// Verifying contract address
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verifying_contract: Option<String>,
+    /// Salt (optional)
+    #[serde(
+        default,
+        skip_serializing_if = "Option::is_none",
+        serialize_with = "serialize_salt",
+        deserialize_with = "deserialize_salt"
+    )]
+    pub salt: Option<Vec<u8>>,
+}
+
+/// Read a chain ID given as a JSON number or a decimal/0x-prefixed string
+pub(crate) fn parse_chain_id_value(value: &serde_json::Value) -> Result<u64, String> {
+    match value {
+        serde_json::Value::String(s) => {
+            let s = s.trim();
+            match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+                Some(hex_str) => u64::from_str_radix(hex_str, 16),
+                None => s.parse::<u64>(),
+            }
+            .map_err(|e| format!("Invalid chainId '{}': {}", s, e))
+        }
+        _ => value
+            .as_u64()
+            .ok_or_else(|| format!("Invalid chainId: {}", value)),
+    }
+}
+
+fn deserialize_chain_id<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<u64>, D::Error> {
+    Option::<serde_json::Value>::deserialize(deserializer)?
+        .map(|value| parse_chain_id_value(&value).map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+fn serialize_salt<S: Serializer>(salt: &Option<Vec<u8>>, serializer: S) -> Result<S::Ok, S::Error> {
+    match salt {
+        Some(salt) => serializer.serialize_str(&format!("0x{}", hex::encode(salt))),
+        None => serializer.serialize_none(),
+    }
+}
+
+fn deserialize_salt<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<u8>>, D::Error> {
+    Option::<String>::deserialize(deserializer)?
+        .map(|salt| {
+            hex::decode(salt.trim_start_matches("0x"))
+                .map_err(|e| serde::de::Error::custom(format!("Invalid salt hex: {}", e)))
+        })
+        .transpose()
+}
+
+impl Eip712Domain {
+    /// Create a new domain
+    pub fn new() -> Self {
+        Eip712Domain {
+            name: None,
+            version: None,
+            chain_id: None,
+            verifying_contract: None,
+            salt: None,
+        }
+    }
+
+    /// Set the domain name
+    pub fn with_name(mut self, name: String) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    /// Set the domain version
+    pub fn with_version(mut self, version: String) -> Self {
+        self.version = Some(version);
+        self
+    }
+
+    /// Set the chain ID
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = Some(chain_id);
+        self
+    }
+
+    /// Set the verifying contract address
+    pub fn with_verifying_contract(mut self, address: String) -> Self {
+        self.verifying_contract = Some(address);
+        self
+    }
+
+    /// Set the salt
+    pub fn with_salt(mut self, salt: Vec<u8>) -> Self {
+        self.salt = Some(salt);
+        self
+    }
+
+    /// `EIP712Domain` struct definition for the fields that are set
+    ///
+    /// Fields follow the canonical order (name, version, chainId, verifyingContract,
+    /// salt), which is also the order the domain values are sent in. Typed data
+    /// whose `types` omit `EIP712Domain` is signed with this definition.
+    pub fn to_struct_definition(&self) -> Eip712StructDefinition {
+        let fields = [
+            (self.name.is_some(), "name", Eip712FieldType::String),
+            (self.version.is_some(), "version", Eip712FieldType::String),
+            (
+                self.chain_id.is_some(),
+                "chainId",
+                Eip712FieldType::Uint(32),
+            ),
+            (
+                self.verifying_contract.is_some(),
+                "verifyingContract",
+                Eip712FieldType::Address,
+            ),
+            (self.salt.is_some(), "salt", Eip712FieldType::FixedBytes(32)),
+        ];
+
+        Eip712StructDefinition {
+            name: "EIP712Domain".to_string(),
+            fields: fields
+                .into_iter()
+                .filter(|(present, _, _)| *present)
+                .map(|(_, name, field_type)| {
+                    Eip712FieldDefinition::new(field_type, name.to_string())
+                })
+                .collect(),
+        }
+    }
+
+    /// `EIP712Domain` struct implementation matching [`Eip712Domain::to_struct_definition`]
+    ///
+    /// Some firmware expect the domain values in the canonical order, whatever
+    /// the order of the `EIP712Domain` fields in the typed data.
+    pub fn to_struct_implementation(&self) -> Result<Eip712StructImplementation, String> {
+        let mut values = Vec::new();
+        if let Some(name) = &self.name {
+            values.push(Eip712FieldValue::from_string(name));
+        }
+        if let Some(version) = &self.version {
+            values.push(Eip712FieldValue::from_string(version));
+        }
+        if let Some(chain_id) = self.chain_id {
+            // uint256, left-padded like every other integer value
+            values.push(Eip712FieldValue::from_biguint(
+                &BigUint::from(chain_id),
+                32,
+            )?);
+        }
+        if let Some(address) = &self.verifying_contract {
+            values.push(Eip712FieldValue::from_address_string(address)?);
+        }
+        if let Some(salt) = &self.salt {
+            values.push(Eip712FieldValue::from_bytes(salt.clone()));
+        }
+
+        Ok(Eip712StructImplementation {
+            name: "EIP712Domain".to_string(),
+            values,
+        })
+    }
+}
+
+impl Default for Eip712Domain {
+    fn default() -> Self {
         Self::new()
     }
 }
@@ -829,7 +2215,10 @@ impl Eip712Field {
 }
 
 /// EIP-712 struct definition for high-level API
+///
+/// (De)serializes as the plain array of `{ "name", "type" }` fields.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
 pub struct Eip712Struct {
     /// Struct fields
     pub fields: Vec<Eip712Field>,
@@ -855,10 +2244,17 @@ impl Default for Eip712Struct {
 }
 
 /// EIP-712 types mapping (struct name -> struct definition)
+///
+/// A plain `HashMap`: build it with `insert`, query it with `contains_key`.
 pub type Eip712Types = HashMap<String, Eip712Struct>;
 
 /// EIP-712 typed data (matching viem interface)
+///
+/// (De)serializes as the standard `eth_signTypedData_v4` JSON. Unlike
+/// [`crate::Eip712Converter::parse_json_to_typed_data`], deserializing does not
+/// check that `primaryType` is defined.
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct Eip712TypedData {
     /// Domain separator
     pub domain: Eip712Domain,
@@ -885,11 +2281,195 @@ impl Eip712TypedData {
             message,
         }
     }
+
+    /// Serialize to compact JSON with a stable layout
+    ///
+    /// Object keys (types, domain and message keys) are sorted so equal typed
+    /// data always gives the same string, and hashes of it can be compared.
+    /// Fields keep their declaration order, which is part of the EIP-712 type
+    /// hash.
+    pub fn canonical_json(&self) -> String {
+        let value = serde_json::to_value(self).expect("typed data serializes to JSON");
+        sort_object_keys(value).to_string()
+    }
+}
+
+/// Rebuild objects with their keys inserted in sorted order
+fn sort_object_keys(value: serde_json::Value) -> serde_json::Value {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.into_iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            serde_json::Value::Object(
+                entries
+                    .into_iter()
+                    .map(|(key, value)| (key, sort_object_keys(value)))
+                    .collect(),
+            )
+        }
+        serde_json::Value::Array(items) => {
+            serde_json::Value::Array(items.into_iter().map(sort_object_keys).collect())
+        }
+        other => other,
+    }
 }
 
 #[cfg(test)]
 mod eip712_typed_data_tests {
     use super::*;
+    use crate::Eip712Converter;
+
+    const FIXTURES: [&str; 3] = [
+        include_str!("commands/eip712/fixtures/seaport_order.json"),
+        include_str!("commands/eip712/fixtures/permit2_permit_single.json"),
+        include_str!("commands/eip712/fixtures/safe_tx.json"),
+    ];
+
+    #[test]
+    fn test_domain_struct_definition_subsets() {
+        let all = [
+            ("name", Eip712FieldType::String),
+            ("version", Eip712FieldType::String),
+            ("chainId", Eip712FieldType::Uint(32)),
+            ("verifyingContract", Eip712FieldType::Address),
+            ("salt", Eip712FieldType::FixedBytes(32)),
+        ];
+
+        for mask in 0..32u8 {
+            let set = |bit: u8| mask & (1 << bit) != 0;
+            let mut domain = Eip712Domain::new();
+            if set(0) {
+                domain = domain.with_name("Ether Mail".to_string());
+            }
+            if set(1) {
+                domain = domain.with_version("1".to_string());
+            }
+            if set(2) {
+                domain = domain.with_chain_id(1);
+            }
+            if set(3) {
+                domain = domain.with_verifying_contract(
+                    "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC".to_string(),
+                );
+            }
+            if set(4) {
+                domain = domain.with_salt(vec![0xAB; 32]);
+            }
+
+            let definition = domain.to_struct_definition();
+            let expected: Vec<_> = all
+                .iter()
+                .enumerate()
+                .filter(|(bit, _)| set(*bit as u8))
+                .map(|(_, (name, field_type))| {
+                    Eip712FieldDefinition::new(field_type.clone(), name.to_string())
+                })
+                .collect();
+            assert_eq!(definition.name, "EIP712Domain");
+            assert_eq!(definition.fields, expected, "mask {:05b}", mask);
+        }
+    }
+
+    #[test]
+    fn test_typed_data_json_round_trip() {
+        for fixture in FIXTURES {
+            let parsed = Eip712Converter::parse_json_to_typed_data(fixture).unwrap();
+            let json = serde_json::to_string(&parsed).unwrap();
+
+            assert_eq!(
+                Eip712Converter::parse_json_to_typed_data(&json).unwrap(),
+                parsed
+            );
+            assert_eq!(
+                serde_json::from_str::<Eip712TypedData>(&json).unwrap(),
+                parsed
+            );
+            assert_eq!(
+                serde_json::from_str::<Eip712TypedData>(fixture).unwrap(),
+                parsed
+            );
+        }
+    }
+
+    #[test]
+    fn test_typed_data_standard_json_shape() {
+        let domain = Eip712Domain::new()
+            .with_chain_id(1)
+            .with_verifying_contract("0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC".to_string())
+            .with_salt(vec![0xAB; 32]);
+        let mut types = Eip712Types::new();
+        types.insert(
+            "Mail".to_string(),
+            Eip712Struct::new().with_field(Eip712Field::new(
+                "contents".to_string(),
+                "string".to_string(),
+            )),
+        );
+        let typed_data = Eip712TypedData::new(
+            domain,
+            types,
+            "Mail".to_string(),
+            serde_json::json!({ "contents": "Hello" }),
+        );
+
+        let value = serde_json::to_value(&typed_data).unwrap();
+        assert_eq!(
+            value,
+            serde_json::json!({
+                "domain": {
+                    "chainId": 1,
+                    "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC",
+                    "salt": format!("0x{}", "ab".repeat(32)),
+                },
+                "types": {
+                    "Mail": [{ "name": "contents", "type": "string" }],
+                },
+                "primaryType": "Mail",
+                "message": { "contents": "Hello" },
+            })
+        );
+
+        // chainId as a hex string, as some wallets send it
+        let domain: Eip712Domain = serde_json::from_str(r#"{"chainId":"0x89"}"#).unwrap();
+        assert_eq!(domain.chain_id, Some(137));
+    }
+
+    #[test]
+    fn test_canonical_json_is_stable() {
+        let fields = |name: &str| {
+            Eip712Struct::new()
+                .with_field(Eip712Field::new("b".to_string(), "uint256".to_string()))
+                .with_field(Eip712Field::new(name.to_string(), "address".to_string()))
+        };
+        let mut types_a = Eip712Types::new();
+        let mut types_b = Eip712Types::new();
+        for name in ["Mail", "Person", "Group", "Order"] {
+            types_a.insert(name.to_string(), fields("a"));
+        }
+        for name in ["Order", "Group", "Person", "Mail"] {
+            types_b.insert(name.to_string(), fields("a"));
+        }
+        let message_a = serde_json::from_str(r#"{"z":1,"a":{"y":2,"b":3}}"#).unwrap();
+        let message_b = serde_json::from_str(r#"{"a":{"b":3,"y":2},"z":1}"#).unwrap();
+
+        let a = Eip712TypedData::new(Eip712Domain::new(), types_a, "Mail".to_string(), message_a);
+        let b = Eip712TypedData::new(Eip712Domain::new(), types_b, "Mail".to_string(), message_b);
+        assert_eq!(a.canonical_json(), b.canonical_json());
+        assert!(a
+            .canonical_json()
+            .starts_with(r#"{"domain":{},"message":{"a":{"b":3,"y":2},"z":1},"primaryType":"Mail","types":{"Group":"#));
+        // Field order is part of the type and kept
+        assert!(a
+            .canonical_json()
+            .contains(r#"[{"name":"b","type":"uint256"},{"name":"a","type":"address"}]"#));
+
+        for fixture in FIXTURES {
+            let parsed = Eip712Converter::parse_json_to_typed_data(fixture).unwrap();
+            let reparsed =
+                Eip712Converter::parse_json_to_typed_data(&parsed.canonical_json()).unwrap();
+            assert_eq!(reparsed.canonical_json(), parsed.canonical_json());
+        }
+    }
 
     #[test]
     fn test_eip712_domain_creation() {
@@ -960,3 +2540,374 @@ mod eip712_typed_data_tests {
         assert!(typed_data.types.contains_key("Person"));
     }
 }
+
+#[cfg(test)]
+mod eip712_field_type_tests {
+    use super::*;
+    use crate::Eip712Converter;
+
+    #[test]
+    fn test_to_solidity_string() {
+        let cases = [
+            (Eip712FieldType::Custom("Person".to_string()), "Person"),
+            (Eip712FieldType::Int(1), "int8"),
+            (Eip712FieldType::Int(32), "int256"),
+            (Eip712FieldType::Uint(16), "uint128"),
+            (Eip712FieldType::Uint(32), "uint256"),
+            (Eip712FieldType::Address, "address"),
+            (Eip712FieldType::Bool, "bool"),
+            (Eip712FieldType::String, "string"),
+            (Eip712FieldType::FixedBytes(1), "bytes1"),
+            (Eip712FieldType::FixedBytes(32), "bytes32"),
+            (Eip712FieldType::DynamicBytes, "bytes"),
+        ];
+
+        for (field_type, expected) in cases {
+            assert_eq!(field_type.to_solidity_string(), expected);
+            assert_eq!(
+                Eip712Converter::parse_field_type(expected).unwrap(),
+                field_type
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod eip712_field_value_tests {
+    use super::*;
+
+    #[test]
+    fn test_from_decimal_str_max_uint256() {
+        // USDC permit max allowance
+        let value = Eip712FieldValue::from_decimal_str(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+        )
+        .unwrap();
+        assert_eq!(value.value, vec![0xFF; 32]);
+
+        // 2^256 does not fit
+        assert!(Eip712FieldValue::from_decimal_str(
+            "115792089237316195423570985008687907853269984665640564039457584007913129639936"
+        )
+        .is_err());
+        assert!(Eip712FieldValue::from_decimal_str("12a").is_err());
+    }
+
+    #[test]
+    fn test_from_biguint_sized() {
+        let value = Eip712FieldValue::from_biguint(&BigUint::from(0x1234u32), 4).unwrap();
+        assert_eq!(value.value, vec![0x00, 0x00, 0x12, 0x34]);
+        assert_eq!(
+            Eip712FieldValue::from_biguint(&BigUint::from(0u32), 1)
+                .unwrap()
+                .value,
+            vec![0x00]
+        );
+        assert!(Eip712FieldValue::from_biguint(&BigUint::from(256u32), 1).is_err());
+    }
+
+    #[test]
+    fn test_from_uint_sized_widths() {
+        // uint8
+        assert_eq!(
+            Eip712FieldValue::from_uint_sized(1, 0xAB).unwrap().value,
+            vec![0xAB]
+        );
+        // uint64
+        assert_eq!(
+            Eip712FieldValue::from_uint_sized(8, 0x0102).unwrap().value,
+            vec![0, 0, 0, 0, 0, 0, 0x01, 0x02]
+        );
+        // Values wider than the field are rejected, not truncated
+        assert!(Eip712FieldValue::from_uint_sized(1, 0x0100).is_err());
+        // uint256, wider than u128 itself
+        let value = Eip712FieldValue::from_uint_sized(32, u128::MAX)
+            .unwrap()
+            .value;
+        assert_eq!(&value[..16], &[0u8; 16]);
+        assert_eq!(&value[16..], &[0xFF; 16]);
+        assert_eq!(
+            value,
+            Eip712FieldValue::from_biguint(&BigUint::from(u128::MAX), 32)
+                .unwrap()
+                .value
+        );
+    }
+
+    #[test]
+    fn test_from_int_sized_sign_extends() {
+        // int256
+        assert_eq!(
+            Eip712FieldValue::from_int_sized(32, -1).unwrap().value,
+            vec![0xFF; 32]
+        );
+        // int16
+        assert_eq!(
+            Eip712FieldValue::from_int_sized(2, -2).unwrap().value,
+            vec![0xFF, 0xFE]
+        );
+        assert_eq!(
+            Eip712FieldValue::from_int_sized(2, 0x0102).unwrap().value,
+            vec![0x01, 0x02]
+        );
+        // int8 bounds
+        assert_eq!(
+            Eip712FieldValue::from_int_sized(1, 127).unwrap().value,
+            vec![0x7F]
+        );
+        assert_eq!(
+            Eip712FieldValue::from_int_sized(1, -128).unwrap().value,
+            vec![0x80]
+        );
+        // Values wider than the field are rejected, not truncated
+        assert!(Eip712FieldValue::from_int_sized(1, 128).is_err());
+        assert!(Eip712FieldValue::from_int_sized(1, -129).is_err());
+    }
+}
+
+#[cfg(test)]
+mod eip712_filter_params_tests {
+    use super::*;
+
+    #[test]
+    fn test_filter_params_constructors() {
+        assert_eq!(
+            Eip712FilterParams::activation().filter_type,
+            Eip712FilterType::Activation
+        );
+        assert_eq!(
+            Eip712FilterParams::discarded_filter_path("items.[].amount").filter_type,
+            Eip712FilterType::DiscardedFilterPath("items.[].amount".to_string())
+        );
+        assert_eq!(
+            Eip712FilterParams::message_info("Permit", 2, vec![0x01]).filter_type,
+            Eip712FilterType::MessageInfo {
+                display_name: "Permit".to_string(),
+                filters_count: 2,
+                signature: vec![0x01],
+            }
+        );
+        assert_eq!(
+            Eip712FilterParams::trusted_name("To", vec![0x01], vec![0x02], vec![0x03]).filter_type,
+            Eip712FilterType::TrustedName {
+                display_name: "To".to_string(),
+                name_types: vec![0x01],
+                name_sources: vec![0x02],
+                signature: vec![0x03],
+            }
+        );
+        assert_eq!(
+            Eip712FilterParams::date_time("Expires", vec![0x04]).filter_type,
+            Eip712FilterType::DateTime {
+                display_name: "Expires".to_string(),
+                signature: vec![0x04],
+            }
+        );
+        assert_eq!(
+            Eip712FilterParams::amount_join_token(1, vec![0x05]).filter_type,
+            Eip712FilterType::AmountJoinToken {
+                token_index: 1,
+                signature: vec![0x05],
+            }
+        );
+        assert_eq!(
+            Eip712FilterParams::amount_join_value("Amount", 1, vec![0x06]).filter_type,
+            Eip712FilterType::AmountJoinValue {
+                display_name: "Amount".to_string(),
+                token_index: 1,
+                signature: vec![0x06],
+            }
+        );
+
+        let raw = Eip712FilterParams::raw_field("Spender", vec![0x07]);
+        assert_eq!(
+            raw.filter_type,
+            Eip712FilterType::RawField {
+                display_name: "Spender".to_string(),
+                signature: vec![0x07],
+            }
+        );
+        assert!(!raw.discarded);
+        assert!(raw.discarded().discarded);
+    }
+}
+
+#[cfg(test)]
+mod signature_tests {
+    use super::*;
+
+    fn sample() -> Signature {
+        Signature::new(0x1b, vec![0x11; 32], vec![0x22; 32]).unwrap()
+    }
+
+    #[test]
+    fn test_to_rsv_layout() {
+        let rsv = sample().to_rsv();
+        assert_eq!(&rsv[..32], &[0x11; 32]);
+        assert_eq!(&rsv[32..64], &[0x22; 32]);
+        assert_eq!(rsv[64], 0x1b);
+    }
+
+    #[test]
+    fn test_rsv_round_trip() {
+        let signature = sample();
+        assert_eq!(Signature::from_rsv(&signature.to_rsv()).unwrap(), signature);
+    }
+
+    #[test]
+    fn test_normalize_v() {
+        let with_v = |v| Signature::new(v, vec![0x11; 32], vec![0x22; 32]).unwrap();
+
+        for (v, expected) in [(0, 27), (1, 28), (27, 27), (28, 28)] {
+            assert_eq!(with_v(v).normalize_v(None).unwrap().v, expected);
+        }
+        assert!(with_v(2).normalize_v(None).is_err());
+        assert!(with_v(37).normalize_v(None).is_err());
+
+        // EIP-155: mainnet 37/38, chain 137 = 309/310 truncated to 53/54
+        assert_eq!(with_v(38).normalize_v(Some(1)).unwrap().v, 28);
+        assert_eq!(with_v(53).normalize_v(Some(137)).unwrap().v, 27);
+        assert_eq!(with_v(54).normalize_v(Some(137)).unwrap().v, 28);
+        assert!(with_v(39).normalize_v(Some(1)).is_err());
+    }
+
+    #[test]
+    fn test_to_hex() {
+        let hex = sample().to_hex();
+        assert_eq!(hex.len(), 2 + 130);
+        assert_eq!(hex, format!("0x{}{}1b", "11".repeat(32), "22".repeat(32)));
+
+        let bytes: [u8; 65] = hex::decode(&hex[2..]).unwrap().try_into().unwrap();
+        assert_eq!(Signature::from_rsv(&bytes).unwrap(), sample());
+    }
+}
+
+#[cfg(test)]
+mod bip_path_tests {
+    use super::*;
+
+    #[test]
+    fn test_from_alias_eth() {
+        let path = BipPath::from_alias("eth", 0, 3).unwrap();
+        assert_eq!(path, BipPath::ethereum_standard(0, 3));
+        assert_eq!(path.to_string(), "m/44'/60'/0'/0/3");
+
+        let path = BipPath::from_alias("Polygon", 1, 0).unwrap();
+        assert_eq!(path.to_string(), "m/44'/966'/1'/0/0");
+    }
+
+    #[test]
+    fn test_from_alias_unknown() {
+        let err = BipPath::from_alias("dogecoin", 0, 0).unwrap_err();
+        assert!(err.contains("dogecoin"), "{}", err);
+        assert!(BipPath::from_alias("eth", 0x80000000, 0).is_err());
+    }
+}
+
+#[cfg(test)]
+mod sign_message_params_tests {
+    use super::*;
+
+    #[test]
+    fn test_from_text_and_hex() {
+        let path = BipPath::ethereum_standard(0, 0);
+
+        let params = SignMessageParams::from_text(path.clone(), "héllo");
+        assert_eq!(params.message, "héllo".as_bytes());
+
+        let params = SignMessageParams::from_hex(path.clone(), "0xdeadBEEF").unwrap();
+        assert_eq!(params.message, vec![0xde, 0xad, 0xbe, 0xef]);
+        assert_eq!(
+            SignMessageParams::from_hex(path.clone(), "deadbeef").unwrap(),
+            params
+        );
+
+        assert!(SignMessageParams::from_hex(path.clone(), "0xabc").is_err());
+        assert!(SignMessageParams::from_hex(path, "hello").is_err());
+    }
+}
+
+#[cfg(test)]
+mod eip7702_tests {
+    use super::*;
+
+    fn authorization() -> Eip7702Authorization {
+        Eip7702Authorization::new(1, [0x11; 20], 7)
+    }
+
+    #[test]
+    fn test_authorization_tlv() {
+        assert_eq!(
+            hex::encode(authorization().to_tlv()),
+            concat!(
+                "000101",
+                "01141111111111111111111111111111111111111111",
+                "02080000000000000001",
+                "03080000000000000007"
+            )
+        );
+    }
+
+    #[test]
+    fn test_parse_eip7702_response() {
+        let mut data = vec![0x01];
+        data.extend_from_slice(&[0xAA; 32]);
+        data.extend_from_slice(&[0xBB; 32]);
+
+        let signature = Eip7702Signature::from_response(authorization(), &data).unwrap();
+        assert_eq!(signature.authorization, authorization());
+        assert_eq!(signature.y_parity, 1);
+        assert_eq!(signature.r, vec![0xAA; 32]);
+        assert_eq!(signature.s, vec![0xBB; 32]);
+        assert_eq!(signature.to_signature().v, 1);
+
+        data[0] = 27;
+        let signature = Eip7702Signature::from_response(authorization(), &data).unwrap();
+        assert_eq!(signature.y_parity, 0);
+
+        data[0] = 37;
+        assert!(Eip7702Signature::from_response(authorization(), &data).is_err());
+        assert!(Eip7702Signature::from_response(authorization(), &data[..64]).is_err());
+        data[0] = 0;
+        data.push(0x00);
+        assert!(Eip7702Signature::from_response(authorization(), &data).is_err());
+    }
+}
+
+#[cfg(test)]
+mod eth_address_tests {
+    use super::*;
+
+    // EIP-55 specification vectors
+    const CHECKSUMMED: [&str; 6] = [
+        "0x52908400098527886E0F7030069857D2E4169EE7",
+        "0xde709f2102306220921060314715629080e2fb77",
+        "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAed",
+        "0xfB6916095ca1df60bB79Ce92cE3Ea74c37c5d359",
+        "0xdbF03B407c01E7cD3CBea99509d93f8DDDC8C6FB",
+        "0xD1220A0cf47c7B9Be7A2E6BA89F429762e7b9aDb",
+    ];
+
+    #[test]
+    fn test_eip55_vectors() {
+        for expected in CHECKSUMMED {
+            let lower = EthAddress::new(expected.to_lowercase()).unwrap();
+            assert_eq!(lower.to_checksummed(), expected);
+
+            let address = EthAddress::new_checksummed(expected.to_string()).unwrap();
+            assert!(address.is_valid_checksum());
+        }
+    }
+
+    #[test]
+    fn test_wrong_checksum_and_non_hex() {
+        // Last letter's case flipped
+        let typo = "0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeD";
+        let address = EthAddress::new(typo.to_string()).unwrap();
+        assert!(!address.is_valid_checksum());
+        assert!(EthAddress::new_checksummed(typo.to_string()).is_err());
+
+        assert!(EthAddress::new("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAeg".to_string()).is_err());
+        assert!(EthAddress::new("0x5aAeb6053F3E94C9b9A09f33669435E7Ef1BeAé".to_string()).is_err());
+    }
+}
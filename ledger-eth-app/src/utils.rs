@@ -2,9 +2,17 @@
 
 //! Utility functions for Ethereum application
 
+use ledger_sdk_transport::APDUCommand;
+use sha3::{Digest, Keccak256};
+
 use crate::errors::{EthAppError, EthAppResult};
 use crate::instructions::length;
-use crate::types::{BipPath, EthAddress};
+use crate::types::{BipPath, Eip712Domain, Eip712Struct, EthAddress, PathPolicy, Signature};
+
+/// Keccak-256 hash
+pub fn keccak256(data: &[u8]) -> [u8; 32] {
+    Keccak256::digest(data).into()
+}
 
 /// Encode BIP32 path for APDU command
 pub fn encode_bip32_path(path: &BipPath) -> Vec<u8> {
@@ -65,35 +73,81 @@ pub fn decode_bip32_path<E: std::error::Error>(data: &[u8]) -> EthAppResult<(Bip
     Ok((path, offset))
 }
 
-/// Validate BIP32 path for Ethereum usage
+/// Validate BIP32 path for Ethereum usage, with the default [`PathPolicy::Strict`]
 pub fn validate_bip32_path<E: std::error::Error>(path: &BipPath) -> EthAppResult<(), E> {
-    if path.indices.is_empty() {
-        return Err(EthAppError::InvalidBip32Path("Empty path".to_string()));
-    }
+    validate_bip32_path_with_policy(path, PathPolicy::Strict)
+}
 
-    if path.indices.len() > length::MAX_BIP32_PATH_DEPTH {
-        return Err(EthAppError::InvalidBip32Path(format!(
-            "Path too deep: {} (max {})",
-            path.indices.len(),
+/// Validate BIP32 path according to `policy`
+///
+/// Errors name the policy and the reason, e.g. "m/44'/60'/0/0/0 rejected by
+/// strict policy: account index must be hardened".
+pub fn validate_bip32_path_with_policy<E: std::error::Error>(
+    path: &BipPath,
+    policy: PathPolicy,
+) -> EthAppResult<(), E> {
+    check_path_policy(&path.indices, policy).map_err(|reason| {
+        EthAppError::InvalidBip32Path(format!(
+            "{} rejected by {} policy: {}",
+            path, policy, reason
+        ))
+    })
+}
+
+/// Reason `indices` breaks `policy`, if it does
+fn check_path_policy(indices: &[u32], policy: PathPolicy) -> Result<(), String> {
+    const HARDENED: u32 = 0x8000_0000;
+    const PURPOSE: u32 = HARDENED | 44;
+    const ETH_COIN_TYPE: u32 = HARDENED | 60;
+    const TESTNET_COIN_TYPE: u32 = HARDENED | 1;
+    // EIP-2334: m / 12381 / 3600 / account / 0 [/ 0], all unhardened
+    const BLS_PURPOSE: u32 = 12381;
+    const BLS_COIN_TYPE: u32 = 3600;
+
+    if indices.is_empty() {
+        return Err("empty path".to_string());
+    }
+    if indices.len() > length::MAX_BIP32_PATH_DEPTH {
+        return Err(format!(
+            "too deep: {} (max {})",
+            indices.len(),
             length::MAX_BIP32_PATH_DEPTH
-        )));
+        ));
     }
 
-    // Validate Ethereum standard path format (optional)
-    if path.indices.len() >= 2 {
-        // Check for standard Ethereum derivation (m/44'/60'/...)
-        if path.indices.len() >= 3 && path.indices[0] == 0x8000002C && path.indices[1] == 0x8000003C
-        {
-            // Standard Ethereum path: ensure account is hardened
-            if (path.indices[2] & 0x80000000) == 0 {
-                return Err(EthAppError::InvalidBip32Path(
-                    "Account index should be hardened for Ethereum".to_string(),
-                ));
+    let has_prefix =
+        |coin_type: u32| indices.len() >= 3 && indices[0] == PURPOSE && indices[1] == coin_type;
+    let hardened_account = || {
+        if indices[2] & HARDENED == 0 {
+            Err("account index must be hardened".to_string())
+        } else {
+            Ok(())
+        }
+    };
+
+    match policy {
+        PathPolicy::Permissive => Ok(()),
+        // Standard Ethereum path: ensure account is hardened
+        PathPolicy::Strict if has_prefix(ETH_COIN_TYPE) => hardened_account(),
+        PathPolicy::Strict => Ok(()),
+        PathPolicy::EthereumOnly if has_prefix(ETH_COIN_TYPE) => hardened_account(),
+        PathPolicy::EthereumOnly => Err("expected m/44'/60'/account'/...".to_string()),
+        PathPolicy::Testnet if has_prefix(TESTNET_COIN_TYPE) => hardened_account(),
+        PathPolicy::Testnet => Err("expected m/44'/1'/account'/...".to_string()),
+        PathPolicy::Eth2Validator => {
+            let eip2334 = matches!(indices.len(), 4 | 5)
+                && indices[0] == BLS_PURPOSE
+                && indices[1] == BLS_COIN_TYPE
+                && indices[3..].iter().all(|&index| index == 0);
+            if !eip2334 {
+                Err("expected m/12381/3600/i/0 or m/12381/3600/i/0/0".to_string())
+            } else if indices[2] & HARDENED != 0 {
+                Err("EIP-2334 indices are not hardened".to_string())
+            } else {
+                Ok(())
             }
         }
     }
-
-    Ok(())
 }
 
 /// Encode chain ID for APDU command (8 bytes big-endian)
@@ -126,6 +180,116 @@ pub fn decode_chain_id<E: std::error::Error>(data: &[u8]) -> EthAppResult<u64, E
     Ok(chain_id)
 }
 
+/// EIP-712 domain separator, `keccak256(typeHash || encodeData(domain))`
+///
+/// `domain_type` is the `EIP712Domain` entry of the typed data types: it sets
+/// which domain fields are hashed, and in which order. Compare the result with
+/// a known value, e.g. the `DOMAIN_SEPARATOR()` of the verifying contract, to
+/// check the domain before signing.
+pub fn eip712_domain_separator(
+    domain: &Eip712Domain,
+    domain_type: &Eip712Struct,
+) -> Result<[u8; 32], String> {
+    fn missing(field: &str) -> String {
+        format!("Domain has no value for EIP712Domain field '{}'", field)
+    }
+
+    let type_string = format!(
+        "EIP712Domain({})",
+        domain_type
+            .fields
+            .iter()
+            .map(|field| format!("{} {}", field.r#type, field.name))
+            .collect::<Vec<_>>()
+            .join(",")
+    );
+
+    let mut encoded = keccak256(type_string.as_bytes()).to_vec();
+    for field in &domain_type.fields {
+        let mut word = [0u8; 32];
+        match (field.name.as_str(), field.r#type.as_str()) {
+            ("name", "string") => {
+                let name = domain.name.as_ref().ok_or_else(|| missing("name"))?;
+                word = keccak256(name.as_bytes());
+            }
+            ("version", "string") => {
+                let version = domain.version.as_ref().ok_or_else(|| missing("version"))?;
+                word = keccak256(version.as_bytes());
+            }
+            ("chainId", "uint256") => {
+                let chain_id = domain.chain_id.ok_or_else(|| missing("chainId"))?;
+                word[24..].copy_from_slice(&chain_id.to_be_bytes());
+            }
+            ("verifyingContract", "address") => {
+                let address = domain
+                    .verifying_contract
+                    .as_ref()
+                    .ok_or_else(|| missing("verifyingContract"))?;
+                let bytes = EthAddress::new(address.clone())?
+                    .to_bytes()
+                    .map_err(|e| format!("Invalid verifying contract: {}", e))?;
+                word[12..].copy_from_slice(&bytes);
+            }
+            ("salt", "bytes32") => {
+                let salt = domain.salt.as_ref().ok_or_else(|| missing("salt"))?;
+                word = salt
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| format!("Salt must be 32 bytes, got {}", salt.len()))?;
+            }
+            (name, r#type) => {
+                return Err(format!(
+                    "Unsupported EIP712Domain field '{} {}'",
+                    r#type, name
+                ));
+            }
+        }
+        encoded.extend_from_slice(&word);
+    }
+
+    Ok(keccak256(&encoded))
+}
+
+/// Parse a `v || r || s` signature response
+///
+/// Every signing command of the Ethereum app answers with exactly these 65
+/// bytes, so device responses go through this strict version: a short answer
+/// is a protocol error, not a signature without recovery id.
+pub fn parse_signature_response<E: std::error::Error>(data: &[u8]) -> EthAppResult<Signature, E> {
+    if data.len() != 65 {
+        return Err(EthAppError::InvalidResponseData(format!(
+            "Invalid signature response length: {} bytes (expected 65)",
+            data.len()
+        )));
+    }
+
+    let v = data[0];
+    let r = data[1..33].to_vec();
+    let s = data[33..65].to_vec();
+
+    Signature::new(v, r, s).map_err(EthAppError::InvalidSignature)
+}
+
+/// Parse a `v || r || s` signature or a 64-byte compact `r || s` one
+///
+/// For signatures from other sources (test vectors, external signers) that may
+/// omit the recovery id. A compact signature gets `v = 0`; the caller derives
+/// the actual value, e.g. by trying both recovery ids against the expected
+/// address. Device responses use [`parse_signature_response`].
+pub fn parse_signature_response_flexible<E: std::error::Error>(
+    data: &[u8],
+) -> EthAppResult<Signature, E> {
+    match data.len() {
+        65 => parse_signature_response(data),
+        64 => Signature::new(0, data[..32].to_vec(), data[32..].to_vec())
+            .map_err(EthAppError::InvalidSignature),
+        len => Err(EthAppError::InvalidResponseData(format!(
+            "Invalid signature response length: {} bytes (expected 64 or 65)",
+            len
+        ))),
+    }
+}
+
 /// Split data into chunks for multi-chunk APDU operations
 pub fn chunk_data(data: &[u8], chunk_size: usize) -> Vec<Vec<u8>> {
     if chunk_size == 0 {
@@ -137,6 +301,144 @@ pub fn chunk_data(data: &[u8], chunk_size: usize) -> Vec<Vec<u8>> {
         .collect()
 }
 
+/// Frames a payload streamed over several APDUs of the same instruction
+///
+/// The first frame carries the prefix (e.g. the BIP32 path) and as much of the
+/// body as fits; the following frames carry up to `MAX_MESSAGE_CHUNK_SIZE` bytes
+/// of body each. An empty body is sent as a single frame.
+///
+/// A body streamed from elsewhere is framed with [`ChunkedPayload::chunk_sizes`]
+/// and [`ChunkedPayload::frame`], leaving the payload's own body empty.
+#[derive(Clone, Debug)]
+pub struct ChunkedPayload<'a> {
+    cla: u8,
+    ins: u8,
+    p1_first: u8,
+    p1_subsequent: u8,
+    p1_last: Option<u8>,
+    p2: u8,
+    prefix: Vec<u8>,
+    body: &'a [u8],
+}
+
+impl<'a> ChunkedPayload<'a> {
+    /// Frame `body` for the instruction `ins`, with P1 and P2 set to 0
+    pub fn new(cla: u8, ins: u8, body: &'a [u8]) -> Self {
+        ChunkedPayload {
+            cla,
+            ins,
+            p1_first: 0x00,
+            p1_subsequent: 0x00,
+            p1_last: None,
+            p2: 0x00,
+            prefix: Vec::new(),
+            body,
+        }
+    }
+
+    /// P1 of the first frame and of the following ones
+    pub fn with_p1(mut self, first: u8, subsequent: u8) -> Self {
+        self.p1_first = first;
+        self.p1_subsequent = subsequent;
+        self
+    }
+
+    /// P1 of the last frame, overriding the first or subsequent P1
+    pub fn with_last_p1(mut self, last: u8) -> Self {
+        self.p1_last = Some(last);
+        self
+    }
+
+    /// P2 of every frame
+    pub fn with_p2(mut self, p2: u8) -> Self {
+        self.p2 = p2;
+        self
+    }
+
+    /// Bytes sent before the body in the first frame
+    pub fn with_prefix(mut self, prefix: Vec<u8>) -> Self {
+        self.prefix = prefix;
+        self
+    }
+
+    /// Body bytes the first frame can carry, `None` if the prefix leaves no
+    /// room for any
+    pub fn first_frame_capacity(&self) -> Option<usize> {
+        length::MAX_MESSAGE_CHUNK_SIZE
+            .checked_sub(self.prefix.len())
+            .filter(|&capacity| capacity > 0)
+    }
+
+    /// Body bytes carried by each frame of a `len`-byte body, in sending order
+    pub fn chunk_sizes(&self, len: usize) -> impl Iterator<Item = usize> {
+        let first = len.min(self.first_frame_capacity().unwrap_or(0));
+        let rest = len - first;
+        std::iter::once(first).chain((0..rest.div_ceil(length::MAX_MESSAGE_CHUNK_SIZE)).map(
+            move |i| {
+                (rest - i * length::MAX_MESSAGE_CHUNK_SIZE).min(length::MAX_MESSAGE_CHUNK_SIZE)
+            },
+        ))
+    }
+
+    /// Frame number `index` carrying `chunk`, `last` when no frame follows
+    pub fn frame(&self, index: usize, chunk: &[u8], last: bool) -> APDUCommand<Vec<u8>> {
+        let p1 = match self.p1_last {
+            Some(p1) if last => p1,
+            _ if index == 0 => self.p1_first,
+            _ => self.p1_subsequent,
+        };
+        let data = if index == 0 {
+            let mut data = Vec::with_capacity(self.prefix.len() + chunk.len());
+            data.extend_from_slice(&self.prefix);
+            data.extend_from_slice(chunk);
+            data
+        } else {
+            chunk.to_vec()
+        };
+
+        APDUCommand {
+            cla: self.cla,
+            ins: self.ins,
+            p1,
+            p2: self.p2,
+            data,
+        }
+    }
+
+    /// Every frame, in sending order
+    pub fn commands(&self) -> Vec<APDUCommand<Vec<u8>>> {
+        let sizes: Vec<usize> = self.chunk_sizes(self.body.len()).collect();
+        let mut body = self.body;
+        sizes
+            .iter()
+            .enumerate()
+            .map(|(index, &size)| {
+                let (chunk, rest) = body.split_at(size);
+                body = rest;
+                self.frame(index, chunk, index + 1 == sizes.len())
+            })
+            .collect()
+    }
+}
+
+/// Prefix a payload with its 2-byte length and split it into APDU sized chunks
+///
+/// Used by the TLV descriptor commands, whose first chunk carries the total length.
+pub fn chunk_length_prefixed<E: std::error::Error>(
+    payload: &[u8],
+) -> EthAppResult<Vec<Vec<u8>>, E> {
+    let len = u16::try_from(payload.len()).map_err(|_| EthAppError::MessageTooLarge {
+        size: payload.len(),
+        max: u16::MAX as usize,
+    })?;
+
+    let mut data = Vec::with_capacity(2 + payload.len());
+    data.extend_from_slice(&len.to_be_bytes());
+    data.extend_from_slice(payload);
+
+    Ok(chunk_data(&data, length::MAX_MESSAGE_CHUNK_SIZE))
+}
+
 /// Validate Ethereum address format
 pub fn validate_ethereum_address<E: std::error::Error>(address: &str) -> EthAppResult<(), E> {
     println!("validate_ethereum_address: {}", address);
@@ -282,6 +584,88 @@ pub fn parse_device_chain_code<E: std::error::Error>(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::Eip712Field;
+
+    fn domain_type(fields: &[(&str, &str)]) -> Eip712Struct {
+        fields
+            .iter()
+            .fold(Eip712Struct::new(), |domain_type, (name, r#type)| {
+                domain_type.with_field(Eip712Field::new(name.to_string(), r#type.to_string()))
+            })
+    }
+
+    #[test]
+    fn test_eip712_domain_separator() {
+        let full = domain_type(&[
+            ("name", "string"),
+            ("version", "string"),
+            ("chainId", "uint256"),
+            ("verifyingContract", "address"),
+        ]);
+
+        // USDC on Ethereum mainnet, `DOMAIN_SEPARATOR()` of the token contract
+        let usdc = Eip712Domain::new()
+            .with_name("USD Coin".to_string())
+            .with_version("2".to_string())
+            .with_chain_id(1)
+            .with_verifying_contract("0xA0b86991c6218b36c1d19D4a2e9Eb0cE3606eB48".to_string());
+        assert_eq!(
+            hex::encode(eip712_domain_separator(&usdc, &full).unwrap()),
+            "06c37168a7db5138defc7866392bb87a741f9b3d104deb5094588ce041cae335"
+        );
+
+        // Mail example from the EIP-712 specification
+        let mail = Eip712Domain::new()
+            .with_name("Ether Mail".to_string())
+            .with_version("1".to_string())
+            .with_chain_id(1)
+            .with_verifying_contract("0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC".to_string());
+        assert_eq!(
+            hex::encode(eip712_domain_separator(&mail, &full).unwrap()),
+            "f2cee375fa42b42143804025fc449deafd50cc031ca257e0b194a650a912090f"
+        );
+
+        // Fields follow the domain type, not the values set on the domain
+        let partial = domain_type(&[("name", "string")]);
+        assert_ne!(
+            eip712_domain_separator(&mail, &partial).unwrap(),
+            eip712_domain_separator(&mail, &full).unwrap()
+        );
+        let err = eip712_domain_separator(&Eip712Domain::new(), &partial).unwrap_err();
+        assert!(err.contains("'name'"), "{}", err);
+        let unknown = domain_type(&[("owner", "address")]);
+        assert!(eip712_domain_separator(&mail, &unknown).is_err());
+    }
+
+    #[test]
+    fn test_parse_signature_response_flexible() {
+        let mut rsv = vec![0x1c];
+        rsv.extend_from_slice(&[0x11; 32]);
+        rsv.extend_from_slice(&[0x22; 32]);
+
+        let signature = parse_signature_response_flexible::<std::io::Error>(&rsv).unwrap();
+        assert_eq!(signature.v, 0x1c);
+        assert_eq!(signature.r, vec![0x11; 32]);
+        assert_eq!(signature.s, vec![0x22; 32]);
+
+        let signature = parse_signature_response_flexible::<std::io::Error>(&rsv[1..]).unwrap();
+        assert_eq!(signature.v, 0);
+        assert_eq!(signature.r, vec![0x11; 32]);
+        assert_eq!(signature.s, vec![0x22; 32]);
+
+        // The strict version still only takes 65 bytes
+        assert!(parse_signature_response::<std::io::Error>(&rsv[1..]).is_err());
+
+        for len in [0, 63, 66] {
+            let err =
+                parse_signature_response_flexible::<std::io::Error>(&vec![0x11; len]).unwrap_err();
+            assert!(
+                matches!(err, EthAppError::InvalidResponseData(_)),
+                "{}",
+                len
+            );
+        }
+    }
 
     #[test]
     fn test_encode_bip32_path() {
@@ -293,6 +677,81 @@ mod tests {
         assert_eq!(&encoded[5..9], &0x8000003Cu32.to_be_bytes());
     }
 
+    #[test]
+    fn test_validate_bip32_path_policies() {
+        let check = |path: &str, policy| {
+            validate_bip32_path_with_policy::<std::io::Error>(
+                &BipPath::from_string(path).unwrap(),
+                policy,
+            )
+            .is_ok()
+        };
+
+        // (path, Strict, EthereumOnly, Eth2Validator, Testnet, Permissive)
+        let cases = [
+            ("m/44'/60'/0'/0/0", true, true, false, false, true),
+            ("m/44'/60'/7'/0/0", true, true, false, false, true), // Ledger Live
+            ("m/44'/60'/0'/0", true, true, false, false, true),   // Ledger Live legacy
+            ("m/44'/60'/0'", true, true, false, false, true),     // MEW legacy
+            ("m/44'/60'/0/0/0", false, false, false, false, true), // unhardened account
+            ("m/44'/0'/0'/0/0", true, false, false, false, true), // other coin type
+            ("m/44'/60'", true, false, false, false, true),
+            ("m/0", true, false, false, false, true),
+            ("m/44'/1'/0'/0/0", true, false, false, true, true),
+            ("m/44'/1'/0/0/0", true, false, false, false, true),
+            ("m/12381/3600/0/0", true, false, true, false, true), // EIP-2334 withdrawal
+            ("m/12381/3600/5/0/0", true, false, true, false, true), // EIP-2334 signing
+            ("m/12381/3600/5'/0/0", true, false, false, false, true),
+            ("m/12381/3600/0/1", true, false, false, false, true),
+            ("m/12381/60/0/0", true, false, false, false, true),
+        ];
+        for (path, strict, ethereum_only, eth2, testnet, permissive) in cases {
+            assert_eq!(check(path, PathPolicy::Strict), strict, "{}", path);
+            assert_eq!(
+                check(path, PathPolicy::EthereumOnly),
+                ethereum_only,
+                "{}",
+                path
+            );
+            assert_eq!(check(path, PathPolicy::Eth2Validator), eth2, "{}", path);
+            assert_eq!(check(path, PathPolicy::Testnet), testnet, "{}", path);
+            assert_eq!(check(path, PathPolicy::Permissive), permissive, "{}", path);
+        }
+
+        // Errors name the policy and the reason
+        let err = validate_bip32_path_with_policy::<std::io::Error>(
+            &BipPath::from_string("m/44'/60'/0/0/0").unwrap(),
+            PathPolicy::Strict,
+        )
+        .unwrap_err();
+        assert!(matches!(
+            err,
+            EthAppError::InvalidBip32Path(ref reason)
+                if reason == "m/44'/60'/0/0/0 rejected by strict policy: account index must be hardened"
+        ));
+        let err = validate_bip32_path_with_policy::<std::io::Error>(
+            &BipPath::from_string("m/44'/60'/0'/0/0").unwrap(),
+            PathPolicy::Eth2Validator,
+        )
+        .unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("ETH2 validator policy: expected m/12381/3600"),
+            "{}",
+            err
+        );
+
+        // Bypasses the depth check of BipPath::new
+        let too_deep = BipPath {
+            indices: vec![0; 11],
+        };
+        assert!(validate_bip32_path_with_policy::<std::io::Error>(
+            &too_deep,
+            PathPolicy::Permissive
+        )
+        .is_err());
+    }
+
     #[test]
     fn test_validate_ethereum_address() {
         assert!(validate_ethereum_address::<std::io::Error>(
@@ -368,4 +827,105 @@ mod tests {
         assert_eq!(chunks[2], vec![7, 8, 9]);
         assert_eq!(chunks[3], vec![10]);
     }
+
+    fn framed(body: &[u8], prefix_len: usize) -> Vec<(u8, usize)> {
+        ChunkedPayload::new(0xE0, 0x04, body)
+            .with_p1(0x00, 0x80)
+            .with_prefix(vec![0xAA; prefix_len])
+            .commands()
+            .iter()
+            .map(|command| (command.p1, command.data.len()))
+            .collect()
+    }
+
+    #[test]
+    fn test_chunked_payload_boundaries() {
+        // Empty body: the prefix alone
+        assert_eq!(framed(&[], 21), vec![(0x00, 21)]);
+        // Exactly one full frame
+        assert_eq!(framed(&[0x01; 234], 21), vec![(0x00, 255)]);
+        // One byte over
+        assert_eq!(framed(&[0x01; 235], 21), vec![(0x00, 255), (0x80, 1)]);
+        // Full first frame, then a full subsequent one
+        assert_eq!(
+            framed(&[0x01; 234 + 255], 21),
+            vec![(0x00, 255), (0x80, 255)]
+        );
+        // Multiples of the frame size without a prefix
+        assert_eq!(framed(&[0x01; 510], 0), vec![(0x00, 255), (0x80, 255)]);
+        assert_eq!(
+            framed(&[0x01; 511], 0),
+            vec![(0x00, 255), (0x80, 255), (0x80, 1)]
+        );
+    }
+
+    #[test]
+    fn test_chunked_payload_layout() {
+        let body: Vec<u8> = (0..=255).collect();
+        let commands = ChunkedPayload::new(0xE0, 0x04, &body)
+            .with_p1(0x00, 0x80)
+            .with_p2(0x01)
+            .with_prefix(vec![0xAA; 5])
+            .commands();
+
+        assert_eq!(commands.len(), 2);
+        assert_eq!(&commands[0].data[..5], &[0xAA; 5]);
+        assert_eq!(&commands[0].data[5..], &body[..250]);
+        assert_eq!(commands[1].data, &body[250..]);
+        assert!(commands.iter().all(|command| command.p2 == 0x01));
+    }
+
+    #[test]
+    fn test_chunked_payload_last_p1() {
+        let p1s = |len: usize| -> Vec<u8> {
+            ChunkedPayload::new(0xE0, 0x1C, &vec![0x01; len])
+                .with_p1(0x01, 0x01)
+                .with_last_p1(0x00)
+                .with_prefix(vec![0x00; 2])
+                .commands()
+                .iter()
+                .map(|command| command.p1)
+                .collect()
+        };
+
+        assert_eq!(p1s(0), vec![0x00]);
+        assert_eq!(p1s(253), vec![0x00]);
+        assert_eq!(p1s(254), vec![0x01, 0x00]);
+    }
+
+    #[test]
+    fn test_chunked_payload_streamed() {
+        let body: Vec<u8> = (0..600u32).map(|i| i as u8).collect();
+        let buffered = ChunkedPayload::new(0xE0, 0x08, &body)
+            .with_p1(0x00, 0x80)
+            .with_prefix(vec![0xAA; 25])
+            .commands();
+
+        // Same frames when the body is fed in chunks from elsewhere
+        let payload = ChunkedPayload::new(0xE0, 0x08, &[])
+            .with_p1(0x00, 0x80)
+            .with_prefix(vec![0xAA; 25]);
+        let sizes: Vec<usize> = payload.chunk_sizes(body.len()).collect();
+        assert_eq!(sizes, vec![230, 255, 115]);
+        let mut rest = &body[..];
+        for (index, &size) in sizes.iter().enumerate() {
+            let (chunk, tail) = rest.split_at(size);
+            rest = tail;
+            let frame = payload.frame(index, chunk, index + 1 == sizes.len());
+            assert_eq!(
+                (frame.p1, &frame.data),
+                (buffered[index].p1, &buffered[index].data)
+            );
+        }
+    }
+
+    #[test]
+    fn test_chunked_payload_capacity() {
+        let payload =
+            |prefix_len| ChunkedPayload::new(0xE0, 0x04, &[]).with_prefix(vec![0; prefix_len]);
+        assert_eq!(payload(21).first_frame_capacity(), Some(234));
+        assert_eq!(payload(254).first_frame_capacity(), Some(1));
+        assert_eq!(payload(255).first_frame_capacity(), None);
+        assert_eq!(payload(300).first_frame_capacity(), None);
+    }
 }
@@ -0,0 +1,68 @@
+//! Compile-time checks that the public command paths keep resolving
+
+use ledger_sdk_eth_app::commands::eip712::{self, signing, structs};
+use ledger_sdk_eth_app::commands::sign_transaction::TransactionMode;
+use ledger_sdk_eth_app::{commands, EthApp};
+use ledger_sdk_transport::mock::MockExchange;
+
+fn eip712_submodule_traits<T>()
+where
+    T: structs::Eip712StructDef<MockExchange> + structs::Eip712StructImpl<MockExchange>,
+{
+}
+
+fn eip712_module_traits<T>()
+where
+    T: eip712::Eip712Filtering<MockExchange>,
+{
+}
+
+fn commands_module_traits<T>()
+where
+    T: commands::Eip712StructDef<MockExchange>
+        + commands::Eip712StructImpl<MockExchange>
+        + commands::SignEip712Full<MockExchange>,
+{
+}
+
+fn crate_root_traits<T>()
+where
+    T: ledger_sdk_eth_app::Eip712Filtering<MockExchange>
+        + ledger_sdk_eth_app::Eip712StructDef<MockExchange>
+        + ledger_sdk_eth_app::Eip712StructImpl<MockExchange>
+        + ledger_sdk_eth_app::GetAddress<MockExchange>
+        + ledger_sdk_eth_app::GetConfiguration<MockExchange>
+        + ledger_sdk_eth_app::GetEth2PublicKey<MockExchange>
+        + ledger_sdk_eth_app::PerformPrivacyOperation<MockExchange>
+        + ledger_sdk_eth_app::ProvideNetworkInformation<MockExchange>
+        + ledger_sdk_eth_app::ProvideNftInformation<MockExchange>
+        + ledger_sdk_eth_app::ProvideSafeAccount<MockExchange>
+        + ledger_sdk_eth_app::ProvideTxSimulation<MockExchange>
+        + ledger_sdk_eth_app::SetEth2WithdrawalIndex<MockExchange>
+        + ledger_sdk_eth_app::SetExternalPlugin<MockExchange>
+        + ledger_sdk_eth_app::SetPlugin<MockExchange>
+        + ledger_sdk_eth_app::SignEip712Full<MockExchange>
+        + ledger_sdk_eth_app::SignEip712TypedData<MockExchange>
+        + ledger_sdk_eth_app::SignEip712V0<MockExchange>
+        + ledger_sdk_eth_app::SignPersonalMessage<MockExchange>
+        + ledger_sdk_eth_app::SignTransaction<MockExchange>,
+{
+}
+
+#[test]
+fn test_command_traits_resolve() {
+    eip712_submodule_traits::<EthApp>();
+    eip712_module_traits::<EthApp>();
+    commands_module_traits::<EthApp>();
+    crate_root_traits::<EthApp>();
+}
+
+#[test]
+fn test_public_paths_resolve() {
+    let _ = TransactionMode::ProcessAndStart;
+
+    let response = [0x1b; 65];
+    assert!(signing::parse_signature_response::<std::io::Error>(&response).is_ok());
+    assert!(eip712::parse_signature_response::<std::io::Error>(&response).is_ok());
+    assert!(commands::parse_signature_response::<std::io::Error>(&response).is_ok());
+}
@@ -0,0 +1,207 @@
+use hidapi::DeviceInfo;
+use ledger_sdk_device_base::DeviceModel;
+use serde::{Deserialize, Serialize};
+
+use crate::{pid, LEDGER_USAGE_PAGE, LEDGER_VENDOR_ID};
+
+/// Ledger model of a USB product ID, whether or not the device is in its bootloader
+pub fn model_from_product_id(product_id: u16) -> DeviceModel {
+    // Outside the bootloader the model is the high byte, the low byte lists the
+    // enabled USB interfaces (e.g. 0x4011 for a Nano X)
+    let model_id = if product_id > 0xFF {
+        product_id >> 8
+    } else {
+        product_id
+    };
+    match model_id {
+        pid::BLUE => DeviceModel::Blue,
+        pid::NANO_S | pid::NANO_S_BL => DeviceModel::NanoS,
+        pid::NANO_S_PLUS | pid::NANO_S_PLUS_BL => DeviceModel::NanoSPlus,
+        pid::NANO_X | pid::NANO_X_BL => DeviceModel::NanoX,
+        pid::STAX | pid::STAX_BL => DeviceModel::Stax,
+        pid::FLEX | pid::FLEX_BL => DeviceModel::Flex,
+        _ => DeviceModel::Unknown,
+    }
+}
+
+/// Whether a USB product ID is the one of a device running its bootloader
+pub fn is_bootloader_product_id(product_id: u16) -> bool {
+    matches!(
+        product_id,
+        pid::NANO_S_BL | pid::NANO_S_PLUS_BL | pid::NANO_X_BL | pid::STAX_BL | pid::FLEX_BL
+    )
+}
+
+/// Connected Ledger device, as listed by [`crate::TransportNativeHID::list_devices`]
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LedgerDeviceInfo {
+    /// Model, also set in the bootloader; see [`LedgerDeviceInfo::is_bootloader`]
+    pub model: DeviceModel,
+    pub product_id: u16,
+    pub serial_number: Option<String>,
+    /// Platform specific HID path, accepted by `open_by_path`
+    pub path: String,
+}
+
+/// Fields of a HID device entry needed to detect Ledgers.
+///
+/// Implemented for [`DeviceInfo`]; tests use synthetic entries since `DeviceInfo`
+/// cannot be built outside hidapi.
+pub(crate) trait HidDeviceInfo {
+    fn vendor_id(&self) -> u16;
+    fn product_id(&self) -> u16;
+    fn usage_page(&self) -> u16;
+    fn serial_number(&self) -> Option<&str>;
+    fn path(&self) -> String;
+}
+
+impl HidDeviceInfo for DeviceInfo {
+    fn vendor_id(&self) -> u16 {
+        DeviceInfo::vendor_id(self)
+    }
+
+    fn product_id(&self) -> u16 {
+        DeviceInfo::product_id(self)
+    }
+
+    fn usage_page(&self) -> u16 {
+        DeviceInfo::usage_page(self)
+    }
+
+    fn serial_number(&self) -> Option<&str> {
+        DeviceInfo::serial_number(self)
+    }
+
+    fn path(&self) -> String {
+        DeviceInfo::path(self).to_string_lossy().into_owned()
+    }
+}
+
+pub(crate) fn is_ledger<D: HidDeviceInfo>(dev: &D) -> bool {
+    dev.vendor_id() == LEDGER_VENDOR_ID && dev.usage_page() == LEDGER_USAGE_PAGE
+}
+
+impl LedgerDeviceInfo {
+    /// Whether the device is running its bootloader
+    pub fn is_bootloader(&self) -> bool {
+        is_bootloader_product_id(self.product_id)
+    }
+
+    pub(crate) fn detect<D: HidDeviceInfo>(dev: &D) -> Option<Self> {
+        if !is_ledger(dev) {
+            return None;
+        }
+        Some(LedgerDeviceInfo {
+            model: model_from_product_id(dev.product_id()),
+            product_id: dev.product_id(),
+            serial_number: dev.serial_number().map(str::to_string),
+            path: dev.path(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeDeviceInfo {
+        vendor_id: u16,
+        product_id: u16,
+        usage_page: u16,
+        serial_number: Option<&'static str>,
+        path: &'static str,
+    }
+
+    impl HidDeviceInfo for FakeDeviceInfo {
+        fn vendor_id(&self) -> u16 {
+            self.vendor_id
+        }
+
+        fn product_id(&self) -> u16 {
+            self.product_id
+        }
+
+        fn usage_page(&self) -> u16 {
+            self.usage_page
+        }
+
+        fn serial_number(&self) -> Option<&str> {
+            self.serial_number
+        }
+
+        fn path(&self) -> String {
+            self.path.to_string()
+        }
+    }
+
+    fn ledger(product_id: u16, path: &'static str) -> FakeDeviceInfo {
+        FakeDeviceInfo {
+            vendor_id: LEDGER_VENDOR_ID,
+            product_id,
+            usage_page: LEDGER_USAGE_PAGE,
+            serial_number: Some("0001"),
+            path,
+        }
+    }
+
+    #[test]
+    fn test_model_from_product_id() {
+        let cases = [
+            (pid::NANO_S, pid::NANO_S_BL, DeviceModel::NanoS),
+            (
+                pid::NANO_S_PLUS,
+                pid::NANO_S_PLUS_BL,
+                DeviceModel::NanoSPlus,
+            ),
+            (pid::NANO_X, pid::NANO_X_BL, DeviceModel::NanoX),
+            (pid::STAX, pid::STAX_BL, DeviceModel::Stax),
+            (pid::FLEX, pid::FLEX_BL, DeviceModel::Flex),
+        ];
+        for (product_id, bootloader_id, model) in cases {
+            assert_eq!(model_from_product_id(product_id), model);
+            // Same model with USB interfaces in the low byte
+            assert_eq!(model_from_product_id(product_id << 8 | 0x11), model);
+            assert_eq!(model_from_product_id(bootloader_id), model);
+            assert!(is_bootloader_product_id(bootloader_id));
+            assert!(!is_bootloader_product_id(product_id << 8 | 0x11));
+        }
+        assert_eq!(model_from_product_id(pid::BLUE), DeviceModel::Blue);
+        assert_eq!(model_from_product_id(0x2011), DeviceModel::Unknown);
+    }
+
+    #[test]
+    fn test_detect_ledgers() {
+        let other_vendor = FakeDeviceInfo {
+            vendor_id: 0x046d,
+            ..ledger(0x4011, "/dev/hidraw0")
+        };
+        let other_interface = FakeDeviceInfo {
+            usage_page: 0x0001,
+            ..ledger(0x4011, "/dev/hidraw1")
+        };
+        assert!(LedgerDeviceInfo::detect(&other_vendor).is_none());
+        assert!(LedgerDeviceInfo::detect(&other_interface).is_none());
+
+        let nano_x = LedgerDeviceInfo::detect(&ledger(0x4011, "/dev/hidraw2")).unwrap();
+        let stax = LedgerDeviceInfo::detect(&ledger(0x6011, "/dev/hidraw3")).unwrap();
+        assert_eq!(nano_x.model, DeviceModel::NanoX);
+        assert_eq!(stax.model, DeviceModel::Stax);
+        assert!(!stax.is_bootloader());
+        let bootloader = LedgerDeviceInfo::detect(&ledger(pid::STAX_BL, "/dev/hidraw3")).unwrap();
+        assert_eq!(bootloader.model, DeviceModel::Stax);
+        assert!(bootloader.is_bootloader());
+        assert_eq!(stax.path, "/dev/hidraw3");
+        assert_eq!(stax.serial_number.as_deref(), Some("0001"));
+
+        let json = serde_json::to_value(&stax).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!({
+                "model": "Stax",
+                "product_id": 0x6011,
+                "serial_number": "0001",
+                "path": "/dev/hidraw3",
+            })
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use ledger_sdk_transport::hid_framing::FramingError;
 use thiserror::Error;
 
 #[derive(Error, Debug)]
@@ -5,6 +6,16 @@ pub enum LedgerHIDError {
     /// Device not found error
     #[error("Ledger device not found")]
     DeviceNotFound,
+    /// No answer within the read timeout
+    #[error("Ledger device: no answer within the timeout")]
+    Timeout,
+    /// The device disconnected mid-exchange
+    ///
+    /// The transport re-opens it according to its `ReconnectPolicy`, but the
+    /// device lost the state of any multi-APDU operation: restart it from the
+    /// first command. The source is an `std::io::Error` of kind `NotConnected`.
+    #[error("Ledger device disconnected")]
+    Disconnected(#[source] std::io::Error),
     /// Communication error
     #[error("Ledger device: communication error `{0}`")]
     Comm(&'static str),
@@ -18,3 +29,9 @@ pub enum LedgerHIDError {
     #[error("Ledger device: UTF8 error")]
     UTF8(#[from] std::str::Utf8Error),
 }
+
+impl From<FramingError> for LedgerHIDError {
+    fn from(error: FramingError) -> Self {
+        LedgerHIDError::Comm(error.0)
+    }
+}
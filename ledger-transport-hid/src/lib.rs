@@ -1,17 +1,34 @@
+mod device;
 mod errors;
-
-use std::{io::Cursor, ops::Deref, sync::Mutex};
-
-use byteorder::{BigEndian, ReadBytesExt};
+mod reconnect;
+mod watcher;
+
+use std::{
+    cell::RefCell,
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    io,
+    ops::Deref,
+    sync::{Arc, Mutex, MutexGuard},
+    time::Duration,
+};
+
+pub use device::{is_bootloader_product_id, model_from_product_id, LedgerDeviceInfo};
 pub use errors::LedgerHIDError;
 pub use hidapi;
 use hidapi::{DeviceInfo, HidApi, HidDevice};
-use ledger_sdk_transport::{async_trait, APDUAnswer, APDUCommand, Exchange};
+pub use ledger_sdk_device_base::DeviceModel;
+use ledger_sdk_transport::hid_framing::{self, AnswerReassembler};
+use ledger_sdk_transport::{async_trait, APDUAnswer, APDUCommand, Exchange, ExchangeTimeouts};
 use log::info;
+pub use reconnect::ReconnectPolicy;
+pub use watcher::{DeviceEvent, DeviceWatcher};
 
-pub const LEDGER_VENDOR_ID: u16 = 0x2c97;
+pub use ledger_sdk_transport::hid_framing::{
+    LEDGER_USAGE_PAGE, LEDGER_VENDOR_ID, MAX_FOREIGN_FRAMES,
+};
+/// Channel used by other Ledger libraries; transports pick a random one by default
 pub const LEDGER_CHANNEL: u16 = 0x0101;
-pub const LEDGER_USAGE_PAGE: u16 = 0xffa0;
 // for Windows compatability, we prepend the buffer with a 0x00
 // so the actual buffer is 64 bytes
 pub const LEDGER_PACKET_WRITE_SIZE: u8 = 65;
@@ -20,6 +37,11 @@ pub const LEDGER_TIMEOUT: i32 = 10_000_000;
 
 // USB Product IDs (Normal / Bootloader)
 pub mod pid {
+    pub const BLUE: u16 = 0x0000;
+
+    pub const NANO_S: u16 = 0x0010; // Identifiers: 0x10
+    pub const NANO_S_BL: u16 = 0x0001;
+
     pub const NANO_S_PLUS: u16 = 0x0050; // Identifiers: 0x50
     pub const NANO_S_PLUS_BL: u16 = 0x0005;
 
@@ -33,27 +55,88 @@ pub mod pid {
     pub const FLEX_BL: u16 = 0x0007;
 }
 
+/// HID transport to a Ledger device
+///
+/// Clones share the opened device. Each exchange holds the device for its whole
+/// write and read, so the frames of concurrent exchanges from different clones
+/// or threads are never interleaved. Sequences of several APDUs (e.g. the chunks
+/// of a transaction) can still interleave with other callers: hold a
+/// [`HidSession`] from [`TransportNativeHID::lock_session`] for blocking code, or
+/// share one `ledger_sdk_transport::ExchangeGuard` between async tasks.
+///
+/// When the device disconnects mid-exchange, the transport re-opens it following
+/// its [`ReconnectPolicy`] and fails the exchange with
+/// [`LedgerHIDError::Disconnected`]. Through the [`Exchange`] trait the delay
+/// between attempts is an async sleep, so the executor is not blocked.
+#[derive(Clone)]
 pub struct TransportNativeHID {
-    device: Mutex<HidDevice>,
+    device: Arc<Mutex<Box<dyn HidIo + Send>>>,
+    channel: u16,
+    max_stale_frames: usize,
+    serial_number: Option<String>,
+    timeouts: Option<ExchangeTimeouts>,
+    reopen: Option<Reopen>,
+    reconnect_policy: ReconnectPolicy,
+}
+
+/// Opens the transport's device again after a disconnection
+type Reopen = Arc<dyn Fn() -> Result<Box<dyn HidIo + Send>, LedgerHIDError> + Send + Sync>;
+
+/// Minimal HID I/O surface needed by the APDU framing.
+///
+/// Implemented for [`HidDevice`]; keeping the framing generic over it lets the
+/// packet parsing run against an in-memory device in tests.
+trait HidIo {
+    fn write(&self, data: &[u8]) -> Result<usize, hidapi::HidError>;
+    fn read_timeout(&self, buf: &mut [u8], timeout: i32) -> Result<usize, hidapi::HidError>;
+}
+
+impl HidIo for HidDevice {
+    fn write(&self, data: &[u8]) -> Result<usize, hidapi::HidError> {
+        HidDevice::write(self, data)
+    }
+
+    fn read_timeout(&self, buf: &mut [u8], timeout: i32) -> Result<usize, hidapi::HidError> {
+        HidDevice::read_timeout(self, buf, timeout)
+    }
 }
 
 impl TransportNativeHID {
-    fn is_ledger(dev: &DeviceInfo) -> bool {
-        dev.vendor_id() == LEDGER_VENDOR_ID && dev.usage_page() == LEDGER_USAGE_PAGE
+    fn from_device<D: HidIo + Send + 'static>(device: D, serial_number: Option<String>) -> Self {
+        TransportNativeHID {
+            device: Arc::new(Mutex::new(Box::new(device))),
+            channel: random_channel(),
+            max_stale_frames: 0,
+            serial_number,
+            timeouts: None,
+            reopen: None,
+            reconnect_policy: ReconnectPolicy::default(),
+        }
     }
 
     pub fn list_ledgers(api: &HidApi) -> impl Iterator<Item = &DeviceInfo> {
-        api.device_list().filter(|dev| Self::is_ledger(dev))
+        api.device_list().filter(|dev| device::is_ledger(*dev))
+    }
+
+    /// Connected Ledgers with their model, serial number and HID path
+    pub fn list_devices(api: &HidApi) -> Vec<LedgerDeviceInfo> {
+        api.device_list()
+            .filter_map(LedgerDeviceInfo::detect)
+            .collect()
     }
 
     pub fn open_device(api: &HidApi, device: &DeviceInfo) -> Result<Self, LedgerHIDError> {
+        let serial_number = device.serial_number().map(str::to_string);
+        let path = device.path().to_owned();
         let device = device.open_device(api)?;
         let _ = device.set_blocking_mode(true);
-        let ledger = TransportNativeHID {
-            device: Mutex::new(device),
-        };
 
-        Ok(ledger)
+        let mut transport = Self::from_device(device, serial_number.clone());
+        transport.reopen = Some(Arc::new(move || {
+            let device = reconnect::reopen(&path, serial_number.as_deref())?;
+            Ok(Box::new(device) as Box<dyn HidIo + Send>)
+        }));
+        Ok(transport)
     }
 
     pub fn new(api: &HidApi) -> Result<Self, LedgerHIDError> {
@@ -64,101 +147,193 @@ impl TransportNativeHID {
         Self::open_device(api, first_ledger)
     }
 
-    fn write_apdu(
-        device: &HidDevice,
+    /// Connected Ledgers with their model
+    pub fn list_models(api: &HidApi) -> Vec<(DeviceModel, &DeviceInfo)> {
+        Self::list_ledgers(api)
+            .map(|dev| (model_from_product_id(dev.product_id()), dev))
+            .collect()
+    }
+
+    /// Open the first connected Ledger of the given model, in its bootloader or not
+    pub fn open_by_model(api: &HidApi, model: DeviceModel) -> Result<Self, LedgerHIDError> {
+        let ledger = Self::list_ledgers(api)
+            .find(|dev| model_from_product_id(dev.product_id()) == model)
+            .ok_or(LedgerHIDError::DeviceNotFound)?;
+
+        Self::open_device(api, ledger)
+    }
+
+    /// Open the Ledger at `path`, as reported in [`LedgerDeviceInfo::path`]
+    pub fn open_by_path(api: &HidApi, path: &str) -> Result<Self, LedgerHIDError> {
+        let ledger = Self::list_ledgers(api)
+            .find(|dev| dev.path().to_string_lossy() == path)
+            .ok_or(LedgerHIDError::DeviceNotFound)?;
+
+        Self::open_device(api, ledger)
+    }
+
+    /// USB serial number of the opened device, if the platform reports one
+    ///
+    /// Read once when the device is opened. It identifies the device across
+    /// sessions and reconnections (unlike the HID path, which changes when the
+    /// device is re-plugged), so it can be persisted to recognise a known Ledger.
+    pub fn serial_number(&self) -> Option<String> {
+        self.serial_number.clone()
+    }
+
+    /// Skip up to `max_frames` unexpected frames per answer instead of failing.
+    ///
+    /// A frame left over from a previously aborted exchange (wrong channel, tag
+    /// or sequence index) normally fails the read with `Comm("Invalid ...")`.
+    /// With skipping enabled such frames are drained and the read carries on.
+    ///
+    /// This only helps when the stale frame can be told apart from the expected
+    /// one: a leftover first frame of an older answer has the same sequence index
+    /// as the current one and is accepted as the answer. Use it to recover from
+    /// interrupted sessions, not as a substitute for resynchronising the device.
+    /// Disabled (0) by default.
+    pub fn with_stale_frame_skipping(mut self, max_frames: usize) -> Self {
+        self.max_stale_frames = max_frames;
+        self
+    }
+
+    /// Frame commands on `channel` instead of the random one picked when opening
+    ///
+    /// The device answers on the channel of the command, so a random channel
+    /// tells answers to this transport apart from frames left over by other
+    /// clients. Use [`LEDGER_CHANNEL`] for HID proxies expecting the fixed value.
+    pub fn with_channel(mut self, channel: u16) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /// HID channel commands are framed on
+    pub fn channel(&self) -> u16 {
+        self.channel
+    }
+
+    /// Bound the wait for each answer, depending on whether the command needs
+    /// user confirmation.
+    ///
+    /// Without timeouts every read waits up to [`LEDGER_TIMEOUT`]. With them, a
+    /// command the classifier reports as non-interactive fails with
+    /// [`LedgerHIDError::Timeout`] after `timeouts.protocol`, so a dead device is
+    /// noticed quickly while signing still waits `timeouts.confirmation` for the user.
+    pub fn with_timeouts(mut self, timeouts: ExchangeTimeouts) -> Self {
+        self.timeouts = Some(timeouts);
+        self
+    }
+
+    /// Re-open the device after a disconnection following `policy`
+    ///
+    /// Defaults to [`ReconnectPolicy::default`]; transports not opened from a
+    /// `DeviceInfo` cannot reconnect.
+    pub fn with_reconnect_policy(mut self, policy: ReconnectPolicy) -> Self {
+        self.reconnect_policy = policy;
+        self
+    }
+
+    /// Open the device again, or `None` once the policy's attempts are spent
+    fn reconnect(&self) -> Option<Box<dyn HidIo + Send>> {
+        let reopen = self.reopen.as_ref()?;
+        for attempt in 1..=self.reconnect_policy.attempts {
+            std::thread::sleep(self.reconnect_policy.delay);
+            if let Some(device) = Self::reopen_attempt(reopen, attempt) {
+                return Some(device);
+            }
+        }
+        None
+    }
+
+    /// Same as [`TransportNativeHID::reconnect`], sleeping without blocking the executor
+    async fn reconnect_async(&self) -> Option<Box<dyn HidIo + Send>> {
+        let reopen = self.reopen.as_ref()?;
+        for attempt in 1..=self.reconnect_policy.attempts {
+            tokio::time::sleep(self.reconnect_policy.delay).await;
+            if let Some(device) = Self::reopen_attempt(reopen, attempt) {
+                return Some(device);
+            }
+        }
+        None
+    }
+
+    fn reopen_attempt(reopen: &Reopen, attempt: u32) -> Option<Box<dyn HidIo + Send>> {
+        reopen()
+            .inspect_err(|error| info!("reconnection attempt {} failed: {}", attempt, error))
+            .ok()
+    }
+
+    /// Read timeout for `command`, in milliseconds
+    fn read_timeout_ms<I: Deref<Target = [u8]>>(
+        timeouts: Option<&ExchangeTimeouts>,
+        command: &APDUCommand<I>,
+    ) -> i32 {
+        timeouts.map_or(LEDGER_TIMEOUT, |timeouts| {
+            duration_to_ms(timeouts.for_command(command))
+        })
+    }
+
+    fn write_apdu<D: HidIo + ?Sized>(
+        device: &D,
         channel: u16,
         apdu_command: &[u8],
     ) -> Result<i32, LedgerHIDError> {
-        let command_length = apdu_command.len();
-        let mut in_data = Vec::with_capacity(command_length + 2);
-        in_data.push(((command_length >> 8) & 0xFF) as u8);
-        in_data.push((command_length & 0xFF) as u8);
-        in_data.extend_from_slice(apdu_command);
-
-        let mut buffer = vec![0u8; LEDGER_PACKET_WRITE_SIZE as usize];
-        // Windows platform requires 0x00 prefix and Linux/Mac tolerate this as well
-        buffer[0] = 0x00;
-        buffer[1] = ((channel >> 8) & 0xFF) as u8;
-        buffer[2] = (channel & 0xFF) as u8;
-        buffer[3] = 0x05u8;
-
-        for (idx, chunk) in in_data
-            .chunks((LEDGER_PACKET_WRITE_SIZE - 6) as usize)
-            .enumerate()
-        {
-            buffer[4] = ((idx >> 8) & 0xFF) as u8;
-            buffer[5] = (idx & 0xFF) as u8;
-            buffer[6..6 + chunk.len()].copy_from_slice(chunk);
-
-            info!("[{:3}] << {:}", buffer.len(), hex::encode(&buffer));
-
-            let result = device.write(&buffer);
-
-            match result {
-                Ok(size) => {
-                    if size < buffer.len() {
-                        return Err(LedgerHIDError::Comm(
-                            "USB write error. Could not send whole message",
-                        ));
-                    }
-                }
-                Err(x) => return Err(LedgerHIDError::Hid(x)),
+        for frame in hid_framing::frame_command(channel, apdu_command)? {
+            // Windows platform requires 0x00 prefix and Linux/Mac tolerate this as well
+            let mut report = Vec::with_capacity(LEDGER_PACKET_WRITE_SIZE as usize);
+            report.push(0x00);
+            report.extend_from_slice(&frame);
+            let frame = report;
+            info!("[{:3}] << {:}", frame.len(), hex::encode(&frame));
+
+            let size = device.write(&frame).map_err(LedgerHIDError::Hid)?;
+            if size < frame.len() {
+                return Err(LedgerHIDError::Comm(
+                    "USB write error. Could not send whole message",
+                ));
             }
         }
 
         Ok(1)
     }
 
-    fn read_apdu(
-        device: &HidDevice,
+    fn read_apdu<D: HidIo + ?Sized>(
+        device: &D,
         channel: u16,
+        max_stale_frames: usize,
+        timeout_ms: i32,
         apdu_answer: &mut Vec<u8>,
     ) -> Result<usize, LedgerHIDError> {
         let mut buffer: Vec<u8> = vec![0u8; LEDGER_PACKET_READ_SIZE as usize];
-        let mut sequence_idx = 0u16;
-        let mut expected_apdu_len = 0usize;
+        let mut reassembler = AnswerReassembler::new(channel, max_stale_frames);
 
         loop {
-            let res = device.read_timeout(&mut buffer, LEDGER_TIMEOUT)?;
-
-            if (sequence_idx == 0 && res < 7) || res < 5 {
-                return Err(LedgerHIDError::Comm("USB read error. Incomplete header"));
+            let res = device.read_timeout(&mut buffer, timeout_ms)?;
+            if res == 0 && reassembler.awaiting_first_frame() {
+                return Err(LedgerHIDError::Timeout);
             }
 
-            let mut rdr = Cursor::new(&buffer);
-
-            let rcv_channel: u16 = rdr.read_u16::<BigEndian>()?;
-            let rcv_tag: u8 = rdr.read_u8()?;
-            let rcv_seq_idx: u16 = rdr.read_u16::<BigEndian>()?;
-
-            if rcv_channel != channel {
-                return Err(LedgerHIDError::Comm("Invalid channel"));
-            }
-            if rcv_tag != 0x05u8 {
-                return Err(LedgerHIDError::Comm("Invalid tag"));
-            }
-            if rcv_seq_idx != sequence_idx {
-                return Err(LedgerHIDError::Comm("Invalid sequence index"));
-            }
-            if rcv_seq_idx == 0 {
-                expected_apdu_len = rdr.read_u16::<BigEndian>()? as usize;
-            }
-
-            let available: usize = buffer.len() - rdr.position() as usize;
-            let missing: usize = expected_apdu_len - apdu_answer.len();
-            let end_p = rdr.position() as usize + std::cmp::min(available, missing);
-
-            let new_chunk = &buffer[rdr.position() as usize..end_p];
-
-            info!("[{:3}] << {:}", new_chunk.len(), hex::encode(new_chunk));
-
-            apdu_answer.extend_from_slice(new_chunk);
-
-            if apdu_answer.len() >= expected_apdu_len {
+            if reassembler.push(&buffer[..res], apdu_answer)? {
+                info!(
+                    "[{:3}] >> {:}",
+                    apdu_answer.len(),
+                    hex::encode(&*apdu_answer)
+                );
                 return Ok(apdu_answer.len());
             }
+        }
+    }
 
-            sequence_idx += 1;
+    /// Hold the device until the returned session is dropped
+    ///
+    /// Exchanges from other clones and threads wait for the session to end, so a
+    /// multi-APDU sequence sent through it reaches the device uninterrupted. Do
+    /// not call [`TransportNativeHID::exchange`] on this transport or a clone
+    /// while holding the session on the same thread: it would wait forever.
+    pub fn lock_session(&self) -> HidSession<'_> {
+        HidSession {
+            transport: self,
+            device: RefCell::new(self.device.lock().expect("HID device poisoned")),
         }
     }
 
@@ -166,19 +341,83 @@ impl TransportNativeHID {
         &self,
         command: &APDUCommand<I>,
     ) -> Result<APDUAnswer<Vec<u8>>, LedgerHIDError> {
-        let device = self.device.lock().expect("HID device poisoned");
+        self.lock_session().exchange(command)
+    }
 
+    fn exchange_on<D: HidIo + ?Sized, I: Deref<Target = [u8]>>(
+        &self,
+        device: &D,
+        command: &APDUCommand<I>,
+    ) -> Result<APDUAnswer<Vec<u8>>, LedgerHIDError> {
         // Serialize once and log APDU hex before sending
-        let serialized = command.serialize();
-        Self::write_apdu(&device, LEDGER_CHANNEL, &serialized)?;
+        let serialized = command
+            .try_serialize()
+            .map_err(|_| LedgerHIDError::Comm("command data longer than 255 bytes"))?;
+        Self::write_apdu(device, self.channel, &serialized)?;
 
         let mut answer = Vec::with_capacity(256);
-        Self::read_apdu(&device, LEDGER_CHANNEL, &mut answer)?;
+        Self::read_apdu(
+            device,
+            self.channel,
+            self.max_stale_frames,
+            Self::read_timeout_ms(self.timeouts.as_ref(), command),
+            &mut answer,
+        )?;
 
         APDUAnswer::from_answer(answer).map_err(|_| LedgerHIDError::Comm("response was too short"))
     }
 }
 
+/// Exclusive access to a [`TransportNativeHID`] device, see
+/// [`TransportNativeHID::lock_session`]
+pub struct HidSession<'a> {
+    transport: &'a TransportNativeHID,
+    device: RefCell<MutexGuard<'a, Box<dyn HidIo + Send>>>,
+}
+
+impl HidSession<'_> {
+    /// Exchange `command` without releasing the device
+    ///
+    /// A HID I/O error means the device went away: it is re-opened, for the
+    /// next exchanges, and this one fails with [`LedgerHIDError::Disconnected`].
+    pub fn exchange<I: Deref<Target = [u8]>>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> Result<APDUAnswer<Vec<u8>>, LedgerHIDError> {
+        let mut device = self.device.borrow_mut();
+        match self.transport.exchange_on(&***device, command) {
+            Err(LedgerHIDError::Hid(error)) => {
+                info!("device disconnected: {}", error);
+                if let Some(reopened) = self.transport.reconnect() {
+                    **device = reopened;
+                }
+                Err(disconnected(error))
+            }
+            result => result,
+        }
+    }
+}
+
+/// Error returned for the exchange interrupted by a disconnection
+fn disconnected(error: hidapi::HidError) -> LedgerHIDError {
+    LedgerHIDError::Disconnected(io::Error::new(
+        io::ErrorKind::NotConnected,
+        error.to_string(),
+    ))
+}
+
+/// Random channel, so answers meant for another client are recognisable
+fn random_channel() -> u16 {
+    // RandomState is seeded from the OS; enough to pick a channel without a
+    // dependency on a random number crate
+    RandomState::new().build_hasher().finish() as u16
+}
+
+/// Milliseconds as taken by `read_timeout`, saturating
+fn duration_to_ms(duration: Duration) -> i32 {
+    i32::try_from(duration.as_millis()).unwrap_or(i32::MAX)
+}
+
 #[async_trait]
 impl Exchange for TransportNativeHID {
     type Error = LedgerHIDError;
@@ -191,6 +430,536 @@ impl Exchange for TransportNativeHID {
     where
         I: Deref<Target = [u8]> + Send + Sync,
     {
-        self.exchange(command)
+        // The device is released before reconnecting, so the wait between
+        // attempts can be an async sleep
+        let result = {
+            let device = self.device.lock().expect("HID device poisoned");
+            self.exchange_on(&**device, command)
+        };
+        match result {
+            Err(LedgerHIDError::Hid(error)) => {
+                info!("device disconnected: {}", error);
+                if let Some(reopened) = self.reconnect_async().await {
+                    *self.device.lock().expect("HID device poisoned") = reopened;
+                }
+                Err(disconnected(error))
+            }
+            result => result,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{cell::RefCell, collections::VecDeque};
+
+    use super::*;
+
+    /// In-memory HID device replaying canned read packets and recording writes
+    #[derive(Default)]
+    struct StubDevice {
+        reads: RefCell<VecDeque<Vec<u8>>>,
+        writes: RefCell<Vec<Vec<u8>>>,
+        timeouts: RefCell<Vec<i32>>,
+    }
+
+    impl StubDevice {
+        fn with_reads(reads: Vec<Vec<u8>>) -> Self {
+            StubDevice {
+                reads: RefCell::new(reads.into()),
+                writes: RefCell::default(),
+                timeouts: RefCell::default(),
+            }
+        }
+    }
+
+    impl HidIo for StubDevice {
+        fn write(&self, data: &[u8]) -> Result<usize, hidapi::HidError> {
+            self.writes.borrow_mut().push(data.to_vec());
+            Ok(data.len())
+        }
+
+        fn read_timeout(&self, buf: &mut [u8], timeout: i32) -> Result<usize, hidapi::HidError> {
+            self.timeouts.borrow_mut().push(timeout);
+            // An exhausted stub behaves like a device that timed out
+            let Some(packet) = self.reads.borrow_mut().pop_front() else {
+                return Ok(0);
+            };
+            let len = packet.len().min(buf.len());
+            buf[..len].copy_from_slice(&packet[..len]);
+            Ok(len)
+        }
+    }
+
+    /// Thread-safe device recording writes and answering every command with 0x9000
+    #[derive(Clone, Default)]
+    struct RecordingDevice {
+        writes: Arc<Mutex<Vec<Vec<u8>>>>,
+    }
+
+    impl HidIo for RecordingDevice {
+        fn write(&self, data: &[u8]) -> Result<usize, hidapi::HidError> {
+            self.writes.lock().unwrap().push(data.to_vec());
+            // Give the other thread a chance to cut in between frames
+            std::thread::yield_now();
+            Ok(data.len())
+        }
+
+        fn read_timeout(&self, buf: &mut [u8], _timeout: i32) -> Result<usize, hidapi::HidError> {
+            let answer = packet(0, &[0x00, 0x02, 0x90, 0x00]);
+            buf[..answer.len()].copy_from_slice(&answer);
+            Ok(answer.len())
+        }
+    }
+
+    /// Device unplugged: every write fails
+    struct UnpluggedDevice;
+
+    impl HidIo for UnpluggedDevice {
+        fn write(&self, _data: &[u8]) -> Result<usize, hidapi::HidError> {
+            Err(hidapi::HidError::HidApiError {
+                message: "No such device".to_string(),
+            })
+        }
+
+        fn read_timeout(&self, _buf: &mut [u8], _timeout: i32) -> Result<usize, hidapi::HidError> {
+            unreachable!("nothing is read after a failed write")
+        }
+    }
+
+    /// Build a device->host packet: channel, tag, sequence index, payload
+    fn packet(seq: u16, payload: &[u8]) -> Vec<u8> {
+        let mut p = Vec::with_capacity(LEDGER_PACKET_READ_SIZE as usize);
+        p.extend_from_slice(&LEDGER_CHANNEL.to_be_bytes());
+        p.push(0x05);
+        p.extend_from_slice(&seq.to_be_bytes());
+        p.extend_from_slice(payload);
+        p.resize(LEDGER_PACKET_READ_SIZE as usize, 0);
+        p
+    }
+
+    fn read(reads: Vec<Vec<u8>>) -> Result<Vec<u8>, LedgerHIDError> {
+        let device = StubDevice::with_reads(reads);
+        let mut answer = Vec::new();
+        TransportNativeHID::read_apdu(&device, LEDGER_CHANNEL, 0, LEDGER_TIMEOUT, &mut answer)?;
+        Ok(answer)
+    }
+
+    #[test]
+    #[ignore = "requires a connected Ledger"]
+    fn test_serial_number_captured() {
+        let api = HidApi::new().unwrap();
+        let info = TransportNativeHID::list_devices(&api)
+            .into_iter()
+            .next()
+            .expect("no Ledger connected");
+
+        let ledger = TransportNativeHID::open_by_path(&api, &info.path).unwrap();
+        assert_eq!(ledger.serial_number(), info.serial_number);
+    }
+
+    #[test]
+    fn test_write_apdu_large_command_frames() {
+        // 5-byte APDU header + 250 bytes of data, e.g. a long EIP-712 field value
+        let mut command = vec![0xE0, 0x1C, 0x00, 0xFF, 250];
+        command.extend((0..250u16).map(|i| i as u8));
+
+        let device = StubDevice::default();
+        TransportNativeHID::write_apdu(&device, LEDGER_CHANNEL, &command).unwrap();
+        let writes = device.writes.into_inner();
+
+        // 2 + 255 bytes: 57 in the first frame, then 59 per continuation frame
+        assert_eq!(writes.len(), 5);
+
+        let mut data = Vec::new();
+        for (idx, frame) in writes.iter().enumerate() {
+            assert_eq!(frame.len(), LEDGER_PACKET_WRITE_SIZE as usize);
+            assert_eq!(frame[0], 0x00);
+            assert_eq!(&frame[1..3], &LEDGER_CHANNEL.to_be_bytes());
+            assert_eq!(frame[3], 0x05);
+            assert_eq!(&frame[4..6], &(idx as u16).to_be_bytes());
+            data.extend_from_slice(&frame[6..]);
+        }
+
+        assert_eq!(&data[..2], &(command.len() as u16).to_be_bytes());
+        assert_eq!(&data[2..2 + command.len()], &command[..]);
+        // Last frame padding is zeroed, not left over from the previous frame
+        assert!(data[2 + command.len()..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_clones_do_not_interleave_frames() {
+        let device = RecordingDevice::default();
+        let transport =
+            TransportNativeHID::from_device(device.clone(), None).with_channel(LEDGER_CHANNEL);
+        // 200 bytes of data take 4 frames, each command filled with its sender's tag
+        let command = |ins: u8, tag: u8| APDUCommand {
+            cla: 0xE0,
+            ins,
+            p1: 0x00,
+            p2: 0x00,
+            data: vec![tag; 200],
+        };
+
+        std::thread::scope(|scope| {
+            let session_transport = transport.clone();
+            scope.spawn(move || {
+                let session = session_transport.lock_session();
+                for ins in 1..=3 {
+                    session.exchange(&command(ins, 0xAA)).unwrap();
+                }
+            });
+            let other = transport.clone();
+            scope.spawn(move || {
+                for _ in 0..20 {
+                    other.exchange(&command(0x10, 0xBB)).unwrap();
+                }
+            });
+        });
+
+        // Reassemble commands from the frames, which must arrive in sequence
+        let writes = device.writes.lock().unwrap();
+        let mut commands: Vec<Vec<u8>> = Vec::new();
+        for frame in writes.iter() {
+            let seq = u16::from_be_bytes([frame[4], frame[5]]);
+            if seq == 0 {
+                commands.push(frame[8..].to_vec());
+            } else {
+                let current = commands.last_mut().unwrap();
+                assert_eq!(seq as usize, (current.len() - 57) / 59 + 1);
+                current.extend_from_slice(&frame[6..]);
+            }
+        }
+        assert_eq!(commands.len(), 23);
+        for command in &commands {
+            assert!(command[5..205].iter().all(|&b| b == command[5]));
+        }
+
+        // The session's three commands went out back to back
+        let session_start = commands.iter().position(|c| c[5] == 0xAA).unwrap();
+        let instructions: Vec<u8> = commands[session_start..session_start + 3]
+            .iter()
+            .map(|c| c[1])
+            .collect();
+        assert_eq!(instructions, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_exchange_uses_configured_channel() {
+        let device = RecordingDevice::default();
+        let transport = TransportNativeHID::from_device(device.clone(), None);
+        let command = APDUCommand {
+            cla: 0xE0,
+            ins: 0x06,
+            p1: 0x00,
+            p2: 0x00,
+            data: vec![],
+        };
+
+        // The stub answers on LEDGER_CHANNEL: foreign to a random channel, so the
+        // answer is discarded and the read gives up
+        let transport = transport.with_channel(LEDGER_CHANNEL ^ 0x8000);
+        assert!(transport.exchange(&command).is_err());
+
+        let transport = transport.with_channel(LEDGER_CHANNEL);
+        assert_eq!(transport.channel(), LEDGER_CHANNEL);
+        assert_eq!(transport.exchange(&command).unwrap().retcode(), 0x9000);
+        let writes = device.writes.lock().unwrap();
+        assert_eq!(&writes[0][1..3], &(LEDGER_CHANNEL ^ 0x8000).to_be_bytes());
+        assert_eq!(&writes[1][1..3], &LEDGER_CHANNEL.to_be_bytes());
+    }
+
+    #[test]
+    fn test_write_apdu_single_frame() {
+        let command = [0xE0, 0x06, 0x00, 0x00, 0x00];
+        let device = StubDevice::default();
+        TransportNativeHID::write_apdu(&device, LEDGER_CHANNEL, &command).unwrap();
+        let writes = device.writes.into_inner();
+
+        assert_eq!(writes.len(), 1);
+        assert_eq!(
+            &writes[0][..13],
+            &[0x00, 0x01, 0x01, 0x05, 0x00, 0x00, 0x00, 0x05, 0xE0, 0x06, 0x00, 0x00, 0x00]
+        );
+    }
+
+    #[test]
+    fn test_read_apdu_single_packet() {
+        let answer = read(vec![packet(0, &[0x00, 0x03, 0xAA, 0x90, 0x00])]).unwrap();
+        assert_eq!(answer, vec![0xAA, 0x90, 0x00]);
+    }
+
+    #[test]
+    fn test_read_apdu_multi_packet() {
+        let body: Vec<u8> = (0..100u8).collect();
+        let mut first = vec![0x00, body.len() as u8];
+        first.extend_from_slice(&body[..57]);
+        let reads = vec![packet(0, &first), packet(1, &body[57..])];
+
+        assert_eq!(read(reads).unwrap(), body);
+    }
+
+    #[test]
+    fn test_read_apdu_rejects_zero_length() {
+        let err = read(vec![packet(0, &[0x00, 0x00])]).unwrap_err();
+        assert!(matches!(err, LedgerHIDError::Comm("Invalid answer length")));
+    }
+
+    #[test]
+    fn test_read_apdu_skips_stale_frames() {
+        // Continuation frame of an aborted answer, then a foreign channel, then the answer
+        let mut foreign = packet(0, &[0x00, 0x02, 0x6E, 0x00]);
+        foreign[..2].copy_from_slice(&0x0202u16.to_be_bytes());
+        let reads = || {
+            vec![
+                packet(3, &[0xDE, 0xAD]),
+                foreign.clone(),
+                packet(0, &[0x00, 0x03, 0xAA, 0x90, 0x00]),
+            ]
+        };
+
+        let device = StubDevice::with_reads(reads());
+        let mut answer = Vec::new();
+        TransportNativeHID::read_apdu(&device, LEDGER_CHANNEL, 2, LEDGER_TIMEOUT, &mut answer)
+            .unwrap();
+        assert_eq!(answer, vec![0xAA, 0x90, 0x00]);
+
+        // Bounded: one skip is not enough for two stale frames; the foreign
+        // channel frame does not count against it
+        let device = StubDevice::with_reads(reads());
+        let mut answer = Vec::new();
+        TransportNativeHID::read_apdu(&device, LEDGER_CHANNEL, 1, LEDGER_TIMEOUT, &mut answer)
+            .unwrap();
+        let mut stale_twice = reads();
+        stale_twice[1] = packet(2, &[0xBE, 0xEF]);
+        let device = StubDevice::with_reads(stale_twice);
+        let mut answer = Vec::new();
+        let err =
+            TransportNativeHID::read_apdu(&device, LEDGER_CHANNEL, 1, LEDGER_TIMEOUT, &mut answer)
+                .unwrap_err();
+        assert!(matches!(
+            err,
+            LedgerHIDError::Comm("Invalid sequence index")
+        ));
+
+        // Disabled by default
+        let err = read(reads()).unwrap_err();
+        assert!(matches!(
+            err,
+            LedgerHIDError::Comm("Invalid sequence index")
+        ));
+    }
+
+    #[test]
+    fn test_read_apdu_length_mismatch_does_not_underflow() {
+        let device = StubDevice::with_reads(vec![packet(0, &[0x00, 0x02, 0x90, 0x00])]);
+        // Stale data already accumulated beyond what the header announces
+        let mut answer = vec![0u8; 8];
+        let err =
+            TransportNativeHID::read_apdu(&device, LEDGER_CHANNEL, 0, LEDGER_TIMEOUT, &mut answer)
+                .unwrap_err();
+        assert!(matches!(err, LedgerHIDError::Comm("length mismatch")));
+
+        // First frame announcing less than it carries: extra bytes are dropped
+        let answer = read(vec![packet(0, &[0x00, 0x02, 0x90, 0x00, 0xAA, 0xBB])]).unwrap();
+        assert_eq!(answer, vec![0x90, 0x00]);
+    }
+
+    #[test]
+    fn test_read_apdu_truncated_packets() {
+        // Header shorter than channel + tag + seq + length
+        for len in 0..7 {
+            let mut p = packet(0, &[0x00, 0x02, 0x90, 0x00]);
+            p.truncate(len);
+            assert!(read(vec![p]).is_err(), "accepted {} byte header", len);
+        }
+
+        // Announced length never fully delivered
+        let err = read(vec![packet(0, &[0x01, 0x00, 0xAA])]).unwrap_err();
+        assert!(matches!(
+            err,
+            LedgerHIDError::Comm("USB read error. Incomplete header")
+        ));
+    }
+
+    #[test]
+    fn test_read_apdu_timeout() {
+        let err = read(vec![]).unwrap_err();
+        assert!(matches!(err, LedgerHIDError::Timeout));
+    }
+
+    #[test]
+    fn test_non_interactive_command_uses_protocol_timeout() {
+        fn signs(_cla: u8, ins: u8, _p1: u8, _p2: u8) -> bool {
+            ins == 0x04
+        }
+        let timeouts =
+            ExchangeTimeouts::new(Duration::from_secs(2), Duration::from_secs(300), signs);
+        let command = |ins| APDUCommand {
+            cla: 0xE0,
+            ins,
+            p1: 0x00,
+            p2: 0x00,
+            data: vec![],
+        };
+
+        let get_configuration = command(0x06);
+        let timeout_ms = TransportNativeHID::read_timeout_ms(Some(&timeouts), &get_configuration);
+        assert_eq!(timeout_ms, 2_000);
+        assert_eq!(
+            TransportNativeHID::read_timeout_ms(Some(&timeouts), &command(0x04)),
+            300_000
+        );
+        assert_eq!(
+            TransportNativeHID::read_timeout_ms(None, &get_configuration),
+            LEDGER_TIMEOUT
+        );
+
+        let device = StubDevice::with_reads(vec![]);
+        let mut answer = Vec::new();
+        let err =
+            TransportNativeHID::read_apdu(&device, LEDGER_CHANNEL, 0, timeout_ms, &mut answer)
+                .unwrap_err();
+        assert!(matches!(err, LedgerHIDError::Timeout));
+        assert_eq!(*device.timeouts.borrow(), vec![2_000]);
+    }
+
+    #[test]
+    fn test_read_apdu_corrupt_headers() {
+        // Frames from other channels are discarded, up to a bound
+        let mut bad_channel = packet(0, &[0x00, 0x02, 0x6A, 0x80]);
+        bad_channel[0] ^= 0xFF;
+        let answer = packet(0, &[0x00, 0x02, 0x90, 0x00]);
+        let mut reads = vec![bad_channel.clone(); MAX_FOREIGN_FRAMES];
+        reads.push(answer.clone());
+        assert_eq!(read(reads).unwrap(), vec![0x90, 0x00]);
+        let mut reads = vec![bad_channel; MAX_FOREIGN_FRAMES + 1];
+        reads.push(answer);
+        assert!(matches!(
+            read(reads).unwrap_err(),
+            LedgerHIDError::Comm("Invalid channel")
+        ));
+
+        let mut bad_tag = packet(0, &[0x00, 0x02, 0x90, 0x00]);
+        bad_tag[2] = 0x02;
+        assert!(matches!(
+            read(vec![bad_tag]).unwrap_err(),
+            LedgerHIDError::Comm("Invalid tag")
+        ));
+
+        assert!(matches!(
+            read(vec![packet(1, &[0x00, 0x02, 0x90, 0x00])]).unwrap_err(),
+            LedgerHIDError::Comm("Invalid sequence index")
+        ));
+    }
+
+    #[test]
+    fn test_read_apdu_random_packets_never_panic() {
+        // Small xorshift generator so the corpus is deterministic without extra dependencies
+        let mut state = 0x2545_F491_4F6C_DD1Du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+
+        for _ in 0..2_000 {
+            let count = (next() % 4 + 1) as usize;
+            let reads = (0..count)
+                .map(|seq| {
+                    let len = (next() % (LEDGER_PACKET_READ_SIZE as u64 + 1)) as usize;
+                    let mut p: Vec<u8> = (0..len).map(|_| next() as u8).collect();
+                    // Keep most packets on-channel so the length handling gets exercised
+                    if len >= 5 && next() % 4 != 0 {
+                        p[..2].copy_from_slice(&LEDGER_CHANNEL.to_be_bytes());
+                        p[2] = 0x05;
+                        p[3..5].copy_from_slice(&(seq as u16).to_be_bytes());
+                    }
+                    p
+                })
+                .collect();
+
+            let _ = read(reads);
+        }
+    }
+
+    #[test]
+    fn test_reconnects_after_disconnection() {
+        let replugged = RecordingDevice::default();
+        let mut transport =
+            TransportNativeHID::from_device(UnpluggedDevice, None).with_channel(LEDGER_CHANNEL);
+        let reopened = replugged.clone();
+        transport.reopen = Some(Arc::new(move || {
+            Ok(Box::new(reopened.clone()) as Box<dyn HidIo + Send>)
+        }));
+        let transport = transport.with_reconnect_policy(ReconnectPolicy {
+            attempts: 1,
+            delay: Duration::ZERO,
+        });
+        let command = APDUCommand {
+            cla: 0xE0,
+            ins: 0x04,
+            p1: 0x00,
+            p2: 0x00,
+            data: vec![0xAB; 10],
+        };
+
+        // The interrupted command is not resent: the caller restarts the operation
+        let err = transport.exchange(&command).unwrap_err();
+        let LedgerHIDError::Disconnected(source) = err else {
+            panic!("expected Disconnected, got {:?}", err);
+        };
+        assert_eq!(source.kind(), io::ErrorKind::NotConnected);
+        assert!(replugged.writes.lock().unwrap().is_empty());
+
+        assert_eq!(transport.exchange(&command).unwrap().retcode(), 0x9000);
+        assert_eq!(replugged.writes.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_async_exchange_reconnects_after_disconnection() {
+        let replugged = RecordingDevice::default();
+        let mut transport =
+            TransportNativeHID::from_device(UnpluggedDevice, None).with_channel(LEDGER_CHANNEL);
+        let reopened = replugged.clone();
+        transport.reopen = Some(Arc::new(move || {
+            Ok(Box::new(reopened.clone()) as Box<dyn HidIo + Send>)
+        }));
+        let transport = transport.with_reconnect_policy(ReconnectPolicy {
+            attempts: 1,
+            delay: Duration::from_millis(1),
+        });
+        let command = APDUCommand {
+            cla: 0xE0,
+            ins: 0x04,
+            p1: 0x00,
+            p2: 0x00,
+            data: vec![0xAB; 10],
+        };
+
+        let err = Exchange::exchange(&transport, &command).await.unwrap_err();
+        assert!(matches!(err, LedgerHIDError::Disconnected(_)));
+        let answer = Exchange::exchange(&transport, &command).await.unwrap();
+        assert_eq!(answer.retcode(), 0x9000);
+        assert_eq!(replugged.writes.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_disconnection_without_reconnection() {
+        let transport = TransportNativeHID::from_device(UnpluggedDevice, None)
+            .with_reconnect_policy(ReconnectPolicy::disabled());
+        let command = APDUCommand {
+            cla: 0xE0,
+            ins: 0x06,
+            p1: 0x00,
+            p2: 0x00,
+            data: vec![],
+        };
+
+        for _ in 0..2 {
+            let err = transport.exchange(&command).unwrap_err();
+            assert!(matches!(err, LedgerHIDError::Disconnected(_)));
+        }
     }
 }
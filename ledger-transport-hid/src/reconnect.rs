@@ -0,0 +1,62 @@
+//! Re-opening a device after a USB disconnection.
+//!
+//! A cable hiccup invalidates the opened `HidDevice`: every later write or read
+//! fails. [`crate::TransportNativeHID`] then re-opens the same Ledger, first by
+//! its HID path, then by serial number since re-plugging can change the path.
+
+use std::{ffi::CStr, time::Duration};
+
+use hidapi::{HidApi, HidDevice};
+
+use crate::{device, LedgerHIDError};
+
+/// How [`crate::TransportNativeHID`] re-opens its device after an I/O error
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ReconnectPolicy {
+    /// Re-open attempts before giving up; 0 disables reconnection
+    pub attempts: u32,
+    /// Delay before each attempt, giving the device time to enumerate again
+    pub delay: Duration,
+}
+
+impl ReconnectPolicy {
+    /// Never re-open the device
+    pub const fn disabled() -> Self {
+        ReconnectPolicy {
+            attempts: 0,
+            delay: Duration::ZERO,
+        }
+    }
+}
+
+impl Default for ReconnectPolicy {
+    /// 5 attempts, 500ms apart
+    fn default() -> Self {
+        ReconnectPolicy {
+            attempts: 5,
+            delay: Duration::from_millis(500),
+        }
+    }
+}
+
+/// Open the Ledger at `path`, or the one with `serial_number` if the path is gone
+pub(crate) fn reopen(
+    path: &CStr,
+    serial_number: Option<&str>,
+) -> Result<HidDevice, LedgerHIDError> {
+    let api = HidApi::new()?;
+    let device = match api.open_path(path) {
+        Ok(device) => device,
+        Err(error) => {
+            let serial_number = serial_number.ok_or(LedgerHIDError::Hid(error))?;
+            api.device_list()
+                .filter(|dev| device::is_ledger(*dev))
+                .find(|dev| dev.serial_number() == Some(serial_number))
+                .ok_or(LedgerHIDError::DeviceNotFound)?
+                .open_device(&api)?
+        }
+    };
+    let _ = device.set_blocking_mode(true);
+
+    Ok(device)
+}
@@ -0,0 +1,186 @@
+//! Hotplug notifications for Ledger devices.
+//!
+//! hidapi has no portable hotplug callback, so [`DeviceWatcher`] polls the HID
+//! device list from a background thread and reports the difference between two
+//! scans as [`DeviceEvent`]s.
+
+use std::{
+    collections::BTreeMap,
+    sync::mpsc::{self, RecvTimeoutError},
+    thread::{self, JoinHandle},
+    time::Duration,
+};
+
+use hidapi::HidApi;
+use log::warn;
+
+use crate::{LedgerDeviceInfo, LedgerHIDError, TransportNativeHID};
+
+/// Change in the set of connected Ledgers
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum DeviceEvent {
+    /// A Ledger appeared, or re-enumerated under a new product ID
+    Connected(LedgerDeviceInfo),
+    /// A Ledger went away; carries the last known information about it
+    Disconnected(LedgerDeviceInfo),
+}
+
+/// Background thread polling for Ledgers being plugged in and out
+///
+/// Devices are identified by their HID path. A device switching to or from its
+/// bootloader re-enumerates with another product ID, and is reported as a
+/// disconnection followed by a connection even if the platform keeps its path.
+/// Devices already connected when the watcher starts are reported as connected
+/// on the first scan.
+///
+/// Dropping the watcher stops the thread and waits for it to exit.
+pub struct DeviceWatcher {
+    stop: Option<mpsc::Sender<()>>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl DeviceWatcher {
+    /// Poll interval used by [`DeviceWatcher::spawn`]
+    pub const DEFAULT_INTERVAL: Duration = Duration::from_millis(500);
+
+    /// Watch with the default interval, calling `on_event` from the watcher thread
+    pub fn spawn<F>(on_event: F) -> Result<Self, LedgerHIDError>
+    where
+        F: FnMut(DeviceEvent) + Send + 'static,
+    {
+        Self::with_interval(Self::DEFAULT_INTERVAL, on_event)
+    }
+
+    /// Watch, scanning for devices every `interval`
+    ///
+    /// A failing scan is logged and skipped; the watcher keeps running.
+    pub fn with_interval<F>(interval: Duration, mut on_event: F) -> Result<Self, LedgerHIDError>
+    where
+        F: FnMut(DeviceEvent) + Send + 'static,
+    {
+        let mut api = HidApi::new()?;
+        let (stop, stopped) = mpsc::channel::<()>();
+
+        let thread = thread::Builder::new()
+            .name("ledger-device-watcher".into())
+            .spawn(move || {
+                let mut tracker = DeviceTracker::default();
+                loop {
+                    match api.refresh_devices() {
+                        Ok(()) => {
+                            let devices = TransportNativeHID::list_devices(&api);
+                            tracker.update(devices).into_iter().for_each(&mut on_event);
+                        }
+                        Err(err) => warn!("device scan failed: {}", err),
+                    }
+
+                    // Doubles as the sleep: dropping the sender wakes the thread up
+                    match stopped.recv_timeout(interval) {
+                        Err(RecvTimeoutError::Timeout) => continue,
+                        Ok(()) | Err(RecvTimeoutError::Disconnected) => break,
+                    }
+                }
+            })?;
+
+        Ok(DeviceWatcher {
+            stop: Some(stop),
+            thread: Some(thread),
+        })
+    }
+}
+
+impl Drop for DeviceWatcher {
+    fn drop(&mut self) {
+        drop(self.stop.take());
+        if let Some(thread) = self.thread.take() {
+            // A panicking callback already reported itself; nothing left to clean up
+            let _ = thread.join();
+        }
+    }
+}
+
+/// Devices seen in the previous scan, keyed by HID path
+#[derive(Default)]
+struct DeviceTracker {
+    known: BTreeMap<String, LedgerDeviceInfo>,
+}
+
+impl DeviceTracker {
+    /// Record a new scan and return what changed since the previous one
+    fn update(&mut self, devices: Vec<LedgerDeviceInfo>) -> Vec<DeviceEvent> {
+        // A Ledger can list the same path more than once (one entry per
+        // collection); keep a single entry per path
+        let mut current = BTreeMap::new();
+        for device in devices {
+            current.entry(device.path.clone()).or_insert(device);
+        }
+
+        let mut events = Vec::new();
+        for (path, known) in &self.known {
+            match current.get(path) {
+                Some(device) if device.product_id == known.product_id => {}
+                _ => events.push(DeviceEvent::Disconnected(known.clone())),
+            }
+        }
+        for (path, device) in &current {
+            match self.known.get(path) {
+                Some(known) if known.product_id == device.product_id => {}
+                _ => events.push(DeviceEvent::Connected(device.clone())),
+            }
+        }
+
+        self.known = current;
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{model_from_product_id, pid};
+
+    fn device(path: &str, product_id: u16) -> LedgerDeviceInfo {
+        LedgerDeviceInfo {
+            model: model_from_product_id(product_id),
+            product_id,
+            serial_number: Some("0001".into()),
+            path: path.into(),
+        }
+    }
+
+    #[test]
+    fn test_tracker_events() {
+        let nano_x = device("/dev/hidraw1", 0x4011);
+        let stax = device("/dev/hidraw2", 0x6011);
+        let mut tracker = DeviceTracker::default();
+
+        // Initial scan, with the duplicate entry of a second HID collection
+        let events = tracker.update(vec![nano_x.clone(), nano_x.clone()]);
+        assert_eq!(events, vec![DeviceEvent::Connected(nano_x.clone())]);
+        assert!(tracker.update(vec![nano_x.clone()]).is_empty());
+
+        let events = tracker.update(vec![nano_x.clone(), stax.clone()]);
+        assert_eq!(events, vec![DeviceEvent::Connected(stax.clone())]);
+
+        let events = tracker.update(vec![stax.clone()]);
+        assert_eq!(events, vec![DeviceEvent::Disconnected(nano_x)]);
+
+        // Rebooting into the bootloader keeps the path but changes the product ID
+        let bootloader = device("/dev/hidraw2", pid::STAX_BL);
+        let events = tracker.update(vec![bootloader.clone()]);
+        assert_eq!(
+            events,
+            vec![
+                DeviceEvent::Disconnected(stax),
+                DeviceEvent::Connected(bootloader.clone()),
+            ]
+        );
+        assert!(matches!(
+            &events[1],
+            DeviceEvent::Connected(info) if info.is_bootloader()
+        ));
+
+        let events = tracker.update(vec![]);
+        assert_eq!(events, vec![DeviceEvent::Disconnected(bootloader)]);
+    }
+}
@@ -0,0 +1,11 @@
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum LedgerSpeculosError {
+    /// Communication error
+    #[error("Speculos: communication error `{0}`")]
+    Comm(&'static str),
+    /// i/o error
+    #[error("Speculos: i/o error")]
+    Io(#[from] std::io::Error),
+}
@@ -0,0 +1,255 @@
+//! Transport talking to the [Speculos](https://github.com/LedgerHQ/speculos) emulator
+//! over its raw APDU TCP socket.
+//!
+//! Framing on the wire:
+//! - host -> emulator: 4-byte big-endian length, then the serialized APDU
+//! - emulator -> host: 4-byte big-endian length of the response data, then the
+//!   data, then the 2-byte status word (not counted in the length)
+
+mod errors;
+
+use std::ops::Deref;
+
+pub use errors::LedgerSpeculosError;
+use ledger_sdk_transport::{async_trait, APDUAnswer, APDUCommand, Exchange};
+use log::info;
+use tokio::{
+    io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt},
+    net::{TcpStream, ToSocketAddrs},
+    sync::Mutex,
+};
+
+/// Default host Speculos listens on
+pub const SPECULOS_DEFAULT_HOST: &str = "127.0.0.1";
+/// Default Speculos APDU port (`--apdu-port`)
+pub const SPECULOS_DEFAULT_APDU_PORT: u16 = 9999;
+/// Largest response payload accepted; anything bigger means the stream is out of sync
+pub const SPECULOS_MAX_ANSWER_SIZE: usize = 0x10000;
+
+pub struct TransportSpeculos {
+    stream: Mutex<TcpStream>,
+}
+
+impl TransportSpeculos {
+    /// Connect to a Speculos instance's APDU socket
+    pub async fn connect(host: &str, port: u16) -> Result<Self, LedgerSpeculosError> {
+        Self::connect_addr((host, port)).await
+    }
+
+    /// Connect to any address resolvable by tokio
+    pub async fn connect_addr<A: ToSocketAddrs>(addr: A) -> Result<Self, LedgerSpeculosError> {
+        let stream = TcpStream::connect(addr).await?;
+        // APDUs are tiny request/response pairs, don't let Nagle hold them back
+        stream.set_nodelay(true)?;
+
+        Ok(TransportSpeculos {
+            stream: Mutex::new(stream),
+        })
+    }
+
+    /// Connect to Speculos on its default host and APDU port
+    pub async fn connect_default() -> Result<Self, LedgerSpeculosError> {
+        Self::connect(SPECULOS_DEFAULT_HOST, SPECULOS_DEFAULT_APDU_PORT).await
+    }
+
+    async fn write_apdu<W: AsyncWrite + Unpin>(
+        writer: &mut W,
+        apdu_command: &[u8],
+    ) -> Result<(), LedgerSpeculosError> {
+        let len = u32::try_from(apdu_command.len())
+            .map_err(|_| LedgerSpeculosError::Comm("APDU command too large"))?;
+
+        let mut frame = Vec::with_capacity(4 + apdu_command.len());
+        frame.extend_from_slice(&len.to_be_bytes());
+        frame.extend_from_slice(apdu_command);
+
+        info!("[{:3}] << {:}", frame.len(), hex::encode(&frame));
+
+        writer.write_all(&frame).await?;
+        writer.flush().await?;
+        Ok(())
+    }
+
+    async fn read_apdu<R: AsyncRead + Unpin>(
+        reader: &mut R,
+    ) -> Result<Vec<u8>, LedgerSpeculosError> {
+        let mut len = [0u8; 4];
+        reader.read_exact(&mut len).await?;
+        let len = u32::from_be_bytes(len) as usize;
+
+        if len > SPECULOS_MAX_ANSWER_SIZE {
+            return Err(LedgerSpeculosError::Comm("Invalid answer length"));
+        }
+
+        // Response data followed by the status word
+        let mut answer = vec![0u8; len + 2];
+        reader.read_exact(&mut answer).await?;
+
+        info!("[{:3}] >> {:}", answer.len(), hex::encode(&answer));
+
+        Ok(answer)
+    }
+
+    pub async fn exchange<I: Deref<Target = [u8]>>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> Result<APDUAnswer<Vec<u8>>, LedgerSpeculosError> {
+        let serialized = command
+            .try_serialize()
+            .map_err(|_| LedgerSpeculosError::Comm("command data longer than 255 bytes"))?;
+        let mut stream = self.stream.lock().await;
+
+        Self::write_apdu(&mut *stream, &serialized).await?;
+        let answer = Self::read_apdu(&mut *stream).await?;
+
+        APDUAnswer::from_answer(answer)
+            .map_err(|_| LedgerSpeculosError::Comm("response was too short"))
+    }
+}
+
+#[async_trait]
+impl Exchange for TransportSpeculos {
+    type Error = LedgerSpeculosError;
+    type AnswerType = Vec<u8>;
+
+    async fn exchange<I>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> Result<APDUAnswer<Self::AnswerType>, Self::Error>
+    where
+        I: Deref<Target = [u8]> + Send + Sync,
+    {
+        self.exchange(command).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    /// One request/response pair as seen on the Speculos socket
+    struct Recorded {
+        command: Vec<u8>,
+        answer: Vec<u8>,
+    }
+
+    /// Accept one connection and replay `session`, asserting each incoming frame
+    async fn mock_speculos(session: Vec<Recorded>) -> (u16, tokio::task::JoinHandle<()>) {
+        let listener = TcpListener::bind((SPECULOS_DEFAULT_HOST, 0)).await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let handle = tokio::spawn(async move {
+            let (mut socket, _) = listener.accept().await.unwrap();
+            for exchange in session {
+                let mut len = [0u8; 4];
+                socket.read_exact(&mut len).await.unwrap();
+                let mut command = vec![0u8; u32::from_be_bytes(len) as usize];
+                socket.read_exact(&mut command).await.unwrap();
+                assert_eq!(command, exchange.command);
+
+                let data_len = (exchange.answer.len() - 2) as u32;
+                socket.write_all(&data_len.to_be_bytes()).await.unwrap();
+                socket.write_all(&exchange.answer).await.unwrap();
+            }
+        });
+
+        (port, handle)
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_get_app_configuration() {
+        // GET_APP_CONFIGURATION answered by the Ethereum app 1.10.4
+        let (port, server) = mock_speculos(vec![Recorded {
+            command: vec![0xE0, 0x06, 0x00, 0x00, 0x00],
+            answer: vec![0x0F, 0x01, 0x0A, 0x04, 0x90, 0x00],
+        }])
+        .await;
+
+        let transport = TransportSpeculos::connect(SPECULOS_DEFAULT_HOST, port)
+            .await
+            .unwrap();
+        let command = APDUCommand {
+            cla: 0xE0,
+            ins: 0x06,
+            p1: 0x00,
+            p2: 0x00,
+            data: Vec::new(),
+        };
+        let answer = transport.exchange(&command).await.unwrap();
+
+        assert_eq!(answer.retcode(), 0x9000);
+        assert_eq!(answer.data(), &[0x0F, 0x01, 0x0A, 0x04]);
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_round_trip_multiple_exchanges_via_trait() {
+        let (port, server) = mock_speculos(vec![
+            Recorded {
+                command: vec![0xE0, 0x02, 0x00, 0x00, 0x01, 0x00],
+                answer: vec![0x6A, 0x80],
+            },
+            Recorded {
+                command: vec![0xB0, 0x01, 0x00, 0x00, 0x00],
+                answer: vec![0x01, 0x03, b'E', b'T', b'H', 0x90, 0x00],
+            },
+        ])
+        .await;
+
+        let transport = TransportSpeculos::connect(SPECULOS_DEFAULT_HOST, port)
+            .await
+            .unwrap();
+
+        let rejected = Exchange::exchange(
+            &transport,
+            &APDUCommand {
+                cla: 0xE0,
+                ins: 0x02,
+                p1: 0x00,
+                p2: 0x00,
+                data: vec![0x00],
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(rejected.retcode(), 0x6A80);
+        assert!(rejected.data().is_empty());
+
+        let app_info = Exchange::exchange(
+            &transport,
+            &APDUCommand {
+                cla: 0xB0,
+                ins: 0x01,
+                p1: 0x00,
+                p2: 0x00,
+                data: Vec::new(),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(app_info.retcode(), 0x9000);
+        assert_eq!(app_info.data(), &[0x01, 0x03, b'E', b'T', b'H']);
+
+        server.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_read_apdu_rejects_oversized_length() {
+        let mut frame: &[u8] = &[0xFF, 0xFF, 0xFF, 0xFF, 0x90, 0x00];
+        let err = TransportSpeculos::read_apdu(&mut frame).await.unwrap_err();
+        assert!(matches!(
+            err,
+            LedgerSpeculosError::Comm("Invalid answer length")
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_read_apdu_truncated_stream() {
+        // Announces 4 data bytes but the emulator closes after 1
+        let mut frame: &[u8] = &[0x00, 0x00, 0x00, 0x04, 0xAA];
+        let err = TransportSpeculos::read_apdu(&mut frame).await.unwrap_err();
+        assert!(matches!(err, LedgerSpeculosError::Io(_)));
+    }
+}
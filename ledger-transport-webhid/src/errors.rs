@@ -0,0 +1,34 @@
+use ledger_sdk_transport::hid_framing::FramingError;
+use thiserror::Error;
+use wasm_bindgen::JsValue;
+
+#[derive(Error, Debug)]
+pub enum WebHidError {
+    /// The browser does not expose `navigator.hid`
+    #[error("WebHID is not available in this browser")]
+    Unsupported,
+    /// The user closed the device picker without choosing a device
+    #[error("No Ledger device selected")]
+    NoDeviceSelected,
+    /// The device went away mid-exchange
+    #[error("Ledger device disconnected")]
+    Disconnected,
+    /// Communication error
+    #[error("WebHID: communication error `{0}`")]
+    Comm(&'static str),
+    /// A WebHID call was rejected, e.g. `requestDevice` outside a user gesture
+    #[error("WebHID: {0}")]
+    Js(String),
+}
+
+impl From<JsValue> for WebHidError {
+    fn from(value: JsValue) -> Self {
+        WebHidError::Js(format!("{:?}", value))
+    }
+}
+
+impl From<FramingError> for WebHidError {
+    fn from(error: FramingError) -> Self {
+        WebHidError::Comm(error.0)
+    }
+}
@@ -0,0 +1,182 @@
+//! Transport talking to a Ledger device from the browser through
+//! [WebHID](https://wicg.github.io/webhid/), for `wasm32` targets.
+//!
+//! APDUs are framed as by `ledger-sdk-transport-hid`, with the framing shared
+//! in `ledger_sdk_transport::hid_framing`: 64-byte reports sent with report ID 0,
+//! answers received through `inputreport` events.
+//!
+//! ```ignore
+//! // From a click handler: browsers only show the picker on a user gesture
+//! let device = TransportWebHid::request_device().await?;
+//! let app = EthereumApp::new(TransportWebHid::open(device).await?);
+//! ```
+#![cfg(target_arch = "wasm32")]
+
+mod errors;
+
+use std::ops::Deref;
+
+pub use errors::WebHidError;
+use futures::{
+    channel::mpsc::{self, UnboundedReceiver},
+    lock::Mutex,
+    StreamExt,
+};
+use js_sys::{Array, Reflect, Uint8Array};
+use ledger_sdk_transport::hid_framing::{self, AnswerReassembler, LEDGER_VENDOR_ID};
+use ledger_sdk_transport::{async_trait, APDUAnswer, APDUCommand, Exchange};
+use send_wrapper::SendWrapper;
+use wasm_bindgen::{closure::Closure, JsCast};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{HidDevice, HidDeviceFilter, HidDeviceRequestOptions, HidInputReportEvent};
+
+/// Channel used by other Ledger libraries; transports pick a random one by default
+pub const LEDGER_CHANNEL: u16 = 0x0101;
+
+/// WebHID transport to a Ledger device
+///
+/// Exchanges are serialized: each one holds the device until its answer is
+/// complete. Reports left over from an abandoned exchange are dropped before the
+/// next command is sent. WebHID objects live on the browser's thread; the transport is only
+/// `Send` and `Sync` so it satisfies [`Exchange`] bounds, and panics if used from
+/// another thread.
+pub struct TransportWebHid {
+    device: SendWrapper<HidDevice>,
+    channel: u16,
+    reports: Mutex<UnboundedReceiver<Vec<u8>>>,
+    // Keeps the `inputreport` handler alive as long as the transport
+    _on_input_report: SendWrapper<Closure<dyn FnMut(HidInputReportEvent)>>,
+}
+
+impl TransportWebHid {
+    /// Whether the browser exposes WebHID
+    pub fn is_supported() -> bool {
+        web_sys::window()
+            .and_then(|window| Reflect::has(&window.navigator(), &"hid".into()).ok())
+            .unwrap_or(false)
+    }
+
+    /// Prompt the user to pick a Ledger device
+    ///
+    /// Browsers only show the picker from a user gesture, e.g. a click handler.
+    pub async fn request_device() -> Result<HidDevice, WebHidError> {
+        let window = web_sys::window().ok_or(WebHidError::Unsupported)?;
+        if !Self::is_supported() {
+            return Err(WebHidError::Unsupported);
+        }
+
+        let filter = HidDeviceFilter::new();
+        filter.set_vendor_id(u32::from(LEDGER_VENDOR_ID));
+        let options = HidDeviceRequestOptions::new(&[filter]);
+
+        let devices = JsFuture::from(window.navigator().hid().request_device(&options)).await?;
+        Array::from(&devices)
+            .get(0)
+            .dyn_into::<HidDevice>()
+            .map_err(|_| WebHidError::NoDeviceSelected)
+    }
+
+    /// Open `device`, e.g. from [`TransportWebHid::request_device`]
+    pub async fn open(device: HidDevice) -> Result<Self, WebHidError> {
+        if !device.opened() {
+            JsFuture::from(device.open()).await?;
+        }
+
+        let (sender, receiver) = mpsc::unbounded();
+        let on_input_report =
+            Closure::<dyn FnMut(HidInputReportEvent)>::new(move |event: HidInputReportEvent| {
+                let data = event.data();
+                let report = Uint8Array::new_with_byte_offset_and_length(
+                    &data.buffer(),
+                    data.byte_offset() as u32,
+                    data.byte_length() as u32,
+                );
+                // Fails only once the transport is gone
+                let _ = sender.unbounded_send(report.to_vec());
+            });
+        device.set_oninputreport(Some(on_input_report.as_ref().unchecked_ref()));
+
+        Ok(TransportWebHid {
+            device: SendWrapper::new(device),
+            channel: random_channel(),
+            reports: Mutex::new(receiver),
+            _on_input_report: SendWrapper::new(on_input_report),
+        })
+    }
+
+    /// Frame commands on `channel` instead of the random one picked when opening
+    pub fn with_channel(mut self, channel: u16) -> Self {
+        self.channel = channel;
+        self
+    }
+
+    /// HID channel commands are framed on
+    pub fn channel(&self) -> u16 {
+        self.channel
+    }
+
+    /// Close the device
+    pub async fn close(self) -> Result<(), WebHidError> {
+        self.device.set_oninputreport(None);
+        JsFuture::from(self.device.close()).await?;
+        Ok(())
+    }
+
+    pub async fn exchange<I: Deref<Target = [u8]>>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> Result<APDUAnswer<Vec<u8>>, WebHidError> {
+        let serialized = command
+            .try_serialize()
+            .map_err(|_| WebHidError::Comm("command data longer than 255 bytes"))?;
+        let mut reports = self.reports.lock().await;
+        // Answers to an exchange whose future was dropped are still queued
+        while let Ok(Some(_)) = reports.try_next() {}
+
+        for frame in hid_framing::frame_command(self.channel, &serialized)? {
+            let report = Uint8Array::from(&frame[..]);
+            JsFuture::from(self.device.send_report_with_buffer_source(0, &report)).await?;
+        }
+
+        let mut reassembler = AnswerReassembler::new(self.channel, 0);
+        let mut answer = Vec::new();
+        loop {
+            let report = reports.next().await.ok_or(WebHidError::Disconnected)?;
+            if reassembler.push(&report, &mut answer)? {
+                break;
+            }
+        }
+
+        APDUAnswer::from_answer(answer).map_err(|_| WebHidError::Comm("response was too short"))
+    }
+}
+
+impl Drop for TransportWebHid {
+    fn drop(&mut self) {
+        // The handler is freed with the transport; JS must not call it afterwards
+        self.device.set_oninputreport(None);
+    }
+}
+
+/// Random channel, so answers meant for another client are recognisable
+fn random_channel() -> u16 {
+    // std has no entropy source on wasm32-unknown-unknown
+    (js_sys::Math::random() * f64::from(u16::MAX)) as u16
+}
+
+#[async_trait]
+impl Exchange for TransportWebHid {
+    type Error = WebHidError;
+    type AnswerType = Vec<u8>;
+
+    async fn exchange<I>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> Result<APDUAnswer<Self::AnswerType>, Self::Error>
+    where
+        I: Deref<Target = [u8]> + Send + Sync,
+    {
+        // WebHID promises are not Send; wasm32 polls them on the browser's thread
+        SendWrapper::new(TransportWebHid::exchange(self, command)).await
+    }
+}
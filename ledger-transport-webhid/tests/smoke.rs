@@ -0,0 +1,27 @@
+//! Browser smoke tests: `wasm-pack test --headless --chrome`
+#![cfg(target_arch = "wasm32")]
+
+use ledger_sdk_transport::Exchange;
+use ledger_sdk_transport_webhid::{TransportWebHid, WebHidError};
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn assert_exchange<E: Exchange + Send + Sync>() {}
+
+#[wasm_bindgen_test]
+fn test_transport_is_an_exchange() {
+    // `EthereumApp` requires a `Send + Sync` transport
+    assert_exchange::<TransportWebHid>();
+}
+
+#[wasm_bindgen_test]
+async fn test_request_device_needs_a_user_gesture() {
+    let err = TransportWebHid::request_device().await.unwrap_err();
+    if TransportWebHid::is_supported() {
+        // No click: the browser refuses to show the picker
+        assert!(matches!(err, WebHidError::Js(_)), "{:?}", err);
+    } else {
+        assert!(matches!(err, WebHidError::Unsupported));
+    }
+}
@@ -0,0 +1,91 @@
+//! Synchronous adapter for any [`Exchange`].
+//!
+//! [`BlockingExchange`] drives each exchange to completion on a single-threaded
+//! Tokio runtime it owns, for code without a runtime of its own (scripts, FFI
+//! callers, other sync SDKs). Don't use it from inside an async context: Tokio
+//! panics when a runtime is blocked on from within another one.
+
+use std::ops::Deref;
+
+use ledger_sdk_apdu::{APDUAnswer, APDUCommand};
+use tokio::runtime::{Builder, Runtime};
+
+use crate::Exchange;
+
+/// Transport wrapper exchanging APDUs without `async`
+#[derive(Debug)]
+pub struct BlockingExchange<E> {
+    inner: E,
+    runtime: Runtime,
+}
+
+impl<E: Exchange> BlockingExchange<E> {
+    /// Wrap `inner`
+    ///
+    /// Fails if the runtime cannot be created.
+    pub fn new(inner: E) -> std::io::Result<Self> {
+        let runtime = Builder::new_current_thread().enable_all().build()?;
+        Ok(BlockingExchange { inner, runtime })
+    }
+
+    /// Send `command` and wait for the answer
+    pub fn exchange<I>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> Result<APDUAnswer<E::AnswerType>, E::Error>
+    where
+        I: Deref<Target = [u8]> + Send + Sync,
+    {
+        self.runtime.block_on(self.inner.exchange(command))
+    }
+
+    /// Get a reference to the wrapped transport
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    /// Consume the wrapper and return the wrapped transport
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::mock::{DelayingExchange, MockExchange};
+
+    fn command() -> APDUCommand<Vec<u8>> {
+        APDUCommand {
+            cla: 0xE0,
+            ins: 0x06,
+            p1: 0x00,
+            p2: 0x00,
+            data: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_exchange_without_runtime() {
+        let mock = MockExchange::new();
+        mock.push_ok(&[0x00, 1, 12, 0]).push_status(0x6985);
+        let transport = BlockingExchange::new(mock).unwrap();
+
+        let answer = transport.exchange(&command()).unwrap();
+        assert_eq!(answer.split(), (&[0x00, 1, 12, 0][..], 0x9000));
+        assert_eq!(transport.exchange(&command()).unwrap().retcode(), 0x6985);
+        assert_eq!(transport.into_inner().sent().len(), 2);
+    }
+
+    #[test]
+    fn test_drives_timers() {
+        let mock = MockExchange::new();
+        mock.push_ok(&[]);
+        let transport =
+            BlockingExchange::new(DelayingExchange::new(mock, Duration::from_millis(5))).unwrap();
+
+        assert_eq!(transport.exchange(&command()).unwrap().retcode(), 0x9000);
+    }
+}
@@ -0,0 +1,159 @@
+//! Transport sharing between tasks.
+//!
+//! Many commands span several APDUs and the device keeps state between them:
+//! an APDU from another task landing in the middle of a transaction or an
+//! EIP-712 message corrupts the flow. [`ExchangeGuard`] serializes access to a
+//! transport, one exchange at a time, or for a whole session run with
+//! [`ExchangeGuard::with_session`].
+
+use std::{
+    future::Future,
+    ops::Deref,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use async_trait::async_trait;
+use ledger_sdk_apdu::{APDUAnswer, APDUCommand};
+use tokio::sync::Mutex;
+
+use crate::Exchange;
+
+static NEXT_GUARD_ID: AtomicUsize = AtomicUsize::new(0);
+
+tokio::task_local! {
+    /// Guards whose session the current task holds
+    static HELD_SESSIONS: Vec<usize>;
+}
+
+/// Transport wrapper giving one task at a time access to the device
+#[derive(Debug)]
+pub struct ExchangeGuard<E> {
+    inner: E,
+    lock: Mutex<()>,
+    id: usize,
+}
+
+impl<E: Exchange> ExchangeGuard<E> {
+    /// Wrap `inner`
+    pub fn new(inner: E) -> Self {
+        ExchangeGuard {
+            inner,
+            lock: Mutex::new(()),
+            id: NEXT_GUARD_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    /// Run `f` with exclusive access to the transport
+    ///
+    /// Exchanges from other tasks wait until `f` completes. Exchanges made by
+    /// `f` itself, including nested sessions, go through right away; they must
+    /// run on the calling task, not on a spawned one, and one after the other.
+    pub async fn with_session<F: Future>(&self, f: F) -> F::Output {
+        if self.session_held() {
+            return f.await;
+        }
+
+        let _lock = self.lock.lock().await;
+        let mut held = HELD_SESSIONS.try_with(Vec::clone).unwrap_or_default();
+        held.push(self.id);
+        HELD_SESSIONS.scope(held, f).await
+    }
+
+    /// Get a reference to the wrapped transport
+    ///
+    /// Exchanges made directly on it bypass the guard.
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    /// Consume the wrapper and return the wrapped transport
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+
+    fn session_held(&self) -> bool {
+        HELD_SESSIONS
+            .try_with(|held| held.contains(&self.id))
+            .unwrap_or(false)
+    }
+}
+
+#[async_trait]
+impl<E> Exchange for ExchangeGuard<E>
+where
+    E: Exchange + Send + Sync,
+{
+    type Error = E::Error;
+    type AnswerType = E::AnswerType;
+
+    async fn exchange<I>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> Result<APDUAnswer<Self::AnswerType>, Self::Error>
+    where
+        I: Deref<Target = [u8]> + Send + Sync,
+    {
+        if self.session_held() {
+            return self.inner.exchange(command).await;
+        }
+
+        let _lock = self.lock.lock().await;
+        self.inner.exchange(command).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, time::Duration};
+
+    use super::*;
+    use crate::mock::{DelayingExchange, MockExchange};
+
+    fn command(ins: u8) -> APDUCommand<Vec<u8>> {
+        APDUCommand {
+            cla: 0xE0,
+            ins,
+            p1: 0x00,
+            p2: 0x00,
+            data: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_sessions_are_not_interleaved() {
+        let mock = MockExchange::new().with_responder(|_| Ok(vec![0x90, 0x00]));
+        let guard = Arc::new(ExchangeGuard::new(DelayingExchange::new(
+            mock,
+            Duration::from_millis(1),
+        )));
+
+        let tasks: Vec<_> = (0..4u8)
+            .map(|ins| {
+                let guard = guard.clone();
+                tokio::spawn(async move {
+                    guard
+                        .with_session(async {
+                            for _ in 0..3 {
+                                guard.exchange(&command(ins)).await.unwrap();
+                                // Nested sessions do not wait for the held lock
+                                guard
+                                    .with_session(guard.exchange(&command(ins)))
+                                    .await
+                                    .unwrap();
+                            }
+                        })
+                        .await
+                })
+            })
+            .collect();
+        for task in tasks {
+            task.await.unwrap();
+        }
+
+        let sent: Vec<u8> = guard.inner().inner().sent().iter().map(|c| c.ins).collect();
+        assert_eq!(sent.len(), 24);
+        for session in sent.chunks(6) {
+            assert!(session.iter().all(|ins| *ins == session[0]), "{:?}", sent);
+        }
+    }
+}
@@ -0,0 +1,289 @@
+//! Ledger HID APDU framing.
+//!
+//! Every HID transport (native hidapi, WebHID) speaks the same protocol: an APDU
+//! is split into 64-byte frames, each starting with the channel, the 0x05 tag
+//! and a sequence index; the first frame also carries the APDU length. Answers
+//! come back framed the same way. Keeping it here lets the transports share the
+//! framing code and its tests.
+
+use thiserror::Error;
+
+/// USB vendor ID of Ledger devices
+pub const LEDGER_VENDOR_ID: u16 = 0x2c97;
+/// HID usage page of the Ledger APDU interface
+pub const LEDGER_USAGE_PAGE: u16 = 0xffa0;
+/// Size of a frame, without the report ID some HID APIs prepend
+pub const HID_FRAME_SIZE: usize = 64;
+/// Tag of APDU frames
+pub const TAG_APDU: u8 = 0x05;
+/// Frames from other channels discarded while reading one answer before giving up
+pub const MAX_FOREIGN_FRAMES: usize = 16;
+
+/// Channel (2), tag (1) and sequence index (2) starting every frame
+const FRAME_HEADER_SIZE: usize = 5;
+
+/// Malformed command or answer frames
+#[derive(Clone, Copy, Debug, Error, PartialEq, Eq)]
+#[error("{0}")]
+pub struct FramingError(pub &'static str);
+
+/// Split a serialized APDU into host->device frames
+///
+/// Every frame is: channel (2), tag (1), sequence index (2). Only the first frame
+/// then carries the 2-byte APDU length; continuation frames carry APDU data
+/// right after the sequence index. Frames are zero padded.
+pub fn frame_command(
+    channel: u16,
+    apdu_command: &[u8],
+) -> Result<Vec<[u8; HID_FRAME_SIZE]>, FramingError> {
+    let command_length =
+        u16::try_from(apdu_command.len()).map_err(|_| FramingError("APDU command too long"))?;
+
+    let mut frames = Vec::new();
+    let mut remaining = apdu_command;
+    let mut sequence_idx = 0u16;
+    loop {
+        let mut frame = [0u8; HID_FRAME_SIZE];
+        frame[0..2].copy_from_slice(&channel.to_be_bytes());
+        frame[2] = TAG_APDU;
+        frame[3..5].copy_from_slice(&sequence_idx.to_be_bytes());
+
+        let mut offset = FRAME_HEADER_SIZE;
+        if sequence_idx == 0 {
+            frame[offset..offset + 2].copy_from_slice(&command_length.to_be_bytes());
+            offset += 2;
+        }
+
+        let chunk_len = remaining.len().min(HID_FRAME_SIZE - offset);
+        frame[offset..offset + chunk_len].copy_from_slice(&remaining[..chunk_len]);
+        remaining = &remaining[chunk_len..];
+        frames.push(frame);
+
+        if remaining.is_empty() {
+            return Ok(frames);
+        }
+        // Cannot overflow: a u16-sized command needs far fewer than 2^16 frames
+        sequence_idx += 1;
+    }
+}
+
+/// Reassembles an APDU answer from device->host frames
+///
+/// Frames from other channels are discarded, up to [`MAX_FOREIGN_FRAMES`].
+/// Frames on our channel with a wrong tag or sequence index are only skipped
+/// when stale frame skipping allows it.
+#[derive(Debug)]
+pub struct AnswerReassembler {
+    channel: u16,
+    max_stale_frames: usize,
+    sequence_idx: u16,
+    expected_len: usize,
+    stale_frames: usize,
+    foreign_frames: usize,
+}
+
+impl AnswerReassembler {
+    /// Reassemble an answer on `channel`, skipping up to `max_stale_frames`
+    /// frames left over from an earlier exchange
+    pub fn new(channel: u16, max_stale_frames: usize) -> Self {
+        AnswerReassembler {
+            channel,
+            max_stale_frames,
+            sequence_idx: 0,
+            expected_len: 0,
+            stale_frames: 0,
+            foreign_frames: 0,
+        }
+    }
+
+    /// Whether no frame of the answer was accepted yet
+    pub fn awaiting_first_frame(&self) -> bool {
+        self.sequence_idx == 0
+    }
+
+    /// Add `frame` to `answer`, returning whether the answer is complete
+    pub fn push(&mut self, frame: &[u8], answer: &mut Vec<u8>) -> Result<bool, FramingError> {
+        if frame.len() < FRAME_HEADER_SIZE {
+            return Err(FramingError("USB read error. Incomplete header"));
+        }
+
+        let rcv_channel = u16::from_be_bytes([frame[0], frame[1]]);
+        let rcv_tag = frame[2];
+        let rcv_seq_idx = u16::from_be_bytes([frame[3], frame[4]]);
+
+        if rcv_channel != self.channel {
+            if self.foreign_frames == MAX_FOREIGN_FRAMES {
+                return Err(FramingError("Invalid channel"));
+            }
+            self.foreign_frames += 1;
+            #[cfg(feature = "logging")]
+            tracing::debug!("discarding frame from channel {:#06x}", rcv_channel);
+            return Ok(false);
+        }
+
+        let header_error = if rcv_tag != TAG_APDU {
+            Some("Invalid tag")
+        } else if rcv_seq_idx != self.sequence_idx {
+            Some("Invalid sequence index")
+        } else {
+            None
+        };
+        if let Some(error) = header_error {
+            if self.stale_frames == self.max_stale_frames {
+                return Err(FramingError(error));
+            }
+            self.stale_frames += 1;
+            #[cfg(feature = "logging")]
+            tracing::debug!("skipping stale frame: {}", error);
+            return Ok(false);
+        }
+
+        let mut start = FRAME_HEADER_SIZE;
+        if rcv_seq_idx == 0 {
+            if frame.len() < FRAME_HEADER_SIZE + 2 {
+                return Err(FramingError("USB read error. Incomplete header"));
+            }
+            self.expected_len = u16::from_be_bytes([frame[5], frame[6]]) as usize;
+            // Every answer carries at least the 2-byte status word
+            if self.expected_len < 2 {
+                return Err(FramingError("Invalid answer length"));
+            }
+            start += 2;
+        }
+
+        let missing = self
+            .expected_len
+            .checked_sub(answer.len())
+            .ok_or(FramingError("length mismatch"))?;
+        let end = start + std::cmp::min(frame.len() - start, missing);
+        answer.extend_from_slice(&frame[start..end]);
+
+        if answer.len() >= self.expected_len {
+            return Ok(true);
+        }
+
+        self.sequence_idx = self
+            .sequence_idx
+            .checked_add(1)
+            .ok_or(FramingError("Sequence index overflow"))?;
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Device->host frame: channel, tag, sequence index, payload
+    fn frame(channel: u16, seq: u16, payload: &[u8]) -> Vec<u8> {
+        let mut frame = channel.to_be_bytes().to_vec();
+        frame.push(TAG_APDU);
+        frame.extend_from_slice(&seq.to_be_bytes());
+        frame.extend_from_slice(payload);
+        frame.resize(HID_FRAME_SIZE, 0);
+        frame
+    }
+
+    #[test]
+    fn test_frame_layout() {
+        let frames = frame_command(0x0101, &[0xE0, 0x06, 0x00, 0x00, 0x00]).unwrap();
+        assert_eq!(frames.len(), 1);
+        assert_eq!(
+            &frames[0][..12],
+            &[0x01, 0x01, 0x05, 0x00, 0x00, 0x00, 0x05, 0xE0, 0x06, 0x00, 0x00, 0x00]
+        );
+        assert!(frames[0][12..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_framing_round_trip() {
+        for len in [0usize, 1, 57, 58, 116, 117, 300] {
+            let command: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let frames = frame_command(0xBEEF, &command).unwrap();
+            assert_eq!(
+                frames.len(),
+                if len <= 57 {
+                    1
+                } else {
+                    1 + (len - 57).div_ceil(59)
+                }
+            );
+
+            // An answer always carries a status word, shorter ones cannot be fed back
+            if len < 2 {
+                continue;
+            }
+            // Device->host frames have the same layout
+            let mut reassembler = AnswerReassembler::new(0xBEEF, 0);
+            let mut answer = Vec::new();
+            let mut complete = false;
+            for frame in &frames {
+                assert!(!complete);
+                complete = reassembler.push(frame, &mut answer).unwrap();
+            }
+            assert!(complete);
+            assert_eq!(answer, command);
+        }
+
+        assert_eq!(
+            frame_command(0x0101, &vec![0u8; 0x10000]).unwrap_err(),
+            FramingError("APDU command too long")
+        );
+    }
+
+    #[test]
+    fn test_reassembler_discards_foreign_frames() {
+        let mut reassembler = AnswerReassembler::new(0x0101, 0);
+        let mut answer = Vec::new();
+        for _ in 0..MAX_FOREIGN_FRAMES {
+            let foreign = frame(0x0202, 0, &[0x00, 0x02, 0x90, 0x00]);
+            assert_eq!(reassembler.push(&foreign, &mut answer), Ok(false));
+        }
+        assert!(reassembler.awaiting_first_frame());
+
+        let answer_frame = frame(0x0101, 0, &[0x00, 0x02, 0x90, 0x00]);
+        assert_eq!(reassembler.push(&answer_frame, &mut answer), Ok(true));
+        assert_eq!(answer, [0x90, 0x00]);
+
+        let mut reassembler = AnswerReassembler::new(0x0101, 0);
+        for _ in 0..MAX_FOREIGN_FRAMES {
+            let _ = reassembler.push(&frame(0x0202, 0, &[]), &mut answer);
+        }
+        assert_eq!(
+            reassembler.push(&frame(0x0202, 0, &[]), &mut answer),
+            Err(FramingError("Invalid channel"))
+        );
+    }
+
+    #[test]
+    fn test_reassembler_stale_frames() {
+        let stale = frame(0x0101, 3, &[0xFF; 8]);
+        let answer_frame = frame(0x0101, 0, &[0x00, 0x02, 0x90, 0x00]);
+
+        let mut answer = Vec::new();
+        let mut reassembler = AnswerReassembler::new(0x0101, 0);
+        assert_eq!(
+            reassembler.push(&stale, &mut answer),
+            Err(FramingError("Invalid sequence index"))
+        );
+
+        let mut reassembler = AnswerReassembler::new(0x0101, 1);
+        assert_eq!(reassembler.push(&stale, &mut answer), Ok(false));
+        assert_eq!(reassembler.push(&answer_frame, &mut answer), Ok(true));
+        assert_eq!(answer, [0x90, 0x00]);
+    }
+
+    #[test]
+    fn test_reassembler_rejects_short_frames() {
+        let mut reassembler = AnswerReassembler::new(0x0101, 0);
+        let mut answer = Vec::new();
+        assert_eq!(
+            reassembler.push(&[0x01, 0x01, 0x05], &mut answer),
+            Err(FramingError("USB read error. Incomplete header"))
+        );
+        assert_eq!(
+            reassembler.push(&[0x01, 0x01, 0x05, 0x00, 0x00, 0x00, 0x01], &mut answer),
+            Err(FramingError("Invalid answer length"))
+        );
+    }
+}
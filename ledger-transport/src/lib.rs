@@ -1,7 +1,32 @@
 use std::ops::Deref;
 
+mod blocking;
+mod guard;
+pub mod hid_framing;
+#[cfg(feature = "logging")]
+mod logging;
+pub mod mock;
+#[cfg(feature = "retry")]
+mod retry;
+mod timeouts;
+#[cfg(feature = "unlock-wait")]
+mod unlock;
+
 pub use async_trait::async_trait;
+pub use blocking::BlockingExchange;
+pub use guard::ExchangeGuard;
 pub use ledger_sdk_apdu::{APDUAnswer, APDUCommand, APDUErrorCode};
+#[cfg(feature = "logging")]
+pub use logging::{ExchangeLogger, LoggingExchange};
+#[cfg(feature = "retry")]
+pub use retry::RetryExchange;
+pub use timeouts::{ConfirmationClassifier, ExchangeTimeouts};
+#[cfg(feature = "timeouts")]
+pub use timeouts::{TimeoutError, TimeoutExchange};
+#[cfg(feature = "unlock-wait")]
+pub use unlock::{
+    wait_until_unlocked, UnlockWaitExchange, DEFAULT_UNLOCK_POLL_INTERVAL, SW_DEVICE_LOCKED,
+};
 
 /// Use to talk to the ledger device
 #[async_trait]
@@ -0,0 +1,254 @@
+//! Logging [`Exchange`] decorator.
+//!
+//! [`ExchangeLogger`] reports every exchange through `tracing`: command header,
+//! payload length, elapsed time and status word at `debug` level, payloads as hex
+//! only at `trace` level. With redaction enabled payloads are never logged, which
+//! keeps signing data and addresses out of logs shared in bug reports. Apps can
+//! attach a status word decoder so the logs name each status.
+
+use std::{fmt::Write, ops::Deref, time::Instant};
+
+use async_trait::async_trait;
+use ledger_sdk_apdu::{APDUAnswer, APDUCommand};
+use tracing::{debug, enabled, trace, Level};
+
+use crate::Exchange;
+
+/// Transport wrapper logging every exchange
+#[derive(Debug)]
+pub struct ExchangeLogger<E> {
+    inner: E,
+    redact: bool,
+    describe_status: Option<fn(u16) -> &'static str>,
+}
+
+/// [`ExchangeLogger`], named after the other transport decorators
+pub type LoggingExchange<E> = ExchangeLogger<E>;
+
+impl<E: Exchange> ExchangeLogger<E> {
+    /// Wrap `inner`, logging payloads at trace level
+    pub fn new(inner: E) -> Self {
+        ExchangeLogger {
+            inner,
+            redact: false,
+            describe_status: None,
+        }
+    }
+
+    /// Replace command and answer payloads with their length in trace logs
+    pub fn with_redaction(mut self, redact: bool) -> Self {
+        self.redact = redact;
+        self
+    }
+
+    /// Log the description `describe` gives of each answer's status word
+    pub fn with_status_description(mut self, describe: fn(u16) -> &'static str) -> Self {
+        self.describe_status = Some(describe);
+        self
+    }
+
+    /// Get a reference to the wrapped transport
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    /// Consume the wrapper and return the wrapped transport
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+
+    /// Payload as logged at trace level
+    fn format_data(&self, data: &[u8]) -> String {
+        if self.redact {
+            return format!("<redacted {} bytes>", data.len());
+        }
+        data.iter()
+            .fold(String::with_capacity(data.len() * 2), |mut out, byte| {
+                let _ = write!(out, "{:02x}", byte);
+                out
+            })
+    }
+}
+
+#[async_trait]
+impl<E> Exchange for ExchangeLogger<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: std::fmt::Display + Send,
+{
+    type Error = E::Error;
+    type AnswerType = E::AnswerType;
+
+    async fn exchange<I>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> Result<APDUAnswer<Self::AnswerType>, Self::Error>
+    where
+        I: Deref<Target = [u8]> + Send + Sync,
+    {
+        debug!(
+            cla = format_args!("0x{:02X}", command.cla),
+            ins = format_args!("0x{:02X}", command.ins),
+            p1 = format_args!("0x{:02X}", command.p1),
+            p2 = format_args!("0x{:02X}", command.p2),
+            len = command.data.len(),
+            "=> APDU"
+        );
+        if enabled!(Level::TRACE) {
+            trace!(data = %self.format_data(&command.data), "=> APDU data");
+        }
+
+        let start = Instant::now();
+        let result = self.inner.exchange(command).await;
+        let elapsed_ms = start.elapsed().as_millis() as u64;
+
+        match &result {
+            Ok(answer) => {
+                let sw = answer.retcode();
+                debug!(
+                    sw = format_args!("0x{:04X}", sw),
+                    status = self.describe_status.map(|describe| describe(sw)),
+                    len = answer.data().len(),
+                    elapsed_ms,
+                    "<= APDU"
+                );
+                if enabled!(Level::TRACE) {
+                    trace!(data = %self.format_data(answer.data()), "<= APDU data");
+                }
+            }
+            Err(error) => debug!(%error, elapsed_ms, "<= APDU transport error"),
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use tracing_subscriber::fmt::MakeWriter;
+
+    use super::*;
+    use crate::mock::MockExchange;
+
+    /// Log sink shared between the subscriber and the test
+    #[derive(Clone, Default)]
+    struct Capture(Arc<Mutex<Vec<u8>>>);
+
+    impl Capture {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    impl std::io::Write for Capture {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl<'a> MakeWriter<'a> for Capture {
+        type Writer = Capture;
+
+        fn make_writer(&'a self) -> Self::Writer {
+            self.clone()
+        }
+    }
+
+    fn command() -> APDUCommand<Vec<u8>> {
+        APDUCommand {
+            cla: 0xE0,
+            ins: 0x08,
+            p1: 0x00,
+            p2: 0x00,
+            data: vec![0xDE, 0xAD, 0xBE, 0xEF],
+        }
+    }
+
+    async fn exchange_logged(redact: bool) -> String {
+        let capture = Capture::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(Level::TRACE)
+            .with_ansi(false)
+            .with_writer(capture.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let mock = MockExchange::new();
+        mock.push_ok(&[0xCA, 0xFE]);
+        let transport = ExchangeLogger::new(mock).with_redaction(redact);
+        let answer = transport.exchange(&command()).await.unwrap();
+        assert_eq!(answer.split(), (&[0xCA, 0xFE][..], 0x9000));
+
+        capture.contents()
+    }
+
+    #[tokio::test]
+    async fn test_logs_header_status_and_payloads() {
+        let logs = exchange_logged(false).await;
+        assert!(logs.contains("ins=0x08"), "{}", logs);
+        assert!(logs.contains("len=4"), "{}", logs);
+        assert!(logs.contains("sw=0x9000"), "{}", logs);
+        assert!(logs.contains("deadbeef"), "{}", logs);
+        assert!(logs.contains("cafe"), "{}", logs);
+    }
+
+    #[tokio::test]
+    async fn test_redacts_payloads() {
+        let logs = exchange_logged(true).await;
+        assert!(logs.contains("ins=0x08"), "{}", logs);
+        assert!(logs.contains("sw=0x9000"), "{}", logs);
+        assert!(logs.contains("<redacted 4 bytes>"), "{}", logs);
+        assert!(logs.contains("<redacted 2 bytes>"), "{}", logs);
+        assert!(!logs.contains("deadbeef"), "{}", logs);
+        assert!(!logs.contains("cafe"), "{}", logs);
+    }
+
+    #[tokio::test]
+    async fn test_forwards_commands_unchanged() {
+        let mock = MockExchange::new();
+        mock.push_status(0x6985);
+        let transport: LoggingExchange<_> = ExchangeLogger::new(mock);
+
+        let answer = transport.exchange(&command()).await.unwrap();
+        assert_eq!(answer.retcode(), 0x6985);
+
+        let sent = transport.inner().sent();
+        assert_eq!(sent.len(), 1);
+        let expected = command();
+        assert_eq!(
+            (sent[0].cla, sent[0].ins, sent[0].p1, sent[0].p2),
+            (expected.cla, expected.ins, expected.p1, expected.p2)
+        );
+        assert_eq!(sent[0].data, expected.data);
+    }
+
+    #[tokio::test]
+    async fn test_logs_status_description() {
+        let capture = Capture::default();
+        let subscriber = tracing_subscriber::fmt()
+            .with_max_level(Level::DEBUG)
+            .with_ansi(false)
+            .with_writer(capture.clone())
+            .finish();
+        let _guard = tracing::subscriber::set_default(subscriber);
+
+        let mock = MockExchange::new();
+        mock.push_status(0x6985);
+        let transport = ExchangeLogger::new(mock).with_status_description(|sw| match sw {
+            0x6985 => "Condition not satisfied",
+            _ => "Unknown status",
+        });
+        transport.exchange(&command()).await.unwrap();
+
+        let logs = capture.contents();
+        assert!(logs.contains("sw=0x6985"), "{}", logs);
+        assert!(logs.contains("Condition not satisfied"), "{}", logs);
+    }
+}
@@ -0,0 +1,230 @@
+//! In-memory [`Exchange`] implementation for tests.
+//!
+//! [`MockExchange`] records every command it is given and answers from a queue of
+//! canned responses, falling back to an optional responder closure once the queue
+//! is empty. `DelayingExchange` (feature `timeouts`) slows down any transport to
+//! exercise timeouts.
+//!
+//! Answers are `Vec<u8>` by default. `MockExchange::<Arc<[u8]>>::default()` (or any
+//! other `From<Vec<u8>>` buffer) checks that code generic over
+//! [`Exchange::AnswerType`] does not rely on `Vec`.
+
+use std::{
+    collections::VecDeque,
+    marker::PhantomData,
+    ops::Deref,
+    sync::{Mutex, MutexGuard},
+};
+
+use async_trait::async_trait;
+use ledger_sdk_apdu::{APDUAnswer, APDUCommand};
+use thiserror::Error;
+
+use crate::Exchange;
+
+#[cfg(any(test, feature = "timeouts"))]
+mod delaying;
+#[cfg(any(test, feature = "timeouts"))]
+pub use delaying::DelayingExchange;
+
+/// Errors produced by [`MockExchange`]
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum MockError {
+    /// No queued response and no responder configured
+    #[error("mock transport: no response queued")]
+    NoResponse,
+    /// The queued answer was shorter than the 2-byte status word
+    #[error("mock transport: answer too short")]
+    InvalidAnswer,
+    /// Transport failure injected by the test
+    #[error("mock transport: {0}")]
+    Transport(String),
+}
+
+type Responder = Box<dyn Fn(&APDUCommand<Vec<u8>>) -> Result<Vec<u8>, MockError> + Send + Sync>;
+
+/// Scripted transport recording sent commands, answering with `A` buffers
+pub struct MockExchange<A = Vec<u8>> {
+    responses: Mutex<VecDeque<Result<Vec<u8>, MockError>>>,
+    responder: Option<Responder>,
+    sent: Mutex<Vec<APDUCommand<Vec<u8>>>>,
+    answer_type: PhantomData<fn() -> A>,
+}
+
+impl<A> Default for MockExchange<A> {
+    fn default() -> Self {
+        MockExchange {
+            responses: Mutex::default(),
+            responder: None,
+            sent: Mutex::default(),
+            answer_type: PhantomData,
+        }
+    }
+}
+
+impl MockExchange {
+    /// Create a mock with no queued responses
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<A> MockExchange<A> {
+    /// Answer every command not covered by the queue with `responder`
+    pub fn with_responder<F>(mut self, responder: F) -> Self
+    where
+        F: Fn(&APDUCommand<Vec<u8>>) -> Result<Vec<u8>, MockError> + Send + Sync + 'static,
+    {
+        self.responder = Some(Box::new(responder));
+        self
+    }
+
+    /// Queue a raw answer (payload followed by the status word)
+    pub fn push_answer(&self, answer: impl Into<Vec<u8>>) -> &Self {
+        lock(&self.responses).push_back(Ok(answer.into()));
+        self
+    }
+
+    /// Queue a successful answer carrying `data`
+    pub fn push_ok(&self, data: &[u8]) -> &Self {
+        let mut answer = data.to_vec();
+        answer.extend_from_slice(&[0x90, 0x00]);
+        self.push_answer(answer)
+    }
+
+    /// Queue an answer with no payload and the given status word
+    pub fn push_status(&self, sw: u16) -> &Self {
+        self.push_answer(sw.to_be_bytes().to_vec())
+    }
+
+    /// Queue a transport failure
+    pub fn push_error(&self, error: MockError) -> &Self {
+        lock(&self.responses).push_back(Err(error));
+        self
+    }
+
+    /// Commands sent so far, in order
+    pub fn sent(&self) -> Vec<APDUCommand<Vec<u8>>> {
+        lock(&self.sent).clone()
+    }
+
+    /// Number of sent commands matching `cla` and `ins`
+    pub fn count(&self, cla: u8, ins: u8) -> usize {
+        lock(&self.sent)
+            .iter()
+            .filter(|command| command.cla == cla && command.ins == ins)
+            .count()
+    }
+
+    /// Number of queued responses not consumed yet
+    pub fn remaining(&self) -> usize {
+        lock(&self.responses).len()
+    }
+}
+
+fn lock<T>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex.lock().expect("mock transport poisoned")
+}
+
+#[async_trait]
+impl<A> Exchange for MockExchange<A>
+where
+    A: From<Vec<u8>> + Deref<Target = [u8]> + Send,
+{
+    type Error = MockError;
+    type AnswerType = A;
+
+    async fn exchange<I>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> Result<APDUAnswer<Self::AnswerType>, Self::Error>
+    where
+        I: Deref<Target = [u8]> + Send + Sync,
+    {
+        let command = APDUCommand {
+            cla: command.cla,
+            ins: command.ins,
+            p1: command.p1,
+            p2: command.p2,
+            data: command.data.to_vec(),
+        };
+
+        let queued = lock(&self.responses).pop_front();
+        let answer = match (queued, &self.responder) {
+            (Some(answer), _) => answer,
+            (None, Some(responder)) => responder(&command),
+            (None, None) => Err(MockError::NoResponse),
+        };
+        lock(&self.sent).push(command);
+
+        APDUAnswer::from_answer(A::from(answer?)).map_err(|_| MockError::InvalidAnswer)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(ins: u8) -> APDUCommand<Vec<u8>> {
+        APDUCommand {
+            cla: 0xE0,
+            ins,
+            p1: 0x00,
+            p2: 0x00,
+            data: vec![ins],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_queue_then_responder() {
+        let mock = MockExchange::new().with_responder(|_| Ok(vec![0x6D, 0x00]));
+        mock.push_ok(&[0xAA])
+            .push_error(MockError::Transport("unplugged".into()));
+
+        let answer = mock.exchange(&command(0x01)).await.unwrap();
+        assert_eq!(answer.split(), (&[0xAA][..], 0x9000));
+
+        let err = mock.exchange(&command(0x02)).await.unwrap_err();
+        assert_eq!(err, MockError::Transport("unplugged".into()));
+
+        let answer = mock.exchange(&command(0x03)).await.unwrap();
+        assert_eq!(answer.retcode(), 0x6D00);
+
+        let sent = mock.sent();
+        assert_eq!(sent.len(), 3);
+        assert_eq!(sent[1].data, vec![0x02]);
+        assert_eq!(mock.count(0xE0, 0x03), 1);
+        assert_eq!(mock.remaining(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_shared_answer_type() {
+        use std::sync::Arc;
+
+        let mock = MockExchange::<Arc<[u8]>>::default();
+        mock.push_ok(&[0xAA, 0xBB]).push_answer(vec![0x90]);
+
+        let answer: APDUAnswer<Arc<[u8]>> = mock.exchange(&command(0x01)).await.unwrap();
+        assert_eq!(answer.split(), (&[0xAA, 0xBB][..], 0x9000));
+        assert_eq!(
+            mock.exchange(&command(0x02)).await.unwrap_err(),
+            MockError::InvalidAnswer
+        );
+        assert_eq!(mock.sent().len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_exhausted_and_short_answers() {
+        let mock = MockExchange::new();
+        assert_eq!(
+            mock.exchange(&command(0x01)).await.unwrap_err(),
+            MockError::NoResponse
+        );
+
+        mock.push_answer(vec![0x90]);
+        assert_eq!(
+            mock.exchange(&command(0x01)).await.unwrap_err(),
+            MockError::InvalidAnswer
+        );
+    }
+}
@@ -0,0 +1,105 @@
+//! Slowed-down transport for timeout tests.
+
+use std::{
+    ops::Deref,
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use async_trait::async_trait;
+use ledger_sdk_apdu::{APDUAnswer, APDUCommand};
+
+use crate::Exchange;
+
+/// Transport wrapper sleeping before exchanges, for timeout tests
+#[derive(Debug)]
+pub struct DelayingExchange<E> {
+    inner: E,
+    delay: Duration,
+    nth: Option<usize>,
+    exchanges: AtomicUsize,
+}
+
+impl<E: Exchange> DelayingExchange<E> {
+    /// Wrap `inner`, sleeping `delay` before every exchange
+    pub fn new(inner: E, delay: Duration) -> Self {
+        DelayingExchange {
+            inner,
+            delay,
+            nth: None,
+            exchanges: AtomicUsize::new(0),
+        }
+    }
+
+    /// Only delay the exchange at index `n` (0-based), e.g. the signing command
+    /// after a configuration query
+    pub fn only_nth(mut self, n: usize) -> Self {
+        self.nth = Some(n);
+        self
+    }
+
+    /// Get a reference to the wrapped transport
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    /// Consume the wrapper and return the wrapped transport
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+}
+
+#[async_trait]
+impl<E> Exchange for DelayingExchange<E>
+where
+    E: Exchange + Send + Sync,
+{
+    type Error = E::Error;
+    type AnswerType = E::AnswerType;
+
+    async fn exchange<I>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> Result<APDUAnswer<Self::AnswerType>, Self::Error>
+    where
+        I: Deref<Target = [u8]> + Send + Sync,
+    {
+        let index = self.exchanges.fetch_add(1, Ordering::SeqCst);
+        if self.nth.map_or(true, |n| n == index) {
+            tokio::time::sleep(self.delay).await;
+        }
+        self.inner.exchange(command).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockExchange;
+
+    fn command(ins: u8) -> APDUCommand<Vec<u8>> {
+        APDUCommand {
+            cla: 0xE0,
+            ins,
+            p1: 0x00,
+            p2: 0x00,
+            data: vec![ins],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_delaying_exchange_only_nth() {
+        let mock = MockExchange::new();
+        mock.push_ok(&[]).push_ok(&[]).push_ok(&[]);
+        let transport = DelayingExchange::new(mock, Duration::from_millis(50)).only_nth(1);
+
+        let mut elapsed = Vec::new();
+        for ins in 0..3 {
+            let start = std::time::Instant::now();
+            transport.exchange(&command(ins)).await.unwrap();
+            elapsed.push(start.elapsed() >= Duration::from_millis(50));
+        }
+        assert_eq!(elapsed, vec![false, true, false]);
+        assert_eq!(transport.inner().sent().len(), 3);
+    }
+}
@@ -0,0 +1,176 @@
+//! Retrying [`Exchange`] decorator.
+//!
+//! USB links drop when the device screen sleeps or the user navigates menus.
+//! [`RetryExchange`] retries commands that failed at the transport layer,
+//! reopening the inner transport in between. APDU status errors (e.g. `0x6985`)
+//! are part of a successful exchange and are returned as-is, never retried.
+
+use std::{ops::Deref, time::Duration};
+
+use async_trait::async_trait;
+use ledger_sdk_apdu::{APDUAnswer, APDUCommand};
+use tokio::sync::RwLock;
+
+use crate::Exchange;
+
+/// Delay before the first retry; doubled after every failed attempt
+pub const DEFAULT_RETRY_BACKOFF: Duration = Duration::from_millis(100);
+
+/// Transport wrapper retrying failed exchanges over a reopened transport
+pub struct RetryExchange<E, F> {
+    inner: RwLock<E>,
+    max_retries: usize,
+    backoff: Duration,
+    reconnect: F,
+}
+
+impl<E, F> RetryExchange<E, F>
+where
+    E: Exchange,
+    F: Fn() -> Result<E, E::Error>,
+{
+    /// Wrap `inner`, retrying up to `max_retries` times and reopening with `reconnect`
+    pub fn new(inner: E, max_retries: usize, reconnect: F) -> Self {
+        RetryExchange {
+            inner: RwLock::new(inner),
+            max_retries,
+            backoff: DEFAULT_RETRY_BACKOFF,
+            reconnect,
+        }
+    }
+
+    /// Set the delay before the first retry
+    pub fn with_backoff(mut self, backoff: Duration) -> Self {
+        self.backoff = backoff;
+        self
+    }
+
+    /// Consume the wrapper and return the current inner transport
+    pub fn into_inner(self) -> E {
+        self.inner.into_inner()
+    }
+}
+
+#[async_trait]
+impl<E, F> Exchange for RetryExchange<E, F>
+where
+    E: Exchange + Send + Sync,
+    E::Error: Send,
+    F: Fn() -> Result<E, E::Error> + Send + Sync,
+{
+    type Error = E::Error;
+    type AnswerType = E::AnswerType;
+
+    async fn exchange<I>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> Result<APDUAnswer<Self::AnswerType>, Self::Error>
+    where
+        I: Deref<Target = [u8]> + Send + Sync,
+    {
+        let mut backoff = self.backoff;
+        let mut attempt = 0;
+
+        loop {
+            let error = match self.inner.read().await.exchange(command).await {
+                Ok(answer) => return Ok(answer),
+                Err(error) => error,
+            };
+
+            if attempt == self.max_retries {
+                return Err(error);
+            }
+            attempt += 1;
+
+            tokio::time::sleep(backoff).await;
+            backoff = backoff.saturating_mul(2);
+
+            // A failed reopen counts as a failed attempt; the old transport stays in place
+            match (self.reconnect)() {
+                Ok(transport) => *self.inner.write().await = transport,
+                Err(error) if attempt == self.max_retries => return Err(error),
+                Err(_) => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use super::*;
+    use crate::mock::{MockError, MockExchange};
+
+    fn command() -> APDUCommand<Vec<u8>> {
+        APDUCommand {
+            cla: 0xE0,
+            ins: 0x06,
+            p1: 0x00,
+            p2: 0x00,
+            data: Vec::new(),
+        }
+    }
+
+    fn unplugged() -> MockExchange {
+        let mock = MockExchange::new();
+        mock.push_error(MockError::Transport("unplugged".into()));
+        mock
+    }
+
+    #[tokio::test]
+    async fn test_retries_transport_errors_until_success() {
+        let reconnects = AtomicUsize::new(0);
+        let transport = RetryExchange::new(unplugged(), 3, || {
+            // The first reopened device still fails, the second one answers
+            match reconnects.fetch_add(1, Ordering::SeqCst) {
+                0 => Ok(unplugged()),
+                _ => {
+                    let mock = MockExchange::new();
+                    mock.push_ok(&[0x01, 0x02]);
+                    Ok(mock)
+                }
+            }
+        })
+        .with_backoff(Duration::ZERO);
+
+        let answer = transport.exchange(&command()).await.unwrap();
+        assert_eq!(answer.split(), (&[0x01, 0x02][..], 0x9000));
+        assert_eq!(reconnects.load(Ordering::SeqCst), 2);
+        assert_eq!(transport.into_inner().sent().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let reconnects = AtomicUsize::new(0);
+        let transport = RetryExchange::new(unplugged(), 2, || {
+            reconnects.fetch_add(1, Ordering::SeqCst);
+            Ok(unplugged())
+        })
+        .with_backoff(Duration::ZERO);
+
+        let err = transport.exchange(&command()).await.unwrap_err();
+        assert_eq!(err, MockError::Transport("unplugged".into()));
+        assert_eq!(reconnects.load(Ordering::SeqCst), 2);
+
+        // A failing reopen is reported once retries run out
+        let transport = RetryExchange::new(unplugged(), 1, || Err(MockError::NoResponse))
+            .with_backoff(Duration::ZERO);
+        assert_eq!(
+            transport.exchange(&command()).await.unwrap_err(),
+            MockError::NoResponse
+        );
+    }
+
+    #[tokio::test]
+    async fn test_status_errors_are_not_retried() {
+        let mock = MockExchange::new();
+        mock.push_status(0x6985);
+        let transport = RetryExchange::new(mock, 3, || -> Result<MockExchange, MockError> {
+            panic!("status errors must not trigger a reconnect")
+        });
+
+        let answer = transport.exchange(&command()).await.unwrap();
+        assert_eq!(answer.retcode(), 0x6985);
+    }
+}
@@ -0,0 +1,169 @@
+//! Per-command answer timeouts.
+//!
+//! Most commands are answered right away, so a device that stays silent for a
+//! few seconds is gone. Commands the user has to confirm on the device can take
+//! minutes. [`ExchangeTimeouts`] holds one timeout for each case and an
+//! app-provided classifier telling them apart; transports that can bound a read
+//! (e.g. HID) pick the timeout for each command from it.
+//!
+//! `TimeoutExchange` (feature `timeouts`) applies them to any transport whose
+//! exchange yields to the runtime while waiting, such as Speculos. A blocking
+//! transport like HID never yields, so it must bound its reads itself.
+
+use std::{ops::Deref, time::Duration};
+
+use ledger_sdk_apdu::APDUCommand;
+#[cfg(feature = "timeouts")]
+use {async_trait::async_trait, ledger_sdk_apdu::APDUAnswer, thiserror::Error};
+
+#[cfg(feature = "timeouts")]
+use crate::Exchange;
+
+/// Decides from `(cla, ins, p1, p2)` whether a command waits for user confirmation
+pub type ConfirmationClassifier = fn(cla: u8, ins: u8, p1: u8, p2: u8) -> bool;
+
+/// Protocol and confirmation timeouts
+#[derive(Clone, Copy, Debug)]
+pub struct ExchangeTimeouts {
+    /// Timeout for commands answered without user interaction
+    pub protocol: Duration,
+    /// Timeout for commands waiting on the user
+    pub confirmation: Duration,
+    /// Tells the two kinds of commands apart
+    pub requires_confirmation: ConfirmationClassifier,
+}
+
+impl ExchangeTimeouts {
+    /// Create timeouts using `requires_confirmation` to classify commands
+    pub fn new(
+        protocol: Duration,
+        confirmation: Duration,
+        requires_confirmation: ConfirmationClassifier,
+    ) -> Self {
+        ExchangeTimeouts {
+            protocol,
+            confirmation,
+            requires_confirmation,
+        }
+    }
+
+    /// Timeout to wait for the answer to `command`
+    pub fn for_command<I>(&self, command: &APDUCommand<I>) -> Duration
+    where
+        I: Deref<Target = [u8]>,
+    {
+        if (self.requires_confirmation)(command.cla, command.ins, command.p1, command.p2) {
+            self.confirmation
+        } else {
+            self.protocol
+        }
+    }
+}
+
+/// Errors produced by [`TimeoutExchange`]
+#[cfg(feature = "timeouts")]
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum TimeoutError<E> {
+    /// The device did not answer in time
+    #[error("no answer within {0:?}")]
+    Timeout(Duration),
+    /// Error from the wrapped transport
+    #[error(transparent)]
+    Transport(#[from] E),
+}
+
+/// Transport wrapper failing exchanges the device does not answer in time
+#[cfg(feature = "timeouts")]
+#[derive(Debug)]
+pub struct TimeoutExchange<E> {
+    inner: E,
+    timeouts: ExchangeTimeouts,
+}
+
+#[cfg(feature = "timeouts")]
+impl<E: Exchange> TimeoutExchange<E> {
+    /// Wrap `inner`, bounding each exchange by the timeout `timeouts` picks for it
+    pub fn new(inner: E, timeouts: ExchangeTimeouts) -> Self {
+        TimeoutExchange { inner, timeouts }
+    }
+
+    /// Get a reference to the wrapped transport
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    /// Consume the wrapper and return the wrapped transport
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+}
+
+#[cfg(feature = "timeouts")]
+#[async_trait]
+impl<E> Exchange for TimeoutExchange<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: Send,
+{
+    type Error = TimeoutError<E::Error>;
+    type AnswerType = E::AnswerType;
+
+    async fn exchange<I>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> Result<APDUAnswer<Self::AnswerType>, Self::Error>
+    where
+        I: Deref<Target = [u8]> + Send + Sync,
+    {
+        let timeout = self.timeouts.for_command(command);
+        match tokio::time::timeout(timeout, self.inner.exchange(command)).await {
+            Ok(result) => result.map_err(TimeoutError::Transport),
+            Err(_) => Err(TimeoutError::Timeout(timeout)),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "timeouts"))]
+mod tests {
+    use super::*;
+    use crate::mock::{DelayingExchange, MockExchange};
+
+    fn signs(_cla: u8, ins: u8, _p1: u8, _p2: u8) -> bool {
+        ins == 0x04
+    }
+
+    fn command(ins: u8) -> APDUCommand<Vec<u8>> {
+        APDUCommand {
+            cla: 0xE0,
+            ins,
+            p1: 0x00,
+            p2: 0x00,
+            data: vec![],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_slow_answer_times_out() {
+        let mock = MockExchange::new();
+        mock.push_ok(&[]).push_ok(&[]).push_ok(&[]);
+        // The second exchange takes longer than the protocol timeout
+        let transport = TimeoutExchange::new(
+            DelayingExchange::new(mock, Duration::from_millis(200)).only_nth(1),
+            ExchangeTimeouts::new(Duration::from_millis(20), Duration::from_secs(5), signs),
+        );
+
+        transport.exchange(&command(0x06)).await.unwrap();
+        let err = transport.exchange(&command(0x06)).await.unwrap_err();
+        assert_eq!(err, TimeoutError::Timeout(Duration::from_millis(20)));
+        transport.exchange(&command(0x06)).await.unwrap();
+
+        // A command waiting on the user gets the confirmation timeout
+        let mock = MockExchange::new();
+        mock.push_ok(&[]);
+        let transport = TimeoutExchange::new(
+            DelayingExchange::new(mock, Duration::from_millis(50)),
+            ExchangeTimeouts::new(Duration::from_millis(20), Duration::from_secs(5), signs),
+        );
+        transport.exchange(&command(0x04)).await.unwrap();
+    }
+}
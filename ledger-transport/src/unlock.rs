@@ -0,0 +1,187 @@
+//! Waiting for a locked device.
+//!
+//! The device can auto-lock while the user is reading a long review. Some
+//! firmware then rejects the next command with [`SW_DEVICE_LOCKED`] without
+//! processing it. [`UnlockWaitExchange`] waits for the user to unlock the device
+//! and sends the rejected command again, so a multi-APDU signing flow carries on
+//! where it stopped.
+
+use std::{
+    ops::Deref,
+    time::{Duration, Instant},
+};
+
+use async_trait::async_trait;
+use ledger_sdk_apdu::{APDUAnswer, APDUCommand};
+
+use crate::Exchange;
+
+/// Status word of a command rejected because the device is locked
+pub const SW_DEVICE_LOCKED: u16 = 0x5515;
+
+/// Delay between two checks of a locked device
+pub const DEFAULT_UNLOCK_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Transport wrapper resending commands rejected by a locked device once it is unlocked
+///
+/// If the device is still locked after `timeout`, the `0x5515` answer is
+/// returned as-is.
+#[derive(Debug)]
+pub struct UnlockWaitExchange<E> {
+    inner: E,
+    timeout: Duration,
+    poll_interval: Duration,
+}
+
+impl<E: Exchange> UnlockWaitExchange<E> {
+    /// Wrap `inner`, waiting up to `timeout` for the device to be unlocked
+    pub fn new(inner: E, timeout: Duration) -> Self {
+        UnlockWaitExchange {
+            inner,
+            timeout,
+            poll_interval: DEFAULT_UNLOCK_POLL_INTERVAL,
+        }
+    }
+
+    /// Set the delay between two checks of a locked device
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Get a reference to the wrapped transport
+    pub fn inner(&self) -> &E {
+        &self.inner
+    }
+
+    /// Consume the wrapper and return the wrapped transport
+    pub fn into_inner(self) -> E {
+        self.inner
+    }
+
+    /// Poll the device until it is unlocked, for at most `timeout`
+    ///
+    /// See [`wait_until_unlocked`].
+    pub async fn wait_until_ready(&self, timeout: Duration) -> Result<bool, E::Error>
+    where
+        E: Sync,
+    {
+        wait_until_unlocked(&self.inner, timeout, self.poll_interval).await
+    }
+}
+
+/// Poll the device every `poll_interval` until it is unlocked, for at most `timeout`
+///
+/// Probes with the OS level "get app and version" command, answered by the
+/// dashboard and every app. Returns whether the device was unlocked in time.
+pub async fn wait_until_unlocked<E>(
+    transport: &E,
+    timeout: Duration,
+    poll_interval: Duration,
+) -> Result<bool, E::Error>
+where
+    E: Exchange + Sync,
+{
+    let probe = APDUCommand {
+        cla: 0xB0,
+        ins: 0x01,
+        p1: 0x00,
+        p2: 0x00,
+        data: Vec::new(),
+    };
+    let deadline = Instant::now() + timeout;
+
+    loop {
+        if transport.exchange(&probe).await?.retcode() != SW_DEVICE_LOCKED {
+            return Ok(true);
+        }
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        if remaining.is_zero() {
+            return Ok(false);
+        }
+        tokio::time::sleep(poll_interval.min(remaining)).await;
+    }
+}
+
+#[async_trait]
+impl<E> Exchange for UnlockWaitExchange<E>
+where
+    E: Exchange + Send + Sync,
+    E::Error: Send,
+{
+    type Error = E::Error;
+    type AnswerType = E::AnswerType;
+
+    async fn exchange<I>(
+        &self,
+        command: &APDUCommand<I>,
+    ) -> Result<APDUAnswer<Self::AnswerType>, Self::Error>
+    where
+        I: Deref<Target = [u8]> + Send + Sync,
+    {
+        let deadline = Instant::now() + self.timeout;
+
+        loop {
+            let answer = self.inner.exchange(command).await?;
+            if answer.retcode() != SW_DEVICE_LOCKED {
+                return Ok(answer);
+            }
+
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            if remaining.is_zero() || !self.wait_until_ready(remaining).await? {
+                return Ok(answer);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock::MockExchange;
+
+    fn command() -> APDUCommand<Vec<u8>> {
+        APDUCommand {
+            cla: 0xE0,
+            ins: 0x04,
+            p1: 0x80,
+            p2: 0x00,
+            data: vec![0xAB; 4],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resends_after_unlock() {
+        let mock = MockExchange::new();
+        mock.push_status(SW_DEVICE_LOCKED)
+            .push_status(SW_DEVICE_LOCKED)
+            .push_ok(&[0x01])
+            .push_ok(&[0x25; 65]);
+        let transport = UnlockWaitExchange::new(mock, Duration::from_secs(5))
+            .with_poll_interval(Duration::from_millis(1));
+
+        let answer = transport.exchange(&command()).await.unwrap();
+        assert_eq!(answer.retcode(), 0x9000);
+        assert_eq!(answer.data(), &[0x25; 65]);
+
+        // Command, two probes, then the same command again
+        let sent = transport.inner().sent();
+        let ins: Vec<u8> = sent.iter().map(|c| c.ins).collect();
+        assert_eq!(ins, vec![0x04, 0x01, 0x01, 0x04]);
+        assert_eq!((sent[3].p1, &sent[3].data), (sent[0].p1, &sent[0].data));
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_timeout() {
+        let mock = MockExchange::new();
+        for _ in 0..100 {
+            mock.push_status(SW_DEVICE_LOCKED);
+        }
+        let transport = UnlockWaitExchange::new(mock, Duration::from_millis(20))
+            .with_poll_interval(Duration::from_millis(5));
+
+        let answer = transport.exchange(&command()).await.unwrap();
+        assert_eq!(answer.retcode(), SW_DEVICE_LOCKED);
+        assert!(transport.inner().sent().len() < 100);
+    }
+}
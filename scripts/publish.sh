@@ -106,6 +106,10 @@ update_dependencies() {
     sed -i.bak "s|ledger-transport = { path = \"../ledger-transport\" }|ledger-transport = \"$version\"|" ledger-transport-hid/Cargo.toml
     rm ledger-transport-hid/Cargo.toml.bak
     
+    # Update ledger-transport-speculos dependencies
+    sed -i.bak "s|ledger-transport = { path = \"../ledger-transport\" }|ledger-transport = \"$version\"|" ledger-transport-speculos/Cargo.toml
+    rm ledger-transport-speculos/Cargo.toml.bak
+    
     # Update ledger-eth-app dependencies
     sed -i.bak "s|ledger-transport = { path = \"../ledger-transport\" }|ledger-transport = \"$version\"|" ledger-eth-app/Cargo.toml
     sed -i.bak "s|ledger-device-base = { path = \"../ledger-device-base\" }|ledger-device-base = \"$version\"|" ledger-eth-app/Cargo.toml
@@ -130,6 +134,10 @@ revert_dependencies() {
     sed -i.bak "s|ledger-transport = \"[^\"]*\"|ledger-transport = { path = \"../ledger-transport\" }|" ledger-transport-hid/Cargo.toml
     rm ledger-transport-hid/Cargo.toml.bak
     
+    # Revert ledger-transport-speculos dependencies
+    sed -i.bak "s|ledger-transport = \"[^\"]*\"|ledger-transport = { path = \"../ledger-transport\" }|" ledger-transport-speculos/Cargo.toml
+    rm ledger-transport-speculos/Cargo.toml.bak
+    
     # Revert ledger-eth-app dependencies
     sed -i.bak "s|ledger-transport = \"[^\"]*\"|ledger-transport = { path = \"../ledger-transport\" }|" ledger-eth-app/Cargo.toml
     sed -i.bak "s|ledger-device-base = \"[^\"]*\"|ledger-device-base = { path = \"../ledger-device-base\" }|" ledger-eth-app/Cargo.toml
@@ -144,7 +152,7 @@ publish_crates() {
     print_status "Publishing crates to crates.io..."
     
     # Publish in dependency order
-    local crates=("ledger-apdu" "ledger-transport" "ledger-device-base" "ledger-transport-hid" "ledger-eth-app")
+    local crates=("ledger-apdu" "ledger-transport" "ledger-device-base" "ledger-transport-hid" "ledger-transport-speculos" "ledger-eth-app")
     
     for crate in "${crates[@]}"; do
         print_status "Publishing $crate..."